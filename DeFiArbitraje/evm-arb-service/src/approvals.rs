@@ -1,10 +1,15 @@
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
 use ethers::prelude::*;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
 use crate::config::Network;
+use crate::utils::fmt_addr;
 
 abigen!(
     IERC20,
@@ -16,12 +21,92 @@ abigen!(
     r#"[function approve(address token, address spender, uint160 amount, uint48 expiration, uint48 nonce)]"#,
 );
 
+/// Сколько `allowance` читается одновременно на старте
+const ALLOWANCE_CHECK_CONCURRENCY: usize = 8;
+
+/// Кэш увиденных allowance на диске: рестарт не перепроверяет on-chain пары
+/// token×spender, у которых allowance уже был не ниже порога
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AllowanceCache {
+    /// chain_id → экзекутор, при котором записаны allowance сети
+    #[serde(default)]
+    executors: BTreeMap<u64, String>,
+    /// `chain:owner:token:spender` → allowance (десятичная строка)
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+impl AllowanceCache {
+    /// Нет файла или он битый — пустой кэш (всё проверится on-chain)
+    pub fn load_or_default(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn persist(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn key(chain_id: u64, owner: Address, token: Address, spender: Address) -> String {
+        format!("{chain_id}:{owner:?}:{token:?}:{spender:?}")
+    }
+
+    /// Запоминает экзекутор сети; если он сменился — записи сети сбрасываются (true)
+    pub fn bind_executor(&mut self, chain_id: u64, executor: Address) -> bool {
+        let exec = format!("{executor:?}");
+        if self.executors.get(&chain_id) == Some(&exec) {
+            return false;
+        }
+        let had = self.executors.insert(chain_id, exec).is_some();
+        let prefix = format!("{chain_id}:");
+        self.entries.retain(|k, _| !k.starts_with(&prefix));
+        had
+    }
+
+    /// Кэшированный allowance не ниже `min` — on-chain проверка не нужна
+    pub fn covers(
+        &self,
+        chain_id: u64,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        min: U256,
+    ) -> bool {
+        self.entries
+            .get(&Self::key(chain_id, owner, token, spender))
+            .and_then(|v| U256::from_dec_str(v).ok())
+            .is_some_and(|a| a >= min)
+    }
+
+    pub fn record(
+        &mut self,
+        chain_id: u64,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        allowance: U256,
+    ) {
+        self.entries
+            .insert(Self::key(chain_id, owner, token, spender), allowance.to_string());
+    }
+}
+
+/// Проверяет allowance token×spender и одобряет недостающие. Пары, покрытые `cache`,
+/// не проверяются; достаточные allowance записываются в кэш (после отправки approve —
+/// нет: он ещё не в блоке, следующий старт проверит заново).
 pub async fn ensure_approvals<M, S>(
     sm: Arc<SignerMiddleware<M, S>>,
     net: &Network,
     tokens: Vec<Address>,
     spenders: Vec<Address>,
     min_allowance: U256,
+    cache: &mut AllowanceCache,
 ) -> Result<()>
 where
     M: Middleware + 'static,
@@ -41,68 +126,94 @@ where
         U256::from_str_radix("ffffffffffffffffffffffffffffffffffffffff", 16).unwrap_or(U256::MAX);
     let permit2_exp: u64 = (1u64 << 48) - 1;
 
-    for token in tokens {
+    // чтения allowance параллельно, approve — последовательно (один nonce за раз)
+    let pairs: Vec<(Address, Address)> = tokens
+        .iter()
+        .flat_map(|t| spenders.iter().map(move |s| (*t, *s)))
+        .filter(|(t, s)| {
+            let hit = cache.covers(net.chain_id, me, *t, *s, min_allowance);
+            if hit {
+                debug!("allowance cached token={} spender={}", fmt_addr(t), fmt_addr(s));
+            }
+            !hit
+        })
+        .collect();
+    let checks: Vec<_> = stream::iter(pairs)
+        .map(|(token, spender)| {
+            let c = IERC20::new(token, sm.clone());
+            async move { (token, spender, c.allowance(me, spender).call().await) }
+        })
+        .buffer_unordered(ALLOWANCE_CHECK_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut missing: Vec<(Address, Address)> = Vec::new();
+    for (token, spender, res) in checks {
+        match res {
+            Ok(allowance) if allowance < min_allowance => missing.push((token, spender)),
+            Ok(allowance) => {
+                debug!("allowance ok token={} spender={}", fmt_addr(&token), fmt_addr(&spender));
+                cache.record(net.chain_id, me, token, spender, allowance);
+            }
+            Err(e) => {
+                debug!(
+                    "allowance check failed token={} spender={} err={e:?}",
+                    fmt_addr(&token), fmt_addr(&spender)
+                );
+            }
+        }
+    }
+    // порядок завершения чтений случаен — approve шлём в стабильном порядке
+    missing.sort();
+
+    for (token, spender) in missing {
+        let spender = &spender;
         let c = IERC20::new(token, sm.clone());
-        for spender in &spenders {
-            match c.allowance(me, *spender).call().await {
-                Ok(allowance) => {
-                    if allowance < min_allowance {
-                        let mut used_permit2 = false;
-                        if let Some(p2addr) = permit2 {
-                            if dry {
-                                info!(
-                                    "DRY: permit2 approve token={:?} spender={:?}",
-                                    token, spender
-                                );
-                                used_permit2 = true;
-                            } else {
-                                let p2 = IPermit2::new(p2addr, sm.clone());
-                                match p2
-                                    .approve(token, *spender, permit2_max, permit2_exp, 0u64)
-                                    .gas(80_000u64)
-                                    .send()
-                                    .await
-                                {
-                                    Ok(pending) => {
-                                        let tx = pending.tx_hash();
-                                        info!(
-                                            "permit2 approve sent token={:?} spender={:?} tx={:?}",
-                                            token, spender, tx
-                                        );
-                                        used_permit2 = true;
-                                    }
-                                    Err(e) => {
-                                        info!(
-                                            "permit2 approve failed token={:?} spender={:?} err={e:?}; falling back",
-                                            token, spender
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        if !used_permit2 {
-                            if dry {
-                                info!("DRY: approve token={:?} spender={:?}", token, spender);
-                            } else {
-                                let call = c.approve(*spender, U256::MAX).gas(60_000u64);
-                                let pending = call.send().await?;
-                                let tx = pending.tx_hash();
-                                info!(
-                                    "approve sent token={:?} spender={:?} tx={:?}",
-                                    token, spender, tx
-                                );
-                            }
-                        }
-                    } else {
-                        debug!("allowance ok token={:?} spender={:?}", token, spender);
+        let mut used_permit2 = false;
+        // сам Permit2 одобряется обычным approve токена
+        if let Some(p2addr) = permit2.filter(|p2| p2 != spender) {
+            if dry {
+                info!(
+                    "DRY: permit2 approve token={} spender={}",
+                    fmt_addr(&token), fmt_addr(spender)
+                );
+                used_permit2 = true;
+            } else {
+                let p2 = IPermit2::new(p2addr, sm.clone());
+                match p2
+                    .approve(token, *spender, permit2_max, permit2_exp, 0u64)
+                    .gas(80_000u64)
+                    .send()
+                    .await
+                {
+                    Ok(pending) => {
+                        let tx = pending.tx_hash();
+                        info!(
+                            "permit2 approve sent token={} spender={} tx={:?}",
+                            fmt_addr(&token), fmt_addr(spender), tx
+                        );
+                        used_permit2 = true;
+                    }
+                    Err(e) => {
+                        info!(
+                            "permit2 approve failed token={} spender={} err={e:?}; falling back",
+                            fmt_addr(&token), fmt_addr(spender)
+                        );
                     }
                 }
-                Err(e) => {
-                    debug!(
-                        "allowance check failed token={:?} spender={:?} err={e:?}",
-                        token, spender
-                    );
-                }
+            }
+        }
+        if !used_permit2 {
+            if dry {
+                info!("DRY: approve token={} spender={}", fmt_addr(&token), fmt_addr(spender));
+            } else {
+                let call = c.approve(*spender, U256::MAX).gas(60_000u64);
+                let pending = call.send().await?;
+                let tx = pending.tx_hash();
+                info!(
+                    "approve sent token={} spender={} tx={:?}",
+                    fmt_addr(&token), fmt_addr(spender), tx
+                );
             }
         }
     }
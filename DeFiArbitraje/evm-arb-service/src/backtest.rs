@@ -0,0 +1,233 @@
+//! Бэктест по записанным кандидатам (`candidates-<chain>.jsonl`): повторно применяет
+//! фильтры slippage/min_profit из конфига и считает, что было бы исполнено, без RPC.
+
+use anyhow::{Context, Result};
+use ethers::types::U256;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::dex::min_out_bps;
+
+/// Строка лога кандидатов (схема `route::log_candidate`)
+#[derive(Clone, Debug, Deserialize)]
+pub struct Candidate {
+    pub ts: u64,
+    pub chain_id: u64,
+    pub pair_or_tri: String,
+    #[serde(default)]
+    pub strategy: Option<String>,
+    #[serde(default)]
+    pub legs: usize,
+    #[serde(default)]
+    pub routers: Vec<String>,
+    pub amount_in: String,
+    pub amount_out: String,
+    #[serde(default)]
+    pub gas_estimate: u64,
+    #[serde(default)]
+    pub pnl_usd: f64,
+    #[serde(default)]
+    pub pnl_usd_priced: bool,
+    /// Прибыль eth_call-симуляции (нет в старых логах и без экзекутора)
+    #[serde(default)]
+    pub sim_profit: Option<String>,
+}
+
+/// Фильтры сети — те же, что применяет движок перед отправкой
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Filters {
+    pub slippage_bps: u32,
+    pub min_profit_bps: u32,
+}
+
+impl Filters {
+    /// None — сети нет в конфиге
+    pub fn for_chain(cfg: &Config, chain_id: u64) -> Option<Self> {
+        let net = cfg.network_by_chain_id(chain_id)?;
+        let ov = net.strategy_overrides.as_ref();
+        Some(Self {
+            slippage_bps: ov
+                .and_then(|o| o.slippage_bps)
+                .unwrap_or(cfg.global.quote.slippage_bps_default),
+            min_profit_bps: ov.and_then(|o| o.min_profit_bps).unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Execute,
+    /// Выход после слиппеджа не покрывает вход
+    BelowMinOut,
+    /// Прибыль ниже `min_profit_bps`
+    BelowMinProfit,
+    /// Сети нет в конфиге или суммы не читаются
+    Invalid,
+}
+
+impl Verdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            Verdict::Execute => "execute",
+            Verdict::BelowMinOut => "below_min_out",
+            Verdict::BelowMinProfit => "below_min_profit",
+            Verdict::Invalid => "invalid",
+        }
+    }
+}
+
+/// Решение движка по кандидату при заданных фильтрах
+pub fn evaluate(c: &Candidate, filters: Option<Filters>) -> Verdict {
+    let (Some(f), Ok(amount_in), Ok(amount_out)) = (
+        filters,
+        U256::from_dec_str(&c.amount_in),
+        U256::from_dec_str(&c.amount_out),
+    ) else {
+        return Verdict::Invalid;
+    };
+    if min_out_bps(amount_out, f.slippage_bps) <= amount_in || amount_out <= amount_in {
+        return Verdict::BelowMinOut;
+    }
+    let min_profit = amount_in * U256::from(f.min_profit_bps) / U256::from(10_000u64);
+    if amount_out - amount_in < min_profit {
+        return Verdict::BelowMinProfit;
+    }
+    Verdict::Execute
+}
+
+/// Итог по сети
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainSummary {
+    pub candidates: u64,
+    pub executed: u64,
+    pub below_min_out: u64,
+    pub below_min_profit: u64,
+    pub invalid: u64,
+    /// Сумма оценочного PnL исполненных кандидатов с USD-ценой
+    pub pnl_usd: f64,
+    /// Исполненные кандидаты без USD-цены (в `pnl_usd` не входят)
+    pub unpriced: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Backtest {
+    pub chains: BTreeMap<u64, ChainSummary>,
+}
+
+impl Backtest {
+    pub fn add(&mut self, c: &Candidate, verdict: Verdict) {
+        let s = self.chains.entry(c.chain_id).or_default();
+        s.candidates += 1;
+        match verdict {
+            Verdict::Execute => {
+                s.executed += 1;
+                if c.pnl_usd_priced {
+                    s.pnl_usd += c.pnl_usd;
+                } else {
+                    s.unpriced += 1;
+                }
+            }
+            Verdict::BelowMinOut => s.below_min_out += 1,
+            Verdict::BelowMinProfit => s.below_min_profit += 1,
+            Verdict::Invalid => s.invalid += 1,
+        }
+    }
+
+    /// Сводная таблица (по сети + итог)
+    pub fn table(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:>8} {:>10} {:>9} {:>13} {:>16} {:>8} {:>14} {:>9}",
+            "chain", "candidates", "executed", "below_min_out", "below_min_profit", "invalid",
+            "pnl_usd", "unpriced"
+        );
+        let mut total = ChainSummary::default();
+        let row = |out: &mut String, label: &str, s: &ChainSummary| {
+            let _ = writeln!(
+                out,
+                "{:>8} {:>10} {:>9} {:>13} {:>16} {:>8} {:>14.2} {:>9}",
+                label,
+                s.candidates,
+                s.executed,
+                s.below_min_out,
+                s.below_min_profit,
+                s.invalid,
+                s.pnl_usd,
+                s.unpriced
+            );
+        };
+        for (chain, s) in &self.chains {
+            row(&mut out, &chain.to_string(), s);
+            total.candidates += s.candidates;
+            total.executed += s.executed;
+            total.below_min_out += s.below_min_out;
+            total.below_min_profit += s.below_min_profit;
+            total.invalid += s.invalid;
+            total.pnl_usd += s.pnl_usd;
+            total.unpriced += s.unpriced;
+        }
+        row(&mut out, "total", &total);
+        out
+    }
+}
+
+/// Файлы кандидатов в каталоге, включая ротированные (`candidates-<chain>[-<ts>].jsonl`)
+pub fn candidate_files(dir: &Path, chain: Option<u64>) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read dir {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(rest) = name
+            .strip_prefix("candidates-")
+            .and_then(|r| r.strip_suffix(".jsonl"))
+        else {
+            continue;
+        };
+        let file_chain = rest.split('-').next().and_then(|c| c.parse::<u64>().ok());
+        if file_chain.is_some() && (chain.is_none() || file_chain == chain) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Кандидаты из JSONL; битые строки пропускаются (возвращается их число)
+pub fn read_candidates(path: &Path) -> Result<(Vec<Candidate>, usize)> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let mut out = Vec::new();
+    let mut bad = 0;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<Candidate>(line) {
+            Ok(c) => out.push(c),
+            Err(_) => bad += 1,
+        }
+    }
+    Ok((out, bad))
+}
+
+/// Строка CSV по кандидату (заголовок — `CSV_HEADER`)
+pub const CSV_HEADER: &str =
+    "ts,chain_id,pair_or_tri,strategy,amount_in,amount_out,pnl_usd,pnl_usd_priced,verdict";
+
+pub fn csv_row(c: &Candidate, verdict: Verdict) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{},{}",
+        c.ts,
+        c.chain_id,
+        c.pair_or_tri,
+        c.strategy.as_deref().unwrap_or(""),
+        c.amount_in,
+        c.amount_out,
+        c.pnl_usd,
+        c.pnl_usd_priced,
+        verdict.label()
+    )
+}
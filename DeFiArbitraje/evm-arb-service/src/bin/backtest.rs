@@ -0,0 +1,105 @@
+//! Бэктест фильтров конфига по записанным кандидатам, без RPC.
+//!
+//! Пример:
+//!   cargo run --bin backtest -- --config ./config/defi_config.json \
+//!       --logs ./logs --chain 8453 --csv ./backtest.csv
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::io::Write;
+use std::path::PathBuf;
+
+use DeFiArbitraje::backtest::{
+    Backtest, CSV_HEADER, Filters, candidate_files, csv_row, evaluate, read_candidates,
+};
+use DeFiArbitraje::config::Config;
+
+const USAGE: &str = "usage: backtest --config <path> [--logs <dir>] [--chain <id>] [--csv <path>]";
+
+struct Args {
+    config: String,
+    logs: Option<PathBuf>,
+    chain: Option<u64>,
+    csv: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut config = std::env::var("DEFI_CONFIG").ok();
+    let (mut logs, mut chain, mut csv) = (None, None, None);
+
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        let mut value = || {
+            it.next()
+                .ok_or_else(|| anyhow!("{flag} needs a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--config" => config = Some(value()?),
+            "--logs" => logs = Some(PathBuf::from(value()?)),
+            "--chain" => chain = Some(value()?.parse().context("--chain")?),
+            "--csv" => csv = Some(PathBuf::from(value()?)),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            other => bail!("unknown argument '{other}'\n{USAGE}"),
+        }
+    }
+
+    Ok(Args {
+        config: config
+            .ok_or_else(|| anyhow!("--config (или ENV DEFI_CONFIG) обязателен\n{USAGE}"))?,
+        logs,
+        chain,
+        csv,
+    })
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args = parse_args()?;
+    let cfg = Config::load(&args.config)
+        .with_context(|| format!("loading config from {}", args.config))?;
+    // по умолчанию — каталог, куда движок пишет кандидатов
+    let dir = args
+        .logs
+        .unwrap_or_else(|| PathBuf::from(&cfg.telemetry.logs.candidates.dir));
+    let files = candidate_files(&dir, args.chain)?;
+    if files.is_empty() {
+        bail!("no candidates-*.jsonl in {}", dir.display());
+    }
+
+    let mut csv = match &args.csv {
+        Some(path) => {
+            let mut f = std::fs::File::create(path)
+                .with_context(|| format!("create {}", path.display()))?;
+            writeln!(f, "{CSV_HEADER}")?;
+            Some(f)
+        }
+        None => None,
+    };
+
+    let mut bt = Backtest::default();
+    let mut bad_lines = 0;
+    for file in &files {
+        let (candidates, bad) = read_candidates(file)?;
+        bad_lines += bad;
+        for c in &candidates {
+            let verdict = evaluate(c, Filters::for_chain(&cfg, c.chain_id));
+            bt.add(c, verdict);
+            if let Some(f) = csv.as_mut() {
+                writeln!(f, "{}", csv_row(c, verdict))?;
+            }
+        }
+    }
+
+    println!("files: {}, unreadable lines: {}", files.len(), bad_lines);
+    print!("{}", bt.table());
+    if let Some(path) = &args.csv {
+        println!("per-candidate CSV: {}", path.display());
+    }
+    Ok(())
+}
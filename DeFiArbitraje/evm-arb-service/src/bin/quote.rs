@@ -0,0 +1,167 @@
+//! Разовая котировка одной cross-dex пары без запуска движка.
+//!
+//! Пример:
+//!   cargo run --bin quote -- --config ./config/defi_config.json \
+//!       --chain 8453 --pair USDC,WETH --dex UniswapV3,AerodromeV2 --amount 100
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::json;
+use std::time::Duration;
+
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::router::{QuoteOutcome, quote_cross_dex_pair};
+use DeFiArbitraje::utils::{fmt_addr, set_checksum_output, u256_from_decimals};
+use DeFiArbitraje::utils_gas::GasPriceMode;
+
+const USAGE: &str =
+    "usage: quote --config <path> --chain <id> --pair A,B --dex dexA,dexB [--amount 1.0]";
+
+struct Args {
+    config: String,
+    chain: u64,
+    pair: (String, String),
+    dex: (String, String),
+    amount: f64,
+}
+
+fn split_two(flag: &str, v: &str) -> Result<(String, String)> {
+    match v.split_once(',') {
+        Some((a, b)) if !a.trim().is_empty() && !b.trim().is_empty() => {
+            Ok((a.trim().to_string(), b.trim().to_string()))
+        }
+        _ => bail!("{flag} expects two comma-separated values, got '{v}'"),
+    }
+}
+
+fn parse_args() -> Result<Args> {
+    let mut config = std::env::var("DEFI_CONFIG").ok();
+    let (mut chain, mut pair, mut dex, mut amount) = (None, None, None, 1.0);
+
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        let mut value = || {
+            it.next()
+                .ok_or_else(|| anyhow!("{flag} needs a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--config" => config = Some(value()?),
+            "--chain" => chain = Some(value()?.parse().context("--chain")?),
+            "--pair" => pair = Some(split_two("--pair", &value()?)?),
+            "--dex" => dex = Some(split_two("--dex", &value()?)?),
+            "--amount" => amount = value()?.parse().context("--amount")?,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            other => bail!("unknown argument '{other}'\n{USAGE}"),
+        }
+    }
+
+    Ok(Args {
+        config: config
+            .ok_or_else(|| anyhow!("--config (или ENV DEFI_CONFIG) обязателен\n{USAGE}"))?,
+        chain: chain.ok_or_else(|| anyhow!("--chain обязателен\n{USAGE}"))?,
+        pair: pair.ok_or_else(|| anyhow!("--pair обязателен\n{USAGE}"))?,
+        dex: dex.ok_or_else(|| anyhow!("--dex обязателен\n{USAGE}"))?,
+        amount,
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args = parse_args()?;
+    let cfg = Config::load(&args.config)
+        .with_context(|| format!("loading config from {}", args.config))?;
+    set_checksum_output(cfg.telemetry.logs.checksum_addresses);
+
+    // Поднимаем клиент только для нужной сети
+    let mut one = cfg.clone();
+    one.networks.retain(|n| n.chain_id == args.chain);
+    if one.networks.is_empty() {
+        bail!("chain {} not found in config", args.chain);
+    }
+    let chains = MultiChain::from_config(&one).await?;
+    let client = chains
+        .clients
+        .get(&args.chain)
+        .ok_or_else(|| anyhow!("chain {} client not initialized", args.chain))?;
+    let net = &client.cfg;
+
+    let sym_a = args.pair.0.to_uppercase();
+    let sym_b = args.pair.1.to_uppercase();
+    let find_dex = |name: &str| {
+        net.dexes
+            .iter()
+            .find(|d| d.name == name)
+            .ok_or_else(|| anyhow!("dex '{}' not found on chain {}", name, net.chain_id))
+    };
+    let dex_a = find_dex(&args.dex.0)?;
+    let dex_b = find_dex(&args.dex.1)?;
+    let dec = net
+        .tokens
+        .get(&sym_a)
+        .map(|t| t.decimals)
+        .ok_or_else(|| anyhow!("token {} not found on chain {}", sym_a, net.chain_id))?;
+    let amount_in = u256_from_decimals(args.amount, dec);
+
+    // Слиппедж, таймаут ноги, сэмплинг тиков v3 и режим цены газа — как в движке
+    let slip_bps = net
+        .strategy_overrides
+        .as_ref()
+        .and_then(|ov| ov.slippage_bps)
+        .unwrap_or(cfg.global.quote.slippage_bps_default);
+    let leg_timeout = Duration::from_millis(cfg.global.quote.leg_quote_timeout_ms);
+    let tick_samples = cfg.global.quote.tick_liquidity_sample.unwrap_or(0);
+    let gas_mode = GasPriceMode::parse(&cfg.global.quote.gas_price_mode)?;
+
+    let qr = quote_cross_dex_pair(
+        client,
+        net,
+        (&sym_a, &sym_b),
+        &[],
+        dex_a,
+        dex_b,
+        amount_in,
+        slip_bps,
+        leg_timeout,
+        tick_samples,
+        gas_mode,
+    )
+    .await?;
+
+    let out = match qr {
+        QuoteOutcome::Filled(qr) => json!({
+            "chain_id": net.chain_id,
+            "pair": format!("{}-{}", sym_a, sym_b),
+            "dexes": [dex_a.name, dex_b.name],
+            "slippage_bps": slip_bps,
+            "amount_in": qr.amount_in.to_string(),
+            "amount_out": qr.amount_out.to_string(),
+            "gas_estimate": qr.gas_estimate,
+            "gas_price": qr.gas_price.to_string(),
+            "routers": qr.legs.iter().map(|l| fmt_addr(&l.router())).collect::<Vec<_>>(),
+            "profit_native": qr.profit_native,
+            "pnl_usd": qr.pnl_usd,
+            "pnl_usd_priced": qr.pnl_usd_priced,
+        }),
+        skip => json!({
+            "chain_id": net.chain_id,
+            "pair": format!("{}-{}", sym_a, sym_b),
+            "dexes": [dex_a.name, dex_b.name],
+            "quote": null,
+            "reason": skip.reason(),
+            "detail": match &skip {
+                QuoteOutcome::MissingConfig(e) => Some(e.to_string()),
+                _ => None,
+            },
+        }),
+    };
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
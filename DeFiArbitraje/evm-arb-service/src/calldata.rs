@@ -1,6 +1,46 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use ethers::abi::{self, Token};
-use ethers::types::{Address, Bytes, U256};
+use ethers::types::{Address, Bytes, H256, I256, U256, U512};
+use ethers::utils::keccak256;
+
+/// Ключ пула Uniswap V4 (пулы живут в singleton PoolManager, отдельного контракта нет)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct V4PoolKey {
+    pub currency0: Address,
+    pub currency1: Address,
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: Address,
+}
+
+impl V4PoolKey {
+    /// Валюты сортируются по адресу, как того требует PoolManager
+    pub fn new(a: Address, b: Address, fee: u32, tick_spacing: i32, hooks: Address) -> Self {
+        let (currency0, currency1) = if a < b { (a, b) } else { (b, a) };
+        Self {
+            currency0,
+            currency1,
+            fee,
+            tick_spacing,
+            hooks,
+        }
+    }
+
+    pub fn abi_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::Address(self.currency0),
+            Token::Address(self.currency1),
+            Token::Uint(U256::from(self.fee)),
+            Token::Int(I256::from(self.tick_spacing).into_raw()),
+            Token::Address(self.hooks),
+        ]
+    }
+
+    /// PoolId = keccak256(abi.encode(poolKey))
+    pub fn pool_id(&self) -> H256 {
+        H256::from(keccak256(abi::encode(&self.abi_tokens())))
+    }
+}
 
 #[derive(Clone, Debug)]
 pub enum LegKind {
@@ -20,20 +60,159 @@ pub enum LegKind {
         stable: bool,
         token_in: Address,
     },
+    /// Algebra (QuickSwap v3 / Camelot v3): комиссия динамическая, в ноге её нет
+    AlgebraV3 {
+        router: Address,
+        token_in: Address,
+        token_out: Address,
+    },
+    /// Uniswap V4: свап через UniversalRouter по ключу пула
+    V4 {
+        router: Address,
+        pool_key: V4PoolKey,
+        zero_for_one: bool,
+    },
+    /// native → wrapped (`WETH.deposit`) 1:1; первая нога маршрута с native-входом
+    Wrap { weth: Address },
+    /// wrapped → native (`WETH.withdraw`) 1:1; последняя нога маршрута с native-выходом
+    Unwrap { weth: Address },
+}
+
+impl LegKind {
+    /// Обёртка native, а не свап: не считается в лимит ног маршрута
+    pub fn is_wrap(&self) -> bool {
+        matches!(self, LegKind::Wrap { .. } | LegKind::Unwrap { .. })
+    }
+
+    /// Нога только для котировок: V4 Executor.sol не исполняет (`BAD_PROTOCOL`)
+    pub fn is_quote_only(&self) -> bool {
+        matches!(self, LegKind::V4 { .. })
+    }
+}
+
+/// Маршрут с ногой только для котировок не отправляется
+pub fn quote_only(legs: &[LegQuote]) -> bool {
+    legs.iter().any(|l| l.kind.is_quote_only())
 }
 
 #[derive(Clone, Debug)]
 pub struct LegQuote {
     pub kind: LegKind,
+    /// Выход ноги по котировке (0 — неизвестен, минимум ноги не проверяется)
+    pub quoted_out: U256,
+}
+
+impl LegQuote {
+    pub fn router(&self) -> Address {
+        match &self.kind {
+            LegKind::V2 { router, .. }
+            | LegKind::V3 { router, .. }
+            | LegKind::Solidly { router, .. }
+            | LegKind::AlgebraV3 { router, .. }
+            | LegKind::V4 { router, .. } => *router,
+            LegKind::Wrap { weth } | LegKind::Unwrap { weth } => *weth,
+        }
+    }
+}
+
+/// Дедлайн маршрута в unix-секундах (сравнивается с block.timestamp в экзекуторе):
+/// `now + deadline_seconds`; `deadline_seconds = 0` — дедлайн не задан (0).
+pub fn route_deadline(now_unix: u64, deadline_seconds: u32) -> U256 {
+    if deadline_seconds == 0 {
+        U256::zero()
+    } else {
+        U256::from(now_unix + deadline_seconds as u64)
+    }
+}
+
+/// Предел ног маршрута, если стратегия не задала `max_route_hops` (треугольник)
+pub const MAX_ROUTE_LEGS: usize = 3;
+
+/// Проверка перед кодированием: маршрут круговой (выход в токене входа), поэтому
+/// `min_out <= amount_in` — гарантированный убыток, а не сделка
+pub fn validate_route(
+    legs: &[LegQuote],
+    amount_in: U256,
+    min_out: U256,
+    max_legs: usize,
+) -> Result<()> {
+    if legs.is_empty() {
+        bail!("route has no legs");
+    }
+    let swaps = legs.iter().filter(|l| !l.kind.is_wrap()).count();
+    if swaps > max_legs {
+        bail!("route has {swaps} legs, max {max_legs}");
+    }
+    if min_out <= amount_in {
+        bail!("min_out {min_out} <= amount_in {amount_in}: round-trip would lose");
+    }
+    Ok(())
+}
+
+/// Свой запас промежуточных ног (`quote.leg_slippage_bps`). Котировки ног — на вход
+/// `quoted_in`; при другом фактическом входе они пересчитываются пропорционально
+#[derive(Clone, Copy, Debug)]
+pub struct LegSlippage {
+    pub bps: u32,
+    pub quoted_in: U256,
+}
+
+/// Минимумы выхода каждой ноги: котировка ноги с тем же запасом, что `min_out` к котировке
+/// последней ноги (`quoted_i * min_out / quoted_last`). Сэндвич на промежуточной ноге
+/// откатывает весь маршрут, а не съедает запас финального `min_out`. Пропорция сохраняется
+/// и при изменении размера сделки pre-send хуком. Без котировки последней ноги — нули.
+///
+/// `leg_slippage` — свой запас промежуточных ног от их котировок, пересчитанных на вход
+/// `amount_in` (`quoted_i * amount_in / quoted_in * (1 - bps)`); последняя нога всё равно
+/// ограничена `min_out`.
+pub fn leg_min_outs(
+    legs: &[LegQuote],
+    amount_in: U256,
+    min_out: U256,
+    leg_slippage: Option<LegSlippage>,
+) -> Vec<U256> {
+    let last = legs.last().map(|l| l.quoted_out).unwrap_or_default();
+    legs.iter()
+        .enumerate()
+        .map(|(i, leg)| {
+            if last.is_zero() {
+                return U256::zero();
+            }
+            if let Some(slip) = leg_slippage.filter(|_| i + 1 < legs.len()) {
+                let quoted = if slip.quoted_in.is_zero() {
+                    leg.quoted_out
+                } else {
+                    let scaled = leg.quoted_out.full_mul(amount_in) / U512::from(slip.quoted_in);
+                    U256::try_from(scaled).unwrap_or(U256::MAX)
+                };
+                let keep = U256::from(10_000 - slip.bps.min(10_000));
+                let min = quoted.full_mul(keep) / U512::from(10_000u64);
+                return U256::try_from(min).unwrap_or(U256::MAX);
+            }
+            let min = leg.quoted_out.full_mul(min_out) / U512::from(last);
+            U256::try_from(min).unwrap_or(U256::MAX)
+        })
+        .collect()
 }
 
-pub fn encode_route_calldata(legs: &[LegQuote], amount_in: U256, min_out: U256) -> Result<Bytes> {
+/// Calldata маршрута: amount_in, min_out, число ног, ноги (каждая заканчивается своим
+/// минимумом выхода, см. `leg_min_outs`), затем `deadline` (unix-секунды, 0 — без дедлайна).
+/// Маршрут сначала проходит `validate_route`.
+pub fn encode_route_calldata(
+    legs: &[LegQuote],
+    amount_in: U256,
+    min_out: U256,
+    leg_slippage: Option<LegSlippage>,
+    deadline: U256,
+    max_legs: usize,
+) -> Result<Bytes> {
+    validate_route(legs, amount_in, min_out, max_legs)?;
     let mut tokens: Vec<Token> = Vec::new();
     tokens.push(Token::Uint(amount_in));
     tokens.push(Token::Uint(min_out));
     tokens.push(Token::Uint(U256::from(legs.len() as u64)));
 
-    for leg in legs {
+    for (leg, leg_min) in legs.iter().zip(leg_min_outs(legs, amount_in, min_out, leg_slippage)) {
         match &leg.kind {
             LegKind::V2 { router, path } => {
                 tokens.push(Token::Uint(U256::from(1u8)));
@@ -65,8 +244,39 @@ pub fn encode_route_calldata(legs: &[LegQuote], amount_in: U256, min_out: U256)
                 tokens.push(Token::Bool(*stable));
                 tokens.push(Token::Address(*token_in));
             }
+            LegKind::AlgebraV3 {
+                router,
+                token_in,
+                token_out,
+            } => {
+                tokens.push(Token::Uint(U256::from(4u8)));
+                tokens.push(Token::Address(*router));
+                tokens.push(Token::Address(*token_in));
+                tokens.push(Token::Address(*token_out));
+            }
+            LegKind::V4 {
+                router,
+                pool_key,
+                zero_for_one,
+            } => {
+                tokens.push(Token::Uint(U256::from(5u8)));
+                tokens.push(Token::Address(*router));
+                tokens.extend(pool_key.abi_tokens());
+                tokens.push(Token::Bool(*zero_for_one));
+            }
+            // тег 5 занят V4
+            LegKind::Wrap { weth } => {
+                tokens.push(Token::Uint(U256::from(6u8)));
+                tokens.push(Token::Address(*weth));
+            }
+            LegKind::Unwrap { weth } => {
+                tokens.push(Token::Uint(U256::from(7u8)));
+                tokens.push(Token::Address(*weth));
+            }
         }
+        tokens.push(Token::Uint(leg_min));
     }
+    tokens.push(Token::Uint(deadline));
 
     Ok(Bytes::from(abi::encode(&tokens)))
 }
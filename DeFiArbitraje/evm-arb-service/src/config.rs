@@ -1,10 +1,74 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use std::{env, fs};
 
+/// Версия схемы конфига, которую понимает сервис. Другой major — ошибка загрузки,
+/// кроме предыдущего major: он мигрируется (`migrate_config`) с предупреждением.
+pub const SUPPORTED_CONFIG_VERSION: &str = "2.0.0";
+
+/// Major из `x.y.z`
+pub fn config_major(version: &str) -> Result<u64> {
+    version
+        .trim()
+        .split('.')
+        .next()
+        .and_then(|m| m.parse().ok())
+        .ok_or_else(|| anyhow!("config version '{version}': expected x.y.z"))
+}
+
+/// Приводит сырой конфиг к `SUPPORTED_CONFIG_VERSION`. Текущий major не трогается.
+pub fn migrate_config(raw: &mut serde_json::Value) -> Result<()> {
+    let version = raw
+        .get("version")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("config.version is missing"))?
+        .to_string();
+    let supported = config_major(SUPPORTED_CONFIG_VERSION)?;
+    match config_major(&version)? {
+        m if m == supported => Ok(()),
+        1 => {
+            migrate_v1_to_v2(raw);
+            tracing::warn!(
+                "config version {version} мигрирован в {SUPPORTED_CONFIG_VERSION}: \
+                 обновите файл (version и новые поля), чтобы убрать предупреждение"
+            );
+            Ok(())
+        }
+        m => Err(anyhow!(
+            "config version {version} (major {m}) не поддерживается: ожидается {supported}.x \
+             ({SUPPORTED_CONFIG_VERSION})"
+        )),
+    }
+}
+
+/// 1.x → 2.0: в 2.0 по умолчанию включены пропуск отстающих RPC (`rpc.max_block_lag`)
+/// и /healthz по свежести скана (`health_stale_secs`). Конфиг 1.x без этих полей
+/// сохраняет прежнее поведение — обе проверки выключены.
+fn migrate_v1_to_v2(raw: &mut serde_json::Value) {
+    use serde_json::{Value, json};
+    let Some(root) = raw.as_object_mut() else {
+        return;
+    };
+    let rpc = root.entry("rpc").or_insert_with(|| json!({}));
+    if let Some(rpc) = rpc.as_object_mut() {
+        rpc.entry("max_block_lag").or_insert(json!(0));
+    }
+    if let Some(prom) = root
+        .get_mut("telemetry")
+        .and_then(|t| t.get_mut("prometheus"))
+        .and_then(Value::as_object_mut)
+    {
+        prom.entry("health_stale_secs").or_insert(json!(0));
+    }
+    root.insert("version".into(), json!(SUPPORTED_CONFIG_VERSION));
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Версия схемы (`SUPPORTED_CONFIG_VERSION`)
     pub version: String,
     pub created_at: String,
     #[serde(default)]
@@ -16,16 +80,109 @@ pub struct Config {
     pub routing: Routing,
     pub safety: Safety,
     pub telemetry: Telemetry,
+    #[serde(default)]
+    pub rpc: RpcCfg,
+    /// Вывод pool-discovery-cli (`pools.generated.json`): адреса пулов прогревают
+    /// кэш `ChainClient`, и квоты не ходят в factory за getPair/getPool
+    #[serde(default)]
+    pub pools_file: Option<String>,
+    /// Конфиг по URL: период повторной загрузки, сек (0 — только по SIGHUP).
+    /// Ошибка загрузки оставляет последний рабочий конфиг.
+    #[serde(default)]
+    pub config_refresh_sec: u64,
+    /// chain_id → индекс в `networks` (строится в `load`, см. `network_by_chain_id`)
+    #[serde(skip)]
+    chain_index: HashMap<u64, usize>,
+}
+
+/// Десериализация конфига по расширению файла; неизвестное расширение — пробуем JSON
+pub fn parse_config<T: DeserializeOwned>(path: &str, data: &str) -> Result<T> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "json" => serde_json::from_str(data).with_context(|| format!("parse JSON config {path}")),
+        "yaml" | "yml" => {
+            serde_yaml::from_str(data).with_context(|| format!("parse YAML config {path}"))
+        }
+        "toml" => toml::from_str(data).with_context(|| format!("parse TOML config {path}")),
+        _ => serde_json::from_str(data).map_err(|e| {
+            anyhow!(
+                "config {path}: unknown extension '{ext}' (expected .json/.yaml/.yml/.toml), \
+                 JSON fallback failed: {e}"
+            )
+        }),
+    }
+}
+
+/// Таймаут загрузки конфига по URL
+pub const CONFIG_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Источник конфига — http(s)-URL (в т.ч. presigned S3), а не путь к файлу
+pub fn is_config_url(src: &str) -> bool {
+    let s = src.trim_start().to_ascii_lowercase();
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+/// Источник для логов и сообщений об ошибках: у URL — хост и путь без query
+/// (подпись presigned-ссылки в логи не попадает); по пути же определяется формат
+pub fn config_source_label(src: &str) -> String {
+    if !is_config_url(src) {
+        return src.to_string();
+    }
+    reqwest::Url::parse(src.trim())
+        .map(|u| format!("{}{}", u.host_str().unwrap_or(""), u.path()))
+        .unwrap_or_else(|_| "config-url".into())
+}
+
+async fn fetch_config(url: &str) -> Result<String> {
+    let hint = config_source_label(url);
+    let resp = reqwest::Client::builder()
+        .timeout(CONFIG_FETCH_TIMEOUT)
+        .build()?
+        .get(url.trim())
+        .send()
+        .await
+        .with_context(|| format!("fetch config {hint}"))?
+        .error_for_status()
+        .with_context(|| format!("fetch config {hint}"))?;
+    Ok(resp.text().await?)
 }
 
 impl Config {
+    /// Формат определяется по расширению: .json / .yaml / .yml / .toml
     pub fn load(path: &str) -> Result<Self> {
-        let data = fs::read_to_string(path)?;
-        let mut c: Self = serde_json::from_str(&data)?;
+        let data = fs::read_to_string(path).with_context(|| format!("read config {path}"))?;
+        Self::parse(path, &data)
+    }
+
+    /// Путь к файлу или http(s)-URL (формат — по расширению в пути URL)
+    pub async fn load_source(src: &str) -> Result<Self> {
+        if !is_config_url(src) {
+            return Self::load(src);
+        }
+        let data = fetch_config(src).await?;
+        Self::parse(&config_source_label(src), &data)
+    }
+
+    /// Разбор текста конфига; `path` задаёт формат и попадает в сообщения об ошибках
+    pub fn parse(path: &str, data: &str) -> Result<Self> {
+        let mut raw: serde_json::Value = parse_config(path, data)?;
+        let before = raw.get("version").cloned();
+        migrate_config(&mut raw).with_context(|| format!("config {path}"))?;
+        // без миграции парсим сам файл — ошибки типов остаются с номером строки
+        let mut c: Self = if raw.get("version") == before.as_ref() {
+            parse_config(path, data)?
+        } else {
+            serde_json::from_value(raw).with_context(|| format!("parse migrated config {path}"))?
+        };
         c.expand_env_in_rpcs();
         c.normalize_addresses(); // адреса -> lower, пары/треугольники/маршруты -> UPPER
         c.normalize_token_keys(); // КЛЮЧИ tokens -> UPPERCASE
         c.validate()?;
+        c.index_networks();
         Ok(c)
     }
 
@@ -74,12 +231,18 @@ impl Config {
     /// Нормализация адресов (нижний регистр) и символов пар/треугольников/маршрутов (UPPERCASE)
     fn normalize_addresses(&mut self) {
         for net in &mut self.networks {
+            if let Some(safe) = net.safe_address.as_mut() {
+                *safe = safe.trim().to_lowercase();
+            }
             for t in net.tokens.values_mut() {
                 t.address = t.address.trim().to_lowercase();
+                if let Some(f) = t.price_feed.as_mut() {
+                    *f = f.trim().to_lowercase();
+                }
             }
             if let Some(routes) = net.routes_cross_dex.as_mut() {
                 for r in routes.iter_mut() {
-                    for s in r.pair.iter_mut() {
+                    for s in r.pair.iter_mut().chain(r.via.iter_mut()) {
                         *s = s.trim().to_uppercase();
                     }
                 }
@@ -124,6 +287,24 @@ impl Config {
         for s in &mut self.global.risk.stables {
             *s = s.trim().to_uppercase();
         }
+        // стейблы без явной цены считаем по 1 USD
+        for net in &mut self.networks {
+            for (sym, t) in net.tokens.iter_mut() {
+                if t.usd_price.is_none() && self.global.risk.stables.contains(sym) {
+                    t.usd_price = Some(1.0);
+                }
+            }
+        }
+    }
+
+    /// Пересобирает индекс chain_id → сеть (после ручной правки `networks`)
+    pub fn index_networks(&mut self) {
+        self.chain_index = self
+            .networks
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.chain_id, i))
+            .collect();
     }
 
     /// Базовая валидация конфигурации
@@ -132,6 +313,26 @@ impl Config {
             return Err(anyhow!("config.networks is empty"));
         }
 
+        // сети: уникальный chain_id; id/name (без учёта регистра) не должны указывать
+        // на разные сети — иначе `network(id_or_name)` неоднозначен
+        let mut chain_ids = HashSet::new();
+        let mut keys: HashMap<String, usize> = HashMap::new();
+        for (i, n) in self.networks.iter().enumerate() {
+            if !chain_ids.insert(n.chain_id) {
+                return Err(anyhow!("duplicate chain_id in config: {}", n.chain_id));
+            }
+            for key in [&n.id, &n.name] {
+                if let Some(&j) = keys.get(&key.to_lowercase()) && j != i {
+                    return Err(anyhow!(
+                        "network '{key}' is ambiguous: used by chain_id {} and {}",
+                        self.networks[j].chain_id,
+                        n.chain_id
+                    ));
+                }
+                keys.insert(key.to_lowercase(), i);
+            }
+        }
+
         // стратегии: уникальные имена + лимиты
         let mut names = HashSet::new();
         for s in &self.strategies {
@@ -147,6 +348,8 @@ impl Config {
             }
         }
 
+        crate::utils_gas::GasPriceMode::parse(&self.global.quote.gas_price_mode)?;
+
         // сети
         for n in &self.networks {
             if n.id.trim().is_empty() || n.name.trim().is_empty() {
@@ -161,6 +364,23 @@ impl Config {
                     n.name
                 ));
             }
+            if let Some(safe) = n.safe_address.as_deref() && !is_hex_addr(safe) {
+                return Err(anyhow!(
+                    "network '{}' has invalid safe_address '{}'",
+                    n.name,
+                    safe
+                ));
+            }
+            if !(n.daily_notional_cap_usd.is_finite() && n.daily_notional_cap_usd >= 0.0) {
+                return Err(anyhow!(
+                    "network '{}' daily_notional_cap_usd must be >= 0 (got {})",
+                    n.name,
+                    n.daily_notional_cap_usd
+                ));
+            }
+            if let Some((key, _)) = n.gas_hints.iter().find(|(_, g)| **g == 0) {
+                return Err(anyhow!("network '{}': gas_hints.{} must be > 0", n.name, key));
+            }
             // токены
             for (sym, t) in &n.tokens {
                 if !is_hex_addr(&t.address) {
@@ -171,6 +391,14 @@ impl Config {
                         t.address
                     ));
                 }
+                if let Some(feed) = t.price_feed.as_deref() && !is_hex_addr(feed) {
+                    return Err(anyhow!(
+                        "network '{}': token {} has invalid price_feed '{}'",
+                        n.name,
+                        sym,
+                        feed
+                    ));
+                }
                 if t.decimals > 30 {
                     return Err(anyhow!(
                         "network '{}': token {} decimals looks wrong: {}",
@@ -203,6 +431,18 @@ impl Config {
                     }
                 }
             }
+            // промежуточные токены маршрутов
+            for r in n.routes_cross_dex.iter().flatten() {
+                if let Some(sym) = r.via.iter().find(|s| !n.tokens.contains_key(*s)) {
+                    return Err(anyhow!(
+                        "network '{}': route {}-{} via unknown token '{}'",
+                        n.name,
+                        r.pair[0],
+                        r.pair[1],
+                        sym
+                    ));
+                }
+            }
             // DEX конфиги
             for d in &n.dexes {
                 if d.dex_type.trim().is_empty() || d.name.trim().is_empty() {
@@ -211,25 +451,51 @@ impl Config {
                         n.name
                     ));
                 }
+                // адреса контрактов DEX: опечатка иначе всплывёт только посреди скана
+                let addr_fields = [
+                    ("factory", &d.factory),
+                    ("router", &d.router),
+                    ("smartRouter", &d.smart_router),
+                    ("swapRouter02", &d.swap_router02),
+                    ("universalRouter", &d.universal_router),
+                    ("quoter", &d.quoter),
+                    ("poolManager", &d.pool_manager),
+                    ("hooks", &d.hooks),
+                ];
+                for (field, value) in addr_fields {
+                    if let Some(addr) = value && !is_hex_addr(addr) {
+                        return Err(anyhow!(
+                            "network '{}': dex '{}' field {} has invalid address '{}'",
+                            n.name,
+                            d.name,
+                            field,
+                            addr
+                        ));
+                    }
+                }
 
-                // Разрешаем распространённые тировки для v3/альгебры:
+                // у Algebra комиссия динамическая, tier'ов нет
+                if d.dex_type.eq_ignore_ascii_case("v3_algebra") && d.fee_tiers_bps.is_some() {
+                    tracing::warn!(
+                        "network '{}': dex '{}' is v3_algebra (dynamic fee), feeTiers_bps ignored",
+                        n.name,
+                        d.name
+                    );
+                }
+                // Разрешаем распространённые тировки для v3:
                 // - Uniswap-подобные: 100, 500, 3000, 10000
-                // - Pancake/Algebra и др.: добавляем 250 и 1000
-                if d.dex_type.eq_ignore_ascii_case("v3")
-                    || d.dex_type.eq_ignore_ascii_case("v3_algebra")
-                {
-                    if let Some(fees) = &d.fee_tiers_bps {
-                        const KNOWN_V3_FEES: [u32; 6] = [100, 250, 500, 1000, 3000, 10_000];
-                        for f in fees {
-                            if !KNOWN_V3_FEES.contains(f) {
-                                tracing::warn!(
-                                    "network '{}': dex '{}' has uncommon fee tier: {} bps",
-                                    n.name,
-                                    d.name,
-                                    f
-                                );
-                                // ВАЖНО: не валим конфиг на «нестандартных» тирах
-                            }
+                // - Pancake и др.: добавляем 250 и 1000
+                if d.dex_type.eq_ignore_ascii_case("v3") && let Some(fees) = &d.fee_tiers_bps {
+                    const KNOWN_V3_FEES: [u32; 6] = [100, 250, 500, 1000, 3000, 10_000];
+                    for f in fees {
+                        if !KNOWN_V3_FEES.contains(f) {
+                            tracing::warn!(
+                                "network '{}': dex '{}' has uncommon fee tier: {} bps",
+                                n.name,
+                                d.name,
+                                f
+                            );
+                            // ВАЖНО: не валим конфиг на «нестандартных» тирах
                         }
                     }
                 }
@@ -242,6 +508,20 @@ impl Config {
                 "global.quote.slippage_bps_default too large (>5000 bps)"
             ));
         }
+        if let Some(usd) = self.global.quote.notional_usd && !(usd.is_finite() && usd > 0.0) {
+            return Err(anyhow!("global.quote.notional_usd must be > 0 (got {usd})"));
+        }
+        let ap = &self.global.execution.adaptive_poll;
+        if ap.enabled && (ap.min_ms == 0 || ap.min_ms > ap.max_ms) {
+            return Err(anyhow!(
+                "global.execution.adaptive_poll: need 0 < min_ms <= max_ms (got {}..{})",
+                ap.min_ms,
+                ap.max_ms
+            ));
+        }
+        if self.global.execution.simulate_gas_limit == Some(0) {
+            return Err(anyhow!("global.execution.simulate_gas_limit must be > 0"));
+        }
         if !self.global.risk.permit2.is_empty() && !is_hex_addr(&self.global.risk.permit2) {
             return Err(anyhow!("global.risk.permit2 must be 0x-address or empty"));
         }
@@ -261,8 +541,40 @@ impl Config {
         Ok(())
     }
 
+    /// Проверка, что новый конфиг можно применить «на лету» (SIGHUP):
+    /// набор сетей (id + chain_id) менять нельзя — это требует рестарта
+    pub fn ensure_reload_compatible(&self, next: &Config) -> Result<()> {
+        let ids = |c: &Config| -> Vec<(String, u64)> {
+            let mut v: Vec<(String, u64)> = c
+                .networks
+                .iter()
+                .map(|n| (n.id.trim().to_lowercase(), n.chain_id))
+                .collect();
+            v.sort();
+            v
+        };
+        let (cur, new) = (ids(self), ids(next));
+        if cur != new {
+            return Err(anyhow!(
+                "networks changed ({:?} -> {:?}); restart required",
+                cur,
+                new
+            ));
+        }
+        Ok(())
+    }
+
     // ===== Утилиты =====
 
+    /// Сеть по chain_id за O(1); индекс устарел (правка `networks` без
+    /// `index_networks`) — линейный поиск
+    pub fn network_by_chain_id(&self, chain_id: u64) -> Option<&Network> {
+        match self.chain_index.get(&chain_id).and_then(|&i| self.networks.get(i)) {
+            Some(n) if n.chain_id == chain_id => Some(n),
+            _ => self.networks.iter().find(|n| n.chain_id == chain_id),
+        }
+    }
+
     pub fn network(&self, id_or_name: &str) -> Option<&Network> {
         self.networks.iter().find(|n| {
             n.id.eq_ignore_ascii_case(id_or_name) || n.name.eq_ignore_ascii_case(id_or_name)
@@ -288,6 +600,9 @@ pub struct Global {
     pub mev: Mev,
     pub flashloan: Flashloan,
     pub execution: Execution,
+    /// Сверка decimals/symbol токенов с on-chain на старте (расхождение decimals — ошибка)
+    #[serde(default)]
+    pub verify_tokens: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -302,6 +617,38 @@ pub struct Quote {
     pub gas_price_mode: String,
     #[serde(default = "default_deadline")]
     pub deadline_seconds: u32,
+    /// Таймаут котировки одной ноги маршрута (0 = без таймаута)
+    #[serde(default = "default_leg_quote_timeout_ms")]
+    pub leg_quote_timeout_ms: u64,
+    /// TTL кэша цен из Chainlink-фидов
+    #[serde(default = "default_price_cache_ttl_ms")]
+    pub price_cache_ttl_ms: u64,
+    /// Максимальный возраст раунда Chainlink (`updatedAt`), с: старше — фид считается
+    /// замёрзшим и берётся статическая подсказка. 0 — без проверки
+    #[serde(default = "default_price_feed_max_age_secs")]
+    pub price_feed_max_age_secs: u64,
+    /// Размер входа пары в USD (переводится в токен по его цене); None или нет цены —
+    /// одна целая единица токена
+    #[serde(default)]
+    pub notional_usd: Option<f64>,
+    /// Все ноги скана читают состояние на одном блоке (номер берётся в начале скана)
+    #[serde(default)]
+    pub pin_block: bool,
+    /// Предотсев кросс-dex пар: A→B на обоих DEX параллельно; точная котировка — только
+    /// при спреде не ниже порога (bps). None — без предотсева
+    #[serde(default)]
+    pub prescreen_min_spread_bps: Option<f64>,
+    /// Размер предотсева в USD; None или нет цены — одна целая единица токена
+    #[serde(default)]
+    pub prescreen_notional_usd: Option<f64>,
+    /// Проскальзывание каждой промежуточной ноги к её котировке, bps: minOut ноги —
+    /// `quoted * (1 - bps)`, котировка — пересчитанная на фактический вход (pre-send хук
+    /// мог изменить размер). None — тот же запас, что у финального min_out (пропорцией)
+    #[serde(default)]
+    pub leg_slippage_bps: Option<u32>,
+}
+fn default_allowance_cache_file() -> String {
+    "logs/allowance-cache.json".into()
 }
 fn default_slippage_bps() -> u32 {
     50
@@ -312,6 +659,15 @@ fn default_gas_mode() -> String {
 fn default_deadline() -> u32 {
     120
 }
+fn default_price_cache_ttl_ms() -> u64 {
+    30_000
+}
+fn default_price_feed_max_age_secs() -> u64 {
+    3_600
+}
+fn default_leg_quote_timeout_ms() -> u64 {
+    2_000
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Risk {
@@ -321,8 +677,13 @@ pub struct Risk {
     pub max_price_impact_bps: u32,
     #[serde(default)]
     pub stables: Vec<String>,
+    /// Адреса или символы токенов; маршрут с токеном из списка пропускается
     #[serde(default)]
     pub blacklist_tokens: Vec<String>,
+    /// Адреса или символы токенов; непустой — маршрут с любым токеном вне списка
+    /// пропускается (`blacklist_tokens` внутри списка всё равно действует)
+    #[serde(default)]
+    pub allowlist_tokens: Vec<String>,
     #[serde(default)]
     pub fee_on_transfer_block: bool,
     #[serde(default)]
@@ -365,10 +726,170 @@ pub struct Execution {
     pub max_concurrent_txs: u32,
     #[serde(default = "default_revert_retry")]
     pub revert_retry: u32,
+    /// Лимит газа eth_call `simulate()`; без него — по числу ног (`exec::simulate_gas_limit`)
+    #[serde(default)]
+    pub simulate_gas_limit: Option<u64>,
     #[serde(default)]
     pub approve_spend_on_start: bool,
+    /// Вход маршрута экзекутор забирает по подписи Permit2 (SignatureTransfer) из calldata;
+    /// на старте токены одобряются только самому Permit2
+    #[serde(default)]
+    pub permit2_signatures: bool,
+    /// Файл кэша allowance (approve на старте); пусто — без кэша
+    #[serde(default = "default_allowance_cache_file")]
+    pub allowance_cache_file: String,
     #[serde(default)]
     pub auto_scale_notional: bool,
+    /// Сколько маршрутов сети сканировать за один poll (0 = все);
+    /// остальные покрываются следующими poll'ами по кругу
+    #[serde(default)]
+    pub max_routes_per_poll: u32,
+    /// Сколько сетей сканируется одновременно
+    #[serde(default = "default_max_parallel_networks")]
+    pub max_parallel_networks: u32,
+    /// При остановке: сколько ждать receipts уже отправленных транзакций
+    #[serde(default = "default_shutdown_drain_timeout_ms")]
+    pub shutdown_drain_timeout_ms: u64,
+    #[serde(default)]
+    pub adaptive_poll: AdaptivePollCfg,
+    /// Сеть без исполнителя (нет `EXECUTOR_<id>` или не поднялся ключ) — ошибка старта,
+    /// а не scan-only
+    #[serde(default)]
+    pub require_executors: bool,
+    /// Прогрев кэша адресов пулов всех cross-dex маршрутов до первого скана
+    #[serde(default)]
+    pub warmup_pools: bool,
+    /// Возраст котировки в блоках перед исполнением (от закреплённого блока
+    /// `quote.pin_block` до текущего), выше которого она устарела; 0 — не проверять
+    #[serde(default)]
+    pub max_quote_age_blocks: u64,
+    /// Устаревшая котировка: true — перекотировать на текущем блоке, false — пропустить
+    #[serde(default = "default_requote_stale")]
+    pub requote_stale: bool,
+    /// Симуляция исполнением `execute()` на форке вместо `simulate()` контракта
+    #[serde(default)]
+    pub fork_sim: ForkSimCfg,
+    /// ABI и методы контракта-экзекутора: другая версия контракта — без перекомпиляции
+    #[serde(default)]
+    pub executor_abi: ExecutorAbiCfg,
+    /// Внеочередной скан пары по крупному свопу из mempool (сборка с feature `mempool`)
+    #[serde(default)]
+    pub mempool: MempoolCfg,
+}
+
+/// Триггер по mempool: pending-tx через WS (`eth_subscribe newPendingTransactions`);
+/// крупный своп через роутер DEX маршрута — сразу перекотировать задетые маршруты
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MempoolCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Минимальный объём свопа в USD (по ценовым подсказкам входного токена)
+    #[serde(default = "default_mempool_min_swap_usd")]
+    pub min_swap_usd: f64,
+    /// Не чаще одного внеочередного скана маршрута за этот интервал
+    #[serde(default = "default_mempool_cooldown_ms")]
+    pub route_cooldown_ms: u64,
+}
+fn default_mempool_min_swap_usd() -> f64 {
+    25_000.0
+}
+fn default_mempool_cooldown_ms() -> u64 {
+    1_000
+}
+impl Default for MempoolCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_swap_usd: default_mempool_min_swap_usd(),
+            route_cooldown_ms: default_mempool_cooldown_ms(),
+        }
+    }
+}
+
+/// Методы экзекутора (`exec::ExecutorMethods`); сверяются с ABI при старте
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutorAbiCfg {
+    /// JSON ABI контракта; пусто — встроенный `abis/Executor.json`
+    #[serde(default)]
+    pub abi_file: String,
+    /// `<execute_method>(bytes route, uint256 minProfit)`
+    #[serde(default = "default_execute_method")]
+    pub execute_method: String,
+    /// Тип результата execute: int256 | uint256
+    #[serde(default = "default_execute_returns")]
+    pub execute_returns: String,
+    /// `<simulate_method>(bytes route)`
+    #[serde(default = "default_simulate_method")]
+    pub simulate_method: String,
+    /// Тип результата simulate: int256 | uint256
+    #[serde(default = "default_simulate_returns")]
+    pub simulate_returns: String,
+}
+fn default_execute_method() -> String {
+    "execute".into()
+}
+fn default_execute_returns() -> String {
+    "int256".into()
+}
+fn default_simulate_method() -> String {
+    "simulate".into()
+}
+fn default_simulate_returns() -> String {
+    "uint256".into()
+}
+impl Default for ExecutorAbiCfg {
+    fn default() -> Self {
+        Self {
+            abi_file: String::new(),
+            execute_method: default_execute_method(),
+            execute_returns: default_execute_returns(),
+            simulate_method: default_simulate_method(),
+            simulate_returns: default_simulate_returns(),
+        }
+    }
+}
+
+/// Симуляция на форке (`forksim::ForkSimulator`): eth_call `execute()` со stateOverride
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ForkSimCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    /// RPC форка (anvil `--fork-url`); пусто — RPC сети, stateOverride в eth_call
+    #[serde(default)]
+    pub rpc_url: String,
+    /// Файл с runtime-байткодом (hex) вместо on-chain кода экзекутора; пусто — как есть
+    #[serde(default)]
+    pub executor_code_file: String,
+}
+
+/// Адаптивный интервал скана по сетям: возможности сокращают его, пустые сканы
+/// удлиняют, в пределах [min_ms, max_ms]. Выключен — общий `poll_interval_ms`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdaptivePollCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_adaptive_min_ms")]
+    pub min_ms: u64,
+    #[serde(default = "default_adaptive_max_ms")]
+    pub max_ms: u64,
+}
+fn default_adaptive_min_ms() -> u64 {
+    200
+}
+fn default_adaptive_max_ms() -> u64 {
+    10_000
+}
+impl Default for AdaptivePollCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_ms: default_adaptive_min_ms(),
+            max_ms: default_adaptive_max_ms(),
+        }
+    }
+}
+fn default_requote_stale() -> bool {
+    true
 }
 fn default_poll_ms() -> u32 {
     1500
@@ -382,6 +903,132 @@ fn default_max_conc() -> u32 {
 fn default_revert_retry() -> u32 {
     1
 }
+fn default_max_parallel_networks() -> u32 {
+    4
+}
+fn default_shutdown_drain_timeout_ms() -> u64 {
+    60_000
+}
+
+// ================== RPC ==================
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcCfg {
+    /// Период замера латентности эндпоинтов (0 = health-чекер выключен)
+    #[serde(default = "default_health_interval_ms")]
+    pub health_check_interval_ms: u64,
+    #[serde(default = "default_health_timeout_ms")]
+    pub health_timeout_ms: u64,
+    /// Карантин упавшего эндпоинта: base * 2^(fails-1), не больше max
+    #[serde(default = "default_quarantine_base_ms")]
+    pub quarantine_base_ms: u64,
+    #[serde(default = "default_quarantine_max_ms")]
+    pub quarantine_max_ms: u64,
+    /// Ретраи with_failover: задержка base * multiplier^attempt (не больше max) ± jitter
+    #[serde(default = "default_retry_base_ms")]
+    pub retry_base_delay_ms: u64,
+    #[serde(default = "default_retry_max_ms")]
+    pub retry_max_delay_ms: u64,
+    #[serde(default = "default_retry_multiplier")]
+    pub retry_multiplier: f64,
+    /// Доля случайного разброса задержки (0.2 = ±20%)
+    #[serde(default = "default_retry_jitter")]
+    pub retry_jitter: f64,
+    /// Сколько раз пройти по всем эндпоинтам, прежде чем сдаться
+    #[serde(default = "default_retry_cycles")]
+    pub retry_cycles: u32,
+    /// Общий бюджет одновременных RPC-запросов на сеть (котировки + исполнение)
+    #[serde(default = "default_budget_total")]
+    pub budget_total: usize,
+    /// Сколько из бюджета зарезервировано под исполнение (котировки его не трогают)
+    #[serde(default = "default_budget_exec_reserved")]
+    pub budget_exec_reserved: usize,
+    /// Сколько помнить «пула нет» (нулевой адрес от фабрики); найденные пулы кэшируются навсегда
+    #[serde(default = "default_pool_cache_negative_ttl_ms")]
+    pub pool_cache_negative_ttl_ms: u64,
+    /// Лимит запросов в секунду на один эндпоинт (token bucket; 0 = без лимита)
+    #[serde(default)]
+    pub rate_limit_rps: f64,
+    /// Ёмкость bucket'а (всплеск); 0 = равна rate_limit_rps
+    #[serde(default)]
+    pub rate_limit_burst: u32,
+    /// Пауза эндпоинта после HTTP 429 от провайдера
+    #[serde(default = "default_rate_limited_cooldown_ms")]
+    pub rate_limited_cooldown_ms: u64,
+    /// Насколько блоков эндпоинт может отставать от лучшей виденной высоты сети,
+    /// чтобы failover на него переключился (0 = не проверять)
+    #[serde(default = "default_max_block_lag")]
+    pub max_block_lag: u64,
+    /// Простые чтения начала скана (eth_blockNumber, eth_gasPrice, последний блок) —
+    /// одним batch-запросом JSON-RPC; цена газа снимка общая для всех маршрутов скана
+    #[serde(default)]
+    pub batch_requests: bool,
+}
+fn default_health_interval_ms() -> u64 {
+    15_000
+}
+fn default_health_timeout_ms() -> u64 {
+    3_000
+}
+fn default_quarantine_base_ms() -> u64 {
+    5_000
+}
+fn default_quarantine_max_ms() -> u64 {
+    300_000
+}
+fn default_retry_base_ms() -> u64 {
+    100
+}
+fn default_retry_max_ms() -> u64 {
+    2_000
+}
+fn default_retry_multiplier() -> f64 {
+    2.0
+}
+fn default_retry_jitter() -> f64 {
+    0.2
+}
+fn default_retry_cycles() -> u32 {
+    2
+}
+fn default_budget_total() -> usize {
+    16
+}
+fn default_budget_exec_reserved() -> usize {
+    4
+}
+fn default_pool_cache_negative_ttl_ms() -> u64 {
+    300_000
+}
+fn default_rate_limited_cooldown_ms() -> u64 {
+    10_000
+}
+fn default_max_block_lag() -> u64 {
+    3
+}
+impl Default for RpcCfg {
+    fn default() -> Self {
+        Self {
+            health_check_interval_ms: default_health_interval_ms(),
+            health_timeout_ms: default_health_timeout_ms(),
+            quarantine_base_ms: default_quarantine_base_ms(),
+            quarantine_max_ms: default_quarantine_max_ms(),
+            retry_base_delay_ms: default_retry_base_ms(),
+            retry_max_delay_ms: default_retry_max_ms(),
+            retry_multiplier: default_retry_multiplier(),
+            retry_jitter: default_retry_jitter(),
+            retry_cycles: default_retry_cycles(),
+            budget_total: default_budget_total(),
+            budget_exec_reserved: default_budget_exec_reserved(),
+            pool_cache_negative_ttl_ms: default_pool_cache_negative_ttl_ms(),
+            rate_limit_rps: 0.0,
+            rate_limit_burst: 0,
+            rate_limited_cooldown_ms: default_rate_limited_cooldown_ms(),
+            max_block_lag: default_max_block_lag(),
+            batch_requests: false,
+        }
+    }
+}
 
 // ================== Сеть/DEX/Маршруты ==================
 
@@ -396,6 +1043,9 @@ pub struct Network {
     pub rpc: Vec<String>,
     #[serde(default)]
     pub native_usd_hint: Option<f64>,
+    /// Токен, в котором копим прибыль сессии (например, USDC)
+    #[serde(default)]
+    pub base_token: Option<String>,
     #[serde(default)]
     pub explorer: Option<String>,
     #[serde(default)]
@@ -412,6 +1062,126 @@ pub struct Network {
     pub strategy_overrides: Option<StrategyOverrides>,
     #[serde(default)]
     pub permit2: String,
+    #[serde(default)]
+    pub gas: NetworkGas,
+    /// Минимальная чистая прибыль маршрута в native (после газа); 0 — достаточно окупить газ
+    #[serde(default)]
+    pub min_net_profit_native: f64,
+    /// Лимит отправленного объёма сети в USD за скользящие 24 ч; 0 — без лимита
+    #[serde(default)]
+    pub daily_notional_cap_usd: f64,
+    /// Цена газа сети выше потолка (gwei) — исполнение на паузе, скан продолжается;
+    /// None — без потолка
+    #[serde(default)]
+    pub max_gas_price_gwei: Option<f64>,
+    /// Газ одной ноги по имени DEX или его типу (`v2`, `v3`, `solidly_v2`, ...), имя важнее типа;
+    /// без записи — встроенная оценка типа
+    #[serde(default)]
+    pub gas_hints: HashMap<String, u64>,
+    /// L2 с платой за данные L1 (OP-stack, Arbitrum): к стоимости газа маршрута
+    /// добавляется L1-плата из GasPriceOracle / NodeInterface
+    #[serde(default)]
+    pub l2_data_fees: bool,
+    /// Gnosis Safe, через который исполняется `execute(...)` экзекутора (None — напрямую с EOA)
+    #[serde(default)]
+    pub safe_address: Option<String>,
+    /// Отсев несбалансированных и пыльных v2-пулов до расчёта выхода
+    #[serde(default)]
+    pub v2_reserve_guard: V2ReserveGuard,
+}
+
+impl Network {
+    /// Газ ноги на DEX: `gas_hints` по имени, затем по типу, иначе `default`
+    pub fn leg_gas(&self, dex: &DexConfig, default: u64) -> u64 {
+        let hint = |key: &str| {
+            self.gas_hints
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, g)| *g)
+        };
+        hint(&dex.name).or_else(|| hint(&dex.dex_type)).unwrap_or(default)
+    }
+
+    /// Native-токен сети или его wrapped-версия (ETH/WETH и т.п.)
+    pub fn is_native(&self, sym: &str) -> bool {
+        let s = sym.to_uppercase();
+        let native = self.native_symbol.to_uppercase();
+        s == native || s == format!("W{}", native)
+    }
+
+    /// Сам native-символ (ETH, BNB), не описанный в `tokens`: такой конец маршрута
+    /// котируется через wrapped-токен сети
+    pub fn is_unwrapped_native(&self, sym: &str) -> bool {
+        sym.eq_ignore_ascii_case(&self.native_symbol)
+            && !self.tokens.contains_key(&sym.to_uppercase())
+    }
+
+    /// Символ для котировки: native → его wrapped-токен из `tokens` (ETH → WETH), если описан
+    pub fn quote_symbol<'a>(&'a self, sym: &'a str) -> &'a str {
+        if !self.is_unwrapped_native(sym) {
+            return sym;
+        }
+        let wrapped = format!("W{}", self.native_symbol.to_uppercase());
+        self.tokens
+            .get_key_value(&wrapped)
+            .map_or(sym, |(k, _)| k.as_str())
+    }
+
+    /// USD-цена токена: явная `usd_price` токена, для native/W-native — `native_usd_hint`
+    pub fn token_usd_price(&self, sym: &str) -> Option<f64> {
+        if let Some(px) = self.tokens.get(&sym.to_uppercase()).and_then(|t| t.usd_price) {
+            return Some(px);
+        }
+        if self.is_native(sym) {
+            self.native_usd_hint
+        } else {
+            None
+        }
+    }
+}
+
+/// Границы резервов v2-пула в USD (по ценам токенов): пул, где одна сторона дороже другой
+/// больше чем в `max_ratio` раз или меньшая сторона дешевле `min_reserve_usd`, не котируется.
+/// 0 — проверка выключена; без USD-цены токена проверка пропускается.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct V2ReserveGuard {
+    #[serde(default)]
+    pub max_ratio: f64,
+    #[serde(default)]
+    pub min_reserve_usd: f64,
+}
+
+impl V2ReserveGuard {
+    pub fn enabled(&self) -> bool {
+        self.max_ratio > 0.0 || self.min_reserve_usd > 0.0
+    }
+}
+
+/// Газ сети: цена = base_fee * base_fee_multiplier + tip, но не выше max_fee_gwei
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NetworkGas {
+    #[serde(default = "default_tip_gwei")]
+    pub tip_gwei: f64,
+    /// Потолок цены газа (None — без потолка)
+    #[serde(default)]
+    pub max_fee_gwei: Option<f64>,
+    #[serde(default = "default_base_fee_multiplier")]
+    pub base_fee_multiplier: f64,
+}
+fn default_tip_gwei() -> f64 {
+    2.0
+}
+fn default_base_fee_multiplier() -> f64 {
+    1.0
+}
+impl Default for NetworkGas {
+    fn default() -> Self {
+        Self {
+            tip_gwei: default_tip_gwei(),
+            max_fee_gwei: None,
+            base_fee_multiplier: default_base_fee_multiplier(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -420,6 +1190,19 @@ pub struct Token {
     pub decimals: u8,
     #[serde(default)]
     pub optional: bool,
+    /// Доп. газ на каждый transfer (fee-on-transfer, прокси-токены и т.п.)
+    #[serde(default)]
+    pub extra_gas: Option<u64>,
+    /// USD-цена токена для оценки PnL; стейблам из `risk.stables` без цены ставится 1.0
+    #[serde(default)]
+    pub usd_price: Option<f64>,
+    /// Chainlink-фид USD-цены (latestRoundData); приоритетнее `usd_price`
+    #[serde(default)]
+    pub price_feed: Option<String>,
+    /// Слот mapping'а `balanceOf` в хранилище токена: вход маршрута на балансе экзекутора
+    /// подменяется в симуляции на форке (`execution.fork_sim`)
+    #[serde(default)]
+    pub balance_slot: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -445,12 +1228,34 @@ pub struct DexConfig {
     pub fee_tiers_bps: Option<Vec<u32>>,
     #[serde(default, rename = "stablePools", alias = "stable_pools")]
     pub stable_pools: Option<bool>,
+    /// Quoter DEX (обязателен для `v3_algebra` и `v4`: Algebra Quoter / V4Quoter)
+    #[serde(default)]
+    pub quoter: Option<String>,
+    /// Singleton PoolManager Uniswap V4 (`v4`)
+    #[serde(default, rename = "poolManager", alias = "pool_manager")]
+    pub pool_manager: Option<String>,
+    /// Hooks-контракт пулов V4 (по умолчанию — без хуков, нулевой адрес)
+    #[serde(default)]
+    pub hooks: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RouteDex {
     pub pair: [String; 2],
     pub dexes: Vec<String>,
+    /// Промежуточные токены пути (только v2): A→via…→B на dexes[0], обратно на dexes[1]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<String>,
+}
+
+impl RouteDex {
+    /// Токены пути по порядку: pair[0], via…, pair[1]
+    pub fn path(&self) -> Vec<String> {
+        let mut path = vec![self.pair[0].clone()];
+        path.extend(self.via.iter().cloned());
+        path.push(self.pair[1].clone());
+        path
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -459,6 +1264,9 @@ pub struct StrategyOverrides {
     pub min_profit_bps: Option<u32>,
     #[serde(default)]
     pub slippage_bps: Option<u32>,
+    /// Минимальная чистая PnL маршрута сети в USD (после газа)
+    #[serde(default)]
+    pub min_profit_usd: Option<f64>,
 }
 
 // ================== Стратегии/Маршрутизация ==================
@@ -472,6 +1280,9 @@ pub struct Strategy {
     pub gas_limit: u64,
     #[serde(default)]
     pub max_notional_usd: Option<f64>,
+    /// Минимальная чистая PnL маршрута в USD (после газа) — вместе с `min_profit_bps`
+    #[serde(default)]
+    pub min_profit_usd: Option<f64>,
     #[serde(default)]
     pub use_flash: Option<bool>,
     #[serde(default)]
@@ -519,17 +1330,48 @@ pub struct RouteTemplate {
 pub struct Safety {
     #[serde(default)]
     pub allow_revert_on_no_profit: bool,
+    /// Минимальная прибыль eth_call-симуляции перед отправкой, bps от amount_in
+    /// (0 — достаточно, чтобы simulate() не откатился)
+    #[serde(default)]
+    pub min_simulated_profit_bps: u32,
     #[serde(default)]
     pub halt_on_large_slippage_bps: u32,
+    /// Порог индекса волатильности (коэффициент вариации цены native); 0 — выключено
     #[serde(default)]
     pub halt_on_volatility_index: f64,
+    /// Сколько последних poll-ов входит в индекс волатильности
+    #[serde(default = "default_volatility_window")]
+    pub volatility_window: usize,
+    /// Dead-man's switch: `/metrics` не скрейпили дольше N секунд — исполнение на паузе
+    /// (скан идёт), пока скрейп не возобновится; 0 — выключено
+    #[serde(default)]
+    pub halt_on_scrape_gap_secs: u64,
     pub circuit_breaker: CircuitBreaker,
 }
+fn default_volatility_window() -> usize {
+    20
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CircuitBreaker {
     pub max_losses_in_row: u32,
     pub cooldown_sec: u64,
+    /// Убытков маршрута подряд (revert tx или simulate, ошибка отправки) до его паузы;
+    /// остальные маршруты сети сканируются дальше. 0 — выключено
+    #[serde(default)]
+    pub route_max_losses_in_row: u32,
+    /// Пауза маршрута, сек (0 — как `cooldown_sec`)
+    #[serde(default)]
+    pub route_cooldown_sec: u64,
+}
+
+impl CircuitBreaker {
+    pub fn route_cooldown(&self) -> Duration {
+        match self.route_cooldown_sec {
+            0 => Duration::from_secs(self.cooldown_sec),
+            s => Duration::from_secs(s),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -537,6 +1379,14 @@ pub struct Telemetry {
     pub prometheus: PrometheusCfg,
     pub logs: LogsCfg,
     pub alerts: AlertsCfg,
+    #[serde(default)]
+    pub pnl_report: PnlReportCfg,
+    #[serde(default)]
+    pub dry_run_report: DryRunReportCfg,
+    #[serde(default)]
+    pub paper_trading: PaperTradingCfg,
+    #[serde(default)]
+    pub console: ConsoleCfg,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -545,10 +1395,16 @@ pub struct PrometheusCfg {
     pub enabled: bool,
     #[serde(default = "default_prom_port")]
     pub port: u16,
+    /// /healthz отдаёт 503, если успешного скана не было дольше этого окна (сек; 0 — всегда ok)
+    #[serde(default = "default_health_stale_secs")]
+    pub health_stale_secs: u64,
 }
 fn default_prom_port() -> u16 {
     9090
 }
+fn default_health_stale_secs() -> u64 {
+    60
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LogsCfg {
@@ -556,11 +1412,53 @@ pub struct LogsCfg {
     pub level: String,
     #[serde(default)]
     pub json: bool,
+    /// EIP-55 адреса в логах/candidate-файлах (внутри всё сравнивается без учёта регистра)
+    #[serde(default)]
+    pub checksum_addresses: bool,
+    #[serde(default)]
+    pub candidates: CandidateLogCfg,
 }
 fn default_log_level() -> String {
     "info".into()
 }
 
+/// JSONL-лог кандидатов `<dir>/candidates-<chain_id>.jsonl` с ротацией
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CandidateLogCfg {
+    #[serde(default = "default_candidates_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_candidates_dir")]
+    pub dir: String,
+    /// Ротация по размеру файла (0 — без лимита)
+    #[serde(default = "default_candidates_max_bytes")]
+    pub max_bytes: u64,
+    /// Ротация при смене суток (UTC)
+    #[serde(default = "default_rotate_daily")]
+    pub rotate_daily: bool,
+}
+fn default_candidates_enabled() -> bool {
+    true
+}
+fn default_rotate_daily() -> bool {
+    true
+}
+fn default_candidates_dir() -> String {
+    "logs".into()
+}
+fn default_candidates_max_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+impl Default for CandidateLogCfg {
+    fn default() -> Self {
+        Self {
+            enabled: default_candidates_enabled(),
+            dir: default_candidates_dir(),
+            max_bytes: default_candidates_max_bytes(),
+            rotate_daily: default_rotate_daily(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AlertsCfg {
     #[serde(default)]
@@ -573,6 +1471,126 @@ pub struct AlertsCfg {
     pub tg_token: Option<String>,
     #[serde(default)]
     pub tg_chat_id: Option<String>,
+    /// Попыток доставки алерта подряд до паузы (вебхук лежит — алерт ждёт в очереди)
+    #[serde(default = "default_alert_retry_attempts")]
+    pub retry_attempts: u32,
+    /// Пауза перед повтором; удваивается с каждой попыткой
+    #[serde(default = "default_alert_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Сколько недоставленных алертов держать в памяти; при переполнении вытесняется старейший
+    #[serde(default = "default_alert_queue_size")]
+    pub queue_size: usize,
+}
+fn default_alert_retry_attempts() -> u32 {
+    3
+}
+fn default_alert_retry_backoff_ms() -> u64 {
+    500
+}
+fn default_alert_queue_size() -> usize {
+    64
+}
+
+/// Периодическая (по умолчанию суточная) сводка PnL
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PnlReportCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_pnl_report_interval")]
+    pub interval_sec: u64,
+    #[serde(default = "default_pnl_report_dir")]
+    pub dir: String,
+    /// Дублировать сводку в alerts.webhook_url
+    #[serde(default)]
+    pub webhook: bool,
+}
+fn default_pnl_report_interval() -> u64 {
+    86_400
+}
+fn default_pnl_report_dir() -> String {
+    "logs".into()
+}
+impl Default for PnlReportCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_sec: default_pnl_report_interval(),
+            dir: default_pnl_report_dir(),
+            webhook: false,
+        }
+    }
+}
+
+/// Отчёт DRY_RUN/SAFE_LAUNCH: что было бы отправлено за скан (logs/dryrun-<chain>.json)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunReportCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Сколько лучших кандидатов попадает в отчёт
+    #[serde(default = "default_dry_run_top_n")]
+    pub top_n: usize,
+    #[serde(default = "default_pnl_report_dir")]
+    pub dir: String,
+}
+fn default_dry_run_top_n() -> usize {
+    10
+}
+impl Default for DryRunReportCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            top_n: default_dry_run_top_n(),
+            dir: default_pnl_report_dir(),
+        }
+    }
+}
+
+/// Журнал бумажных сделок: кандидаты, которые были бы отправлены (DRY_RUN/SAFE_LAUNCH
+/// или сеть без исполнителя)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaperTradingCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    /// `jsonl` (append-only файл) или `sqlite` (сборка с feature `sqlite`)
+    #[serde(default = "default_paper_backend")]
+    pub backend: String,
+    #[serde(default = "default_paper_path")]
+    pub path: String,
+}
+fn default_paper_backend() -> String {
+    "jsonl".into()
+}
+fn default_paper_path() -> String {
+    "logs/paper-ledger.jsonl".into()
+}
+impl Default for PaperTradingCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_paper_backend(),
+            path: default_paper_path(),
+        }
+    }
+}
+
+/// Слой tokio-console (сборка с feature `console`): gRPC-сервер для `tokio-console`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConsoleCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_console_bind")]
+    pub bind: String,
+}
+fn default_console_bind() -> String {
+    "127.0.0.1:6669".into()
+}
+impl Default for ConsoleCfg {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_console_bind(),
+        }
+    }
 }
 
 // ================== Helpers ==================
@@ -1,8 +1,10 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use std::sync::Arc;
 
+use crate::calldata::V4PoolKey;
+
 // ---------- Strongly-typed ABI ----------
 abigen!(
     IUniswapV2Pair,
@@ -31,6 +33,9 @@ abigen!(
         function token0() external view returns (address)
         function token1() external view returns (address)
         function fee() external view returns (uint24)
+        function tickSpacing() external view returns (int24)
+        function tickBitmap(int16 wordPosition) external view returns (uint256)
+        function ticks(int24 tick) external view returns (uint128 liquidityGross,int128 liquidityNet,uint256 feeGrowthOutside0X128,uint256 feeGrowthOutside1X128,int56 tickCumulativeOutside,uint160 secondsPerLiquidityOutsideX128,uint32 secondsOutside,bool initialized)
     ]"#
 );
 
@@ -39,6 +44,30 @@ abigen!(
     r#"[ function quoteExactInputSingle(address tokenIn,address tokenOut,uint24 fee,uint256 amountIn,uint160 sqrtPriceLimitX96) external returns (uint256 amountOut,uint160 sqrtPriceX96After,uint32 initializedTicksCrossed,uint256 gasEstimate) ]"#
 );
 
+abigen!(
+    IAlgebraFactory,
+    r#"[ function poolByPair(address tokenA, address tokenB) external view returns (address) ]"#
+);
+
+abigen!(
+    IAlgebraQuoter,
+    r#"[ function quoteExactInputSingle(address tokenIn,address tokenOut,uint256 amountIn,uint160 limitSqrtPrice) external returns (uint256 amountOut,uint16 fee) ]"#
+);
+
+abigen!(
+    IV4PoolManager,
+    r#"[ function extsload(bytes32 slot) external view returns (bytes32) ]"#
+);
+
+abigen!(
+    IV4Quoter,
+    r#"[
+        struct PoolKey { address currency0; address currency1; uint24 fee; int24 tickSpacing; address hooks; }
+        struct QuoteExactSingleParams { PoolKey poolKey; bool zeroForOne; uint128 exactAmount; bytes hookData; }
+        function quoteExactInputSingle(QuoteExactSingleParams params) external returns (uint256 amountOut, uint256 gasEstimate)
+    ]"#
+);
+
 abigen!(
     ISolidlyFactory,
     r#"[ function getPair(address tokenA,address tokenB,bool stable) external view returns (address) ]"#
@@ -55,9 +84,13 @@ pub struct V2Pair {
 }
 
 impl V2Pair {
-    pub async fn get_reserves<M: Middleware + 'static>(&self, mw: Arc<M>) -> Result<(U256, U256)> {
+    pub async fn get_reserves<M: Middleware + 'static>(
+        &self,
+        mw: Arc<M>,
+        block: BlockId,
+    ) -> Result<(U256, U256)> {
         let c = IUniswapV2Pair::new(self.pair, mw);
-        let (r0, r1, _ts) = c.get_reserves().call().await?;
+        let (r0, r1, _ts) = c.get_reserves().block(block).call().await?;
         Ok((U256::from(r0), U256::from(r1)))
     }
 }
@@ -74,6 +107,12 @@ pub fn amount_out_v2(amount_in: U256, reserve_in: U256, reserve_out: U256, fee_b
     numerator / denominator
 }
 
+/// Выход многохопового v2-пути: `amount_out_v2` по цепочке резервов (in, out) каждого хопа
+pub fn amount_out_v2_path(amount_in: U256, hops: &[(U256, U256)], fee_bps: u32) -> U256 {
+    hops.iter()
+        .fold(amount_in, |amt, &(res_in, res_out)| amount_out_v2(amt, res_in, res_out, fee_bps))
+}
+
 pub async fn v2_get_pair<M: Middleware + 'static>(
     mw: Arc<M>,
     factory: Address,
@@ -100,11 +139,99 @@ pub async fn v3_get_pool<M: Middleware + 'static>(
 pub async fn v3_slot0_liquidity<M: Middleware + 'static>(
     mw: Arc<M>,
     pool: Address,
+    block: BlockId,
 ) -> Result<(U256, i32, U256)> {
     let p = IUniswapV3Pool::new(pool, mw);
-    let (sqrt_price_x96, tick, ..) = p.slot_0().call().await?;
-    let liq = U256::from(p.liquidity().call().await?);
-    Ok((U256::from(sqrt_price_x96), tick, liq))
+    let (sqrt_price_x96, tick, ..) = p.slot_0().block(block).call().await?;
+    let liq = U256::from(p.liquidity().block(block).call().await?);
+    Ok((sqrt_price_x96, tick, liq))
+}
+
+/// Инициализированный тик на пути свапа и ликвидность, активная на участке
+/// от предыдущего сэмпла (или текущей цены) до этого тика.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickSample {
+    pub tick: i32,
+    pub liquidity: u128,
+}
+
+/// Сколько слов tickBitmap (256 тиков × tickSpacing) читаем за один сэмплинг
+const V3_MAX_BITMAP_WORDS: i32 = 8;
+const V3_MIN_TICK: i32 = -887_272;
+const V3_MAX_TICK: i32 = 887_272;
+
+/// Идёт по tickBitmap от `current_tick` в сторону свапа (`zero_for_one` — цена вниз)
+/// и возвращает до `samples` ближайших инициализированных тиков с ликвидностью перед ними.
+/// Если за `V3_MAX_BITMAP_WORDS` слов тиков меньше — последним идёт граница просмотра.
+pub async fn v3_sample_liquidity<M: Middleware + 'static>(
+    mw: Arc<M>,
+    pool: Address,
+    current_tick: i32,
+    samples: u32,
+    zero_for_one: bool,
+    block: BlockId,
+) -> Result<Vec<TickSample>> {
+    let p = IUniswapV3Pool::new(pool, mw);
+    let spacing = p.tick_spacing().call().await?;
+    let mut liquidity: u128 = p.liquidity().block(block).call().await?;
+    let mut out = Vec::new();
+    if spacing <= 0 || samples == 0 {
+        return Ok(out);
+    }
+
+    let mut compressed = current_tick.div_euclid(spacing) + if zero_for_one { 0 } else { 1 };
+    for _ in 0..V3_MAX_BITMAP_WORDS {
+        let word_pos = compressed >> 8;
+        let bit_pos = (compressed & 0xff) as usize;
+        let word = p.tick_bitmap(word_pos as i16).block(block).call().await?;
+        let bits: Vec<usize> = if zero_for_one {
+            (0..=bit_pos).rev().filter(|b| word.bit(*b)).collect()
+        } else {
+            (bit_pos..256).filter(|b| word.bit(*b)).collect()
+        };
+        for bit in bits {
+            let tick = ((word_pos << 8) + bit as i32) * spacing;
+            out.push(TickSample { tick, liquidity });
+            if out.len() >= samples as usize {
+                return Ok(out);
+            }
+            // пересечение тика: вниз liquidityNet вычитается, вверх — прибавляется
+            let (_gross, net, ..) = p.ticks(tick).block(block).call().await?;
+            liquidity = if zero_for_one {
+                liquidity.saturating_add_signed(-net)
+            } else {
+                liquidity.saturating_add_signed(net)
+            };
+        }
+        compressed = if zero_for_one { (word_pos << 8) - 1 } else { (word_pos + 1) << 8 };
+        if compressed * spacing <= V3_MIN_TICK || compressed * spacing >= V3_MAX_TICK {
+            break;
+        }
+    }
+    let edge = if zero_for_one { compressed + 1 } else { compressed };
+    let edge = (edge * spacing).clamp(V3_MIN_TICK, V3_MAX_TICK);
+    out.push(TickSample { tick: edge, liquidity });
+    Ok(out)
+}
+
+/// Оценка объёма входного токена (в raw-единицах, без учёта комиссии пула), который
+/// поместится в диапазон сэмплов `v3_sample_liquidity` от текущей цены.
+pub fn v3_input_capacity(sqrt_price_x96: U256, samples: &[TickSample], zero_for_one: bool) -> f64 {
+    let mut sqrt_prev = (sqrt_price_x96 >> 64).as_u128() as f64 / 2f64.powi(32);
+    let mut capacity = 0.0;
+    for s in samples {
+        let sqrt_next = 1.0001f64.powf(s.tick as f64 / 2.0);
+        let l = s.liquidity as f64;
+        capacity += if zero_for_one {
+            // Δx = L · (1/√P_next − 1/√P_prev)
+            l * (1.0 / sqrt_next - 1.0 / sqrt_prev).max(0.0)
+        } else {
+            // Δy = L · (√P_next − √P_prev)
+            l * (sqrt_next - sqrt_prev).max(0.0)
+        };
+        sqrt_prev = sqrt_next;
+    }
+    capacity
 }
 
 /// Квота через QuoterV2
@@ -115,13 +242,110 @@ pub async fn v3_quote_exact_input_single<M: Middleware + 'static>(
     token_out: Address,
     fee: u32,
     amount_in: U256,
+    block: BlockId,
 ) -> Result<(U256, U256)> {
     let q = IQuoterV2::new(quoter_v2, mw);
     let (amount_out, sqrt_after, ..) =
         q.quote_exact_input_single(token_in, token_out, fee, amount_in, U256::zero())
+            .block(block)
             .call()
             .await?;
-    Ok((amount_out, U256::from(sqrt_after)))
+    Ok((amount_out, sqrt_after))
+}
+
+// ---------- Algebra (v3 с динамической комиссией) ----------
+/// Пул Algebra: один на пару токенов, fee tier'ов нет
+pub async fn algebra_pool_by_pair<M: Middleware + 'static>(
+    mw: Arc<M>,
+    factory: Address,
+    a: Address,
+    b: Address,
+) -> Result<Address> {
+    let f = IAlgebraFactory::new(factory, mw);
+    Ok(f.pool_by_pair(a, b).call().await?)
+}
+
+/// Квота через Algebra Quoter; возвращает (amountOut, текущая комиссия пула в сотых bps)
+pub async fn algebra_quote_exact_input_single<M: Middleware + 'static>(
+    mw: Arc<M>,
+    quoter: Address,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    block: BlockId,
+) -> Result<(U256, u16)> {
+    let q = IAlgebraQuoter::new(quoter, mw);
+    let (amount_out, fee) = q
+        .quote_exact_input_single(token_in, token_out, amount_in, U256::zero())
+        .block(block)
+        .call()
+        .await?;
+    Ok((amount_out, fee))
+}
+
+// ---------- Uniswap V4 ----------
+/// Слот `pools` в PoolManager (StateLibrary.POOLS_SLOT)
+const V4_POOLS_SLOT: u64 = 6;
+
+/// Tick spacing стандартных fee tier'ов V4 (как у V3); нестандартный fee — None
+pub fn v4_default_tick_spacing(fee: u32) -> Option<i32> {
+    match fee {
+        100 => Some(1),
+        500 => Some(10),
+        3000 => Some(60),
+        10_000 => Some(200),
+        _ => None,
+    }
+}
+
+/// Слот storage, где лежит slot0 пула: keccak256(poolId . POOLS_SLOT)
+pub fn v4_pool_state_slot(pool_id: H256) -> H256 {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(pool_id.as_bytes());
+    U256::from(V4_POOLS_SLOT).to_big_endian(&mut buf[32..]);
+    H256::from(ethers::utils::keccak256(buf))
+}
+
+/// Пул инициализирован, если sqrtPriceX96 (младшие 160 бит slot0) не ноль
+pub async fn v4_pool_initialized<M: Middleware + 'static>(
+    mw: Arc<M>,
+    pool_manager: Address,
+    key: &V4PoolKey,
+) -> Result<bool> {
+    let pm = IV4PoolManager::new(pool_manager, mw);
+    let slot0 = pm
+        .extsload(v4_pool_state_slot(key.pool_id()).to_fixed_bytes())
+        .call()
+        .await?;
+    Ok(slot0[12..].iter().any(|b| *b != 0))
+}
+
+/// Квота exact-input через V4Quoter (без hookData)
+pub async fn v4_quote_exact_input_single<M: Middleware + 'static>(
+    mw: Arc<M>,
+    quoter: Address,
+    key: &V4PoolKey,
+    zero_for_one: bool,
+    amount_in: U256,
+    block: BlockId,
+) -> Result<U256> {
+    let exact_amount = u128::try_from(amount_in)
+        .map_err(|_| anyhow::anyhow!("v4 quote: amount_in {amount_in} exceeds uint128"))?;
+    let q = IV4Quoter::new(quoter, mw);
+    let params = QuoteExactSingleParams {
+        pool_key: PoolKey {
+            currency_0: key.currency0,
+            currency_1: key.currency1,
+            fee: key.fee,
+            tick_spacing: key.tick_spacing,
+            hooks: key.hooks,
+        },
+        zero_for_one,
+        exact_amount,
+        hook_data: Bytes::default(),
+    };
+    let (amount_out, _gas) = q.quote_exact_input_single(params).block(block).call().await?;
+    Ok(amount_out)
 }
 
 /// Перевод sqrtPriceX96 → цена (tokenOut per tokenIn)
@@ -160,9 +384,10 @@ pub async fn solidly_pair_get_amount_out<M: Middleware + 'static>(
     pair: Address,
     amount_in: U256,
     token_in: Address,
+    block: BlockId,
 ) -> Result<U256> {
     let p = ISolidlyPair::new(pair, mw);
-    Ok(p.get_amount_out(amount_in, token_in).call().await?)
+    Ok(p.get_amount_out(amount_in, token_in).block(block).call().await?)
 }
 
 // ---------- Утилиты ----------
@@ -187,10 +412,3 @@ pub async fn v3_pool_meta<M: Middleware + 'static>(
     Ok((t0, t1, fee))
 }
 
-/// Проверка на нулевой адрес
-pub fn ensure_not_zero(addr: Address, what: &str) -> Result<Address> {
-    if addr == Address::zero() {
-        return Err(anyhow!("{} returned zero address", what));
-    }
-    Ok(addr)
-}
@@ -1,12 +1,308 @@
 use anyhow::{anyhow, Context, Result};
-use ethers::abi::Abi;
+use ethers::abi::{Abi, AbiDecode, AbiEncode, ParamType, Token};
 use ethers::prelude::*;
-use std::sync::Arc;
-use itertools::Itertools;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use tracing::{info, warn};
 
+use crate::config::ExecutorAbiCfg;
+use crate::metrics::METRIC_WALLET_TX_SENT;
 // джиттер/MEV утилиты
 use crate::mev::{jitter_u64_bps, jitter_value_bps, GasJitterCfg, PrivateRelay};
+use crate::permit2::{PermitTransferFrom, unordered_nonce, with_permit};
+use crate::utils::fmt_addr;
+
+abigen!(
+    IGnosisSafe,
+    r#"[
+        function getThreshold() external view returns (uint256)
+        function isOwner(address owner) external view returns (bool)
+        function isModuleEnabled(address module) external view returns (bool)
+        function execTransaction(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,bytes signatures) external payable returns (bool)
+        function execTransactionFromModule(address to,uint256 value,bytes data,uint8 operation) external returns (bool)
+    ]"#
+);
+
+/// Как подписант проводит вызов через Safe
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SafeMode {
+    /// Владелец при пороге 1: `execTransaction` с pre-validated подписью (msg.sender == owner)
+    Owner,
+    /// Включённый модуль: `execTransactionFromModule`, без подписей владельцев
+    Module,
+}
+
+/// Safe, от имени которого вызывается экзекутор
+#[derive(Clone, Copy, Debug)]
+pub struct SafeExec {
+    pub safe: Address,
+    pub mode: SafeMode,
+}
+
+/// Выбор пути исполнения по состоянию Safe. `module_enabled` — Some, если для сети
+/// задан ключ модуля (SAFE_MODULE_KEY_<chain_id>). Одна подпись владельца покрывает
+/// только порог 1; при большем пороге — ошибка с подсказкой про модуль.
+pub fn safe_mode(
+    chain_id: u64,
+    signer: Address,
+    threshold: U256,
+    is_owner: bool,
+    module_enabled: Option<bool>,
+) -> Result<SafeMode> {
+    match module_enabled {
+        Some(true) => Ok(SafeMode::Module),
+        Some(false) => Err(anyhow!(
+            "{} не включён модулем Safe (SAFE_MODULE_KEY_{chain_id}): вызовите enableModule от имени Safe",
+            fmt_addr(&signer)
+        )),
+        None if !is_owner => Err(anyhow!(
+            "{} не владелец Safe: нужен ключ владельца или модуля (SAFE_MODULE_KEY_{chain_id})",
+            fmt_addr(&signer)
+        )),
+        None if threshold > U256::one() => Err(anyhow!(
+            "порог Safe {threshold} > 1: одной подписи {} не хватит для execTransaction; \
+             включите отдельный ключ модулем Safe и задайте SAFE_MODULE_KEY_{chain_id}",
+            fmt_addr(&signer)
+        )),
+        None => Ok(SafeMode::Owner),
+    }
+}
+
+/// Pre-validated подпись Safe (v = 1): r = адрес владельца, s = 0; валидна, когда
+/// транзакцию отправляет сам владелец.
+pub fn prevalidated_signature(owner: Address) -> Bytes {
+    let mut sig = [0u8; 65];
+    sig[12..32].copy_from_slice(owner.as_bytes());
+    sig[64] = 1;
+    Bytes::from(sig.to_vec())
+}
+
+/// Calldata Safe, оборачивающая вызов `inner` экзекутора (operation = CALL, без возмещения газа)
+pub fn safe_calldata(mode: SafeMode, executor: Address, inner: Bytes, signer: Address) -> Bytes {
+    let data = match mode {
+        SafeMode::Owner => ExecTransactionCall {
+            to: executor,
+            value: U256::zero(),
+            data: inner,
+            operation: 0,
+            safe_tx_gas: U256::zero(),
+            base_gas: U256::zero(),
+            gas_price: U256::zero(),
+            gas_token: Address::zero(),
+            refund_receiver: Address::zero(),
+            signatures: prevalidated_signature(signer),
+        }
+        .encode(),
+        SafeMode::Module => ExecTransactionFromModuleCall {
+            to: executor,
+            value: U256::zero(),
+            data: inner,
+            operation: 0,
+        }
+        .encode(),
+    };
+    Bytes::from(data)
+}
+
+/// Встроенный ABI экзекутора (если `execution.executor_abi.abi_file` не задан)
+pub const DEFAULT_EXECUTOR_ABI: &str = include_str!("../abis/Executor.json");
+
+/// Методы экзекутора из `execution.executor_abi`, сверенные с ABI: селекторы
+/// найденных перегрузок и знаковость результата (прибыли)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutorMethods {
+    pub execute: String,
+    pub execute_selector: [u8; 4],
+    /// execute возвращает int256 (иначе uint256)
+    pub execute_signed: bool,
+    pub simulate: String,
+    pub simulate_selector: [u8; 4],
+    pub simulate_signed: bool,
+}
+
+impl Default for ExecutorMethods {
+    /// Встроенный контракт: `execute(bytes,uint256) -> int256`, `simulate(bytes) -> uint256`
+    fn default() -> Self {
+        Self {
+            execute: "execute".into(),
+            execute_selector: ethers::utils::id("execute(bytes,uint256)"),
+            execute_signed: true,
+            simulate: "simulate".into(),
+            simulate_selector: ethers::utils::id("simulate(bytes)"),
+            simulate_signed: false,
+        }
+    }
+}
+
+impl ExecutorMethods {
+    /// Методы из конфига должны быть в ABI с нужными входами и типом результата —
+    /// иначе ошибка старта, а не revert на первой сделке
+    pub fn from_abi(abi: &Abi, cfg: &ExecutorAbiCfg) -> Result<Self> {
+        let (execute_selector, execute_signed) = abi_method(
+            abi,
+            "execute_method",
+            &cfg.execute_method,
+            &[ParamType::Bytes, ParamType::Uint(256)],
+            &cfg.execute_returns,
+        )?;
+        let (simulate_selector, simulate_signed) = abi_method(
+            abi,
+            "simulate_method",
+            &cfg.simulate_method,
+            &[ParamType::Bytes],
+            &cfg.simulate_returns,
+        )?;
+        Ok(Self {
+            execute: cfg.execute_method.clone(),
+            execute_selector,
+            execute_signed,
+            simulate: cfg.simulate_method.clone(),
+            simulate_selector,
+            simulate_signed,
+        })
+    }
+}
+
+/// Перегрузка `name` с входами `inputs` и результатом `returns` (int256 | uint256):
+/// селектор и знаковость результата
+fn abi_method(
+    abi: &Abi,
+    field: &str,
+    name: &str,
+    inputs: &[ParamType],
+    returns: &str,
+) -> Result<([u8; 4], bool)> {
+    let (want, signed) = match returns {
+        "int256" => (ParamType::Int(256), true),
+        "uint256" => (ParamType::Uint(256), false),
+        other => {
+            return Err(anyhow!(
+                "execution.executor_abi: тип результата '{other}' не поддерживается \
+                 (int256 | uint256)"
+            ));
+        }
+    };
+    let sig = format!(
+        "{name}({})",
+        inputs.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+    );
+    let funcs = abi.functions_by_name(name).map_err(|_| {
+        let known: Vec<&str> = abi.functions().map(|f| f.name.as_str()).collect();
+        anyhow!(
+            "Executor ABI: метода '{name}' (execution.executor_abi.{field}) нет; в ABI: {}",
+            known.join(", ")
+        )
+    })?;
+    let f = funcs
+        .iter()
+        .find(|f| f.inputs.iter().map(|p| &p.kind).eq(inputs.iter()))
+        .ok_or_else(|| anyhow!("Executor ABI: нет перегрузки {sig}"))?;
+    match f.outputs.first().map(|p| &p.kind) {
+        Some(kind) if *kind == want => Ok((f.short_signature(), signed)),
+        other => Err(anyhow!(
+            "Executor ABI: {sig} возвращает {}, в конфиге {returns}",
+            other.map_or("ничего".to_string(), |k| k.to_string())
+        )),
+    }
+}
+
+/// Прибыль из результата метода экзекутора: отрицательный int256 — ноль
+pub fn profit_from_token(token: Token) -> Result<U256> {
+    match token {
+        Token::Uint(v) => Ok(v),
+        Token::Int(raw) => {
+            let profit = I256::from_raw(raw);
+            Ok(if profit.is_negative() { U256::zero() } else { raw })
+        }
+        other => Err(anyhow!("executor profit: unexpected {other:?}")),
+    }
+}
+
+/// Сколько pending-nonce сети может стоять на месте, пока локальный счётчик впереди:
+/// дольше — отправленная транзакция выпала из mempool, счётчик догоняет сеть
+pub const NONCE_STALL_RESYNC: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Nonce кошелька исполнителя: выдаётся локально по возрастанию, поэтому следующая
+/// отправка не ждёт, пока pending-nonce сети увидит предыдущую. Не ниже pending-nonce
+/// (транзакции в обход бота). Сбой отправки сбрасывает счётчик; pending-nonce, не
+/// сдвинувшийся за `NONCE_STALL_RESYNC`, — тоже (иначе выпавшая tx оставит дыру,
+/// и все следующие застрянут за ней).
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    state: Mutex<NonceState>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    next: Option<U256>,
+    /// Последний pending-nonce сети и когда он впервые встретился
+    pending: Option<(U256, std::time::Instant)>,
+}
+
+impl NonceManager {
+    /// Следующий nonce при pending-nonce сети `pending` на момент `now`
+    pub fn take(&self, pending: U256, now: std::time::Instant) -> U256 {
+        let mut st = self.state.lock().unwrap();
+        let since = match st.pending {
+            Some((p, since)) if p == pending => since,
+            _ => now,
+        };
+        st.pending = Some((pending, since));
+        let ahead = st.next.is_some_and(|n| n > pending);
+        if ahead && now.duration_since(since) >= NONCE_STALL_RESYNC {
+            warn!(
+                "nonce: pending {pending} не двигается {}s при локальном {} — берём из сети",
+                now.duration_since(since).as_secs(),
+                st.next.unwrap_or_default()
+            );
+            st.next = None;
+            st.pending = Some((pending, now));
+        }
+        let nonce = st.next.map_or(pending, |n| n.max(pending));
+        st.next = Some(nonce + 1);
+        nonce
+    }
+
+    pub fn reset(&self) {
+        self.state.lock().unwrap().next = None;
+    }
+}
+
+/// Кошельки исполнителя сети (один экзекутор-контракт, разные ключи): отправки идут
+/// по кругу, у каждого кошелька свой nonce
+pub struct ExecutorPool<P, S>
+where
+    P: Middleware + 'static,
+    S: Signer + 'static,
+{
+    wallets: Vec<Arc<Executor<P, S>>>,
+    cursor: AtomicUsize,
+}
+
+impl<P, S> ExecutorPool<P, S>
+where
+    P: Middleware + 'static,
+    S: Signer + 'static,
+{
+    /// Ошибка, если `wallets` пуст
+    pub fn new(wallets: Vec<Arc<Executor<P, S>>>) -> Result<Self> {
+        if wallets.is_empty() {
+            return Err(anyhow!("ExecutorPool без кошельков"));
+        }
+        Ok(Self { wallets, cursor: AtomicUsize::new(0) })
+    }
+
+    /// Первый кошелёк: симуляции и прочие вызовы без отправки
+    pub fn primary(&self) -> &Arc<Executor<P, S>> {
+        &self.wallets[0]
+    }
+
+    /// Кошелёк для следующей отправки (round-robin)
+    pub fn next_wallet(&self) -> &Arc<Executor<P, S>> {
+        let i = self.cursor.fetch_add(1, Ordering::Relaxed) % self.wallets.len();
+        &self.wallets[i]
+    }
+}
 
 /// Экзекьютор маршрутов (контракт с методами simulate/execute)
 pub struct Executor<P, S>
@@ -17,6 +313,12 @@ where
     pub client: Arc<SignerMiddleware<P, S>>,
     pub address: Address,
     pub abi: Abi,
+    /// Методы execute/simulate (`execution.executor_abi`)
+    pub methods: ExecutorMethods,
+    /// Локальный nonce кошелька (`client`)
+    pub nonces: NonceManager,
+    /// Исполнение через Gnosis Safe (None — напрямую с EOA)
+    pub safe: Option<SafeExec>,
 }
 
 impl<P, S> Executor<P, S>
@@ -24,8 +326,11 @@ where
     P: Middleware + 'static,
     S: Signer + 'static,
 {
-    /// address берём из ENV: EXECUTOR_<chainId>
-    pub async fn new(client: Arc<SignerMiddleware<P, S>>) -> Result<Self> {
+    /// address берём из ENV: EXECUTOR_<chainId>; ABI и методы — из `execution.executor_abi`
+    pub async fn new(
+        client: Arc<SignerMiddleware<P, S>>,
+        abi_cfg: &ExecutorAbiCfg,
+    ) -> Result<Self> {
         let chain_id = client.provider().get_chainid().await?.as_u64();
         let key = format!("EXECUTOR_{}", chain_id);
         let addr_s = std::env::var(&key)
@@ -33,30 +338,108 @@ where
         let address: Address = addr_s.parse().context("invalid executor address")?;
 
         // грузим ABI один раз
-        let abi: Abi = serde_json::from_str(include_str!("../abis/Executor.json"))
-            .context("bad Executor ABI json")?;
+        let abi = load_executor_abi(abi_cfg)?;
+        let methods = ExecutorMethods::from_abi(&abi, abi_cfg)?;
 
-        // sanity: метод execute(bytes,uint256) должен существовать
-        if abi.function("execute").is_err() {
-            return Err(anyhow!("Executor ABI: method 'execute' not found"));
-        }
+        Ok(Self {
+            client,
+            address,
+            abi,
+            methods,
+            nonces: NonceManager::default(),
+            safe: None,
+        })
+    }
 
-        Ok(Self { client, address, abi })
+    /// Адрес, с которого экзекутор забирает вход маршрута: Safe или ключ исполнителя
+    pub fn payer(&self) -> Address {
+        self.safe.as_ref().map_or(self.client.address(), |s| s.safe)
     }
 
-    /// Статическая симуляция: simulate(bytes) -> uint256 (profit)
-    pub async fn simulate(&self, route_calldata: Bytes) -> Result<U256> {
+    /// Переводит исполнение на Safe: проверяет владельца/модуль и порог на старте,
+    /// чтобы неисполнимая конфигурация не всплывала на первой сделке.
+    pub async fn attach_safe(&mut self, safe: Address, via_module: bool) -> Result<()> {
+        let chain_id = self.client.provider().get_chainid().await?.as_u64();
+        let me = self.client.address();
+        let s = IGnosisSafe::new(safe, self.client.clone());
+        let threshold = s.get_threshold().call().await.context("Safe getThreshold()")?;
+        let is_owner = s.is_owner(me).call().await.context("Safe isOwner()")?;
+        let module_enabled = if via_module {
+            Some(s.is_module_enabled(me).call().await.context("Safe isModuleEnabled()")?)
+        } else {
+            None
+        };
+        let mode = safe_mode(chain_id, me, threshold, is_owner, module_enabled)
+            .with_context(|| format!("Safe {} (chain_id={chain_id})", fmt_addr(&safe)))?;
+        info!(
+            "executor via Safe {}: mode={mode:?} threshold={threshold} signer={}",
+            fmt_addr(&safe),
+            fmt_addr(&me)
+        );
+        self.safe = Some(SafeExec { safe, mode });
+        Ok(())
+    }
+
+    /// eth_call `simulate(bytes)` с лимитом газа `gas_limit` (см. `simulate_gas_limit`)
+    pub fn simulate_call(
+        &self,
+        route_calldata: Bytes,
+        gas_limit: u64,
+    ) -> Result<ContractCall<SignerMiddleware<P, S>, Token>> {
         let c = Contract::new(self.address, self.abi.clone(), self.client.clone());
+        let mut call = c
+            .method_hash::<_, Token>(self.methods.simulate_selector, route_calldata)?
+            .gas(gas_limit);
+        // через Safe экзекутор видит msg.sender = Safe
+        if let Some(safe) = &self.safe {
+            call = call.from(safe.safe);
+        }
+        Ok(call)
+    }
 
-        // небольшой лимит газа, чтобы eth_call не падал
-        let out: U256 = c
-            .method::<_, U256>("simulate", route_calldata)?
-            .gas(200_000u64)
-            .call()
-            .await
-            .context("simulate() call failed")?;
+    /// Статическая симуляция: simulate(bytes) -> profit (uint256/int256). Revert — не ошибка,
+    /// а `SimOutcome::Reverted` с декодированной причиной; Err — только сбой вызова (RPC).
+    pub async fn simulate(&self, route_calldata: Bytes, gas_limit: u64) -> Result<SimOutcome> {
+        match self.simulate_call(route_calldata, gas_limit)?.call().await {
+            Ok(profit) => Ok(SimOutcome::Profit(profit_from_token(profit)?)),
+            Err(e) => match e.as_revert() {
+                Some(data) => Ok(SimOutcome::Reverted(decode_revert_reason(data))),
+                None => Err(anyhow!(e).context("simulate() call failed")),
+            },
+        }
+    }
 
-        Ok(out)
+    /// Подписывает Permit2-разрешение (SignatureTransfer) на вход маршрута и кладёт его
+    /// в хвост calldata: экзекутор заберёт `amount` токена без on-chain approve на себя.
+    /// Через Safe средства у Safe, а ключ исполнителя за него не подпишет — calldata как есть.
+    pub async fn attach_permit(
+        &self,
+        route_calldata: Bytes,
+        permit2: Address,
+        token: Address,
+        amount: U256,
+        deadline: U256,
+    ) -> Result<Bytes> {
+        if self.safe.is_some() {
+            return Ok(route_calldata);
+        }
+        let signer = self.client.signer();
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let permit = PermitTransferFrom {
+            chain_id: signer.chain_id(),
+            permit2,
+            token,
+            amount,
+            spender: self.address,
+            nonce: unordered_nonce(nanos),
+            // дедлайн маршрута 0 — «без дедлайна», а Permit2 проверяет его всегда
+            deadline: if deadline.is_zero() { U256::MAX } else { deadline },
+        };
+        let signature = permit.sign(signer).await?;
+        Ok(with_permit(route_calldata, permit.executor_tokens(&signature)))
     }
 
     /// Быстрый путь (без специальных опций)
@@ -66,6 +449,87 @@ where
     }
 }
 
+/// ABI экзекутора: файл `abi_file` или встроенный
+pub fn load_executor_abi(cfg: &ExecutorAbiCfg) -> Result<Abi> {
+    if cfg.abi_file.is_empty() {
+        return serde_json::from_str(DEFAULT_EXECUTOR_ABI).context("bad Executor ABI json");
+    }
+    let json = std::fs::read_to_string(&cfg.abi_file)
+        .with_context(|| format!("executor_abi.abi_file {}", cfg.abi_file))?;
+    serde_json::from_str(&json).with_context(|| format!("bad Executor ABI json {}", cfg.abi_file))
+}
+
+/// Итог eth_call `simulate()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimOutcome {
+    Profit(U256),
+    /// simulate() откатился; причина из revert data (`decode_revert_reason`)
+    Reverted(String),
+}
+
+impl SimOutcome {
+    pub fn profit(&self) -> Option<U256> {
+        match self {
+            SimOutcome::Profit(p) => Some(*p),
+            SimOutcome::Reverted(_) => None,
+        }
+    }
+}
+
+/// `Error(string)`
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// `Panic(uint256)`
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Читаемая причина revert: строка `require`/`revert("...")` ("STF", "INSUFFICIENT_OUTPUT"),
+/// код `Panic` или селектор custom error
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    if data.len() < 4 {
+        return "revert without reason".into();
+    }
+    let (selector, args) = data.split_at(4);
+    if selector == ERROR_STRING_SELECTOR {
+        if let Ok(reason) = String::decode(args) {
+            return reason;
+        }
+    } else if selector == PANIC_SELECTOR && let Ok(code) = U256::decode(args) {
+        return format!("panic 0x{code:x}");
+    }
+    format!("custom error 0x{}", hex::encode(selector))
+}
+
+/// Лимит газа eth_call `simulate()` без `execution.simulate_gas_limit`: база + на ногу.
+/// Плоские 200k не хватало 3-ногим маршрутам — out-of-gas выглядел как «нет прибыли».
+pub const SIMULATE_GAS_BASE: u64 = 150_000;
+pub const SIMULATE_GAS_PER_LEG: u64 = 250_000;
+
+/// Лимит газа симуляции маршрута из `legs` ног: заданный в конфиге или по числу ног
+pub fn simulate_gas_limit(legs: usize, configured: Option<u64>) -> u64 {
+    configured.unwrap_or(SIMULATE_GAS_BASE + SIMULATE_GAS_PER_LEG * legs as u64)
+}
+
+/// Симуляция пропускает маршрут, если `simulate()` не откатился (`Some`) и вернул
+/// прибыль не ниже `amount_in * min_bps / 10_000`
+pub fn simulation_passes(sim_profit: Option<U256>, amount_in: U256, min_bps: u32) -> bool {
+    sim_profit.is_some_and(|p| p >= amount_in * U256::from(min_bps) / U256::from(10_000u64))
+}
+
+/// `min_profit` для `execute(route, min_profit)`. При `allow_revert_on_no_profit`
+/// передаём 0: контракт откатывает только убыточную сделку (дешёвый revert на проверке
+/// прибыли), иначе — целевую прибыль `amount_in * min_profit_bps / 10_000`, и tx
+/// откатывается, если прибыль ниже цели. Газ за revert платится в обоих случаях.
+pub fn exec_min_profit(
+    allow_revert_on_no_profit: bool,
+    amount_in: U256,
+    min_profit_bps: u32,
+) -> U256 {
+    if allow_revert_on_no_profit {
+        U256::zero()
+    } else {
+        amount_in * U256::from(min_profit_bps) / U256::from(10_000u64)
+    }
+}
+
 /// Опции исполнения
 #[derive(Clone, Debug, Default)]
 pub struct TxOpts {
@@ -85,6 +549,9 @@ pub struct TxOpts {
     /// Legacy режим: фиксированная gas_price (если EIP-1559 не заданы)
     pub legacy_gas_price: Option<U256>,
 
+    /// Отправлять type-0 (legacy) транзакцию: `gas_price_mode = "legacy"`
+    pub legacy_tx: bool,
+
     /// Опциональный приватный релей (Flashbots/bloxroute/…)
     pub private_relay: Option<PrivateRelay>,
 }
@@ -104,7 +571,11 @@ where
         // --- префлайт: сеть/nonce/basefee (диагностика)
         let chain_id = self.client.provider().get_chainid().await?.as_u64();
         let me = self.client.address();
-        let nonce = self.client.get_transaction_count(me, Some(BlockId::Number(BlockNumber::Latest))).await?;
+        let pending = self
+            .client
+            .get_transaction_count(me, Some(BlockId::Number(BlockNumber::Pending)))
+            .await?;
+        let nonce = self.nonces.take(pending, std::time::Instant::now());
         let basefee = self
             .client
             .get_block(BlockNumber::Latest)
@@ -112,14 +583,28 @@ where
             .and_then(|b| b.base_fee_per_gas)
             .unwrap_or_default();
 
-        info!("execute: chain_id={chain_id} addr={me:?} nonce={nonce} basefee={basefee}");
+        info!(
+            "execute: chain_id={chain_id} addr={} nonce={nonce} basefee={basefee}",
+            fmt_addr(&me)
+        );
 
         // --- конструктор контракта
         let c = Contract::new(self.address, self.abi.clone(), self.client.clone());
         let mut call = c
-            // NB: если в контракте execute возвращает int256, здесь I256; если uint256 — поменяй на U256
-            .method::<_, I256>("execute", (route_calldata, min_profit))
-            .context("encode execute(route,min_profit)")?;
+            .method_hash::<_, Token>(self.methods.execute_selector, (route_calldata, min_profit))
+            .with_context(|| format!("encode {}(route,min_profit)", self.methods.execute))?;
+        if opts.legacy_tx {
+            call = call.legacy();
+        }
+        call.tx.set_nonce(nonce);
+
+        // --- Safe: тот же execute(...) уходит внутренним вызовом Safe
+        if let Some(safe) = &self.safe {
+            let inner = call.calldata().context("execute calldata")?;
+            call.tx.set_to(safe.safe);
+            call.tx.set_data(safe_calldata(safe.mode, self.address, inner, me));
+            info!("execute via Safe {} ({:?})", fmt_addr(&safe.safe), safe.mode);
+        }
 
         // --- газ лимит + джиттер
         let base_gas = opts.gas_limit.unwrap_or(1_500_000u64);
@@ -140,10 +625,10 @@ where
             effective_gas_price = Some(legacy_eq);
         }
 
-        if effective_gas_price.is_none() {
-            if let Some(gp) = opts.legacy_gas_price {
-                effective_gas_price = Some(gp);
-            }
+        if effective_gas_price.is_none()
+            && let Some(gp) = opts.legacy_gas_price
+        {
+            effective_gas_price = Some(gp);
         }
 
         if let Some(mut gp) = effective_gas_price {
@@ -156,16 +641,268 @@ where
         }
 
         // --- приватная отправка (заглушка; для реального — нужен raw tx)
-        if opts.private {
-            if let Some(relay) = &opts.private_relay {
-                let _ = relay.send_raw_tx("0x").await; // no-op
-            }
+        if opts.private
+            && let Some(relay) = &opts.private_relay
+        {
+            let _ = relay.send_raw_tx("0x").await; // no-op
         }
 
         // --- отправляем
-        let pending = call.send().await.context("execute() send failed")?;
+        let pending = match call.send().await {
+            Ok(pending) => pending,
+            Err(e) => {
+                // nonce мог остаться неиспользованным — следующий возьмём из сети
+                self.nonces.reset();
+                return Err(anyhow!(e).context("execute() send failed"));
+            }
+        };
         let tx = pending.tx_hash();
-        info!("execute sent: tx={:?} gas_limit={}", tx, gas_limit);
+        METRIC_WALLET_TX_SENT
+            .with_label_values(&[&chain_id.to_string(), &fmt_addr(&me)])
+            .inc();
+        info!(
+            "execute sent: tx={:?} wallet={} nonce={nonce} gas_limit={}",
+            tx,
+            fmt_addr(&me),
+            gas_limit
+        );
         Ok(tx)
     }
 }
+
+/// Ожидаемый выход маршрута по котировке: `amount` токена `token` на адрес `recipient`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectedOut {
+    pub token: Address,
+    pub recipient: Address,
+    /// Откуда экзекутор забирает вход: маршрут круговой, и этот Transfer — не выход
+    pub payer: Address,
+    pub amount: U256,
+}
+
+/// Отправленная транзакция, ждущая receipt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingTx {
+    pub chain_id: u64,
+    pub tx: TxHash,
+    pub route: String,
+    pub expected: Option<ExpectedOut>,
+}
+
+/// Итог транзакции по receipt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// `realized_out` — фактический выход из Transfer-логов (если ожидание было задано)
+    Confirmed { block: u64, realized_out: Option<U256> },
+    Reverted { block: u64 },
+}
+
+/// Сумма ERC20 Transfer токена `token` на `recipient` в логах receipt, кроме переводов
+/// от `payer` (забор входа маршрута тем же токеном)
+pub fn realized_output(logs: &[Log], token: Address, recipient: Address, payer: Address) -> U256 {
+    let transfer = H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)"));
+    logs.iter()
+        .filter(|l| l.address == token && l.topics.len() == 3 && l.topics[0] == transfer)
+        .filter(|l| Address::from(l.topics[2]) == recipient && Address::from(l.topics[1]) != payer)
+        .fold(U256::zero(), |acc, l| acc.saturating_add(U256::from_big_endian(&l.data)))
+}
+
+/// Превышение порога проскальзывания по итогу транзакции: Some(bps), если фактический
+/// выход хуже котировки больше чем на `threshold_bps` (0 — проверка выключена)
+pub fn slippage_breach(threshold_bps: u32, pending: &PendingTx, outcome: &TxOutcome) -> Option<u64> {
+    let (TxOutcome::Confirmed { realized_out: Some(realized), .. }, Some(expected)) = (outcome, pending.expected) else {
+        return None;
+    };
+    let slip = slippage_bps(expected.amount, *realized);
+    (threshold_bps > 0 && slip > threshold_bps as u64).then_some(slip)
+}
+
+/// Проскальзывание фактического выхода относительно котировки, bps (0 — не хуже котировки)
+pub fn slippage_bps(quoted: U256, realized: U256) -> u64 {
+    if quoted.is_zero() || realized >= quoted {
+        return 0;
+    }
+    ((quoted - realized) * U256::from(10_000u64) / quoted).as_u64()
+}
+
+/// Трекер отправленных транзакций: receipts проверяются в начале скана сети
+/// и при остановке сервиса (drain), чтобы отправленный арбитраж не терялся.
+#[derive(Clone, Default)]
+pub struct ReceiptTracker {
+    pending: Arc<std::sync::Mutex<Vec<PendingTx>>>,
+}
+
+impl ReceiptTracker {
+    /// `expected` — ожидаемый выход маршрута: по receipt считается фактический выход
+    pub fn track(&self, chain_id: u64, tx: TxHash, route: &str, expected: Option<ExpectedOut>) {
+        self.pending.lock().unwrap().push(PendingTx {
+            chain_id,
+            tx,
+            route: route.to_string(),
+            expected,
+        });
+    }
+
+    /// Неподтверждённые транзакции (все или одной сети)
+    pub fn pending(&self, chain_id: Option<u64>) -> Vec<PendingTx> {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| chain_id.is_none_or(|c| c == p.chain_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Один проход по receipts: подтверждённые логируются и убираются из трекера
+    pub async fn settle<F, Fut>(&self, chain_id: Option<u64>, fetch: &F) -> Vec<(PendingTx, TxOutcome)>
+    where
+        F: Fn(u64, TxHash) -> Fut,
+        Fut: std::future::Future<Output = Result<Option<TransactionReceipt>>>,
+    {
+        let mut done = Vec::new();
+        for p in self.pending(chain_id) {
+            let receipt = match fetch(p.chain_id, p.tx).await {
+                Ok(Some(r)) => r,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(chain = p.chain_id, "receipt {:?} ({}): {e:#}", p.tx, p.route);
+                    continue;
+                }
+            };
+            let block = receipt.block_number.map(|b| b.as_u64()).unwrap_or_default();
+            let outcome = if receipt.status == Some(U64::one()) {
+                info!(chain = p.chain_id, "tx {:?} ({}) подтверждена в блоке {}", p.tx, p.route, block);
+                let realized_out = p
+                    .expected
+                    .map(|e| realized_output(&receipt.logs, e.token, e.recipient, e.payer));
+                TxOutcome::Confirmed { block, realized_out }
+            } else {
+                warn!(chain = p.chain_id, "tx {:?} ({}) reverted в блоке {}", p.tx, p.route, block);
+                TxOutcome::Reverted { block }
+            };
+            self.pending.lock().unwrap().retain(|x| x.tx != p.tx);
+            done.push((p, outcome));
+        }
+        done
+    }
+
+    /// Ждёт receipts всех отправленных транзакций не дольше `timeout`, опрашивая раз в `poll`;
+    /// итоги каждого прохода — в `on_settled`. Возвращает то, что так и не подтвердилось.
+    pub async fn drain<F, Fut>(
+        &self,
+        timeout: std::time::Duration,
+        poll: std::time::Duration,
+        fetch: F,
+        on_settled: impl Fn(&[(PendingTx, TxOutcome)]),
+    ) -> Vec<PendingTx>
+    where
+        F: Fn(u64, TxHash) -> Fut,
+        Fut: std::future::Future<Output = Result<Option<TransactionReceipt>>>,
+    {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            on_settled(&self.settle(None, &fetch).await);
+            let left = self.pending(None);
+            let now = tokio::time::Instant::now();
+            if left.is_empty() || now >= deadline {
+                for p in &left {
+                    warn!(chain = p.chain_id, "tx {:?} ({}) не подтверждена до остановки", p.tx, p.route);
+                }
+                return left;
+            }
+            tokio::time::sleep(poll.min(deadline - now)).await;
+        }
+    }
+}
+
+/// Состояние повтора маршрута после revert
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryState {
+    /// Ждёт переотправки (номер попытки)
+    Pending(u32),
+    /// Повтор отправлен, ждём receipt
+    InFlight(u32),
+}
+
+/// Повторы после revert по маршрутам (chain_id, route): не больше `revert_retry` попыток,
+/// каждая — после новой котировки в следующем скане сети
+#[derive(Clone, Default)]
+pub struct RevertRetries {
+    state: Arc<std::sync::Mutex<std::collections::HashMap<(u64, String), RetryState>>>,
+}
+
+impl RevertRetries {
+    /// Итог транзакции маршрута. Revert планирует повтор, пока попыток меньше `max`;
+    /// Some(n) — запланирована попытка n. Подтверждение сбрасывает счётчик.
+    pub fn on_outcome(
+        &self,
+        chain_id: u64,
+        route: &str,
+        outcome: &TxOutcome,
+        max: u32,
+    ) -> Option<u32> {
+        let key = (chain_id, route.to_string());
+        let mut state = self.state.lock().unwrap();
+        if let TxOutcome::Confirmed { .. } = outcome {
+            state.remove(&key);
+            return None;
+        }
+        let done = match state.get(&key) {
+            Some(RetryState::InFlight(n)) | Some(RetryState::Pending(n)) => *n,
+            None => 0,
+        };
+        if done < max {
+            state.insert(key, RetryState::Pending(done + 1));
+            Some(done + 1)
+        } else {
+            state.remove(&key);
+            None
+        }
+    }
+
+    /// Номер попытки, если маршрут ждёт переотправки
+    pub fn pending(&self, chain_id: u64, route: &str) -> Option<u32> {
+        match self.state.lock().unwrap().get(&(chain_id, route.to_string())) {
+            Some(RetryState::Pending(n)) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Повтор отправлен
+    pub fn mark_sent(&self, chain_id: u64, route: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(s) = state.get_mut(&(chain_id, route.to_string()))
+            && let RetryState::Pending(n) = *s
+        {
+            *s = RetryState::InFlight(n);
+        }
+    }
+
+    /// Отмена ожидающего повтора (маршрут больше не прибылен); Some(n) — что отменили
+    pub fn cancel(&self, chain_id: u64, route: &str) -> Option<u32> {
+        let mut state = self.state.lock().unwrap();
+        let key = (chain_id, route.to_string());
+        match state.get(&key) {
+            Some(RetryState::Pending(n)) => {
+                let n = *n;
+                state.remove(&key);
+                Some(n)
+            }
+            _ => None,
+        }
+    }
+
+    /// Отмена всех ожидающих повторов сети (circuit breaker / cooldown)
+    pub fn cancel_chain(&self, chain_id: u64) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let before = state.len();
+        state.retain(|(c, _), s| *c != chain_id || matches!(s, RetryState::InFlight(_)));
+        before - state.len()
+    }
+}
+
+/// Цена газа повтора: +10% за каждую попытку
+pub fn retry_gas_price(gas_price: U256, attempt: u32) -> U256 {
+    gas_price * U256::from(100 + 10 * attempt as u64) / U256::from(100u64)
+}
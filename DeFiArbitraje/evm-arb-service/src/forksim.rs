@@ -0,0 +1,154 @@
+//! Симуляция маршрута исполнением на форке (`execution.fork_sim`): eth_call
+//! `execute(calldata, 0)` экзекутора (метод из `execution.executor_abi`) со `stateOverride` —
+//! на RPC сети или на anvil (`anvil --fork-url`). В отличие от `simulate()` контракта,
+//! который считает выход квотерами, прогоняются настоящие свопы: расхождение off-chain
+//! математики с исполнением видно до отправки. Вход маршрута экзекутору подкладывается
+//! подменой баланса.
+
+use anyhow::{Result, anyhow};
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::providers::{RawCall, RpcError, call_raw::spoof};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::keccak256;
+use std::sync::Arc;
+
+use crate::config::{ForkSimCfg, Network};
+use crate::exec::{ExecutorMethods, SimOutcome, decode_revert_reason, profit_from_token};
+use crate::utils::parse_addr;
+
+/// Чем подменяется вход маршрута на балансе экзекутора
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Funding {
+    /// ERC20: слот `balanceOf[executor]` mapping'а балансов `slot`
+    Erc20 { token: Address, slot: u64 },
+    /// Native-вход (нога Wrap): баланс ETH экзекутора
+    Native,
+    /// Слот баланса токена неизвестен — вход не подменяется
+    None,
+}
+
+/// Подмена входа для токена `sym` сети: native, ERC20 с `balance_slot` или ничего
+pub fn funding_for(net: &Network, sym: &str) -> Funding {
+    if net.is_unwrapped_native(sym) {
+        return Funding::Native;
+    }
+    net.tokens
+        .get(sym)
+        .and_then(|t| Some((parse_addr(&t.address).ok()?, t.balance_slot?)))
+        .map_or(Funding::None, |(token, slot)| Funding::Erc20 { token, slot })
+}
+
+/// Ключ хранилища `balanceOf[holder]` для mapping'а в слоте `slot` (раскладка Solidity):
+/// keccak256(abi.encode(holder, slot))
+pub fn balance_slot_key(holder: Address, slot: u64) -> H256 {
+    H256::from(keccak256(abi::encode(&[
+        Token::Address(holder),
+        Token::Uint(U256::from(slot)),
+    ])))
+}
+
+/// Calldata `execute(route_calldata, min_profit)` метода с селектором `selector`
+pub fn execute_calldata(selector: [u8; 4], route_calldata: Bytes, min_profit: U256) -> Bytes {
+    let mut data = selector.to_vec();
+    data.extend(abi::encode(&[Token::Bytes(route_calldata.to_vec()), Token::Uint(min_profit)]));
+    Bytes::from(data)
+}
+
+/// Ответ `execute()` (profit int256 или uint256): отрицательная прибыль — ноль
+pub fn decode_profit(data: &[u8], signed: bool) -> Result<U256> {
+    let kind = if signed { ParamType::Int(256) } else { ParamType::Uint(256) };
+    let token = abi::decode(&[kind], data)
+        .map_err(|e| anyhow!("execute() output: {e}"))?
+        .remove(0);
+    profit_from_token(token)
+}
+
+pub struct ForkSimulator {
+    /// anvil (`fork_sim.rpc_url`); None — RPC сети
+    pub fork: Option<Arc<Provider<Http>>>,
+    pub executor: Address,
+    /// Отправитель вызова: ключ исполнителя или Safe
+    pub from: Address,
+    /// Runtime-байткод вместо on-chain кода экзекутора (`fork_sim.executor_code_file`)
+    pub executor_code: Option<Bytes>,
+    /// Метод execute экзекутора; по умолчанию встроенный контракт
+    pub methods: ExecutorMethods,
+}
+
+impl ForkSimulator {
+    pub fn new(cfg: &ForkSimCfg, executor: Address, from: Address) -> Result<Self> {
+        let fork = match cfg.rpc_url.as_str() {
+            "" => None,
+            url => Some(Arc::new(Provider::<Http>::try_from(url)?)),
+        };
+        let executor_code = match cfg.executor_code_file.as_str() {
+            "" => None,
+            path => {
+                let hex = std::fs::read_to_string(path)
+                    .map_err(|e| anyhow!("fork_sim.executor_code_file {path}: {e}"))?;
+                let code: Bytes = hex
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow!("fork_sim.executor_code_file {path}: {e}"))?;
+                Some(code)
+            }
+        };
+        Ok(Self {
+            fork,
+            executor,
+            from,
+            executor_code,
+            methods: ExecutorMethods::default(),
+        })
+    }
+
+    /// Подмена состояния: код экзекутора (если задан) и вход маршрута на его балансе
+    pub fn state_override(&self, funding: Funding, amount_in: U256) -> spoof::State {
+        let mut state = spoof::State::default();
+        if let Some(code) = &self.executor_code {
+            state.account(self.executor).code(code.clone());
+        }
+        match funding {
+            Funding::Erc20 { token, slot } => {
+                let mut val = [0u8; 32];
+                amount_in.to_big_endian(&mut val);
+                state
+                    .account(token)
+                    .store(balance_slot_key(self.executor, slot), H256::from(val));
+            }
+            Funding::Native => {
+                state.account(self.executor).balance(amount_in);
+            }
+            Funding::None => {}
+        }
+        state
+    }
+
+    /// eth_call `execute(route_calldata, 0)` со stateOverride на форке или на `provider` сети.
+    /// Как и `Executor::simulate`: revert — `SimOutcome::Reverted`, Err — сбой вызова.
+    pub async fn simulate(
+        &self,
+        provider: &Provider<Http>,
+        route_calldata: Bytes,
+        funding: Funding,
+        amount_in: U256,
+        gas_limit: u64,
+    ) -> Result<SimOutcome> {
+        let provider = self.fork.as_deref().unwrap_or(provider);
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(self.from)
+            .to(self.executor)
+            .gas(gas_limit)
+            .data(execute_calldata(self.methods.execute_selector, route_calldata, U256::zero()))
+            .into();
+        let state = self.state_override(funding, amount_in);
+        match provider.call_raw(&tx).state(&state).await {
+            Ok(out) => Ok(SimOutcome::Profit(decode_profit(&out, self.methods.execute_signed)?)),
+            Err(e) => match RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) {
+                Some(data) => Ok(SimOutcome::Reverted(decode_revert_reason(&data))),
+                None => Err(anyhow!(e).context("fork eth_call execute() failed")),
+            },
+        }
+    }
+}
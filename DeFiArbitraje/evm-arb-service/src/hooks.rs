@@ -0,0 +1,223 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use ethers::types::U256;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::config::AlertsCfg;
+use crate::metrics::METRIC_ALERTS_DROPPED;
+
+/// Сделка, готовая к отправке (то, что видит pre-send хук)
+#[derive(Clone, Debug)]
+pub struct TradeContext {
+    pub chain_id: u64,
+    /// "WETH-USDC" для cross-dex, "A-B-C" для треугольника
+    pub route: String,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub gas_estimate: u64,
+    pub pnl_usd: f64,
+}
+
+/// Решение хука
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Send,
+    Skip,
+    /// Отправить с другим amount_in (amount_out масштабируется пропорционально)
+    ModifySize(U256),
+}
+
+/// Последняя проверка перед `exec.execute`: внешние риск-лимиты, инвентарь и т.п.
+#[async_trait]
+pub trait PreSendHook: Send + Sync {
+    async fn should_send(&self, ctx: &TradeContext) -> Decision;
+}
+
+/// Хук по умолчанию — всегда отправляем
+pub struct NoopHook;
+
+#[async_trait]
+impl PreSendHook for NoopHook {
+    async fn should_send(&self, _ctx: &TradeContext) -> Decision {
+        Decision::Send
+    }
+}
+
+/// Спрашивает хук и вызывает `send(amount_in, amount_out)` только если он разрешил.
+/// Ok(None) — хук отказал (или урезал размер до нуля), транзакция не отправлялась.
+pub async fn send_through_hook<T, F, Fut>(
+    hook: &dyn PreSendHook,
+    ctx: &TradeContext,
+    send: F,
+) -> Result<Option<T>>
+where
+    F: FnOnce(U256, U256) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match hook.should_send(ctx).await {
+        Decision::Send => send(ctx.amount_in, ctx.amount_out).await.map(Some),
+        Decision::Skip => {
+            tracing::info!(chain = ctx.chain_id, "pre-send hook: skip {}", ctx.route);
+            Ok(None)
+        }
+        Decision::ModifySize(amount_in) => {
+            if amount_in.is_zero() || ctx.amount_in.is_zero() {
+                tracing::info!(chain = ctx.chain_id, "pre-send hook: size 0 for {}", ctx.route);
+                return Ok(None);
+            }
+            let amount_out = ctx.amount_out * amount_in / ctx.amount_in;
+            tracing::info!(
+                chain = ctx.chain_id,
+                "pre-send hook: resize {} amount_in {} -> {}",
+                ctx.route,
+                ctx.amount_in,
+                amount_in
+            );
+            send(amount_in, amount_out).await.map(Some)
+        }
+    }
+}
+
+/// Получатель алертов (остановка сети, аномалии исполнения)
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn alert(&self, chain_id: u64, kind: &str, message: &str);
+}
+
+/// Недоставленные алерты вебхука по порядку; у каждого номер, чтобы подтверждение
+/// доставки не сняло с головы другой алерт, если отправленный успели вытеснить
+#[derive(Debug)]
+pub struct AlertQueue {
+    items: VecDeque<(u64, serde_json::Value)>,
+    cap: usize,
+    next_seq: u64,
+}
+
+impl AlertQueue {
+    pub fn new(cap: usize) -> Self {
+        Self { items: VecDeque::new(), cap: cap.max(1), next_seq: 0 }
+    }
+
+    /// В хвост; полная очередь вытесняет самый старый алерт (true — был вытеснен)
+    pub fn push(&mut self, body: serde_json::Value) -> bool {
+        let dropped = self.items.len() >= self.cap && self.items.pop_front().is_some();
+        self.items.push_back((self.next_seq, body));
+        self.next_seq += 1;
+        dropped
+    }
+
+    /// Старейший недоставленный алерт (без извлечения)
+    pub fn front(&self) -> Option<(u64, serde_json::Value)> {
+        self.items.front().cloned()
+    }
+
+    /// Алерт `seq` доставлен: снимается, если ещё в голове очереди
+    pub fn ack(&mut self, seq: u64) {
+        if self.items.front().is_some_and(|(s, _)| *s == seq) {
+            self.items.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Потолок паузы между повторами доставки
+pub const ALERT_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Пауза перед повтором `attempt` (с 0): `base * 2^attempt`, не больше `ALERT_BACKOFF_MAX`
+pub fn alert_backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1u32 << attempt.min(16)).min(ALERT_BACKOFF_MAX)
+}
+
+/// Алерт в лог (error) и, если задан `alerts.webhook_url`, POST JSON на вебхук.
+/// Доставка в фоне: `alert` только кладёт в очередь и не ждёт сеть (скан не тормозит),
+/// отдельная задача шлёт с повторами и держит очередь, пока вебхук недоступен.
+pub struct WebhookAlert {
+    queue: Option<(Arc<Mutex<AlertQueue>>, Arc<Notify>)>,
+}
+
+impl WebhookAlert {
+    /// Без `webhook_url` — только лог; иначе запускает задачу доставки (нужен рантайм tokio)
+    pub fn new(cfg: &AlertsCfg) -> Self {
+        let Some(url) = cfg.webhook_url.clone() else {
+            return Self { queue: None };
+        };
+        let queue = Arc::new(Mutex::new(AlertQueue::new(cfg.queue_size)));
+        let notify = Arc::new(Notify::new());
+        let retry = (cfg.retry_attempts.max(1), Duration::from_millis(cfg.retry_backoff_ms));
+        tokio::spawn(deliver_alerts(url, queue.clone(), notify.clone(), retry));
+        Self { queue: Some((queue, notify)) }
+    }
+}
+
+/// Шлёт алерты из головы очереди по одному: `attempts` попыток с растущей паузой, затем
+/// пауза `ALERT_BACKOFF_MAX` и снова — алерт остаётся в очереди, пока вебхук не поднимется
+async fn deliver_alerts(
+    url: String,
+    queue: Arc<Mutex<AlertQueue>>,
+    notify: Arc<Notify>,
+    (attempts, backoff): (u32, Duration),
+) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    loop {
+        let next = queue.lock().unwrap().front();
+        let Some((seq, body)) = next else {
+            notify.notified().await;
+            continue;
+        };
+        let mut delivered = false;
+        for attempt in 0..attempts {
+            match client.post(&url).json(&body).send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => {
+                    delivered = true;
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "alert webhook error (попытка {}/{attempts}): {e:#}",
+                        attempt + 1
+                    );
+                    if attempt + 1 < attempts {
+                        tokio::time::sleep(alert_backoff(backoff, attempt)).await;
+                    }
+                }
+            }
+        }
+        if delivered {
+            queue.lock().unwrap().ack(seq);
+        } else {
+            let pending = queue.lock().unwrap().len();
+            tracing::warn!("alert webhook недоступен: {pending} алертов ждут доставки");
+            tokio::time::sleep(ALERT_BACKOFF_MAX).await;
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookAlert {
+    async fn alert(&self, chain_id: u64, kind: &str, message: &str) {
+        tracing::error!(chain = chain_id, alert = kind, "{message}");
+        let Some((queue, notify)) = &self.queue else {
+            return;
+        };
+        let body = serde_json::json!({ "chain_id": chain_id, "kind": kind, "message": message });
+        if queue.lock().unwrap().push(body) {
+            METRIC_ALERTS_DROPPED.inc();
+            tracing::warn!("очередь алертов переполнена: вытеснен самый старый");
+        }
+        notify.notify_one();
+    }
+}
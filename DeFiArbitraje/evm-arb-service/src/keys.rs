@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Context, Result};
+use ethers::signers::LocalWallet;
+
+/// Откуда берём ключ исполнителя сети
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    /// Зашифрованный JSON keystore (PRIVATE_KEY_FILE_<chain_id>) + пароль из ENV
+    Keystore { path: String, password: String },
+    /// Сырой hex (PRIVATE_KEY_<chain_id> или PRIVATE_KEY) — для совместимости
+    RawHex(String),
+}
+
+/// Выбор источника ключа для сети. `get` — чтение ENV (в тестах подменяется).
+/// Keystore и PRIVATE_KEY_<chain_id> одновременно — ошибка: источник должен быть ровно один.
+pub fn resolve_key_source(
+    chain_id: u64,
+    get: impl Fn(&str) -> Option<String>,
+) -> Result<KeySource> {
+    let file_key = format!("PRIVATE_KEY_FILE_{}", chain_id);
+    let raw_key = format!("PRIVATE_KEY_{}", chain_id);
+    let file = get(&file_key).filter(|v| !v.trim().is_empty());
+    let raw = get(&raw_key).filter(|v| !v.trim().is_empty());
+
+    match (file, raw) {
+        (Some(_), Some(_)) => Err(anyhow!(
+            "chain_id={chain_id}: заданы и {file_key}, и {raw_key} — оставьте один источник ключа"
+        )),
+        (Some(path), None) => {
+            let pass_key = format!("PRIVATE_KEY_PASSWORD_{}", chain_id);
+            let password = get(&pass_key)
+                .or_else(|| get("PRIVATE_KEY_PASSWORD"))
+                .ok_or_else(|| {
+                    anyhow!("{file_key} задан, но нет пароля ({pass_key} или PRIVATE_KEY_PASSWORD)")
+                })?;
+            Ok(KeySource::Keystore { path, password })
+        }
+        (None, Some(pk)) => Ok(KeySource::RawHex(pk)),
+        (None, None) => get("PRIVATE_KEY")
+            .filter(|v| !v.trim().is_empty())
+            .map(KeySource::RawHex)
+            .ok_or_else(|| {
+                anyhow!("ключ не задан: {file_key}, {raw_key} или PRIVATE_KEY")
+            }),
+    }
+}
+
+impl KeySource {
+    pub fn wallet(&self) -> Result<LocalWallet> {
+        match self {
+            KeySource::Keystore { path, password } => LocalWallet::decrypt_keystore(path, password)
+                .with_context(|| format!("не удалось расшифровать keystore {path}")),
+            KeySource::RawHex(pk) => pk.parse().context("invalid raw private key"),
+        }
+    }
+}
+
+/// Источник ключа из реального окружения
+pub fn key_source_from_env(chain_id: u64) -> Result<KeySource> {
+    resolve_key_source(chain_id, |k| std::env::var(k).ok())
+}
+
+/// Ключи исполнителей сети для ротации кошельков: `PRIVATE_KEY_<chain_id>_0`, `_1`, ...
+/// (или `PRIVATE_KEY_FILE_<chain_id>_<i>` + пароль) подряд с нуля; без `_0` — один ключ
+/// по `resolve_key_source`. Нумерованные ключи вместе с одиночным — ошибка.
+pub fn resolve_key_sources(
+    chain_id: u64,
+    get: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<KeySource>> {
+    let is_set = |k: &str| get(k).is_some_and(|v| !v.trim().is_empty());
+    let indexed = |i: usize| {
+        is_set(&format!("PRIVATE_KEY_{chain_id}_{i}"))
+            || is_set(&format!("PRIVATE_KEY_FILE_{chain_id}_{i}"))
+    };
+    if !indexed(0) {
+        return resolve_key_source(chain_id, get).map(|k| vec![k]);
+    }
+    for single in [format!("PRIVATE_KEY_{chain_id}"), format!("PRIVATE_KEY_FILE_{chain_id}")] {
+        if is_set(&single) {
+            return Err(anyhow!(
+                "chain_id={chain_id}: заданы и {single}, и PRIVATE_KEY_{chain_id}_0 — \
+                 оставьте один вариант"
+            ));
+        }
+    }
+    let mut keys = Vec::new();
+    for i in (0..).take_while(|i| indexed(*i)) {
+        // `_<i>` как суффикс chain_id: PRIVATE_KEY_8453_1, PRIVATE_KEY_PASSWORD_8453_1
+        let key = resolve_key_source(chain_id, |k| match k.strip_prefix("PRIVATE_KEY") {
+            Some(rest) if rest.ends_with(&chain_id.to_string()) => get(&format!("{k}_{i}")),
+            Some("_PASSWORD") => get(k),
+            _ => None,
+        })
+        .with_context(|| format!("кошелёк {i} (PRIVATE_KEY_{chain_id}_{i})"))?;
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+/// Набор ключей исполнителей сети из реального окружения
+pub fn key_sources_from_env(chain_id: u64) -> Result<Vec<KeySource>> {
+    resolve_key_sources(chain_id, |k| std::env::var(k).ok())
+}
+
+/// Ключ модуля Safe (SAFE_MODULE_KEY_<chain_id>): EOA, включённый модулем на Safe сети.
+/// Если задан, транзакции подписывает он (`execTransactionFromModule`), а не ключ владельца.
+pub fn resolve_safe_module_key(
+    chain_id: u64,
+    get: impl Fn(&str) -> Option<String>,
+) -> Option<KeySource> {
+    get(&format!("SAFE_MODULE_KEY_{}", chain_id))
+        .filter(|v| !v.trim().is_empty())
+        .map(KeySource::RawHex)
+}
+
+pub fn safe_module_key_from_env(chain_id: u64) -> Option<KeySource> {
+    resolve_safe_module_key(chain_id, |k| std::env::var(k).ok())
+}
@@ -1 +1,28 @@
-pub mod dex;
\ No newline at end of file
+// имя пакета (и крейта) — имя проекта, не snake_case
+#![allow(non_snake_case)]
+
+pub mod approvals;
+pub mod backtest;
+pub mod calldata;
+pub mod config;
+pub mod dex;
+pub mod error;
+pub mod exec;
+pub mod forksim;
+pub mod hooks;
+pub mod keys;
+#[cfg(feature = "mempool")]
+pub mod mempool;
+pub mod metrics;
+pub mod mev;
+pub mod network;
+pub mod paper;
+pub mod permit2;
+pub mod pools;
+pub mod prices;
+pub mod report;
+pub mod route;
+pub mod router;
+pub mod tokens;
+pub mod utils;
+pub mod utils_gas;
@@ -1,39 +1,66 @@
-mod approvals;
-mod calldata;
-mod config;
-mod dex;
-mod error;
-mod exec;
-mod metrics;
-mod mev;
-mod network;
-mod route;
-mod router;
-mod utils;
-mod utils_gas;
-
 use anyhow::{Context, Result};
+use futures::FutureExt;
+use futures::stream::{BoxStream, StreamExt};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::prelude::*;
+
+use DeFiArbitraje::config::{Config, ConsoleCfg, LogsCfg, config_source_label, is_config_url};
+#[cfg(feature = "mempool")]
+use DeFiArbitraje::mempool::{PendingSwap, Triggers};
+use DeFiArbitraje::metrics::serve_metrics;
+use DeFiArbitraje::network::{EndpointStatus, MultiChain, NewHead};
+use DeFiArbitraje::route::{RoutePlanner, StrategyEngine};
+use DeFiArbitraje::{metrics, network, report, tokens, utils};
+
+/// Логгер: JSON при telemetry.logs.json; уровень — RUST_LOG (пример: RUST_LOG=info,DeFiArbitraje=debug),
+/// без него — telemetry.logs.level. Фильтр висит на слое логов: слою tokio-console
+/// нужны trace-события рантайма, которые в лог не попадают.
+fn init_logging(logs: &LogsCfg, console: &ConsoleCfg) {
+    let directive = utils::log_directive(std::env::var("RUST_LOG").ok(), &logs.level);
+    let filter = EnvFilter::try_new(&directive).unwrap_or_else(|e| {
+        eprintln!("некорректный уровень логов '{directive}': {e}; использую info");
+        EnvFilter::new("info")
+    });
+    let fmt = if logs.json {
+        tracing_subscriber::fmt::layer().json().boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+    let registry = tracing_subscriber::registry().with(fmt.with_filter(filter));
 
-use crate::config::Config;
-use crate::metrics::serve_metrics;
-use crate::network::MultiChain;
-use crate::route::{RoutePlanner, StrategyEngine};
+    #[cfg(feature = "console")]
+    if console.enabled {
+        match console.bind.parse::<std::net::SocketAddr>() {
+            Ok(addr) => {
+                let layer = console_subscriber::ConsoleLayer::builder().server_addr(addr).spawn();
+                registry.with(layer).init();
+                info!("tokio-console на {addr}");
+            }
+            Err(e) => {
+                registry.init();
+                error!("telemetry.console.bind '{}': {e}; tokio-console выключен", console.bind);
+            }
+        }
+        return;
+    }
+    registry.init();
+    #[cfg(not(feature = "console"))]
+    if console.enabled {
+        tracing::warn!("telemetry.console.enabled, но сборка без feature `console` — пропускаю");
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Логгер: уровень берём из RUST_LOG (пример: RUST_LOG=info,DeFiArbitraje=debug)
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
     // 1) Выбор пути к конфигу: ENV → argv → набор дефолтов (кроссплатформенно)
     let cfg_path = std::env::var("DEFI_CONFIG")
         .ok()
-        .or_else(|| std::env::args().nth(1))
+        .or_else(|| std::env::args().skip(1).find(|a| !a.starts_with("--")))
         .or_else(|| {
             let candidates = [
                 ".\\config\\defi_config.json", // Windows
@@ -48,7 +75,7 @@ async fn main() -> Result<()> {
         })
         .unwrap_or_else(|| ".\\config\\defi_config.json".to_string());
 
-    if !Path::new(&cfg_path).exists() {
+    if !is_config_url(&cfg_path) && !Path::new(&cfg_path).exists() {
         eprintln!(
             "⚠️ Конфиг не найден: {}\nЗапусти так: cargo run -p evm-arb-service -- .\\config\\defi_config.json\nили задай ENV DEFI_CONFIG",
             cfg_path
@@ -56,8 +83,22 @@ async fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    let cfg =
-        Config::load(&cfg_path).with_context(|| format!("loading config from {}", cfg_path))?;
+    // предупреждения загрузки конфига — через временный логгер (формат из конфига ещё не известен)
+    let bootstrap = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .finish();
+    let cfg_label = config_source_label(&cfg_path);
+    let loaded = {
+        let _guard = tracing::subscriber::set_default(bootstrap);
+        Config::load_source(&cfg_path).await
+    };
+    let cfg = loaded.with_context(|| format!("loading config from {cfg_label}"))?;
+    if std::env::args().any(|a| a == "--validate") {
+        let ok = validate_config(&cfg).await;
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+    init_logging(&cfg.telemetry.logs, &cfg.telemetry.console);
+    utils::set_checksum_output(cfg.telemetry.logs.checksum_addresses);
     info!(
         "Загружен конфиг: version={}, networks={}",
         cfg.version,
@@ -66,21 +107,88 @@ async fn main() -> Result<()> {
 
     // 2) Метрики (Prometheus)
     let prom_port = cfg.telemetry.prometheus.port;
+    let stale_after = Duration::from_secs(cfg.telemetry.prometheus.health_stale_secs);
     let metrics_handle = tokio::spawn(async move {
-        if let Err(e) = serve_metrics(prom_port).await {
+        if let Err(e) = serve_metrics(prom_port, stale_after).await {
             eprintln!("metrics server error: {e:#}");
         }
     });
 
+    // /readyz — 503, пока не поднимутся все сети из конфига
+    metrics::expect_networks(cfg.networks.iter().map(|n| n.chain_id));
+
     // 3) Клиенты сетей
     let chains = Arc::new(MultiChain::from_config(&cfg).await?);
     info!("Инициализировано сетей: {}", chains.clients.len());
+    chains.verify_chain_ids().await?;
+    let health_handles = chains.spawn_health_checkers();
+    if cfg.global.verify_tokens {
+        let checks = chains.clients.values().map(tokens::verify_tokens);
+        for res in futures::future::join_all(checks).await {
+            res?;
+        }
+    }
 
     // 4) Планировщик/движок
     let planner = Arc::new(RoutePlanner::from_config(&cfg));
-    let mut engine = StrategyEngine::new(cfg.clone(), chains.clone(), planner.clone()).await?;
+    // `--refresh-approvals` (или REFRESH_APPROVALS=1): игнорировать кэш allowance на старте
+    let refresh_approvals = std::env::args().any(|a| a == "--refresh-approvals")
+        || std::env::var("REFRESH_APPROVALS").is_ok_and(|v| v == "1");
+    let mut engine =
+        StrategyEngine::new(cfg.clone(), chains.clone(), planner.clone(), refresh_approvals)
+            .await?;
+    for chain_id in engine.ready_networks() {
+        metrics::mark_network_ready(chain_id);
+    }
+    // сводка старта: причины по сетям уже в логе движка
+    let mut scan_only: Vec<u64> = engine.scan_only_networks().keys().copied().collect();
+    if !scan_only.is_empty() {
+        scan_only.sort_unstable();
+        warn!("scan-only сети (транзакции не отправляются): {scan_only:?}");
+    }
+    engine.warmup().await;
 
-    let poll_ms = cfg.global.execution.poll_interval_ms as u64;
+    // Hot-reload конфига по SIGHUP (только Unix)
+    let reload_handle = tokio::spawn(reload_on_sighup(
+        cfg_path.clone(),
+        cfg.clone(),
+        engine.reload_handle(),
+    ));
+    // Конфиг по URL: периодическая перезагрузка (config_refresh_sec)
+    let refetch_handle = (is_config_url(&cfg_path) && cfg.config_refresh_sec > 0).then(|| {
+        tokio::spawn(refetch_config(
+            cfg_path.clone(),
+            cfg.clone(),
+            engine.reload_handle(),
+            Duration::from_secs(cfg.config_refresh_sec),
+        ))
+    });
+
+    // Суточная сводка PnL (файл + опционально вебхук)
+    let report_handle = cfg.telemetry.pnl_report.enabled.then(|| {
+        tokio::spawn(report::run_daily_report(
+            cfg.telemetry.pnl_report.clone(),
+            cfg.telemetry.alerts.webhook_url.clone(),
+            engine.daily_pnl(),
+            engine.session_profit(),
+        ))
+    });
+
+    // адаптивный режим: тик по min_ms, а каждая сеть ждёт своего интервала в движке
+    let adaptive = &cfg.global.execution.adaptive_poll;
+    let poll_ms = if adaptive.enabled {
+        adaptive.min_ms
+    } else {
+        cfg.global.execution.poll_interval_ms as u64
+    };
+
+    // WS-подписка на новые блоки (если в rpc есть ws/wss); иначе — только таймер
+    let mut heads = chains.subscribe_new_heads();
+    if heads.is_some() {
+        info!("WS newHeads: скан по новым блокам, poll_interval_ms — как fallback");
+    }
+    // Mempool: внеочередной скан пар по крупным свопам (execution.mempool)
+    let (triggers, mempool_handles) = spawn_mempool(&chains, &cfg)?;
 
     // 5) Главный цикл + корректное завершение по сигналу
     loop {
@@ -90,7 +198,15 @@ async fn main() -> Result<()> {
                 if let Err(e) = engine.scan_and_execute().await {
                     error!("Ошибка в scan_and_execute: {e:#}");
                 }
-                tokio::time::sleep(Duration::from_millis(poll_ms)).await;
+                let wait = wait_next_scan(&mut heads, poll_ms);
+                tokio::pin!(wait);
+                // пока ждём следующего скана — внеочередные сканы по свопам из mempool
+                loop {
+                    tokio::select! {
+                        _ = &mut wait => break,
+                        swap = next_trigger(&triggers) => scan_trigger(&engine, swap).await,
+                    }
+                }
             } => {},
 
             // Ждём сигнала остановки
@@ -101,12 +217,211 @@ async fn main() -> Result<()> {
         }
     }
 
-    // 6) Останавливем фоновую задачу метрик (если ещё живёт)
+    // Новые маршруты больше не отправляем; ждём receipts уже отправленных tx
+    engine.stop_accepting();
+    let drain_timeout = Duration::from_millis(cfg.global.execution.shutdown_drain_timeout_ms);
+    let unconfirmed = engine.drain_pending(drain_timeout).await;
+    if unconfirmed > 0 {
+        error!("shutdown: {} tx остались без receipt — проверьте вручную", unconfirmed);
+    }
+    engine.log_paper_totals();
+
+    // 6) Останавливем фоновые задачи (метрики, reload), если ещё живут
     metrics_handle.abort();
+    reload_handle.abort();
+    for h in health_handles.into_iter().chain(mempool_handles) {
+        h.abort();
+    }
+    for h in report_handle.into_iter().chain(refetch_handle) {
+        h.abort();
+    }
 
     Ok(())
 }
 
+/// `--validate`: строгая проверка конфига и `eth_chainId` каждого RPC-эндпоинта.
+/// Отчёт — в stdout; false — есть ошибки (для CI: ненулевой код выхода)
+async fn validate_config(cfg: &Config) -> bool {
+    let mut ok = true;
+    match cfg.validate_strict() {
+        Ok(()) => println!("config: ok ({} networks)", cfg.networks.len()),
+        Err(e) => {
+            println!("config: FAIL {e:#}");
+            ok = false;
+        }
+    }
+    for (net, endpoint, status) in network::validate_endpoints(cfg).await {
+        match &status {
+            EndpointStatus::Ok { latency_ms } => {
+                println!("{net} {endpoint}: ok ({latency_ms:.0} ms)")
+            }
+            EndpointStatus::WrongChain { got } => {
+                println!("{net} {endpoint}: FAIL wrong chain (eth_chainId={got})")
+            }
+            EndpointStatus::Unreachable(e) => println!("{net} {endpoint}: FAIL {e}"),
+        }
+        ok &= status.is_ok();
+    }
+    ok
+}
+
+/// Ждём новый блок (если есть подписка) или истечение poll-интервала.
+/// Скопившиеся за время скана блоки схлопываем в один триггер.
+async fn wait_next_scan(heads: &mut Option<BoxStream<'static, NewHead>>, poll_ms: u64) {
+    let Some(stream) = heads.as_mut() else {
+        tokio::time::sleep(Duration::from_millis(poll_ms)).await;
+        return;
+    };
+    while let Some(Some(_)) = stream.next().now_or_never() {}
+    tokio::select! {
+        head = stream.next() => match head {
+            Some(h) => tracing::debug!(chain = h.chain_id, block = h.number, "new head"),
+            None => {
+                error!("WS newHeads: все подписки закрыты, переходим на polling");
+                *heads = None;
+            }
+        },
+        _ = tokio::time::sleep(Duration::from_millis(poll_ms)) => {}
+    }
+}
+
+/// Очередь свопов из mempool и задачи наблюдателей
+#[cfg(feature = "mempool")]
+type MempoolWatchers = (Option<Triggers>, Vec<JoinHandle<()>>);
+/// Без feature `mempool` свопов нет: очередь пустого типа
+#[cfg(not(feature = "mempool"))]
+type MempoolWatchers = (Option<std::convert::Infallible>, Vec<JoinHandle<()>>);
+
+/// Наблюдатели mempool по сетям с ws-эндпоинтами (если `execution.mempool.enabled`)
+#[cfg(feature = "mempool")]
+fn spawn_mempool(
+    chains: &MultiChain,
+    cfg: &Config,
+) -> Result<MempoolWatchers> {
+    if !cfg.global.execution.mempool.enabled {
+        return Ok((None, Vec::new()));
+    }
+    let (triggers, handles) = DeFiArbitraje::mempool::spawn_watchers(chains, cfg);
+    info!(
+        "mempool: наблюдение за {} сетями, своп от ${} — внеочередной скан",
+        handles.len(),
+        cfg.global.execution.mempool.min_swap_usd
+    );
+    Ok((Some(triggers), handles))
+}
+
+#[cfg(not(feature = "mempool"))]
+fn spawn_mempool(
+    _chains: &MultiChain,
+    cfg: &Config,
+) -> Result<MempoolWatchers> {
+    if cfg.global.execution.mempool.enabled {
+        anyhow::bail!(
+            "execution.mempool.enabled: сборка без feature `mempool` \
+             (cargo build --features mempool)"
+        );
+    }
+    Ok((None, Vec::new()))
+}
+
+/// Следующий свежий своп из mempool; без наблюдателей — никогда
+#[cfg(feature = "mempool")]
+async fn next_trigger(triggers: &Option<Triggers>) -> PendingSwap {
+    match triggers {
+        Some(triggers) => triggers.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(feature = "mempool"))]
+async fn next_trigger(_triggers: &Option<std::convert::Infallible>) -> std::convert::Infallible {
+    std::future::pending().await
+}
+
+/// Внеочередной скан маршрутов, задетых свопом
+#[cfg(feature = "mempool")]
+async fn scan_trigger(engine: &StrategyEngine, swap: PendingSwap) {
+    if let Err(e) = engine.scan_triggered(&swap).await {
+        error!("Ошибка в scan_triggered: {e:#}");
+    }
+}
+
+#[cfg(not(feature = "mempool"))]
+async fn scan_trigger(_engine: &StrategyEngine, swap: std::convert::Infallible) {
+    match swap {}
+}
+
+/// SIGHUP → перечитать конфиг, провалидировать и положить в слот движка.
+/// Смена набора сетей/chain_id отклоняется (нужен рестарт).
+#[cfg(unix)]
+async fn reload_on_sighup(path: String, base: Config, slot: Arc<Mutex<Option<Config>>>) {
+    use tokio::signal::unix::{SignalKind, signal};
+    let mut hup = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("failed to install SIGHUP handler: {e:#}");
+            return;
+        }
+    };
+    while hup.recv().await.is_some() {
+        info!("SIGHUP: перечитываем конфиг {}", config_source_label(&path));
+        if let Some(next) = load_for_reload(&path, &base).await {
+            info!(
+                "config reload accepted: version={} (применится на следующем скане)",
+                next.version
+            );
+            *slot.lock().unwrap() = Some(next);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn reload_on_sighup(_path: String, _base: Config, _slot: Arc<Mutex<Option<Config>>>) {
+    std::future::pending::<()>().await
+}
+
+/// Новый конфиг для hot-reload; None — ошибка загрузки или несовместимая смена
+/// (движок остаётся на последнем рабочем конфиге)
+async fn load_for_reload(src: &str, base: &Config) -> Option<Config> {
+    let next = match Config::load_source(src).await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("config reload failed: {e:#}");
+            return None;
+        }
+    };
+    if let Err(e) = base.ensure_reload_compatible(&next) {
+        error!("config reload rejected: {e:#}");
+        return None;
+    }
+    Some(next)
+}
+
+/// Периодическая загрузка конфига по URL; в слот движка кладётся только изменившийся
+async fn refetch_config(
+    url: String,
+    base: Config,
+    slot: Arc<Mutex<Option<Config>>>,
+    every: Duration,
+) {
+    let mut last = serde_json::to_value(&base).ok();
+    let mut tick = tokio::time::interval(every);
+    tick.tick().await;
+    loop {
+        tick.tick().await;
+        let Some(next) = load_for_reload(&url, &base).await else {
+            continue;
+        };
+        let value = serde_json::to_value(&next).ok();
+        if value.is_some() && value == last {
+            continue;
+        }
+        info!("config refetch: version={} (применится на следующем скане)", next.version);
+        last = value;
+        *slot.lock().unwrap() = Some(next);
+    }
+}
+
 /// Ожидание Ctrl+C (везде) + SIGTERM (на Unix).
 async fn shutdown_signal() {
     // Всегда ждём Ctrl+C
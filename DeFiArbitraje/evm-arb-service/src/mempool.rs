@@ -0,0 +1,359 @@
+//! Триггер по mempool (`execution.mempool`): pending-транзакции из WS-подписки
+//! разбираются на свопы через роутеры DEX маршрутов; крупный своп по паре из конфига
+//! сразу перекотирует задетые маршруты обычным путём скана (котировка → гейты → исполнение),
+//! не дожидаясь следующего poll.
+//!
+//! Разбираются только однозначные вызовы: V2 `swapExact*` и V3 `exactInputSingle`
+//! (SwapRouter и SwapRouter02). Мультихоп V2 — по первому хопу пути.
+//!
+//! Свопы не копятся: очередь держит последний своп на пару и DEX, а замеченные до
+//! последнего блока сети отбрасываются — этот блок уже покрыт обычным сканом.
+
+use ethers::abi::{ParamType, Token, decode};
+use ethers::types::{Address, H256, Transaction, U256};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::config::Network;
+use crate::report::usd_price_hint;
+
+/// Своп из pending-транзакции через роутер DEX сети
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingSwap {
+    pub tx: H256,
+    pub chain_id: u64,
+    /// Имя DEX из конфига, чей роутер вызван
+    pub dex: String,
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: U256,
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let h = ethers::utils::id(signature);
+    [h[0], h[1], h[2], h[3]]
+}
+
+/// Адреса роутеров DEX (router / swapRouter02 / smartRouter) → имя DEX
+fn routers(net: &Network) -> HashMap<Address, &str> {
+    let mut out = HashMap::new();
+    for dex in &net.dexes {
+        for addr in [&dex.router, &dex.swap_router02, &dex.smart_router].into_iter().flatten() {
+            if let Ok(a) = addr.parse::<Address>() {
+                out.insert(a, dex.name.as_str());
+            }
+        }
+    }
+    out
+}
+
+fn first_hop(path: &Token) -> Option<(Address, Address)> {
+    let path = path.clone().into_array()?;
+    let a = path.first()?.clone().into_address()?;
+    let b = path.get(1)?.clone().into_address()?;
+    Some((a, b))
+}
+
+/// (token_in, token_out, amount_in) из calldata известных методов роутеров
+fn decode_swap_call(input: &[u8], value: U256) -> Option<(Address, Address, U256)> {
+    if input.len() < 4 {
+        return None;
+    }
+    let (sel, args) = input.split_at(4);
+    let path = ParamType::Array(Box::new(ParamType::Address));
+    let u256 = ParamType::Uint(256);
+
+    let v2_exact_in = [
+        "swapExactTokensForTokens(uint256,uint256,address[],address,uint256)",
+        concat!(
+            "swapExactTokensForTokensSupportingFeeOnTransferTokens",
+            "(uint256,uint256,address[],address,uint256)"
+        ),
+        "swapExactTokensForETH(uint256,uint256,address[],address,uint256)",
+        concat!(
+            "swapExactTokensForETHSupportingFeeOnTransferTokens",
+            "(uint256,uint256,address[],address,uint256)"
+        ),
+    ];
+    if v2_exact_in.iter().any(|s| selector(s) == sel) {
+        let t = decode(
+            &[u256.clone(), u256.clone(), path, ParamType::Address, u256],
+            args,
+        )
+        .ok()?;
+        let (a, b) = first_hop(&t[2])?;
+        return Some((a, b, t[0].clone().into_uint()?));
+    }
+    // вход — native из value, путь начинается с wrapped native
+    let v2_eth_in = [
+        "swapExactETHForTokens(uint256,address[],address,uint256)",
+        "swapExactETHForTokensSupportingFeeOnTransferTokens(uint256,address[],address,uint256)",
+    ];
+    if v2_eth_in.iter().any(|s| selector(s) == sel) {
+        let t = decode(&[u256.clone(), path, ParamType::Address, u256], args).ok()?;
+        let (a, b) = first_hop(&t[1])?;
+        return Some((a, b, value));
+    }
+
+    // SwapRouter: (tokenIn, tokenOut, fee, recipient, deadline, amountIn, amountOutMin, limit)
+    // SwapRouter02: то же без deadline
+    let swap_router =
+        "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))";
+    let swap_router02 =
+        "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))";
+    let (fields, amount_at) = if sel == selector(swap_router) {
+        (8, 5)
+    } else if sel == selector(swap_router02) {
+        (7, 4)
+    } else {
+        return None;
+    };
+    let mut params = vec![
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Uint(24),
+        ParamType::Address,
+    ];
+    params.extend((4..fields - 1).map(|_| u256.clone()));
+    params.push(ParamType::Uint(160));
+    let t = decode(&[ParamType::Tuple(params)], args).ok()?;
+    let t = t.into_iter().next()?.into_tuple()?;
+    Some((
+        t[0].clone().into_address()?,
+        t[1].clone().into_address()?,
+        t[amount_at].clone().into_uint()?,
+    ))
+}
+
+/// Своп через роутер одного из DEX сети; None — не роутер или метод не разобран
+pub fn decode_pending_swap(net: &Network, tx: &Transaction) -> Option<PendingSwap> {
+    let to = tx.to?;
+    let dex = *routers(net).get(&to)?;
+    let (token_in, token_out, amount_in) = decode_swap_call(&tx.input, tx.value)?;
+    Some(PendingSwap {
+        tx: tx.hash,
+        chain_id: net.chain_id,
+        dex: dex.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+    })
+}
+
+/// Символ токена сети по адресу
+fn token_symbol(net: &Network, addr: Address) -> Option<&str> {
+    net.tokens
+        .iter()
+        .find(|(_, t)| t.address.parse::<Address>().ok() == Some(addr))
+        .map(|(sym, _)| sym.as_str())
+}
+
+/// Объём свопа в USD по ценовым подсказкам входного токена; None — токен не из конфига
+/// или без цены
+pub fn swap_usd(net: &Network, stables: &[String], swap: &PendingSwap) -> Option<f64> {
+    let sym = token_symbol(net, swap.token_in)?;
+    let decimals = net.tokens[sym].decimals;
+    let px = usd_price_hint(net, stables, sym)?;
+    let amount = ethers::utils::format_units(swap.amount_in, decimals as u32).ok()?;
+    Some(amount.parse::<f64>().ok()? * px)
+}
+
+/// Индексы `routes_cross_dex`, которые задевает своп: та же пара (в любом направлении)
+/// и DEX свопа среди DEX маршрута
+pub fn affected_routes(net: &Network, swap: &PendingSwap) -> Vec<usize> {
+    let (Some(a), Some(b)) = (token_symbol(net, swap.token_in), token_symbol(net, swap.token_out))
+    else {
+        return Vec::new();
+    };
+    let Some(routes) = &net.routes_cross_dex else {
+        return Vec::new();
+    };
+    routes
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| {
+            let same_pair = (r.pair[0].eq_ignore_ascii_case(a) && r.pair[1].eq_ignore_ascii_case(b))
+                || (r.pair[0].eq_ignore_ascii_case(b) && r.pair[1].eq_ignore_ascii_case(a));
+            same_pair && r.dexes.iter().any(|d| d.eq_ignore_ascii_case(&swap.dex))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Не чаще одного внеочередного скана маршрута за `cooldown`: поток свопов по одной
+/// паре не должен превращаться в поток одинаковых котировок
+#[derive(Debug)]
+pub struct TriggerThrottle {
+    cooldown: Duration,
+    last: HashMap<(u64, usize), Instant>,
+}
+
+impl TriggerThrottle {
+    pub fn new(cooldown: Duration) -> Self {
+        Self { cooldown, last: HashMap::new() }
+    }
+
+    /// Маршруты из `routes`, которым можно внеочередной скан на момент `now`
+    pub fn admit(&mut self, chain_id: u64, routes: &[usize], now: Instant) -> Vec<usize> {
+        let mut admitted = Vec::new();
+        for &i in routes {
+            let last = self.last.get(&(chain_id, i));
+            if last.is_some_and(|last| now.duration_since(*last) < self.cooldown) {
+                continue;
+            }
+            self.last.insert((chain_id, i), now);
+            admitted.push(i);
+        }
+        admitted
+    }
+}
+
+/// Ключ слияния свопов: сеть, DEX и пара (в любом направлении)
+type TriggerKey = (u64, String, Address, Address);
+
+/// Очередь внеочередных сканов: на пару и DEX — один, последний своп; размер ограничен
+/// числом пар. Своп помнит блок сети, на котором замечен, и после следующего блока
+/// не отдаётся: его уже учёл обычный скан этого блока
+#[derive(Debug, Default)]
+pub struct TriggerQueue {
+    swaps: HashMap<TriggerKey, (PendingSwap, u64)>,
+    order: VecDeque<TriggerKey>,
+    heads: HashMap<u64, u64>,
+}
+
+impl TriggerQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Новый блок сети
+    pub fn on_head(&mut self, chain_id: u64, number: u64) {
+        let head = self.heads.entry(chain_id).or_default();
+        *head = (*head).max(number);
+    }
+
+    /// Своп из mempool; ещё не взятый своп по той же паре и DEX заменяется
+    pub fn push(&mut self, swap: PendingSwap) {
+        let seen = self.heads.get(&swap.chain_id).copied().unwrap_or(0);
+        let (a, b) = if swap.token_in < swap.token_out {
+            (swap.token_in, swap.token_out)
+        } else {
+            (swap.token_out, swap.token_in)
+        };
+        let key = (swap.chain_id, swap.dex.to_lowercase(), a, b);
+        if self.swaps.insert(key.clone(), (swap, seen)).is_none() {
+            self.order.push_back(key);
+        }
+    }
+
+    /// Следующий своп в порядке поступления; замеченные до последнего блока сети
+    /// отбрасываются
+    pub fn pop(&mut self) -> Option<PendingSwap> {
+        while let Some(key) = self.order.pop_front() {
+            let Some((swap, seen)) = self.swaps.remove(&key) else {
+                continue;
+            };
+            if self.heads.get(&swap.chain_id).is_none_or(|head| *head <= seen) {
+                return Some(swap);
+            }
+            tracing::debug!(chain = swap.chain_id, "mempool: своп {:?} устарел", swap.tx);
+        }
+        None
+    }
+
+    pub fn len(&self) -> usize {
+        self.swaps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.swaps.is_empty()
+    }
+}
+
+pub use watcher::{Triggers, spawn_watchers};
+
+mod watcher {
+    use super::*;
+    use crate::config::Config;
+    use crate::metrics::METRIC_MEMPOOL_TRIGGERS;
+    use crate::network::MultiChain;
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::Notify;
+    use tokio::task::JoinHandle;
+
+    /// Общая очередь свопов наблюдателей (см. `TriggerQueue`)
+    #[derive(Clone, Default)]
+    pub struct Triggers {
+        queue: Arc<Mutex<TriggerQueue>>,
+        notify: Arc<Notify>,
+    }
+
+    impl Triggers {
+        /// Следующий свежий своп; ждёт, пока очередь пуста
+        pub async fn next(&self) -> PendingSwap {
+            loop {
+                if let Some(swap) = self.queue.lock().unwrap().pop() {
+                    return swap;
+                }
+                self.notify.notified().await;
+            }
+        }
+
+        fn on_head(&self, chain_id: u64, number: u64) {
+            self.queue.lock().unwrap().on_head(chain_id, number);
+        }
+
+        fn push(&self, swap: PendingSwap) {
+            self.queue.lock().unwrap().push(swap);
+            self.notify.notify_one();
+        }
+    }
+
+    /// Наблюдатели mempool по сетям с ws-эндпоинтами; в очередь уходят крупные свопы,
+    /// задевающие хотя бы один маршрут сети. Блоки сети — из той же ws-подписки
+    pub fn spawn_watchers(chains: &MultiChain, cfg: &Config) -> (Triggers, Vec<JoinHandle<()>>) {
+        let triggers = Triggers::default();
+        let mut handles = Vec::new();
+        for client in chains.clients.values() {
+            let (Some(mut pending), Some(mut heads)) =
+                (client.subscribe_pending_txs(), client.subscribe_blocks())
+            else {
+                let chain = client.cfg.chain_id;
+                tracing::warn!(chain, "mempool: нет ws-эндпоинтов — сеть пропущена");
+                continue;
+            };
+            let net = client.cfg.clone();
+            let stables = cfg.global.risk.stables.clone();
+            let min_usd = cfg.global.execution.mempool.min_swap_usd;
+            let triggers = triggers.clone();
+            handles.push(tokio::spawn(async move {
+                loop {
+                    let pending_tx = tokio::select! {
+                        Some(head) = heads.next() => {
+                            triggers.on_head(head.chain_id, head.number);
+                            continue;
+                        }
+                        pending_tx = pending.next() => match pending_tx {
+                            Some(pending_tx) => pending_tx,
+                            None => return,
+                        },
+                    };
+                    let Some(swap) = decode_pending_swap(&net, &pending_tx) else {
+                        continue;
+                    };
+                    if swap_usd(&net, &stables, &swap).is_none_or(|usd| usd < min_usd)
+                        || affected_routes(&net, &swap).is_empty()
+                    {
+                        continue;
+                    }
+                    METRIC_MEMPOOL_TRIGGERS
+                        .with_label_values(&[&net.chain_id.to_string(), &swap.dex])
+                        .inc();
+                    triggers.push(swap);
+                }
+            }));
+        }
+        (triggers, handles)
+    }
+}
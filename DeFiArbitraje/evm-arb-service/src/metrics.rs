@@ -2,10 +2,20 @@ use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Response, Server, StatusCode};
 use lazy_static::lazy_static;
 use prometheus::{
-    Counter, CounterVec, GaugeVec, IntCounter, IntGauge, TextEncoder, register_counter,
-    register_counter_vec, register_gauge_vec, register_int_counter, register_int_gauge,
+    Counter, CounterVec, Gauge, GaugeVec, HistogramVec, IntCounter, IntGauge, TextEncoder,
+    register_counter, register_counter_vec, register_gauge, register_gauge_vec,
+    register_histogram_vec, register_int_counter, register_int_gauge,
 };
+use std::collections::BTreeSet;
 use std::convert::Infallible;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Бакеты латентности: от 5 мс до 30 с
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
 
 lazy_static! {
     pub static ref METRIC_ROUTES_SCANNED: IntCounter = register_int_counter!(
@@ -13,6 +23,13 @@ lazy_static! {
         "Total number of routes scanned"
     ).expect("register routes_scanned_total");
 
+    /// Пропущенные маршруты по причине (метки — `route::SkipReason`)
+    pub static ref METRIC_ROUTES_SKIPPED: CounterVec = register_counter_vec!(
+        "routes_skipped_total",
+        "Routes skipped during a scan, by chain and reason",
+        & ["chain", "reason"]
+    ).expect("register routes_skipped_total");
+
     pub static ref METRIC_PROFITABLE_FOUND: IntCounter = register_int_counter!(
         "profitable_routes_found_total",
         "Total profitable routes found"
@@ -23,19 +40,33 @@ lazy_static! {
         "Total transactions submitted"
     ).expect("register tx_sent_total");
 
+    /// Отправки по кошелькам исполнителя (ротация `PRIVATE_KEY_<chain>_<i>`)
+    pub static ref METRIC_WALLET_TX_SENT: CounterVec = register_counter_vec!(
+        "wallet_tx_sent_total",
+        "Transactions submitted per chain and signer wallet",
+        & ["chain", "wallet"]
+    ).expect("register wallet_tx_sent_total");
+
     /// Целое значение; храните PnL*100 (или *10000) — как решите в коде.
     pub static ref METRIC_PNL_USD: IntGauge = register_int_gauge!(
         "pnl_usd_total",
         "Cumulative PnL in USD (scaled integer)"
     ).expect("register pnl_usd_total");
 
-    /// Аптайм/простой health-гейдж: 1 = OK, 0 = starting/issue
+    /// Health-гейдж: 1 = скан завершался в пределах окна свежести, 0 = старт/завис/RPC лежат
     pub static ref METRIC_HEALTH: IntGauge = register_int_gauge!(
         "service_health",
         "Service health indicator (1=OK)"
     ).expect("register service_health");
 
-    /// Для наглядности — «последний скрейп» в unix-милисекундах
+    /// Сколько секунд назад был последний успешный скан (-1 — ещё не было)
+    pub static ref METRIC_LAST_SCAN_AGE: Gauge = register_gauge!(
+        "last_successful_scan_age_seconds",
+        "Seconds since the last successful scan (-1 = none yet)"
+    ).expect("register last_successful_scan_age_seconds");
+
+    /// «Последний скрейп» в unix-миллисекундах; по нему работает dead-man's switch
+    /// (`safety.halt_on_scrape_gap_secs`)
     pub static ref METRIC_LAST_SCRAPE_MS: IntGauge = register_int_gauge!(
         "metrics_last_scrape_ms",
         "Last /metrics scrape time (unix ms)"
@@ -58,28 +89,348 @@ lazy_static! {
         & ["chain"]
     ).expect("register last_sim_gas");
 
+    /// Прибыль последней eth_call-симуляции маршрута (в токене входа)
+    pub static ref METRIC_SIM_PROFIT: GaugeVec = register_gauge_vec!(
+        "last_sim_profit",
+        "Profit returned by the last executor simulate() call, in input token units, by chain",
+        & ["chain"]
+    ).expect("register last_sim_profit");
+
     pub static ref METRIC_EXEC_OK: CounterVec = register_counter_vec!(
         "exec_success_total",
         "Total successful executions by chain",
         & ["chain"]
     ).expect("register exec_success_total");
 
+    pub static ref METRIC_RPC_LATENCY_MS: GaugeVec = register_gauge_vec!(
+        "rpc_endpoint_latency_ms",
+        "Last measured eth_blockNumber latency by chain and endpoint (ms)",
+        & ["chain", "endpoint"]
+    ).expect("register rpc_endpoint_latency_ms");
+
+    /// 1 = эндпоинт сейчас выбран для сети, 0 = нет
+    pub static ref METRIC_RPC_ACTIVE_ENDPOINT: GaugeVec = register_gauge_vec!(
+        "rpc_active_endpoint",
+        "Currently selected RPC endpoint by chain (1=active)",
+        & ["chain", "endpoint"]
+    ).expect("register rpc_active_endpoint");
+
+    /// Доля попаданий в кэш адресов пулов (factory→pool) с момента старта
+    pub static ref METRIC_POOL_CACHE_HIT_RATE: GaugeVec = register_gauge_vec!(
+        "pool_cache_hit_rate",
+        "Pool address cache hit rate by chain (0..1)",
+        & ["chain"]
+    ).expect("register pool_cache_hit_rate");
+
+    pub static ref METRIC_LEG_QUOTE_TIMEOUTS: CounterVec = register_counter_vec!(
+        "leg_quote_timeouts_total",
+        "Route legs skipped because the quote timed out, by chain",
+        & ["chain"]
+    ).expect("register leg_quote_timeouts_total");
+
+    /// Пропуски котировок маршрутов по причине (no_pool / zero_output / below_min_out / ...)
+    pub static ref METRIC_QUOTE_OUTCOMES: CounterVec = register_counter_vec!(
+        "quote_outcomes_total",
+        "Route quotes that produced no result, by chain and reason",
+        & ["chain", "reason"]
+    ).expect("register quote_outcomes_total");
+
+    /// Прибыль с начала сессии в base_token сети (в единицах токена)
+    pub static ref METRIC_SESSION_PROFIT_BASE: GaugeVec = register_gauge_vec!(
+        "session_profit_base",
+        "Session profit converted to the chain base token",
+        & ["chain", "token"]
+    ).expect("register session_profit_base");
+
+    pub static ref METRIC_SCAN_DURATION: HistogramVec = register_histogram_vec!(
+        "scan_duration_seconds",
+        "Duration of one network scan (seconds)",
+        &["chain"],
+        LATENCY_BUCKETS.to_vec()
+    ).expect("register scan_duration_seconds");
+
+    /// Сколько блоков прошло от котировки маршрута до проверки перед исполнением
+    pub static ref METRIC_QUOTE_AGE_BLOCKS: HistogramVec = register_histogram_vec!(
+        "quote_age_blocks",
+        "Blocks between route quote and the pre-execution check",
+        &["chain"],
+        vec![0.0, 1.0, 2.0, 3.0, 5.0, 10.0]
+    ).expect("register quote_age_blocks");
+
+    pub static ref METRIC_QUOTE_DURATION: HistogramVec = register_histogram_vec!(
+        "quote_duration_seconds",
+        "Duration of a single-leg quote by chain and dex (seconds)",
+        &["chain", "dex"],
+        LATENCY_BUCKETS.to_vec()
+    ).expect("register quote_duration_seconds");
+
+    pub static ref METRIC_RPC_CALLS: CounterVec = register_counter_vec!(
+        "rpc_calls_total",
+        "RPC calls (including retries) by chain and method",
+        & ["chain", "method"]
+    ).expect("register rpc_calls_total");
+
+    pub static ref METRIC_RPC_BATCHED_CALLS: CounterVec = register_counter_vec!(
+        "rpc_batched_calls_total",
+        "JSON-RPC calls sent inside batch requests (one HTTP request per batch)",
+        & ["chain"]
+    ).expect("register rpc_batched_calls_total");
+
+    pub static ref METRIC_RPC_FAILOVERS: CounterVec = register_counter_vec!(
+        "rpc_failovers_total",
+        "RPC endpoint switches after a failed call, by chain",
+        & ["chain"]
+    ).expect("register rpc_failovers_total");
+
+    pub static ref METRIC_RPC_LAG_SKIPS: CounterVec = register_counter_vec!(
+        "rpc_lagging_endpoint_skips_total",
+        "Failover targets skipped for lagging behind the best-seen block, by chain",
+        & ["chain"]
+    ).expect("register rpc_lagging_endpoint_skips_total");
+
+    pub static ref METRIC_PINNED_BLOCK_FALLBACKS: CounterVec = register_counter_vec!(
+        "pinned_block_fallbacks_total",
+        "Quote reads retried at latest because endpoints lag behind the pinned block",
+        & ["chain"]
+    ).expect("register pinned_block_fallbacks_total");
+
     pub static ref METRIC_EXEC_FAIL: CounterVec = register_counter_vec!(
         "exec_fail_total",
         "Total failed executions by chain",
         & ["chain"]
     ).expect("register exec_fail_total");
+
+    pub static ref METRIC_SLIPPAGE_HALTS: CounterVec = register_counter_vec!(
+        "slippage_halts_total",
+        "Network halts after realized slippage exceeded halt_on_large_slippage_bps, by chain",
+        & ["chain"]
+    ).expect("register slippage_halts_total");
+
+    /// Маршруты с прибылью по стратегии, выбранной среди совпавших (лучшая PnL)
+    pub static ref METRIC_STRATEGY_SELECTED: CounterVec = register_counter_vec!(
+        "strategy_selected_total",
+        "Profitable routes by the strategy chosen among all matching ones",
+        & ["chain", "strategy"]
+    ).expect("register strategy_selected_total");
+
+    /// Режим сети: mode="execute" — исполнитель поднят, "scan_only" — только котировки
+    pub static ref METRIC_EXECUTOR_MODE: GaugeVec = register_gauge_vec!(
+        "executor_mode",
+        "1 for the current execution mode of the chain (execute / scan_only)",
+        & ["chain", "mode"]
+    ).expect("register executor_mode");
+
+    /// Алерты, вытесненные из очереди доставки (вебхук долго недоступен)
+    pub static ref METRIC_ALERTS_DROPPED: IntCounter = register_int_counter!(
+        "alerts_dropped_total",
+        "Alerts dropped from the full delivery queue"
+    ).expect("register alerts_dropped_total");
+
+    pub static ref METRIC_ROUTE_COOLDOWN: GaugeVec = register_gauge_vec!(
+        "route_cooldown_active",
+        "1 while the route is paused after consecutive losses, by chain and route",
+        & ["chain", "route"]
+    ).expect("register route_cooldown_active");
+
+    pub static ref METRIC_NETWORK_HALTED: GaugeVec = register_gauge_vec!(
+        "network_halted",
+        "1 while executions on the chain are halted by the slippage guard",
+        & ["chain"]
+    ).expect("register network_halted");
+
+    /// 1, пока цена газа сети выше `max_gas_price_gwei`
+    pub static ref METRIC_GAS_CEILING_HALT: GaugeVec = register_gauge_vec!(
+        "gas_ceiling_halted",
+        "1 while executions on the chain are paused by the gas price ceiling",
+        & ["chain"]
+    ).expect("register gas_ceiling_halted");
+
+    /// Валовой спред круга пары (до газа и слиппеджа), в т.ч. ниже порога исполнения;
+    /// метки — только пары из конфига
+    pub static ref METRIC_PAIR_SPREAD_BPS: GaugeVec = register_gauge_vec!(
+        "pair_spread_bps",
+        "Gross round-trip spread of the last scanned cross-DEX route, by chain and pair",
+        & ["chain", "pair"]
+    ).expect("register pair_spread_bps");
+
+    /// Бумажная торговля (`telemetry.paper_trading`): сделок в журнале и их суммарная PnL
+    pub static ref METRIC_PAPER_FILLS: GaugeVec = register_gauge_vec!(
+        "paper_fills",
+        "Hypothetical fills recorded in the paper-trading ledger, by chain",
+        & ["chain"]
+    ).expect("register paper_fills");
+
+    pub static ref METRIC_PAPER_PNL_USD: GaugeVec = register_gauge_vec!(
+        "paper_pnl_usd",
+        "Cumulative estimated net PnL of paper-trading fills in USD, by chain",
+        & ["chain"]
+    ).expect("register paper_pnl_usd");
+
+    /// Внеочередные сканы по крупным свопам из mempool (`execution.mempool`)
+    pub static ref METRIC_MEMPOOL_TRIGGERS: CounterVec = register_counter_vec!(
+        "mempool_triggers_total",
+        "Large pending swaps on watched DEX routers that triggered a targeted re-quote",
+        & ["chain", "dex"]
+    ).expect("register mempool_triggers_total");
+
+    pub static ref METRIC_VOLATILITY_INDEX: GaugeVec = register_gauge_vec!(
+        "volatility_index",
+        "Coefficient of variation of the native USD price over the last polls, by chain",
+        & ["chain"]
+    ).expect("register volatility_index");
+
+    /// Остаток суточного лимита объёма сети (daily_notional_cap_usd)
+    pub static ref METRIC_NOTIONAL_REMAINING: GaugeVec = register_gauge_vec!(
+        "daily_notional_remaining_usd",
+        "Remaining rolling 24h notional budget in USD, by chain",
+        & ["chain"]
+    ).expect("register daily_notional_remaining_usd");
+
+    pub static ref METRIC_REVERT_RETRIES: CounterVec = register_counter_vec!(
+        "revert_retries_total",
+        "Route resubmissions after a reverted transaction, by chain",
+        & ["chain"]
+    ).expect("register revert_retries_total");
+}
+
+/// Unix-мс последнего успешного скана (0 — ещё не было); пишет движок каждый цикл
+static LAST_SCAN_OK_MS: AtomicU64 = AtomicU64::new(0);
+
+pub fn unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Heartbeat движка: скан завершился (хотя бы одна сеть отсканирована)
+pub fn scan_heartbeat() {
+    LAST_SCAN_OK_MS.store(unix_ms(), Ordering::Relaxed);
+}
+
+/// Возраст последнего успешного скана; None — сканов ещё не было
+pub fn scan_age(last_ok_ms: u64, now_ms: u64) -> Option<Duration> {
+    (last_ok_ms > 0).then(|| Duration::from_millis(now_ms.saturating_sub(last_ok_ms)))
+}
+
+/// Живость для /healthz: был скан и он не старше `stale_after`
+pub fn scan_is_fresh(last_ok_ms: u64, now_ms: u64, stale_after: Duration) -> bool {
+    scan_age(last_ok_ms, now_ms).is_some_and(|age| age <= stale_after)
 }
 
-/// HTTP-хендлер: роутим /metrics и /healthz
-async fn http_handler(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+/// Dead-man's switch: сколько `/metrics` не скрейпили, если дольше `window`.
+/// До первого скрейпа отсчёт идёт от `since_ms` (старт движка) — окно служит грейсом
+pub fn scrape_stalled(
+    last_scrape_ms: u64,
+    since_ms: u64,
+    now_ms: u64,
+    window: Duration,
+) -> Option<Duration> {
+    let gap = Duration::from_millis(now_ms.saturating_sub(last_scrape_ms.max(since_ms)));
+    (gap > window).then_some(gap)
+}
+
+/// `scrape_stalled` по `METRIC_LAST_SCRAPE_MS` на текущий момент
+pub fn scrape_gap(since_ms: u64, window: Duration) -> Option<Duration> {
+    let last = METRIC_LAST_SCRAPE_MS.get().max(0) as u64;
+    scrape_stalled(last, since_ms, unix_ms(), window)
+}
+
+/// Обновляет гейджи здоровья по heartbeat и возвращает живость
+fn refresh_health(stale_after: Duration) -> bool {
+    let last = LAST_SCAN_OK_MS.load(Ordering::Relaxed);
+    let now = unix_ms();
+    let fresh = stale_after.is_zero() || scan_is_fresh(last, now, stale_after);
+    METRIC_HEALTH.set(fresh as i64);
+    METRIC_LAST_SCAN_AGE.set(scan_age(last, now).map_or(-1.0, |a| a.as_secs_f64()));
+    fresh
+}
+
+/// Готовность сервиса (/readyz): какие сети ожидаются и какие уже поднялись
+#[derive(Clone, Debug, Default)]
+pub struct Readiness {
+    /// None — main ещё не объявил набор сетей
+    expected: Option<BTreeSet<u64>>,
+    ready: BTreeSet<u64>,
+}
+
+impl Readiness {
+    pub fn expect(&mut self, chain_ids: impl IntoIterator<Item = u64>) {
+        self.expected = Some(chain_ids.into_iter().collect());
+    }
+
+    pub fn mark_ready(&mut self, chain_id: u64) {
+        self.ready.insert(chain_id);
+    }
+
+    /// Все объявленные сети поднялись (клиент и, где задан, экзекутор)
+    pub fn is_ready(&self) -> bool {
+        self.expected
+            .as_ref()
+            .is_some_and(|exp| exp.is_subset(&self.ready))
+    }
+
+    /// Объявленные сети, которые ещё не готовы
+    pub fn pending(&self) -> Vec<u64> {
+        self.expected
+            .as_ref()
+            .map(|exp| exp.difference(&self.ready).copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+lazy_static! {
+    static ref READINESS: Mutex<Readiness> = Mutex::new(Readiness::default());
+}
+
+/// Объявляет сети из конфига: пока все не отмечены готовыми, /readyz отдаёт 503
+pub fn expect_networks(chain_ids: impl IntoIterator<Item = u64>) {
+    READINESS.lock().unwrap().expect(chain_ids);
+}
+
+pub fn mark_network_ready(chain_id: u64) {
+    READINESS.lock().unwrap().mark_ready(chain_id);
+}
+
+fn readyz_response() -> Response<Body> {
+    let r = READINESS.lock().unwrap().clone();
+    let (status, body) = if r.is_ready() {
+        (StatusCode::OK, "ready".to_string())
+    } else if r.expected.is_none() {
+        (StatusCode::SERVICE_UNAVAILABLE, "starting".to_string())
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, format!("pending chains: {:?}", r.pending()))
+    };
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// HTTP-хендлер: роутим /metrics, /healthz (живость) и /readyz (готовность)
+async fn http_handler(
+    req: Request<Body>,
+    stale_after: Duration,
+) -> Result<Response<Body>, Infallible> {
     match req.uri().path() {
-        "/metrics" => metrics_response().await,
-        "/healthz" => Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header(hyper::header::CONTENT_TYPE, "text/plain; charset=utf-8")
-            .body(Body::from("ok"))
-            .unwrap()),
+        "/metrics" => {
+            refresh_health(stale_after);
+            metrics_response().await
+        }
+        "/healthz" => {
+            let (status, body) = if refresh_health(stale_after) {
+                (StatusCode::OK, "ok")
+            } else {
+                (StatusCode::SERVICE_UNAVAILABLE, "stale: no successful scan")
+            };
+            Ok(Response::builder()
+                .status(status)
+                .header(hyper::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(Body::from(body))
+                .unwrap())
+        }
+        "/readyz" => Ok(readyz_response()),
         _ => Ok(Response::builder()
             .status(StatusCode::NOT_FOUND)
             .header(hyper::header::CONTENT_TYPE, "text/plain; charset=utf-8")
@@ -89,13 +440,8 @@ async fn http_handler(req: Request<Body>) -> Result<Response<Body>, Infallible>
 }
 
 async fn metrics_response() -> Result<Response<Body>, Infallible> {
-    // Проставим «здоровье» и отметим момент скрейпа:
-    METRIC_HEALTH.set(1);
-    let now_ms = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as i64;
-    METRIC_LAST_SCRAPE_MS.set(now_ms);
+    // Отметим момент скрейпа:
+    METRIC_LAST_SCRAPE_MS.set(unix_ms() as i64);
 
     // Сериализация реестра в текст OpenMetrics/Prometheus:
     let encoder = TextEncoder::new();
@@ -124,13 +470,19 @@ async fn metrics_response() -> Result<Response<Body>, Infallible> {
 }
 
 /// Поднимаем отдельный HTTP-сервер метрик.
-/// Вызывается из main: `tokio::spawn(serve_metrics(port));`
-pub async fn serve_metrics(port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Вызывается из main: `tokio::spawn(serve_metrics(port, stale_after));`
+/// `/healthz` отдаёт 503, если успешного скана не было дольше `stale_after`.
+pub async fn serve_metrics(
+    port: u16,
+    stale_after: Duration,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let addr = ([0, 0, 0, 0], port).into();
-    let make_svc = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(http_handler)) });
+    let make_svc = make_service_fn(move |_| async move {
+        Ok::<_, Infallible>(service_fn(move |req| http_handler(req, stale_after)))
+    });
     let server = Server::bind(&addr).serve(make_svc);
 
-    tracing::info!("Prometheus /metrics on http://0.0.0.0:{port}/metrics  (/healthz too)");
+    tracing::info!("Prometheus /metrics on http://0.0.0.0:{port}/metrics  (/healthz, /readyz too)");
     server.await?;
     Ok(())
 }
@@ -1,29 +1,562 @@
-use crate::config::{Config, Network};
-use anyhow::{anyhow, Result};
-use ethers::providers::{Http, Provider, ProviderError};
+use crate::config::{Config, Network, RpcCfg};
+use crate::pools::{POOLS_STALE_AFTER_SECS, PoolsFile};
+use crate::prices::{ChainlinkOracle, PriceOracle};
+use crate::metrics::{
+    METRIC_PINNED_BLOCK_FALLBACKS, METRIC_POOL_CACHE_HIT_RATE, METRIC_RPC_ACTIVE_ENDPOINT,
+    METRIC_RPC_BATCHED_CALLS, METRIC_RPC_CALLS, METRIC_RPC_FAILOVERS, METRIC_RPC_LAG_SKIPS,
+    METRIC_RPC_LATENCY_MS,
+};
+use crate::utils_gas::{GasPriceMode, cap_gas_price, gas_price_from_base_fee};
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use rand::Rng as _;
+use ethers::providers::{
+    Http, HttpClientError, JsonRpcError, Middleware, Provider, ProviderError, Ws,
+};
+use ethers::types::{Address, Block, BlockId, BlockNumber, TxHash, U256, U64};
+use serde_json::{Value, json};
+use futures::stream::{self, BoxStream, StreamExt};
 use std::{
     collections::HashMap,
     future::Future,
-    sync::{Arc, Mutex},
-    time::Duration,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
-use tracing::warn;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 #[derive(Clone)]
 pub struct ChainClient {
     pub cfg: Network,
     endpoints: Vec<String>,
+    providers: Vec<Arc<Provider<Http>>>,
+    // ws:// / wss:// эндпоинты — только для подписки на новые блоки
+    ws_endpoints: Vec<String>,
+    rpc: RpcCfg,
+    budget: Arc<RpcBudget>,
+    pool_cache: Arc<PoolCache>,
+    // token bucket на каждый http-эндпоинт; общий для всех клонов клиента
+    limiters: Arc<Vec<Mutex<TokenBucket>>>,
+    // USD-цены токенов (Chainlink + статические подсказки)
+    prices: Arc<dyn PriceOracle>,
+    // блок, к которому привязаны котировки скана (None — latest)
+    pinned: Option<u64>,
+    // цена газа из batch-снимка начала скана (None — запрашивается на котировке)
+    scan_gas_price: Option<U256>,
+    // RPC копии идут из слотов исполнения (`for_exec`), а не из квоты котировок
+    exec_priority: bool,
+    // общий http-клиент batch-запросов (провайдеры ethers шлют по одному вызову)
+    http: reqwest::Client,
     inner: Arc<Mutex<ClientState>>,
 }
 
+/// Token bucket: `rate` запросов/сек, всплеск до `capacity`. `rate <= 0` — без лимита.
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    rate: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: f64, burst: u32, now: Instant) -> Self {
+        let capacity = if burst > 0 { burst as f64 } else { rate.max(1.0) };
+        Self {
+            capacity,
+            tokens: capacity,
+            rate,
+            last: now,
+        }
+    }
+
+    /// Забрать токен; Err(wait) — сколько подождать до следующего
+    pub fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        if self.rate <= 0.0 {
+            return Ok(());
+        }
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// JSON-RPC коды «лимит запросов»: -32005 (EIP-1474 limit exceeded) и 429, которым
+/// часть провайдеров дублирует HTTP-статус в теле ответа
+const RATE_LIMIT_CODES: [i64; 2] = [-32005, 429];
+
+fn is_429(e: &reqwest::Error) -> bool {
+    e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Провайдер ответил 429: HTTP-статус ответа или код JSON-RPC ошибки
+pub fn is_rate_limited(err: &anyhow::Error) -> bool {
+    let client_err = match err.downcast_ref::<ProviderError>() {
+        Some(ProviderError::HTTPError(e)) => return is_429(e),
+        Some(ProviderError::JsonRpcClientError(e)) => {
+            if let Some(resp) = e.as_error_response() {
+                return RATE_LIMIT_CODES.contains(&resp.code);
+            }
+            let e: &(dyn std::error::Error + 'static) = e.as_ref();
+            e.downcast_ref::<HttpClientError>()
+        }
+        _ => err.downcast_ref::<HttpClientError>(),
+    };
+    match client_err {
+        Some(HttpClientError::ReqwestError(e)) => is_429(e),
+        Some(HttpClientError::JsonRpcError(e)) => RATE_LIMIT_CODES.contains(&e.code),
+        _ => err.downcast_ref::<reqwest::Error>().is_some_and(is_429),
+    }
+}
+
+/// Эндпоинт ещё не видит запрошенный блок (отстаёт от закреплённого)
+pub fn is_block_lag_error(err: &anyhow::Error) -> bool {
+    let text = format!("{err:#}").to_ascii_lowercase();
+    text.contains("header not found")
+        || text.contains("unknown block")
+        || text.contains("block not found")
+}
+
+/// Простые чтения начала скана из одного batch-запроса (`rpc.batch_requests`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScanReads {
+    pub block: u64,
+    /// Цена газа котировок скана по `gas_price_mode`
+    pub gas_price: U256,
+}
+
+/// Тело batch-запроса: массив вызовов с id = индекс в `calls`
+pub fn batch_request_body(calls: &[(&str, Value)]) -> Value {
+    Value::Array(
+        calls
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+            })
+            .collect(),
+    )
+}
+
+/// Ответ батча по порядку вызовов (узел вправе переставить элементы — сверяем id).
+/// Ошибка любого вызова или пропущенный ответ — ошибка всего батча.
+pub fn parse_batch_response(reply: Value, n: usize) -> Result<Vec<Value>, HttpClientError> {
+    let malformed = |text: String| {
+        let err = <serde_json::Error as serde::de::Error>::custom("malformed batch response");
+        HttpClientError::SerdeJson { err, text }
+    };
+    let items = match reply {
+        Value::Array(items) => items,
+        // на весь батч узел отвечает одиночной ошибкой (например, батчи запрещены)
+        Value::Object(ref obj) if obj.contains_key("error") => {
+            let err: JsonRpcError = serde_json::from_value(obj["error"].clone())
+                .map_err(|_| malformed(reply.to_string()))?;
+            return Err(HttpClientError::JsonRpcError(err));
+        }
+        other => return Err(malformed(other.to_string())),
+    };
+    let mut out: Vec<Option<Value>> = vec![None; n];
+    for mut item in items {
+        if let Some(err) = item.get("error") {
+            let err: JsonRpcError =
+                serde_json::from_value(err.clone()).map_err(|_| malformed(item.to_string()))?;
+            return Err(HttpClientError::JsonRpcError(err));
+        }
+        let slot = item["id"].as_u64().and_then(|id| out.get_mut(id as usize));
+        match (slot, item.get_mut("result")) {
+            (Some(slot), Some(result)) => *slot = Some(result.take()),
+            _ => return Err(malformed(item.to_string())),
+        }
+    }
+    out.into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| malformed(format!("{n} calls, not all answered")))
+}
+
+/// Снимок скана из ответов `scan_reads`: eip1559 — из base fee последнего блока
+/// (без оценки eth_feeHistory: лишний запрос съел бы выигрыш батча), иначе eth_gasPrice;
+/// потолок `max_fee_gwei` в обоих случаях
+pub fn scan_reads_from_batch(
+    replies: &[Value],
+    net: &Network,
+    mode: GasPriceMode,
+) -> Result<ScanReads> {
+    let field = |i: usize| replies.get(i).cloned().ok_or_else(|| anyhow!("batch: no reply {i}"));
+    let block: U64 = serde_json::from_value(field(0)?).context("eth_blockNumber")?;
+    let legacy: U256 = serde_json::from_value(field(1)?).context("eth_gasPrice")?;
+    let base_fee = if mode == GasPriceMode::Eip1559 {
+        let latest: Option<Block<TxHash>> =
+            serde_json::from_value(field(2)?).context("eth_getBlockByNumber")?;
+        latest.and_then(|b| b.base_fee_per_gas)
+    } else {
+        None
+    };
+    let gas_price = match base_fee {
+        Some(base) => gas_price_from_base_fee(base, None, &net.gas),
+        None => cap_gas_price(legacy, &net.gas),
+    };
+    Ok(ScanReads { block: block.as_u64(), gas_price })
+}
+
+/// POST батча на эндпоинт; ошибки — как у `Http` ethers, чтобы `with_failover`
+/// ретраил их так же, как одиночные вызовы
+async fn post_batch(
+    http: &reqwest::Client,
+    url: reqwest::Url,
+    body: &Value,
+) -> Result<Value, ProviderError> {
+    let wrap = |e: HttpClientError| ProviderError::JsonRpcClientError(Box::new(e));
+    let resp = http
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| wrap(e.into()))?;
+    let text = resp.text().await.map_err(|e| wrap(e.into()))?;
+    serde_json::from_str(&text).map_err(|err| wrap(HttpClientError::SerdeJson { err, text }))
+}
+
+/// Ключ кэша пулов: (factory, token_in, token_out, fee для v3 / stable-флаг для solidly)
+pub type PoolKey = (Address, Address, Address, u32);
+
+/// Кэш адресов пулов factory→pool. Маппинг неизменен, поэтому найденный адрес
+/// живёт вечно; нулевой адрес («пула нет») — только `negative_ttl`, чтобы
+/// новые пулы со временем подхватывались.
+pub struct PoolCache {
+    entries: DashMap<PoolKey, (Address, Option<Instant>)>,
+    negative_ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PoolCache {
+    pub fn new(negative_ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            negative_ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &PoolKey) -> Option<Address> {
+        let found = match self.entries.get(key).map(|e| *e.value()) {
+            Some((_, Some(expires))) if Instant::now() >= expires => {
+                self.entries.remove(key);
+                None
+            }
+            Some((addr, _)) => Some(addr),
+            None => None,
+        };
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    pub fn insert(&self, key: PoolKey, addr: Address) {
+        let expires = addr.is_zero().then(|| Instant::now() + self.negative_ttl);
+        self.entries.insert(key, (addr, expires));
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let total = hits + self.misses.load(Ordering::Relaxed);
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+}
+
+/// Бюджет одновременных RPC-запросов сети, общий для котировок и исполнения.
+/// Котировки занимают не больше `total - exec_reserved` слотов, поэтому
+/// исполнению (send/receipt) всегда остаётся зарезервированная часть.
+pub struct RpcBudget {
+    shared: Arc<Semaphore>,
+    quote_cap: Arc<Semaphore>,
+}
+
+/// Слот бюджета; освобождается при drop
+pub struct BudgetPermit {
+    _quote: Option<OwnedSemaphorePermit>,
+    _shared: OwnedSemaphorePermit,
+}
+
+impl RpcBudget {
+    pub fn new(total: usize, exec_reserved: usize) -> Self {
+        let total = total.max(1);
+        let quote = total.saturating_sub(exec_reserved).max(1);
+        Self {
+            shared: Arc::new(Semaphore::new(total)),
+            quote_cap: Arc::new(Semaphore::new(quote)),
+        }
+    }
+
+    /// Слот под спекулятивную котировку
+    pub async fn acquire_quote(&self) -> BudgetPermit {
+        let quote = self
+            .quote_cap
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("rpc budget semaphore closed");
+        let shared = self
+            .shared
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("rpc budget semaphore closed");
+        BudgetPermit {
+            _quote: Some(quote),
+            _shared: shared,
+        }
+    }
+
+    /// Слот под исполнение (приоритет: не ограничен квотой котировок)
+    pub async fn acquire_exec(&self) -> BudgetPermit {
+        let shared = self
+            .shared
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("rpc budget semaphore closed");
+        BudgetPermit {
+            _quote: None,
+            _shared: shared,
+        }
+    }
+}
+
 struct ClientState {
     current_index: usize,
-    provider: Arc<Provider<Http>>,
+    health: Vec<EndpointHealth>,
+    // максимальная высота блока, виденная на любом эндпоинте сети
+    best_block: u64,
+}
+
+/// Состояние одного RPC-эндпоинта (по данным health-чекера и ошибок в with_failover)
+#[derive(Clone, Debug, Default)]
+pub struct EndpointHealth {
+    /// Последняя измеренная латентность eth_blockNumber
+    pub latency_ms: Option<f64>,
+    /// Ошибок подряд (для экспоненциального карантина)
+    pub fails: u32,
+    /// До какого момента эндпоинт выключен из выбора
+    pub quarantined_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.quarantined_until.map(|t| now >= t).unwrap_or(true)
+    }
+
+    /// Принудительная пауза (например, после 429); не сокращает уже идущий карантин
+    pub fn cool_down(&mut self, now: Instant, pause: Duration) {
+        let until = now + pause;
+        self.quarantined_until = Some(self.quarantined_until.map_or(until, |t| t.max(until)));
+    }
+
+    /// Ошибка: карантин base * 2^(fails-1), но не больше max
+    pub fn on_failure(&mut self, now: Instant, base: Duration, max: Duration) {
+        self.fails = self.fails.saturating_add(1);
+        let factor = 1u32 << (self.fails - 1).min(16);
+        let backoff = base.saturating_mul(factor).min(max);
+        self.quarantined_until = Some(now + backoff);
+        self.latency_ms = None;
+    }
+
+    pub fn on_success(&mut self, latency_ms: f64) {
+        self.fails = 0;
+        self.quarantined_until = None;
+        self.latency_ms = Some(latency_ms);
+    }
+}
+
+/// Эндпоинт на высоте `height` отстаёт от лучшей `best` больше чем на `max_lag` блоков
+pub fn lags_behind(best: u64, height: u64, max_lag: u64) -> bool {
+    max_lag > 0 && best.saturating_sub(height) > max_lag
+}
+
+/// Индекс доступного эндпоинта с минимальной латентностью (без замеров — в конце очереди)
+pub fn pick_lowest_latency(health: &[EndpointHealth], now: Instant) -> Option<usize> {
+    health
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.is_available(now))
+        .min_by(|(_, a), (_, b)| {
+            let la = a.latency_ms.unwrap_or(f64::MAX);
+            let lb = b.latency_ms.unwrap_or(f64::MAX);
+            la.total_cmp(&lb)
+        })
+        .map(|(i, _)| i)
+}
+
+/// Задержка перед ретраем №attempt (с нуля): base * multiplier^attempt, не больше max, ± jitter
+pub fn retry_delay(rpc: &RpcCfg, attempt: u32) -> Duration {
+    let base = rpc.retry_base_delay_ms as f64;
+    let raw = base * rpc.retry_multiplier.max(1.0).powi(attempt.min(32) as i32);
+    let capped = raw.min(rpc.retry_max_delay_ms as f64);
+    let jitter = rpc.retry_jitter.clamp(0.0, 1.0);
+    let ms = if jitter > 0.0 {
+        let k = rand::rng().random_range(-jitter..=jitter);
+        capped * (1.0 + k)
+    } else {
+        capped
+    };
+    Duration::from_millis(ms.max(0.0) as u64)
+}
+
+/// Новый блок в сети (из WS-подписки)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewHead {
+    pub chain_id: u64,
+    pub number: u64,
+}
+
+pub fn is_ws_url(url: &str) -> bool {
+    let u = url.trim().to_ascii_lowercase();
+    u.starts_with("ws://") || u.starts_with("wss://")
+}
+
+/// Хост эндпоинта для лейблов метрик (без пути/ключей API)
+fn endpoint_label(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
 }
 
 impl ChainClient {
+    /// Общий RPC-бюджет сети (котировки + исполнение)
+    pub fn budget(&self) -> Arc<RpcBudget> {
+        self.budget.clone()
+    }
+
+    /// USD-цена токена сети через оракул цен
+    pub async fn usd_price(&self, sym: &str) -> Option<f64> {
+        self.prices.usd_price(self, sym).await
+    }
+
+    /// USD-цена native-токена сети
+    pub async fn native_usd(&self) -> Option<f64> {
+        self.usd_price(&self.cfg.native_symbol).await
+    }
+
+    /// Копия клиента, читающая состояние пулов на блоке `block` (общие кэш, лимиты, бюджет)
+    pub fn pinned_at(&self, block: u64) -> Self {
+        Self {
+            pinned: Some(block),
+            ..self.clone()
+        }
+    }
+
+    /// Копия клиента со снимком начала скана: цена газа для всех котировок
+    /// и (при `pin`) закреплённый блок
+    pub fn with_scan_reads(&self, reads: ScanReads, pin: bool) -> Self {
+        Self {
+            pinned: if pin { Some(reads.block) } else { self.pinned },
+            scan_gas_price: Some(reads.gas_price),
+            ..self.clone()
+        }
+    }
+
+    /// Копия клиента для пути исполнения (simulate, send, receipt): все её RPC,
+    /// включая чтение цен, берут слот исполнения и не ждут квоту котировок
+    pub fn for_exec(&self) -> Self {
+        Self {
+            exec_priority: true,
+            ..self.clone()
+        }
+    }
+
+    /// Цена газа из снимка скана (`rpc.batch_requests`)
+    pub fn scan_gas_price(&self) -> Option<U256> {
+        self.scan_gas_price
+    }
+
+    /// Батч JSON-RPC `calls` (метод, params) одним HTTP-запросом через `with_failover`:
+    /// сбой батча (транспорт, HTTP, ошибка любого вызова) повторяется целиком на
+    /// следующем эндпоинте. Ответы — в порядке `calls`.
+    pub async fn batch(&self, method: &str, calls: &[(&str, Value)]) -> Result<Vec<Value>> {
+        let (body, n) = (batch_request_body(calls), calls.len());
+        METRIC_RPC_BATCHED_CALLS
+            .with_label_values(&[&self.cfg.chain_id.to_string()])
+            .inc_by(n as f64);
+        self.with_failover(method, |p| {
+            let (http, body) = (self.http.clone(), &body);
+            async move {
+                let reply = post_batch(&http, p.as_ref().as_ref().url().clone(), body).await?;
+                parse_batch_response(reply, n)
+                    .map_err(|e| ProviderError::JsonRpcClientError(Box::new(e)))
+            }
+        })
+        .await
+    }
+
+    /// Снимок начала скана одним батчем: номер блока, eth_gasPrice и (eip1559)
+    /// base fee последнего блока
+    pub async fn scan_reads(&self, mode: GasPriceMode) -> Result<ScanReads> {
+        let mut calls = vec![("eth_blockNumber", json!([])), ("eth_gasPrice", json!([]))];
+        if mode == GasPriceMode::Eip1559 {
+            calls.push(("eth_getBlockByNumber", json!(["latest", false])));
+        }
+        let replies = self.batch("scanReads", &calls).await?;
+        scan_reads_from_batch(&replies, &self.cfg, mode)
+    }
+
+    /// Закреплённый блок котировок; None — latest
+    pub fn pinned_block(&self) -> Option<u64> {
+        self.pinned
+    }
+
+    /// Предзагрузка адресов пулов (например, из `pools_file`); возвращает число записей
+    pub fn prewarm_pools(&self, entries: impl IntoIterator<Item = (PoolKey, Address)>) -> usize {
+        entries
+            .into_iter()
+            .map(|(key, addr)| self.pool_cache.insert(key, addr))
+            .count()
+    }
+
+    /// Адрес пула/пары через кэш; `fetch` вызывается (с failover) только на промахе
+    pub async fn pool_address<Fut>(
+        &self,
+        method: &str,
+        key: PoolKey,
+        fetch: impl Fn(Arc<Provider<Http>>) -> Fut,
+    ) -> Result<Address>
+    where
+        Fut: Future<Output = Result<Address>>,
+    {
+        let cached = self.pool_cache.get(&key);
+        let addr = match cached {
+            Some(addr) => addr,
+            None => {
+                let addr = self.with_failover(method, fetch).await?;
+                self.pool_cache.insert(key, addr);
+                addr
+            }
+        };
+        METRIC_POOL_CACHE_HIT_RATE
+            .with_label_values(&[&self.cfg.chain_id.to_string()])
+            .set(self.pool_cache.hit_rate());
+        Ok(addr)
+    }
+
     pub fn provider(&self) -> Arc<Provider<Http>> {
-        self.inner.lock().unwrap().provider.clone()
+        let idx = self.inner.lock().unwrap().current_index;
+        self.providers[idx].clone()
     }
 
     fn build_provider(url: &str) -> Result<Provider<Http>> {
@@ -35,27 +568,110 @@ impl ChainClient {
         Ok(Provider::new(http).interval(Duration::from_millis(500)))
     }
 
-    fn switch_provider(&self) -> Result<()> {
-        let (next_idx, url) = {
-            let st = self.inner.lock().unwrap();
-            let next = (st.current_index + 1) % self.endpoints.len();
-            (next, self.endpoints[next].clone())
-        };
+    fn publish_active(&self, idx: usize, latency_ms: Option<f64>) {
+        let chain = self.cfg.chain_id.to_string();
+        for (i, url) in self.endpoints.iter().enumerate() {
+            let label = endpoint_label(url);
+            METRIC_RPC_ACTIVE_ENDPOINT
+                .with_label_values(&[&chain, &label])
+                .set(if i == idx { 1.0 } else { 0.0 });
+        }
+        if let Some(ms) = latency_ms {
+            METRIC_RPC_LATENCY_MS
+                .with_label_values(&[&chain, &endpoint_label(&self.endpoints[idx])])
+                .set(ms);
+        }
+    }
+
+    /// Ждём токен в bucket'е эндпоинта (лимит провайдера по req/sec)
+    async fn throttle(&self, idx: usize) {
+        loop {
+            let res = self.limiters[idx].lock().unwrap().try_take(Instant::now());
+            match res {
+                Ok(()) => return,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
 
-        let provider = Arc::new(Self::build_provider(&url)?);
-        {
+    /// Текущий эндпоинт падает → карантин (или пауза `cooldown` после 429)
+    /// и переход на следующий доступный
+    fn switch_provider(&self, cooldown: Option<Duration>) -> Result<()> {
+        let base = Duration::from_millis(self.rpc.quarantine_base_ms);
+        let max = Duration::from_millis(self.rpc.quarantine_max_ms);
+        let next_idx = {
             let mut st = self.inner.lock().unwrap();
-            st.current_index = next_idx;
-            st.provider = provider;
+            let now = Instant::now();
+            let cur = st.current_index;
+            match cooldown {
+                Some(pause) => st.health[cur].cool_down(now, pause),
+                None => st.health[cur].on_failure(now, base, max),
+            }
+            let n = self.endpoints.len();
+            let next = (1..=n)
+                .map(|k| (cur + k) % n)
+                .find(|i| st.health[*i].is_available(now))
+                .unwrap_or((cur + 1) % n);
+            st.current_index = next;
+            next
+        };
+        self.publish_active(next_idx, None);
+        METRIC_RPC_FAILOVERS
+            .with_label_values(&[&self.cfg.chain_id.to_string()])
+            .inc();
+        warn!("RPC failover to {}", self.endpoints[next_idx]);
+        Ok(())
+    }
+
+    /// После failover: пока новый эндпоинт отстаёт от лучшей виденной высоты больше
+    /// `max_block_lag`, переключаемся дальше — иначе маршрут котируется и исполняется
+    /// по устаревшему состоянию. Если отстают все, остаёмся на последнем; если
+    /// eth_blockNumber не ответил, решает сам запрос (его ошибка снова уведёт failover).
+    async fn skip_lagging_endpoints(&self) -> Result<()> {
+        let max_lag = self.rpc.max_block_lag;
+        if max_lag == 0 {
+            return Ok(());
+        }
+        let timeout = Duration::from_millis(self.rpc.health_timeout_ms);
+        for _ in 1..self.endpoints.len() {
+            let idx = self.inner.lock().unwrap().current_index;
+            self.throttle(idx).await;
+            let res = tokio::time::timeout(timeout, self.providers[idx].get_block_number()).await;
+            let Ok(Ok(height)) = res else {
+                return Ok(());
+            };
+            let height = height.as_u64();
+            let best = {
+                let mut st = self.inner.lock().unwrap();
+                st.best_block = st.best_block.max(height);
+                st.best_block
+            };
+            if !lags_behind(best, height, max_lag) {
+                return Ok(());
+            }
+            warn!(
+                chain = self.cfg.chain_id,
+                "RPC {} отстаёт на {} блоков (лучший {best}), пропускаем",
+                endpoint_label(&self.endpoints[idx]),
+                best - height
+            );
+            METRIC_RPC_LAG_SKIPS
+                .with_label_values(&[&self.cfg.chain_id.to_string()])
+                .inc();
+            self.switch_provider(None)?;
         }
-        warn!("RPC failover to {url}");
         Ok(())
     }
 
     fn is_retryable(err: &anyhow::Error) -> bool {
+        if is_rate_limited(err) {
+            return true;
+        }
         if let Some(pe) = err.downcast_ref::<ProviderError>() {
-            if let ProviderError::JsonRpcClientError(_) = pe {
-                return true;
+            match pe {
+                ProviderError::JsonRpcClientError(_) => return true,
+                ProviderError::HTTPError(e) => return e.is_timeout() || e.is_connect(),
+                _ => {}
             }
         }
         if let Some(req_err) = err.downcast_ref::<reqwest::Error>() {
@@ -64,26 +680,331 @@ impl ChainClient {
         false
     }
 
-    pub async fn with_failover<T, Fut, E>(&self, op: impl Fn(Arc<Provider<Http>>) -> Fut) -> Result<T>
+    /// Выполняет op на текущем эндпоинте; на retryable-ошибке ждёт (backoff + jitter)
+    /// и переключается на следующий. Всего до endpoints * retry_cycles попыток.
+    /// `method` — лейбл для rpc_calls_total (getReserves, getPool, gas_price, ...).
+    pub async fn with_failover<T, Fut, E>(
+        &self,
+        method: &str,
+        op: impl Fn(Arc<Provider<Http>>) -> Fut,
+    ) -> Result<T>
+    where
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<anyhow::Error> + Send + Sync + 'static,
+    {
+        self.failover(method, self.exec_priority, op).await
+    }
+
+    /// `with_failover` со слотом исполнения: вызов не ждёт квоту котировок
+    pub async fn with_failover_exec<T, Fut, E>(
+        &self,
+        method: &str,
+        op: impl Fn(Arc<Provider<Http>>) -> Fut,
+    ) -> Result<T>
     where
         Fut: Future<Output = Result<T, E>>,
         E: Into<anyhow::Error> + Send + Sync + 'static,
     {
+        self.failover(method, true, op).await
+    }
+
+    async fn failover<T, Fut, E>(
+        &self,
+        method: &str,
+        exec: bool,
+        op: impl Fn(Arc<Provider<Http>>) -> Fut,
+    ) -> Result<T>
+    where
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<anyhow::Error> + Send + Sync + 'static,
+    {
+        let attempts = self.endpoints.len() * self.rpc.retry_cycles.max(1) as usize;
+        let mut failed: Vec<String> = Vec::new();
         let mut last_err: Option<anyhow::Error> = None;
-        for _ in 0..self.endpoints.len() {
-            let provider = self.provider();
-            match op(provider.clone()).await.map_err(|e| e.into()) {
+        for attempt in 0..attempts {
+            let (idx, provider) = {
+                let idx = self.inner.lock().unwrap().current_index;
+                (idx, self.providers[idx].clone())
+            };
+            self.throttle(idx).await;
+            METRIC_RPC_CALLS
+                .with_label_values(&[&self.cfg.chain_id.to_string(), method])
+                .inc();
+            let res = {
+                let _permit = if exec {
+                    self.budget.acquire_exec().await
+                } else {
+                    self.budget.acquire_quote().await
+                };
+                op(provider).await.map_err(|e| e.into())
+            };
+            match res {
                 Ok(v) => return Ok(v),
                 Err(e) => {
                     if !Self::is_retryable(&e) {
                         return Err(e);
                     }
+                    let cooldown = is_rate_limited(&e)
+                        .then(|| Duration::from_millis(self.rpc.rate_limited_cooldown_ms));
+                    if cooldown.is_some() {
+                        warn!(
+                            chain = self.cfg.chain_id,
+                            "RPC rate limited (429): {}",
+                            endpoint_label(&self.endpoints[idx])
+                        );
+                    }
+                    failed.push(format!("{}: {e}", endpoint_label(&self.endpoints[idx])));
                     last_err = Some(e);
-                    self.switch_provider()?;
+                    if attempt + 1 < attempts {
+                        tokio::time::sleep(retry_delay(&self.rpc, attempt as u32)).await;
+                        self.switch_provider(cooldown)?;
+                        self.skip_lagging_endpoints().await?;
+                    }
+                }
+            }
+        }
+        let err = last_err.unwrap_or_else(|| anyhow!("all RPC endpoints failed"));
+        Err(err.context(format!(
+            "chain {}: all RPC attempts failed [{}]",
+            self.cfg.chain_id,
+            failed.join("; ")
+        )))
+    }
+
+    /// `with_failover` для чтения состояния: op получает закреплённый блок (или latest).
+    /// Если ни один эндпоинт ещё не видит закреплённый блок — повтор на latest.
+    pub async fn with_failover_at<T, Fut, E>(
+        &self,
+        method: &str,
+        op: impl Fn(Arc<Provider<Http>>, BlockId) -> Fut,
+    ) -> Result<T>
+    where
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<anyhow::Error> + Send + Sync + 'static,
+    {
+        let latest = BlockId::Number(BlockNumber::Latest);
+        let Some(block) = self.pinned else {
+            return self.with_failover(method, |p| op(p, latest)).await;
+        };
+        let pinned = BlockId::Number(BlockNumber::Number(block.into()));
+        match self.with_failover(method, |p| op(p, pinned)).await {
+            Err(e) if is_block_lag_error(&e) => {
+                METRIC_PINNED_BLOCK_FALLBACKS
+                    .with_label_values(&[&self.cfg.chain_id.to_string()])
+                    .inc();
+                debug!(chain = self.cfg.chain_id, block, "{method}: RPC отстаёт, читаем latest");
+                self.with_failover(method, |p| op(p, latest)).await
+            }
+            res => res,
+        }
+    }
+
+    /// Один проход health-чекера: меряем eth_blockNumber на всех доступных
+    /// (и вышедших из карантина) эндпоинтах и встаём на самый быстрый
+    pub async fn probe_endpoints(&self) {
+        let base = Duration::from_millis(self.rpc.quarantine_base_ms);
+        let max = Duration::from_millis(self.rpc.quarantine_max_ms);
+        let timeout = Duration::from_millis(self.rpc.health_timeout_ms);
+        let chain = self.cfg.chain_id.to_string();
+
+        for (i, p) in self.providers.iter().enumerate() {
+            if !self.inner.lock().unwrap().health[i].is_available(Instant::now()) {
+                continue;
+            }
+            self.throttle(i).await;
+            let started = Instant::now();
+            let res = tokio::time::timeout(timeout, p.get_block_number()).await;
+            let mut st = self.inner.lock().unwrap();
+            match res {
+                Ok(Ok(n)) => {
+                    st.best_block = st.best_block.max(n.as_u64());
+                    let ms = started.elapsed().as_secs_f64() * 1e3;
+                    st.health[i].on_success(ms);
+                    METRIC_RPC_LATENCY_MS
+                        .with_label_values(&[&chain, &endpoint_label(&self.endpoints[i])])
+                        .set(ms);
+                }
+                _ => {
+                    st.health[i].on_failure(Instant::now(), base, max);
+                    debug!(chain = self.cfg.chain_id, "RPC probe failed: {}", self.endpoints[i]);
+                }
+            }
+        }
+
+        let chosen = {
+            let mut st = self.inner.lock().unwrap();
+            let best = pick_lowest_latency(&st.health, Instant::now());
+            match best {
+                Some(idx) if idx != st.current_index => {
+                    st.current_index = idx;
+                    Some((idx, st.health[idx].latency_ms, true))
+                }
+                Some(idx) => Some((idx, st.health[idx].latency_ms, false)),
+                None => None,
+            }
+        };
+        if let Some((idx, latency, switched)) = chosen {
+            if switched {
+                warn!(
+                    chain = self.cfg.chain_id,
+                    "RPC switch to lowest-latency endpoint {} ({:.1} ms)",
+                    self.endpoints[idx],
+                    latency.unwrap_or_default()
+                );
+            }
+            self.publish_active(idx, latency);
+        }
+    }
+
+    /// Все эндпоинты сети (http и ws) должны отдавать `chain_id` из конфига: подпись
+    /// идёт с chain id сети, и чужой RPC — неверные цены и tx не в той сети.
+    /// Чужая сеть — ошибка старта; недоступный эндпоинт — предупреждение (им займётся failover)
+    pub async fn verify_chain_id(&self) -> Result<()> {
+        let timeout = Duration::from_millis(self.rpc.health_timeout_ms);
+        let expected = self.cfg.chain_id;
+        let urls: Vec<&String> = self.endpoints.iter().chain(&self.ws_endpoints).collect();
+        let statuses =
+            futures::future::join_all(urls.iter().map(|u| probe_endpoint(expected, u, timeout)))
+                .await;
+        let mut confirmed = 0usize;
+        for (url, status) in urls.iter().zip(statuses) {
+            match status {
+                EndpointStatus::Ok { .. } => confirmed += 1,
+                EndpointStatus::WrongChain { got } => {
+                    return Err(anyhow!(
+                        "network '{}': RPC {} serves chain_id {got}, config says {expected}",
+                        self.cfg.name,
+                        endpoint_label(url)
+                    ));
                 }
+                EndpointStatus::Unreachable(e) => warn!(
+                    chain = expected,
+                    "eth_chainId {}: {e}; chain_id не проверен",
+                    endpoint_label(url)
+                ),
             }
         }
-        Err(last_err.unwrap_or_else(|| anyhow!("all RPC endpoints failed")))
+        if confirmed == 0 {
+            warn!(chain = expected, "ни один RPC не подтвердил chain_id {expected}");
+        } else {
+            info!(chain = expected, "chain_id подтверждён на {confirmed}/{} RPC", urls.len());
+        }
+        Ok(())
+    }
+
+    /// Подписка на новые блоки через WS (`eth_subscribe newHeads`).
+    /// None — если для сети нет ws-эндпоинтов. При обрыве переподключаемся
+    /// к следующему ws-эндпоинту с паузой.
+    pub fn subscribe_blocks(&self) -> Option<BoxStream<'static, NewHead>> {
+        if self.ws_endpoints.is_empty() {
+            return None;
+        }
+        let urls = self.ws_endpoints.clone();
+        let chain_id = self.cfg.chain_id;
+        let (tx, rx) = tokio::sync::mpsc::channel::<NewHead>(64);
+
+        tokio::spawn(async move {
+            let mut i = 0usize;
+            loop {
+                let url = &urls[i % urls.len()];
+                match Provider::<Ws>::connect(url.as_str()).await {
+                    Ok(p) => match p.subscribe_blocks().await {
+                        Ok(mut heads) => {
+                            debug!(chain = chain_id, "WS newHeads subscribed: {}", endpoint_label(url));
+                            while let Some(block) = heads.next().await {
+                                let Some(number) = block.number else { continue };
+                                let head = NewHead {
+                                    chain_id,
+                                    number: number.as_u64(),
+                                };
+                                if tx.send(head).await.is_err() {
+                                    return; // получатель закрыт — выходим
+                                }
+                            }
+                            warn!(chain = chain_id, "WS newHeads stream ended: {}", endpoint_label(url));
+                        }
+                        Err(e) => warn!(chain = chain_id, "WS subscribe failed: {e:#}"),
+                    },
+                    Err(e) => warn!(chain = chain_id, "WS connect failed {}: {e:#}", endpoint_label(url)),
+                }
+                if tx.is_closed() {
+                    return;
+                }
+                i += 1;
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        });
+
+        Some(
+            stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|h| (h, rx)) })
+                .boxed(),
+        )
+    }
+
+    /// Pending-транзакции через WS (`eth_subscribe newPendingTransactions`): по хэшу
+    /// тело tx дочитывается тем же WS-провайдером. None — нет ws-эндпоинтов.
+    /// Обрыв — как у `subscribe_blocks`: следующий ws-эндпоинт с паузой.
+    #[cfg(feature = "mempool")]
+    pub fn subscribe_pending_txs(&self) -> Option<BoxStream<'static, ethers::types::Transaction>> {
+        if self.ws_endpoints.is_empty() {
+            return None;
+        }
+        let urls = self.ws_endpoints.clone();
+        let chain_id = self.cfg.chain_id;
+        let (tx, rx) = tokio::sync::mpsc::channel(256);
+
+        tokio::spawn(async move {
+            let mut i = 0usize;
+            loop {
+                let url = &urls[i % urls.len()];
+                match Provider::<Ws>::connect(url.as_str()).await {
+                    Ok(p) => match p.subscribe_pending_txs().await {
+                        Ok(mut hashes) => {
+                            let ep = endpoint_label(url);
+                            debug!(chain = chain_id, "WS pending txs subscribed: {ep}");
+                            while let Some(hash) = hashes.next().await {
+                                // уже в блоке или выброшена из пула — просто пропускаем
+                                let Ok(Some(pending)) = p.get_transaction(hash).await else {
+                                    continue;
+                                };
+                                if tx.send(pending).await.is_err() {
+                                    return;
+                                }
+                            }
+                            warn!(chain = chain_id, "WS pending txs stream ended: {ep}");
+                        }
+                        Err(e) => warn!(chain = chain_id, "WS pending subscribe failed: {e:#}"),
+                    },
+                    Err(e) => {
+                        let ep = endpoint_label(url);
+                        warn!(chain = chain_id, "WS connect failed {ep}: {e:#}")
+                    }
+                }
+                if tx.is_closed() {
+                    return;
+                }
+                i += 1;
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        });
+
+        Some(
+            stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|t| (t, rx)) })
+                .boxed(),
+        )
+    }
+
+    /// Фоновый health-чекер (период из `rpc.health_check_interval_ms`)
+    pub fn spawn_health_checker(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        let every = Duration::from_millis(self.rpc.health_check_interval_ms.max(100));
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(every);
+            loop {
+                tick.tick().await;
+                this.probe_endpoints().await;
+            }
+        })
     }
 }
 
@@ -95,12 +1016,31 @@ pub struct MultiChain {
 impl MultiChain {
     pub async fn from_config(cfg: &Config) -> Result<Self> {
         let mut map = HashMap::new();
+        let prices: Arc<dyn PriceOracle> = Arc::new(ChainlinkOracle::new(
+            Duration::from_millis(cfg.global.quote.price_cache_ttl_ms),
+            cfg.global.quote.price_feed_max_age_secs,
+        ));
 
         for n in &cfg.networks {
             if n.rpc.is_empty() {
                 return Err(anyhow!("network '{}' has no RPC endpoints", n.name));
             }
-            let provider = Arc::new(ChainClient::build_provider(&n.rpc[0])?);
+            let (ws_endpoints, http_endpoints): (Vec<String>, Vec<String>) =
+                n.rpc.iter().cloned().partition(|u| is_ws_url(u));
+            if http_endpoints.is_empty() {
+                return Err(anyhow!(
+                    "network '{}' needs at least one http(s) RPC endpoint (ws is used for subscriptions only)",
+                    n.name
+                ));
+            }
+            let providers = http_endpoints
+                .iter()
+                .map(|u| {
+                    ChainClient::build_provider(u)
+                        .map(Arc::new)
+                        .with_context(|| format!("network '{}': bad rpc url", n.name))
+                })
+                .collect::<Result<Vec<_>>>()?;
 
             if map.contains_key(&n.chain_id) {
                 return Err(anyhow!("duplicate chain_id in config: {}", n.chain_id));
@@ -108,19 +1048,189 @@ impl MultiChain {
 
             let inner = ClientState {
                 current_index: 0,
-                provider,
+                health: vec![EndpointHealth::default(); http_endpoints.len()],
+                best_block: 0,
             };
 
+            let limiters = http_endpoints
+                .iter()
+                .map(|_| {
+                    Mutex::new(TokenBucket::new(
+                        cfg.rpc.rate_limit_rps,
+                        cfg.rpc.rate_limit_burst,
+                        Instant::now(),
+                    ))
+                })
+                .collect();
+
             map.insert(
                 n.chain_id,
                 ChainClient {
                     cfg: n.clone(),
-                    endpoints: n.rpc.clone(),
+                    endpoints: http_endpoints,
+                    providers,
+                    ws_endpoints,
+                    rpc: cfg.rpc.clone(),
+                    budget: Arc::new(RpcBudget::new(
+                        cfg.rpc.budget_total,
+                        cfg.rpc.budget_exec_reserved,
+                    )),
+                    limiters: Arc::new(limiters),
+                    prices: prices.clone(),
+                    pinned: None,
+                    scan_gas_price: None,
+                    exec_priority: false,
+                    http: reqwest::Client::builder()
+                        .timeout(Duration::from_secs(12))
+                        .build()?,
+                    pool_cache: Arc::new(PoolCache::new(Duration::from_millis(
+                        cfg.rpc.pool_cache_negative_ttl_ms,
+                    ))),
                     inner: Arc::new(Mutex::new(inner)),
                 },
             );
         }
 
+        if let Some(path) = &cfg.pools_file {
+            let pools = PoolsFile::load(path)?;
+            match pools.age_secs(crate::report::unix_now()) {
+                Some(age) if age > POOLS_STALE_AFTER_SECS => warn!(
+                    "pools_file {path}: сгенерирован {} ({} дн. назад) — адреса пулов могли \
+                     устареть, перезапустите pool-discovery-cli",
+                    pools.generated_at,
+                    age / 86_400
+                ),
+                Some(_) => {}
+                None => warn!("pools_file {path}: нет корректного generated_at, возраст не проверен"),
+            }
+            for c in map.values() {
+                let n = c.prewarm_pools(
+                    pools
+                        .cache_entries(c.cfg.chain_id)
+                        .with_context(|| format!("pools_file {path}"))?,
+                );
+                info!(chain = c.cfg.chain_id, "pools_file: предзагружено {} адресов пулов", n);
+            }
+        }
+
         Ok(Self { clients: map })
     }
-}
\ No newline at end of file
+
+    /// Клиенты с сетевыми настройками перезагруженного `cfg` (токены, DEX, маршруты,
+    /// оверрайды). Провайдеры, лимитеры и кэш пулов остаются прежними: смена RPC
+    /// эндпоинтов требует рестарта и не применяется
+    pub fn reconfigured(&self, cfg: &Config) -> Self {
+        let mut clients = self.clients.clone();
+        for client in clients.values_mut() {
+            let Some(net) = cfg.network_by_chain_id(client.cfg.chain_id) else {
+                continue;
+            };
+            let mut net = net.clone();
+            if net.rpc != client.cfg.rpc {
+                warn!(
+                    chain = net.chain_id,
+                    "config reload: rpc эндпоинты изменены — применятся после рестарта"
+                );
+                net.rpc = client.cfg.rpc.clone();
+            }
+            client.cfg = net;
+        }
+        Self { clients }
+    }
+
+    /// Объединённый поток новых блоков по всем сетям с WS; None — подписок нет
+    /// (тогда движок работает по таймеру poll_interval_ms)
+    pub fn subscribe_new_heads(&self) -> Option<BoxStream<'static, NewHead>> {
+        let streams: Vec<_> = self
+            .clients
+            .values()
+            .filter_map(|c| c.subscribe_blocks())
+            .collect();
+        if streams.is_empty() {
+            None
+        } else {
+            Some(stream::select_all(streams).boxed())
+        }
+    }
+
+    /// `eth_chainId` всех сетей на старте (см. `ChainClient::verify_chain_id`)
+    pub async fn verify_chain_ids(&self) -> Result<()> {
+        let checks = self.clients.values().map(|c| c.verify_chain_id());
+        for res in futures::future::join_all(checks).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    /// Запуск health-чекеров для всех сетей (если включено в конфиге)
+    pub fn spawn_health_checkers(&self) -> Vec<JoinHandle<()>> {
+        self.clients
+            .values()
+            .filter(|c| c.rpc.health_check_interval_ms > 0)
+            .map(|c| c.spawn_health_checker())
+            .collect()
+    }
+}
+
+// ===== --validate: проверка эндпоинтов до запуска =====
+
+/// Итог проверки одного RPC-эндпоинта
+#[derive(Clone, Debug, PartialEq)]
+pub enum EndpointStatus {
+    Ok { latency_ms: f64 },
+    /// Эндпоинт отвечает, но это другая сеть
+    WrongChain { got: u64 },
+    /// Не ответил за таймаут или вернул ошибку
+    Unreachable(String),
+}
+
+impl EndpointStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, EndpointStatus::Ok { .. })
+    }
+}
+
+/// Сверка ответа `eth_chainId` с `chain_id` сети из конфига
+pub fn endpoint_status(
+    expected: u64,
+    got: Result<u64, String>,
+    latency_ms: f64,
+) -> EndpointStatus {
+    match got {
+        Ok(id) if id == expected => EndpointStatus::Ok { latency_ms },
+        Ok(id) => EndpointStatus::WrongChain { got: id },
+        Err(e) => EndpointStatus::Unreachable(e),
+    }
+}
+
+async fn fetch_chain_id(url: &str) -> Result<u64> {
+    let id = if is_ws_url(url) {
+        Provider::<Ws>::connect(url.trim()).await?.get_chainid().await?
+    } else {
+        Provider::<Http>::try_from(url.trim())?.get_chainid().await?
+    };
+    Ok(id.as_u64())
+}
+
+/// `eth_chainId` на эндпоинте (http или ws) с таймаутом
+pub async fn probe_endpoint(expected: u64, url: &str, timeout: Duration) -> EndpointStatus {
+    let started = Instant::now();
+    let got = match tokio::time::timeout(timeout, fetch_chain_id(url)).await {
+        // корневая причина: в верхних слоях ошибки — полный URL, а в нём бывает ключ API
+        Ok(res) => res.map_err(|e| e.root_cause().to_string()),
+        Err(_) => Err(format!("timeout {} ms", timeout.as_millis())),
+    };
+    endpoint_status(expected, got, started.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Проверка всех эндпоинтов всех сетей: (сеть, хост эндпоинта, статус) в порядке конфига
+pub async fn validate_endpoints(cfg: &Config) -> Vec<(String, String, EndpointStatus)> {
+    let timeout = Duration::from_millis(cfg.rpc.health_timeout_ms);
+    let checks = cfg.networks.iter().flat_map(|n| {
+        n.rpc.iter().map(move |url| async move {
+            let status = probe_endpoint(n.chain_id, url, timeout).await;
+            (n.name.clone(), endpoint_label(url), status)
+        })
+    });
+    futures::future::join_all(checks).await
+}
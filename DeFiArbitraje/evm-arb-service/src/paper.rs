@@ -0,0 +1,263 @@
+//! Бумажная торговля (`telemetry.paper_trading`): кандидаты, которые были бы отправлены,
+//! но не отправлены (DRY_RUN/SAFE_LAUNCH или сеть без исполнителя), пишутся в журнал
+//! гипотетических сделок. Итоги по сетям — в метриках `paper_*`, переживают рестарт:
+//! при старте считаются по уже записанному журналу.
+//!
+//! Хранилище — JSON-lines файл; с feature `sqlite` — ещё и SQLite (`backend: "sqlite"`).
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::PaperTradingCfg;
+use crate::metrics::{METRIC_PAPER_FILLS, METRIC_PAPER_PNL_USD};
+use crate::router::QuoteResult;
+use crate::utils_gas::gas_cost_native;
+
+/// Гипотетическая сделка: котировка на момент решения «отправить»
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaperFill {
+    /// Unix-секунды
+    pub ts: u64,
+    pub chain_id: u64,
+    pub route: String,
+    #[serde(default)]
+    pub strategy: Option<String>,
+    pub amount_in: String,
+    pub amount_out: String,
+    pub gas_estimate: u64,
+    pub gas_price: String,
+    /// Оценка газа в native
+    pub gas_native: f64,
+    /// Чистая PnL в USD после газа; None — нет USD-цены
+    #[serde(default)]
+    pub pnl_usd: Option<f64>,
+    #[serde(default)]
+    pub quote_block: Option<u64>,
+}
+
+impl PaperFill {
+    pub fn from_quote(
+        ts: u64,
+        chain_id: u64,
+        route: &str,
+        strategy: Option<&str>,
+        qr: &QuoteResult,
+    ) -> Self {
+        Self {
+            ts,
+            chain_id,
+            route: route.to_string(),
+            strategy: strategy.map(str::to_string),
+            amount_in: qr.amount_in.to_string(),
+            amount_out: qr.amount_out.to_string(),
+            gas_estimate: qr.gas_estimate,
+            gas_price: qr.gas_price.to_string(),
+            gas_native: gas_cost_native(qr.gas_estimate, qr.gas_price),
+            pnl_usd: qr.pnl_usd_priced.then_some(qr.pnl_usd),
+            quote_block: qr.quote_block,
+        }
+    }
+}
+
+/// Итоги журнала по сети
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PaperTotals {
+    pub fills: u64,
+    /// Сумма оценённых PnL (сделки без USD-цены не входят)
+    pub pnl_usd: f64,
+    pub gas_native: f64,
+}
+
+impl PaperTotals {
+    fn add(&mut self, fill: &PaperFill) {
+        self.fills += 1;
+        self.pnl_usd += fill.pnl_usd.unwrap_or(0.0);
+        self.gas_native += fill.gas_native;
+    }
+}
+
+/// Хранилище журнала
+pub trait LedgerBackend: Send + Sync {
+    fn append(&self, fill: &PaperFill) -> Result<()>;
+    /// Итоги по сетям по всему записанному
+    fn totals(&self) -> Result<BTreeMap<u64, PaperTotals>>;
+}
+
+/// Append-only JSON-lines: одна сделка — одна строка
+pub struct JsonlLedger {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonlLedger {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), lock: Mutex::new(()) }
+    }
+}
+
+impl LedgerBackend for JsonlLedger {
+    fn append(&self, fill: &PaperFill) -> Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut f = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(f, "{}", serde_json::to_string(fill)?)?;
+        Ok(())
+    }
+
+    fn totals(&self) -> Result<BTreeMap<u64, PaperTotals>> {
+        let mut totals: BTreeMap<u64, PaperTotals> = BTreeMap::new();
+        let f = match std::fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(totals),
+            Err(e) => return Err(e.into()),
+        };
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // оборванная при падении последняя строка не должна ронять старт
+            match serde_json::from_str::<PaperFill>(&line) {
+                Ok(fill) => totals.entry(fill.chain_id).or_default().add(&fill),
+                Err(e) => tracing::warn!("{}:{}: {e}", self.path.display(), i + 1),
+            }
+        }
+        Ok(totals)
+    }
+}
+
+/// SQLite: таблица `paper_fills`, итоги — агрегатом по сети
+#[cfg(feature = "sqlite")]
+pub struct SqliteLedger {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteLedger {
+    pub fn open(path: &str) -> Result<Self> {
+        let dir = std::path::Path::new(path).parent();
+        if let Some(dir) = dir.filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS paper_fills (
+                ts INTEGER NOT NULL,
+                chain_id INTEGER NOT NULL,
+                route TEXT NOT NULL,
+                strategy TEXT,
+                amount_in TEXT NOT NULL,
+                amount_out TEXT NOT NULL,
+                gas_estimate INTEGER NOT NULL,
+                gas_price TEXT NOT NULL,
+                gas_native REAL NOT NULL,
+                pnl_usd REAL,
+                quote_block INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS paper_fills_chain ON paper_fills (chain_id, ts);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl LedgerBackend for SqliteLedger {
+    fn append(&self, fill: &PaperFill) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO paper_fills (ts, chain_id, route, strategy, amount_in, amount_out,
+                gas_estimate, gas_price, gas_native, pnl_usd, quote_block)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                fill.ts as i64,
+                fill.chain_id as i64,
+                fill.route,
+                fill.strategy,
+                fill.amount_in,
+                fill.amount_out,
+                fill.gas_estimate as i64,
+                fill.gas_price,
+                fill.gas_native,
+                fill.pnl_usd,
+                fill.quote_block.map(|b| b as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn totals(&self) -> Result<BTreeMap<u64, PaperTotals>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT chain_id, COUNT(*), COALESCE(SUM(pnl_usd), 0), SUM(gas_native)
+             FROM paper_fills GROUP BY chain_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let chain_id: i64 = row.get(0)?;
+            let fills: i64 = row.get(1)?;
+            let totals = PaperTotals {
+                fills: fills as u64,
+                pnl_usd: row.get(2)?,
+                gas_native: row.get(3)?,
+            };
+            Ok((chain_id as u64, totals))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// Хранилище по `backend` конфига: `jsonl` или `sqlite` (только сборка с feature `sqlite`)
+pub fn open_backend(cfg: &PaperTradingCfg) -> Result<Box<dyn LedgerBackend>> {
+    match cfg.backend.to_lowercase().as_str() {
+        "jsonl" | "json" | "file" => Ok(Box::new(JsonlLedger::new(&cfg.path))),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Ok(Box::new(SqliteLedger::open(&cfg.path)?)),
+        #[cfg(not(feature = "sqlite"))]
+        "sqlite" => Err(anyhow!(
+            "paper_trading.backend = sqlite: сборка без feature `sqlite` \
+             (cargo build --features sqlite)"
+        )),
+        other => Err(anyhow!("paper_trading.backend must be jsonl or sqlite, got '{other}'")),
+    }
+}
+
+/// Журнал бумажных сделок с итогами по сетям
+pub struct PaperLedger {
+    backend: Box<dyn LedgerBackend>,
+    totals: Mutex<BTreeMap<u64, PaperTotals>>,
+}
+
+impl PaperLedger {
+    /// Итоги восстанавливаются из уже записанного и сразу уходят в метрики
+    pub fn new(backend: Box<dyn LedgerBackend>) -> Result<Self> {
+        let totals = backend.totals().context("paper ledger: чтение итогов")?;
+        for (chain_id, t) in &totals {
+            publish(*chain_id, t);
+        }
+        Ok(Self { backend, totals: Mutex::new(totals) })
+    }
+
+    pub fn record(&self, fill: &PaperFill) -> Result<()> {
+        self.backend.append(fill)?;
+        let mut totals = self.totals.lock().unwrap();
+        let t = totals.entry(fill.chain_id).or_default();
+        t.add(fill);
+        publish(fill.chain_id, t);
+        Ok(())
+    }
+
+    pub fn totals(&self, chain_id: u64) -> PaperTotals {
+        self.totals.lock().unwrap().get(&chain_id).cloned().unwrap_or_default()
+    }
+}
+
+fn publish(chain_id: u64, t: &PaperTotals) {
+    let chain = chain_id.to_string();
+    METRIC_PAPER_FILLS.with_label_values(&[&chain]).set(t.fills as f64);
+    METRIC_PAPER_PNL_USD.with_label_values(&[&chain]).set(t.pnl_usd);
+}
@@ -0,0 +1,99 @@
+//! Permit2 SignatureTransfer: off-chain подпись `PermitTransferFrom` (EIP-712) кошельком
+//! исполнителя. Экзекутор забирает вход через `permitTransferFrom` по подписи —
+//! без `Permit2.approve(token, executor, ...)` транзакцией на каждый токен.
+
+use ethers::abi::{self, Token};
+use ethers::signers::Signer;
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Bytes, Signature, U256};
+use ethers::utils::keccak256;
+use std::convert::Infallible;
+
+pub const TOKEN_PERMISSIONS_TYPE: &str = "TokenPermissions(address token,uint256 amount)";
+pub const PERMIT_TRANSFER_FROM_TYPE: &str = "PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)";
+
+/// `PermitTransferFrom` Permit2 вместе с доменом (сеть + адрес Permit2)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermitTransferFrom {
+    pub chain_id: u64,
+    pub permit2: Address,
+    pub token: Address,
+    pub amount: U256,
+    /// Тот, кто вызовет `permitTransferFrom` (экзекутор)
+    pub spender: Address,
+    /// Неупорядоченный nonce (bitmap Permit2): каждый используется один раз
+    pub nonce: U256,
+    pub deadline: U256,
+}
+
+impl Eip712 for PermitTransferFrom {
+    type Error = Infallible;
+
+    /// Домен Permit2: только name, chainId и verifyingContract (без version)
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some("Permit2".into()),
+            version: None,
+            chain_id: Some(U256::from(self.chain_id)),
+            verifying_contract: Some(self.permit2),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(PERMIT_TRANSFER_FROM_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let permitted = keccak256(abi::encode(&[
+            Token::FixedBytes(keccak256(TOKEN_PERMISSIONS_TYPE).to_vec()),
+            Token::Address(self.token),
+            Token::Uint(self.amount),
+        ]));
+        Ok(keccak256(abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::FixedBytes(permitted.to_vec()),
+            Token::Address(self.spender),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ])))
+    }
+}
+
+impl PermitTransferFrom {
+    /// Подпись разрешения ключом владельца токенов
+    pub async fn sign<S: Signer>(&self, signer: &S) -> anyhow::Result<Signature> {
+        signer
+            .sign_typed_data(self)
+            .await
+            .map_err(|e| anyhow::anyhow!("permit2 sign: {e}"))
+    }
+
+    /// `Permit2Data` экзекутора: (permit2, ((token, amount), nonce, deadline),
+    /// (to, requestedAmount), signature); `to` экзекутор перезапишет своим адресом
+    pub fn executor_tokens(&self, signature: &Signature) -> Token {
+        Token::Tuple(vec![
+            Token::Address(self.permit2),
+            Token::Tuple(vec![
+                Token::Tuple(vec![Token::Address(self.token), Token::Uint(self.amount)]),
+                Token::Uint(self.nonce),
+                Token::Uint(self.deadline),
+            ]),
+            Token::Tuple(vec![Token::Address(Address::zero()), Token::Uint(self.amount)]),
+            Token::Bytes(signature.to_vec()),
+        ])
+    }
+}
+
+/// Nonce для SignatureTransfer из времени в наносекундах: Permit2 хранит использованные
+/// nonce в bitmap, порядок не важен, важно не повторяться
+pub fn unordered_nonce(unix_nanos: u128) -> U256 {
+    U256::from(unix_nanos)
+}
+
+/// Route calldata с подписанным разрешением в хвосте (после `deadline`)
+pub fn with_permit(route_calldata: Bytes, permit: Token) -> Bytes {
+    let mut data = route_calldata.to_vec();
+    data.extend(abi::encode(&[permit]));
+    Bytes::from(data)
+}
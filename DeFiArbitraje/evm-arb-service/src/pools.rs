@@ -0,0 +1,152 @@
+//! Чтение `pools.generated.json` из pool-discovery-cli. Схема — подмножество её
+//! `Output` (network → dex → pairs/pools); лишние поля (резервы, slot0 и т.п.) игнорируются.
+
+use anyhow::{anyhow, Context, Result};
+use ethers::types::Address;
+use serde::Deserialize;
+
+use crate::config::parse_config;
+use crate::network::PoolKey;
+use crate::utils::parse_addr;
+
+/// Файл старше недели — предупреждение на старте: пулы могли мигрировать,
+/// появиться новые fee tier
+pub const POOLS_STALE_AFTER_SECS: u64 = 7 * 24 * 3600;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PoolsFile {
+    /// Время генерации (RFC 3339), пишет pool-discovery-cli
+    #[serde(default)]
+    pub generated_at: String,
+    pub networks: Vec<PoolsNetwork>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PoolsNetwork {
+    pub chain_id: u64,
+    #[serde(default)]
+    pub dexes: Vec<PoolsDex>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum PoolsDex {
+    #[serde(rename = "v2")]
+    V2 {
+        factory: String,
+        #[serde(default)]
+        pairs: Vec<PoolEntry>,
+    },
+    #[serde(rename = "v3")]
+    V3 {
+        factory: String,
+        #[serde(default)]
+        pools: Vec<PoolEntry>,
+    },
+    #[serde(rename = "solidly_v2")]
+    Solidly {
+        factory: String,
+        #[serde(default)]
+        pairs: Vec<PoolEntry>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PoolEntry {
+    pub address: String,
+    pub token0: String,
+    pub token1: String,
+    /// v3 fee tier
+    #[serde(default)]
+    pub fee: Option<u32>,
+    /// solidly: stable-пул
+    #[serde(default)]
+    pub stable: Option<bool>,
+}
+
+impl PoolsFile {
+    /// Формат по расширению, как у основного конфига
+    pub fn load(path: &str) -> Result<Self> {
+        let data =
+            std::fs::read_to_string(path).with_context(|| format!("read pools_file {path}"))?;
+        parse_config(path, &data)
+    }
+
+    /// Возраст файла (секунды) на unix-время `now`; None — `generated_at` пуст или не разобран
+    pub fn age_secs(&self, now: u64) -> Option<u64> {
+        Some(now.saturating_sub(rfc3339_to_unix(&self.generated_at)?))
+    }
+
+    /// Записи кэша пулов для сети в ключах `ChainClient::pool_address`
+    /// (factory, token_in, token_out, fee | stable) — в обоих направлениях пары.
+    pub fn cache_entries(&self, chain_id: u64) -> Result<Vec<(PoolKey, Address)>> {
+        let addr = |s: &str| parse_addr(s).map_err(|e| anyhow!(e));
+        let mut out = Vec::new();
+        for n in self.networks.iter().filter(|n| n.chain_id == chain_id) {
+            for d in &n.dexes {
+                let (factory, entries, is_v3) = match d {
+                    PoolsDex::V2 { factory, pairs } | PoolsDex::Solidly { factory, pairs } => {
+                        (factory, pairs, false)
+                    }
+                    PoolsDex::V3 { factory, pools } => (factory, pools, true),
+                };
+                let factory = addr(factory)?;
+                for e in entries {
+                    let discriminator = if is_v3 {
+                        e.fee
+                            .ok_or_else(|| anyhow!("v3 pool {} without fee", e.address))?
+                    } else {
+                        e.stable.unwrap_or(false) as u32
+                    };
+                    let (t0, t1, pool) = (addr(&e.token0)?, addr(&e.token1)?, addr(&e.address)?);
+                    out.push(((factory, t0, t1, discriminator), pool));
+                    out.push(((factory, t1, t0, discriminator), pool));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SS[.frac](Z|±HH:MM)` → unix-время (дробная часть отбрасывается)
+fn rfc3339_to_unix(s: &str) -> Option<u64> {
+    let num = |from: usize, len: usize| -> Option<i64> {
+        let part = s.get(from..from + len)?;
+        part.bytes().all(|b| b.is_ascii_digit()).then(|| part.parse().ok())?
+    };
+    let sep = |at: usize, allowed: &[u8]| s.as_bytes().get(at).is_some_and(|b| allowed.contains(b));
+    if !(sep(4, b"-") && sep(7, b"-") && sep(10, b"Tt ") && sep(13, b":") && sep(16, b":")) {
+        return None;
+    }
+    let (y, m, d) = (num(0, 4)?, num(5, 2)?, num(8, 2)?);
+    let (hh, mm, ss) = (num(11, 2)?, num(14, 2)?, num(17, 2)?);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) || hh > 23 || mm > 59 || ss > 60 {
+        return None;
+    }
+    let mut rest = s.get(19..)?;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+        rest = &frac[digits..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (oh, om): (i64, i64) = (rest[1..3].parse().ok()?, rest[4..6].parse().ok()?);
+            sign * (oh * 3600 + om * 60)
+        }
+        _ => return None,
+    };
+    // дни от 1970-01-01 по пролептическому григорианскому календарю
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400 + hh * 3600 + mm * 60 + ss - offset).ok()
+}
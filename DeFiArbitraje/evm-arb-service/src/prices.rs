@@ -0,0 +1,155 @@
+//! USD-цены токенов для PnL и газа: Chainlink-фиды (`Token.price_feed`) с коротким
+//! кэшем и откатом на статические подсказки конфига (`usd_price`, `native_usd_hint`).
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use ethers::prelude::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::config::Network;
+use crate::network::ChainClient;
+use crate::report::unix_now;
+use crate::utils::{parse_addr, u256_to_f64};
+
+abigen!(
+    IChainlinkAggregator,
+    r#"[
+        function latestRoundData() external view returns (uint80 roundId,int256 answer,uint256 startedAt,uint256 updatedAt,uint80 answeredInRound)
+        function decimals() external view returns (uint8)
+    ]"#
+);
+
+/// Источник USD-цен
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    /// USD-цена токена `sym` в сети клиента; None — источника нет
+    async fn usd_price(&self, client: &ChainClient, sym: &str) -> Option<f64>;
+}
+
+/// Статические подсказки из конфига
+pub struct StaticHints;
+
+#[async_trait]
+impl PriceOracle for StaticHints {
+    async fn usd_price(&self, client: &ChainClient, sym: &str) -> Option<f64> {
+        client.cfg.token_usd_price(sym)
+    }
+}
+
+/// Цена из ответа фида: answer / 10^decimals; неположительный ответ — не цена
+pub fn chainlink_price(answer: I256, decimals: u8) -> Option<f64> {
+    if answer <= I256::zero() {
+        return None;
+    }
+    Some(u256_to_f64(answer.into_raw()) / 10f64.powi(decimals as i32))
+}
+
+/// Раунд фида обновлён больше `max_age_secs` назад (замёрзший фид); 0 — без проверки
+pub fn round_is_stale(updated_at: U256, now_secs: u64, max_age_secs: u64) -> bool {
+    max_age_secs > 0 && U256::from(now_secs) > updated_at.saturating_add(U256::from(max_age_secs))
+}
+
+/// Цена из `latestRoundData`; раунд старше `max_age_secs` — ошибка, а не цена
+pub async fn chainlink_latest<M: Middleware + 'static>(
+    mw: Arc<M>,
+    feed: Address,
+    max_age_secs: u64,
+) -> anyhow::Result<f64> {
+    let agg = IChainlinkAggregator::new(feed, mw);
+    let (_round, answer, _started, updated, _answered) = agg.latest_round_data().call().await?;
+    if round_is_stale(updated, unix_now(), max_age_secs) {
+        anyhow::bail!(
+            "feed {:#x}: stale round (updatedAt {}, max age {}s)",
+            feed,
+            updated,
+            max_age_secs
+        );
+    }
+    let decimals = agg.decimals().call().await?;
+    chainlink_price(answer, decimals)
+        .ok_or_else(|| anyhow::anyhow!("feed {:#x}: non-positive answer {}", feed, answer))
+}
+
+/// Кэш цен по (chain_id, символ) с TTL
+pub struct PriceCache {
+    ttl: Duration,
+    entries: DashMap<(u64, String), (f64, Instant)>,
+}
+
+impl PriceCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: DashMap::new(),
+        }
+    }
+
+    pub fn get(&self, chain_id: u64, sym: &str, now: Instant) -> Option<f64> {
+        self.entries
+            .get(&(chain_id, sym.to_uppercase()))
+            .filter(|e| now.duration_since(e.1) < self.ttl)
+            .map(|e| e.0)
+    }
+
+    pub fn insert(&self, chain_id: u64, sym: &str, price: f64, now: Instant) {
+        self.entries.insert((chain_id, sym.to_uppercase()), (price, now));
+    }
+}
+
+/// Адрес фида токена; для native без своей записи — фид wrapped-native (ETH → WETH)
+pub fn price_feed_of(net: &Network, sym: &str) -> Option<Address> {
+    let sym = sym.to_uppercase();
+    let feed = net.tokens.get(&sym).and_then(|t| t.price_feed.as_deref()).or_else(|| {
+        (net.is_native(&sym) && !net.tokens.contains_key(&sym))
+            .then(|| format!("W{}", net.native_symbol.to_uppercase()))
+            .and_then(|w| net.tokens.get(&w))
+            .and_then(|t| t.price_feed.as_deref())
+    })?;
+    parse_addr(feed).ok()
+}
+
+/// Chainlink с кэшем; токены без фида, при ошибке чтения или устаревшем раунде
+/// (старше `max_age_secs`) — статические подсказки
+pub struct ChainlinkOracle {
+    cache: PriceCache,
+    max_age_secs: u64,
+    fallback: StaticHints,
+}
+
+impl ChainlinkOracle {
+    pub fn new(ttl: Duration, max_age_secs: u64) -> Self {
+        Self {
+            cache: PriceCache::new(ttl),
+            max_age_secs,
+            fallback: StaticHints,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceOracle for ChainlinkOracle {
+    async fn usd_price(&self, client: &ChainClient, sym: &str) -> Option<f64> {
+        let chain_id = client.cfg.chain_id;
+        let Some(feed) = price_feed_of(&client.cfg, sym) else {
+            return self.fallback.usd_price(client, sym).await;
+        };
+        if let Some(px) = self.cache.get(chain_id, sym, Instant::now()) {
+            return Some(px);
+        }
+        match client
+            .with_failover("latestRoundData", |p| chainlink_latest(p, feed, self.max_age_secs))
+            .await
+        {
+            Ok(px) => {
+                self.cache.insert(chain_id, sym, px, Instant::now());
+                Some(px)
+            }
+            Err(e) => {
+                warn!(chain = chain_id, "price feed {} {:#x}: {e:#}; статическая подсказка", sym, feed);
+                self.fallback.usd_price(client, sym).await
+            }
+        }
+    }
+}
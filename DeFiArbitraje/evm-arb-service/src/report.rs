@@ -0,0 +1,359 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{CandidateLogCfg, Network, PnlReportCfg};
+use crate::metrics::METRIC_SESSION_PROFIT_BASE;
+
+/// Итог одной сделки (по котировке на момент отправки)
+#[derive(Clone, Debug, Default)]
+pub struct TradeFill {
+    pub success: bool,
+    /// Валовая прибыль в native-токене (до газа)
+    pub gross_native: f64,
+    /// Потраченный газ в native-токене
+    pub gas_native: f64,
+    /// Цена native в USD (если известна)
+    pub native_usd: Option<f64>,
+}
+
+/// Агрегаты по одной сети
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainPnl {
+    pub trades: u64,
+    pub successes: u64,
+    pub gross_native: f64,
+    pub net_native: f64,
+    pub gas_native: f64,
+    pub gross_usd: f64,
+    pub net_usd: f64,
+    pub gas_usd: f64,
+}
+
+impl ChainPnl {
+    fn add(&mut self, other: &ChainPnl) {
+        self.trades += other.trades;
+        self.successes += other.successes;
+        self.gross_native += other.gross_native;
+        self.net_native += other.net_native;
+        self.gas_native += other.gas_native;
+        self.gross_usd += other.gross_usd;
+        self.net_usd += other.net_usd;
+        self.gas_usd += other.gas_usd;
+    }
+}
+
+/// Дневной агрегат PnL; переживает рестарт через JSON-файл состояния
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyPnl {
+    pub period_start: u64,
+    pub chains: BTreeMap<u64, ChainPnl>,
+}
+
+/// Сводка за период (то, что пишем в файл/вебхук)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailySummary {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub success_rate: f64,
+    pub total: ChainPnl,
+    pub chains: BTreeMap<u64, ChainPnl>,
+    /// Накопленная с начала сессии прибыль в base_token сети
+    #[serde(default)]
+    pub session_profit_base: BTreeMap<u64, BaseProfit>,
+}
+
+/// Прибыль сети в её base_token
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BaseProfit {
+    pub token: String,
+    pub amount: f64,
+    pub trades: u64,
+}
+
+/// Прибыль сессии (с момента старта) в base_token каждой сети.
+/// Каждая сделка переводится в base по ценовым подсказкам на момент исполнения.
+#[derive(Clone, Debug, Default)]
+pub struct SessionProfit {
+    chains: BTreeMap<u64, BaseProfit>,
+}
+
+/// USD-цена токена по подсказкам конфига: стейблы = 1, иначе `usd_price` токена
+/// или native_usd_hint для native/wrapped native
+pub fn usd_price_hint(net: &Network, stables: &[String], sym: &str) -> Option<f64> {
+    if stables.iter().any(|s| s.eq_ignore_ascii_case(sym)) {
+        Some(1.0)
+    } else {
+        net.token_usd_price(sym)
+    }
+}
+
+/// Пересчёт суммы токена `sym` в base_token сети; None — base не задан или нет цены
+pub fn to_base_token(net: &Network, stables: &[String], sym: &str, amount: f64) -> Option<f64> {
+    let base = net.base_token.as_deref()?;
+    if base.eq_ignore_ascii_case(sym) {
+        return Some(amount);
+    }
+    let px = usd_price_hint(net, stables, sym)?;
+    let base_px = usd_price_hint(net, stables, base)?;
+    (base_px > 0.0).then(|| amount * px / base_px)
+}
+
+impl SessionProfit {
+    /// Учитывает подтверждённую сделку: прибыль в `token_in` минус газ в native.
+    /// Возвращает прибавку в base_token (None — пересчитать нельзя, сделка не учтена).
+    pub fn record(
+        &mut self,
+        net: &Network,
+        stables: &[String],
+        token_in: &str,
+        profit: f64,
+        gas_native: f64,
+    ) -> Option<f64> {
+        let gross = to_base_token(net, stables, token_in, profit)?;
+        let gas = to_base_token(net, stables, &net.native_symbol, gas_native)?;
+        let base = net.base_token.clone()?;
+        let entry = self.chains.entry(net.chain_id).or_default();
+        entry.token = base.to_uppercase();
+        entry.amount += gross - gas;
+        entry.trades += 1;
+        METRIC_SESSION_PROFIT_BASE
+            .with_label_values(&[&net.chain_id.to_string(), &entry.token])
+            .set(entry.amount);
+        Some(gross - gas)
+    }
+
+    pub fn get(&self, chain_id: u64) -> Option<&BaseProfit> {
+        self.chains.get(&chain_id)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<u64, BaseProfit> {
+        self.chains.clone()
+    }
+}
+
+impl DailyPnl {
+    pub fn new(now: u64) -> Self {
+        Self {
+            period_start: now,
+            chains: BTreeMap::new(),
+        }
+    }
+
+    /// Поднимаем состояние с диска (если есть), иначе начинаем новый период
+    pub fn load_or_new(path: &Path, now: u64) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_else(|| Self::new(now))
+    }
+
+    pub fn persist(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, chain_id: u64, fill: &TradeFill) {
+        let c = self.chains.entry(chain_id).or_default();
+        c.trades += 1;
+        if fill.success {
+            c.successes += 1;
+            c.gross_native += fill.gross_native;
+        }
+        c.gas_native += fill.gas_native;
+        c.net_native = c.gross_native - c.gas_native;
+        if let Some(px) = fill.native_usd {
+            if fill.success {
+                c.gross_usd += fill.gross_native * px;
+            }
+            c.gas_usd += fill.gas_native * px;
+            c.net_usd = c.gross_usd - c.gas_usd;
+        }
+    }
+
+    pub fn summary(&self, now: u64) -> DailySummary {
+        let mut total = ChainPnl::default();
+        for c in self.chains.values() {
+            total.add(c);
+        }
+        let success_rate = if total.trades == 0 {
+            0.0
+        } else {
+            total.successes as f64 / total.trades as f64
+        };
+        DailySummary {
+            period_start: self.period_start,
+            period_end: now,
+            success_rate,
+            total,
+            chains: self.chains.clone(),
+            session_profit_base: BTreeMap::new(),
+        }
+    }
+
+    pub fn reset(&mut self, now: u64) {
+        self.period_start = now;
+        self.chains.clear();
+    }
+}
+
+/// Кандидат, который ушёл бы в сеть (DRY_RUN/SAFE_LAUNCH)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DryRunCandidate {
+    pub pair: String,
+    pub strategy: Option<String>,
+    pub legs: Vec<String>,
+    pub amount_in: String,
+    pub amount_out: String,
+    pub pnl_usd: f64,
+}
+
+/// Итог одного скана сети в dry-run режиме
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DryRunReport {
+    pub chain_id: u64,
+    pub ts: u64,
+    pub routes_scanned: u64,
+    pub profitable: u64,
+    pub total_pnl_usd: f64,
+    pub top: Vec<DryRunCandidate>,
+}
+
+impl DryRunReport {
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            ..Self::default()
+        }
+    }
+
+    pub fn on_route(&mut self) {
+        self.routes_scanned += 1;
+    }
+
+    pub fn on_candidate(&mut self, c: DryRunCandidate) {
+        self.profitable += 1;
+        self.total_pnl_usd += c.pnl_usd;
+        self.top.push(c);
+    }
+
+    /// Оставляет top_n лучших по PnL и пишет `<dir>/dryrun-<chain>.json`
+    pub fn finish(mut self, dir: &str, top_n: usize) -> Result<std::path::PathBuf> {
+        self.ts = unix_now();
+        self.top.sort_by(|a, b| b.pnl_usd.total_cmp(&a.pnl_usd));
+        self.top.truncate(top_n);
+        std::fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("dryrun-{}.json", self.chain_id));
+        std::fs::write(&path, serde_json::to_string_pretty(&self)?)?;
+        Ok(path)
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Нужно ли ротировать лог перед записью `incoming` байт: превышение `max_bytes`
+/// (пустой файл не ротируем) или файл последний раз писался в прошлые сутки UTC.
+pub fn rotation_due(cfg: &CandidateLogCfg, size: u64, incoming: u64, modified: u64, now: u64) -> bool {
+    let by_size = cfg.max_bytes > 0 && size > 0 && size + incoming > cfg.max_bytes;
+    let by_day = cfg.rotate_daily && size > 0 && modified / 86_400 != now / 86_400;
+    by_size || by_day
+}
+
+/// Дописывает строку в `<dir>/candidates-<chain_id>.jsonl`; заполненный/вчерашний файл
+/// переименовывается в `candidates-<chain_id>-<unix_ts>.jsonl`.
+pub fn append_candidate(cfg: &CandidateLogCfg, chain_id: u64, line: &str, now: u64) -> Result<()> {
+    std::fs::create_dir_all(&cfg.dir)?;
+    let dir = Path::new(&cfg.dir);
+    let path = dir.join(format!("candidates-{chain_id}.jsonl"));
+    if let Ok(meta) = std::fs::metadata(&path) {
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(now);
+        if rotation_due(cfg, meta.len(), line.len() as u64 + 1, modified, now) {
+            let mut rolled = dir.join(format!("candidates-{chain_id}-{modified}.jsonl"));
+            let mut n = 1;
+            while rolled.exists() {
+                rolled = dir.join(format!("candidates-{chain_id}-{modified}-{n}.jsonl"));
+                n += 1;
+            }
+            std::fs::rename(&path, rolled)?;
+        }
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Путь к файлу состояния дневного агрегата
+pub fn state_path(cfg: &PnlReportCfg) -> std::path::PathBuf {
+    Path::new(&cfg.dir).join("pnl-daily-state.json")
+}
+
+/// Фоновая задача: раз в `interval_sec` пишет сводку в файл (и, опционально, в вебхук),
+/// затем обнуляет дневные счётчики
+pub async fn run_daily_report(
+    cfg: PnlReportCfg,
+    webhook_url: Option<String>,
+    state: Arc<Mutex<DailyPnl>>,
+    session: Arc<Mutex<SessionProfit>>,
+) {
+    let state_file = state_path(&cfg);
+    let mut tick = tokio::time::interval(Duration::from_secs(cfg.interval_sec.max(1)));
+    tick.tick().await; // первый тик срабатывает сразу — пропускаем
+
+    loop {
+        tick.tick().await;
+        let now = unix_now();
+        let summary = {
+            let mut st = state.lock().unwrap();
+            let mut s = st.summary(now);
+            s.session_profit_base = session.lock().unwrap().snapshot();
+            st.reset(now);
+            if let Err(e) = st.persist(&state_file) {
+                tracing::error!("pnl state persist error: {e:#}");
+            }
+            s
+        };
+
+        let path = Path::new(&cfg.dir).join(format!("pnl-summary-{}.json", now));
+        if let Err(e) = std::fs::create_dir_all(&cfg.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(std::fs::write(&path, serde_json::to_string_pretty(&summary)?)?))
+        {
+            tracing::error!("pnl summary write error: {e:#}");
+        } else {
+            tracing::info!(
+                trades = summary.total.trades,
+                net_usd = summary.total.net_usd,
+                "daily PnL summary written to {}",
+                path.display()
+            );
+        }
+
+        if cfg.webhook
+            && let Some(url) = &webhook_url
+        {
+            let body = json!({ "type": "daily_pnl", "summary": summary });
+            if let Err(e) = reqwest::Client::new().post(url).json(&body).send().await {
+                tracing::warn!("pnl summary webhook error: {e:#}");
+            }
+        }
+    }
+}
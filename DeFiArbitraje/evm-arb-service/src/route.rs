@@ -1,26 +1,63 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use ethers::middleware::SignerMiddleware;
-use ethers::providers::{Http, Provider};
+use ethers::providers::{Http, Middleware, Provider};
 use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, U256};
+use ethers::types::{Address, TransactionReceipt, TxHash, U256};
 use serde_json::json;
-use std::collections::{HashMap, HashSet};
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use futures::stream::{self, StreamExt};
+use tokio::sync::Semaphore;
 
-use crate::approvals::ensure_approvals;
-use crate::calldata::encode_route_calldata;
-use crate::config::{Config, Network};
-use crate::exec::Executor;
+use crate::approvals::{AllowanceCache, ensure_approvals};
+use crate::calldata::{
+    LegSlippage, MAX_ROUTE_LEGS, encode_route_calldata, quote_only, route_deadline,
+};
+use crate::config::{
+    AdaptivePollCfg, CandidateLogCfg, Config, DexConfig, Network, Risk, RouteDex, RouteTemplate,
+    Strategy,
+};
+use crate::dex::{V2Pair, v2_get_pair, v2_pair_tokens};
+use crate::exec::{
+    ExpectedOut, Executor, ExecutorPool, PendingTx, ReceiptTracker, RevertRetries, TxOpts,
+    TxOutcome, SimOutcome, exec_min_profit, retry_gas_price, simulate_gas_limit, simulation_passes,
+    slippage_breach,
+};
+use crate::forksim::{ForkSimulator, funding_for};
+use crate::hooks::{
+    AlertSink, NoopHook, PreSendHook, TradeContext, WebhookAlert, send_through_hook,
+};
+use crate::keys::{KeySource, key_sources_from_env, safe_module_key_from_env};
 use crate::metrics::{
-    METRIC_BEST_PNL_USD, METRIC_EXEC_FAIL, METRIC_EXEC_OK, METRIC_LAST_SIM_GAS, METRIC_OPPS_FOUND,
-    METRIC_PROFITABLE_FOUND, METRIC_ROUTES_SCANNED, METRIC_TX_SENT,
+    METRIC_BEST_PNL_USD, METRIC_EXEC_FAIL, METRIC_EXEC_OK, METRIC_EXECUTOR_MODE,
+    METRIC_GAS_CEILING_HALT, METRIC_LAST_SIM_GAS,
+    METRIC_NETWORK_HALTED, METRIC_NOTIONAL_REMAINING, METRIC_OPPS_FOUND, METRIC_PROFITABLE_FOUND,
+    METRIC_QUOTE_AGE_BLOCKS, METRIC_QUOTE_OUTCOMES, METRIC_REVERT_RETRIES, METRIC_ROUTE_COOLDOWN,
+    METRIC_ROUTES_SCANNED,
+    METRIC_ROUTES_SKIPPED, METRIC_SCAN_DURATION, METRIC_SIM_PROFIT, METRIC_SLIPPAGE_HALTS,
+    METRIC_STRATEGY_SELECTED, METRIC_TX_SENT, METRIC_VOLATILITY_INDEX,
+    scan_heartbeat, scrape_gap, unix_ms,
 };
+#[cfg(feature = "mempool")]
+use crate::mempool::{PendingSwap, TriggerThrottle, affected_routes};
 use crate::network::{ChainClient, MultiChain};
-use crate::router::{QuoteResult, quote_cross_dex_pair};
-use crate::utils::{bps, parse_addr, u256_from_decimals};
+use crate::paper::{PaperFill, PaperLedger, open_backend};
+use crate::report::{
+    DailyPnl, DryRunCandidate, DryRunReport, SessionProfit, TradeFill, append_candidate, state_path,
+    unix_now,
+};
+use crate::router::{
+    QuoteOutcome, QuoteResult, prescreen_cross_dex_pair, quote_cross_dex_pair, record_pair_spread,
+    quote_triangle, resolve_leg_pools,
+};
+use crate::utils::{
+    amount_for_notional, bps, fmt_addr, parse_addr, signed_diff_decimals, u256_to_f64,
+    usd_value,
+};
+use crate::utils_gas::{GasPriceMode, current_gas_price_legacy, gas_cost_native};
 
 fn run_mode() -> Option<&'static str> {
     if std::env::var("SAFE_LAUNCH")
@@ -35,24 +72,40 @@ fn run_mode() -> Option<&'static str> {
     }
 }
 
-fn log_candidate(chain_id: u64, pair_or_tri: &str, legs: usize, qr: &QuoteResult) {
+/// Строка журнала кандидатов (`telemetry.logs.candidates`)
+struct Candidate<'a> {
+    chain_id: u64,
+    pair_or_tri: &'a str,
+    strategy: Option<&'a str>,
+    qr: &'a QuoteResult,
+    sim_profit: Option<U256>,
+    quote_age: Option<u64>,
+}
+
+fn log_candidate(cfg: &CandidateLogCfg, c: &Candidate) {
+    if !cfg.enabled {
+        return;
+    }
+    let Candidate { chain_id, pair_or_tri, strategy, qr, sim_profit, quote_age } = *c;
     if let Err(e) = (|| -> Result<()> {
-        std::fs::create_dir_all("logs")?;
-        let path = format!("logs/candidates-{}.jsonl", chain_id);
-        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
         let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let line = json!({
             "ts": ts,
             "chain_id": chain_id,
             "pair_or_tri": pair_or_tri,
-            "legs": legs,
+            "strategy": strategy,
+            "legs": qr.legs.len(),
+            "routers": qr.legs.iter().map(|l| fmt_addr(&l.router())).collect::<Vec<_>>(),
             "amount_in": qr.amount_in.to_string(),
             "amount_out": qr.amount_out.to_string(),
             "gas_estimate": qr.gas_estimate,
             "pnl_usd": qr.pnl_usd,
+            "pnl_usd_priced": qr.pnl_usd_priced,
+            "sim_profit": sim_profit.map(|p| p.to_string()),
+            "quote_block": qr.quote_block,
+            "quote_age_blocks": quote_age,
         });
-        writeln!(file, "{}", line.to_string())?;
-        Ok(())
+        append_candidate(cfg, chain_id, &line.to_string(), ts)
     })() {
         tracing::error!("candidate log error: {e:#}");
     }
@@ -69,41 +122,295 @@ impl RoutePlanner {
     }
 }
 
+// ===== Route Window =====
+/// Вращающееся окно маршрутов: за poll берём не больше `max` штук,
+/// следующий poll продолжает с места, где остановился предыдущий
+#[derive(Clone, Debug, Default)]
+pub struct RouteWindow {
+    offset: usize,
+}
+impl RouteWindow {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Маска маршрутов для текущего poll (`max == 0` — без лимита); сдвигает окно
+    pub fn advance(&mut self, total: usize, max: usize) -> Vec<bool> {
+        if max == 0 || max >= total {
+            self.offset = 0;
+            return vec![true; total];
+        }
+        let start = self.offset % total;
+        let mut mask = vec![false; total];
+        for k in 0..max {
+            mask[(start + k) % total] = true;
+        }
+        self.offset = (start + max) % total;
+        mask
+    }
+}
+
+// ===== Volatility Index =====
+/// Индекс волатильности сети: коэффициент вариации (stddev / mean) цены native
+/// за последние `window` poll-ов. Память ограничена окном.
+#[derive(Clone, Debug)]
+pub struct VolatilityIndex {
+    window: usize,
+    samples: VecDeque<f64>,
+}
+impl VolatilityIndex {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(2),
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, price: f64) {
+        if !(price.is_finite() && price > 0.0) {
+            return;
+        }
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(price);
+    }
+
+    /// None — меньше двух точек
+    pub fn index(&self) -> Option<f64> {
+        let n = self.samples.len();
+        if n < 2 {
+            return None;
+        }
+        let mean = self.samples.iter().sum::<f64>() / n as f64;
+        let var = self.samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+        Some(var.sqrt() / mean)
+    }
+}
+
+// ===== Adaptive poll =====
+/// Интервал скана сети (`execution.adaptive_poll`): скан с возможностями сокращает его
+/// вдвое, пустой — удлиняет в 1.5 раза; всегда в пределах [min_ms, max_ms]
+#[derive(Clone, Debug)]
+pub struct AdaptiveInterval {
+    min_ms: u64,
+    max_ms: u64,
+    current_ms: u64,
+}
+impl AdaptiveInterval {
+    pub fn new(base_ms: u64, min_ms: u64, max_ms: u64) -> Self {
+        Self {
+            min_ms,
+            max_ms,
+            current_ms: base_ms.clamp(min_ms, max_ms),
+        }
+    }
+
+    pub fn current_ms(&self) -> u64 {
+        self.current_ms
+    }
+
+    /// Итог скана: число найденных возможностей → новый интервал
+    pub fn on_scan(&mut self, opportunities: usize) -> u64 {
+        let next = if opportunities > 0 {
+            self.current_ms / 2
+        } else {
+            self.current_ms.saturating_mul(3) / 2
+        };
+        self.current_ms = next.clamp(self.min_ms, self.max_ms);
+        self.current_ms
+    }
+}
+
+// ===== Daily notional =====
+/// Скользящее окно отправленного объёма сети в USD (по умолчанию 24 ч).
+/// Записи старше окна выпадают сами — сброса по часам нет.
+#[derive(Clone, Debug)]
+pub struct NotionalWindow {
+    window_secs: u64,
+    sent: VecDeque<(u64, f64)>,
+}
+impl NotionalWindow {
+    pub const DAY_SECS: u64 = 86_400;
+
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window_secs,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Учесть отправленную сделку (unix-секунды, объём в USD)
+    pub fn record(&mut self, now: u64, usd: f64) {
+        if usd.is_finite() && usd > 0.0 {
+            self.sent.push_back((now, usd));
+        }
+    }
+
+    /// Объём за последнее окно
+    pub fn spent(&mut self, now: u64) -> f64 {
+        let from = now.saturating_sub(self.window_secs);
+        while self.sent.front().is_some_and(|(ts, _)| *ts <= from) {
+            self.sent.pop_front();
+        }
+        self.sent.iter().map(|(_, usd)| usd).sum()
+    }
+
+    /// Остаток лимита `cap` на текущий момент (не меньше 0)
+    pub fn remaining(&mut self, cap: f64, now: u64) -> f64 {
+        (cap - self.spent(now)).max(0.0)
+    }
+}
+
 // ===== Strategy Engine =====
+/// Кошельки исполнителя одной сети
+type NetworkExecutors = ExecutorPool<Provider<Http>, LocalWallet>;
+
+// Всё состояние за Arc: на время скана движок клонируется в задачу каждой сети
+#[derive(Clone)]
 pub struct StrategyEngine {
-    cfg: Config,
+    cfg: Arc<Config>,
     chains: Arc<MultiChain>,
     planner: Arc<RoutePlanner>,
-    pnl: PnLTracker,
-    // Исполнители по сетям (SignerMiddleware)
-    executors: HashMap<u64, Arc<Executor<Provider<Http>, LocalWallet>>>,
+    // PnL / circuit breaker по сетям: убытки одной сети не тормозят другие
+    pnl: Arc<Mutex<HashMap<u64, PnLTracker>>>,
+    // Исполнители по сетям (SignerMiddleware): кошельки сети, отправки по кругу
+    executors: Arc<HashMap<u64, Arc<NetworkExecutors>>>,
+    // Симуляция на форке вместо simulate() экзекутора (execution.fork_sim)
+    fork_sims: Arc<HashMap<u64, ForkSimulator>>,
+    // Сети без исполнителя (scan-only) и причина: котируются, но не исполняются
+    scan_only: HashMap<u64, String>,
+    // Новый конфиг (SIGHUP), применяется в начале следующего скана
+    pending_cfg: Arc<Mutex<Option<Config>>>,
+    // Дневной агрегат PnL (для сводки)
+    daily: Arc<Mutex<DailyPnl>>,
+    // Прибыль сессии в base_token по сетям
+    session: Arc<Mutex<SessionProfit>>,
+    // Последняя проверка перед отправкой (по умолчанию — no-op)
+    pre_send: Arc<dyn PreSendHook>,
+    // Смещение окна маршрутов по сетям (max_routes_per_poll)
+    route_windows: Arc<Mutex<HashMap<u64, RouteWindow>>>,
+    // Отправленные транзакции до получения receipt
+    receipts: ReceiptTracker,
+    // Сделки отправленных транзакций: PnL пишется по receipt, с фактическим выходом
+    sent: Arc<Mutex<HashMap<TxHash, SentTrade>>>,
+    // false после сигнала остановки: новые маршруты не отправляются
+    accepting: Arc<AtomicBool>,
+    // Сети, где исполнение остановлено защитой от проскальзывания (до момента)
+    halts: Arc<Mutex<HashMap<u64, Instant>>>,
+    // Алерты (лог + вебхук)
+    alerts: Arc<dyn AlertSink>,
+    // Индекс волатильности по сетям
+    volatility: Arc<Mutex<HashMap<u64, VolatilityIndex>>>,
+    // Повторы маршрутов после revert (execution.revert_retry)
+    retries: RevertRetries,
+    // Отправленный объём в USD за 24 ч по сетям (daily_notional_cap_usd)
+    notional: Arc<Mutex<HashMap<u64, NotionalWindow>>>,
+    // Адаптивный интервал и время следующего скана по сетям (execution.adaptive_poll)
+    poll: Arc<Mutex<HashMap<u64, (AdaptiveInterval, Instant)>>>,
+    // Серии убытков маршрутов (circuit_breaker.route_max_losses_in_row)
+    route_losses: Arc<Mutex<RouteCooldowns>>,
+    // Старт движка (unix ms) и состояние dead-man's switch (safety.halt_on_scrape_gap_secs)
+    started_ms: u64,
+    scrape_halted: Arc<AtomicBool>,
+    // Журнал бумажных сделок (telemetry.paper_trading)
+    paper: Option<Arc<PaperLedger>>,
+    // Частота внеочередных сканов маршрутов по свопам из mempool (execution.mempool)
+    #[cfg(feature = "mempool")]
+    mempool_throttle: Arc<Mutex<TriggerThrottle>>,
 }
 
 impl StrategyEngine {
+    /// `refresh_approvals` — игнорировать кэш allowance на старте (`--refresh-approvals`)
     pub async fn new(
         cfg: Config,
         chains: Arc<MultiChain>,
         planner: Arc<RoutePlanner>,
+        refresh_approvals: bool,
     ) -> Result<Self> {
-        let mut executors: HashMap<u64, Arc<Executor<Provider<Http>, LocalWallet>>> =
+        let mut executors: HashMap<u64, Arc<NetworkExecutors>> =
             HashMap::new();
+        let mut fork_sims: HashMap<u64, ForkSimulator> = HashMap::new();
+        let mut scan_only: HashMap<u64, String> = HashMap::new();
+        let require_executors = cfg.global.execution.require_executors;
+        let cache_path = Some(&cfg.global.execution.allowance_cache_file)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+        let mut allowance_cache = match &cache_path {
+            Some(p) if !refresh_approvals => AllowanceCache::load_or_default(p),
+            _ => AllowanceCache::default(),
+        };
 
         for (chain_id, client) in chains.clients.iter() {
+            // все причины пропуска видны в /metrics с нуля, а не с первого пропуска
+            for reason in SkipReason::ALL {
+                METRIC_ROUTES_SKIPPED.with_label_values(&[&chain_id.to_string(), reason.label()]);
+            }
             let env_key_exec = format!("EXECUTOR_{}", chain_id);
-            if std::env::var(&env_key_exec).is_err() {
-                tracing::debug!(
-                    "Executor не задан для chain_id={} (нет ENV {})",
-                    chain_id,
-                    env_key_exec
-                );
+            let Ok(exec_addr) = std::env::var(&env_key_exec) else {
+                let reason = format!("нет ENV {env_key_exec}");
+                mark_scan_only(require_executors, *chain_id, reason, &mut scan_only)?;
                 continue;
-            }
+            };
+            // fail fast: кривой адрес экзекутора не должен всплывать посреди скана
+            parse_addr(exec_addr.trim())
+                .map_err(|e| anyhow!("{env_key_exec} (chain_id={chain_id}): {e}"))?;
 
-            match signer_middleware_for_chain(client.provider(), *chain_id) {
-                Ok(signer_client) => {
-                    let exec = Executor::new(signer_client.clone()).await?;
-                    executors.insert(*chain_id, Arc::new(exec));
-                    tracing::info!("Executor инициализирован для chain_id={}", chain_id);
+            let safe = client
+                .cfg
+                .safe_address
+                .as_deref()
+                .map(|a| parse_addr(a).map_err(|e| anyhow!("safe_address (chain_id={chain_id}): {e}")))
+                .transpose()?;
+            // через Safe модулем подписывает ключ модуля, иначе — ключ исполнителя
+            let module_key = safe.and_then(|_| safe_module_key_from_env(*chain_id));
+            let via_module = module_key.is_some();
+            // источники ключей проверяем на старте: один ключ или PRIVATE_KEY_<id>_0.._N
+            // (ротация кошельков); ключ модуля Safe — всегда один
+            let keys = match module_key {
+                Some(k) => vec![k],
+                None => key_sources_from_env(*chain_id)
+                    .with_context(|| format!("executor key for chain_id={}", chain_id))?,
+            };
+            let signers: Result<Vec<_>> = keys
+                .iter()
+                .map(|key| signer_middleware_for_chain(client.provider(), key, *chain_id))
+                .collect();
+            match signers {
+                Ok(signers) => {
+                    let abi_cfg = &cfg.global.execution.executor_abi;
+                    let mut wallets = Vec::with_capacity(signers.len());
+                    for signer_client in &signers {
+                        let mut exec = Executor::new(signer_client.clone(), abi_cfg)
+                            .await
+                            .with_context(|| format!("executor (chain_id={chain_id})"))?;
+                        if let Some(safe) = safe {
+                            exec.attach_safe(safe, via_module).await?;
+                        }
+                        wallets.push(Arc::new(exec));
+                    }
+                    let exec = wallets[0].clone();
+                    allowance_cache.bind_executor(*chain_id, exec.address);
+                    let fork_cfg = &cfg.global.execution.fork_sim;
+                    if fork_cfg.enabled {
+                        let from = exec.safe.map_or(signers[0].address(), |s| s.safe);
+                        let mut sim = ForkSimulator::new(fork_cfg, exec.address, from)
+                            .with_context(|| format!("fork_sim (chain_id={chain_id})"))?;
+                        sim.methods = exec.methods.clone();
+                        fork_sims.insert(*chain_id, sim);
+                        tracing::info!(
+                            "chain_id={}: симуляция на форке ({})",
+                            chain_id,
+                            if fork_cfg.rpc_url.is_empty() { "stateOverride" } else { "anvil" }
+                        );
+                    }
+                    executors.insert(*chain_id, Arc::new(ExecutorPool::new(wallets)?));
+                    set_executor_mode(*chain_id, true);
+                    tracing::info!(
+                        "Executor инициализирован для chain_id={} (кошельков: {})",
+                        chain_id,
+                        signers.len()
+                    );
 
                     if cfg.global.execution.approve_spend_on_start {
                         if let Some(mode) = run_mode() {
@@ -111,27 +418,33 @@ impl StrategyEngine {
                         } else {
                             let mut spenders: HashSet<Address> = HashSet::new();
                             for d in &client.cfg.dexes {
-                                if let Some(r) = &d.router {
-                                    if let Ok(a) = parse_addr(r) {
-                                        spenders.insert(a);
-                                    }
+                                if let Some(r) = &d.router
+                                    && let Ok(a) = parse_addr(r)
+                                {
+                                    spenders.insert(a);
                                 }
-                                if let Some(r) = &d.swap_router02 {
-                                    if let Ok(a) = parse_addr(r) {
-                                        spenders.insert(a);
-                                    }
+                                if let Some(r) = &d.swap_router02
+                                    && let Ok(a) = parse_addr(r)
+                                {
+                                    spenders.insert(a);
                                 }
-                                if let Some(r) = &d.universal_router {
-                                    if let Ok(a) = parse_addr(r) {
-                                        spenders.insert(a);
-                                    }
+                                if let Some(r) = &d.universal_router
+                                    && let Ok(a) = parse_addr(r)
+                                {
+                                    spenders.insert(a);
                                 }
-                                if let Some(r) = &d.smart_router {
-                                    if let Ok(a) = parse_addr(r) {
-                                        spenders.insert(a);
-                                    }
+                                if let Some(r) = &d.smart_router
+                                    && let Ok(a) = parse_addr(r)
+                                {
+                                    spenders.insert(a);
                                 }
                             }
+                            // подписи Permit2: средства тянет сам Permit2 — ему нужен approve токена
+                            if cfg.global.execution.permit2_signatures
+                                && let Ok(p2) = parse_addr(&client.cfg.permit2)
+                            {
+                                spenders.insert(p2);
+                            }
                             let spenders: Vec<Address> = spenders.into_iter().collect();
                             let tokens: Vec<Address> = client
                                 .cfg
@@ -140,352 +453,2374 @@ impl StrategyEngine {
                                 .filter_map(|t| parse_addr(&t.address).ok())
                                 .collect();
                             let min_allowance = U256::from_dec_str("1000000000000000000000000")?;
-                            ensure_approvals(
-                                signer_client.clone(),
-                                &client.cfg,
-                                tokens,
-                                spenders,
-                                min_allowance,
-                            )
-                            .await?;
+                            // у каждого кошелька ротации свои allowance
+                            for signer_client in &signers {
+                                ensure_approvals(
+                                    signer_client.clone(),
+                                    &client.cfg,
+                                    tokens.clone(),
+                                    spenders.clone(),
+                                    min_allowance,
+                                    &mut allowance_cache,
+                                )
+                                .await?;
+                            }
                         }
                     }
                 }
-                Err(e) => tracing::warn!("Signer init failed for chain_id={}: {e:#}", chain_id),
+                Err(e) => {
+                    let reason = format!("signer init failed: {e:#}");
+                    mark_scan_only(require_executors, *chain_id, reason, &mut scan_only)?;
+                }
             }
         }
+        if let Some(path) = &cache_path && let Err(e) = allowance_cache.persist(path) {
+            tracing::warn!("allowance cache {}: {e:#}", path.display());
+        }
+
+        let paper = match &cfg.telemetry.paper_trading {
+            p if p.enabled => {
+                let ledger = PaperLedger::new(open_backend(p)?)
+                    .with_context(|| format!("paper_trading {}", p.path))?;
+                tracing::info!("paper trading: журнал {} ({})", p.path, p.backend);
+                Some(Arc::new(ledger))
+            }
+            _ => None,
+        };
+        #[cfg(feature = "mempool")]
+        let mempool_cooldown =
+            Duration::from_millis(cfg.global.execution.mempool.route_cooldown_ms);
 
         Ok(Self {
-            cfg,
+            daily: Arc::new(Mutex::new(DailyPnl::load_or_new(
+                &state_path(&cfg.telemetry.pnl_report),
+                unix_now(),
+            ))),
+            alerts: Arc::new(WebhookAlert::new(&cfg.telemetry.alerts)),
+            cfg: Arc::new(cfg),
             chains,
             planner,
-            pnl: PnLTracker::new(),
-            executors,
+            pnl: Arc::new(Mutex::new(HashMap::new())),
+            executors: Arc::new(executors),
+            fork_sims: Arc::new(fork_sims),
+            scan_only,
+            pending_cfg: Arc::new(Mutex::new(None)),
+            route_windows: Arc::new(Mutex::new(HashMap::new())),
+            session: Arc::new(Mutex::new(SessionProfit::default())),
+            pre_send: Arc::new(NoopHook),
+            receipts: ReceiptTracker::default(),
+            sent: Arc::new(Mutex::new(HashMap::new())),
+            accepting: Arc::new(AtomicBool::new(true)),
+            halts: Arc::new(Mutex::new(HashMap::new())),
+            volatility: Arc::new(Mutex::new(HashMap::new())),
+            retries: RevertRetries::default(),
+            notional: Arc::new(Mutex::new(HashMap::new())),
+            poll: Arc::new(Mutex::new(HashMap::new())),
+            route_losses: Arc::new(Mutex::new(RouteCooldowns::default())),
+            started_ms: unix_ms(),
+            scrape_halted: Arc::new(AtomicBool::new(false)),
+            paper,
+            #[cfg(feature = "mempool")]
+            mempool_throttle: Arc::new(Mutex::new(TriggerThrottle::new(mempool_cooldown))),
         })
     }
 
-    pub async fn scan_and_execute(&mut self) -> Result<()> {
-        let chain_ids: Vec<u64> = self.cfg.networks.iter().map(|n| n.chain_id).collect();
+    /// Сети, готовые к работе: клиент поднят и сеть либо с экзекутором, либо осознанно
+    /// переведена в scan-only (в том числе при неудачной инициализации подписанта)
+    pub fn ready_networks(&self) -> Vec<u64> {
+        self.chains
+            .clients
+            .keys()
+            .copied()
+            .filter(|id| self.executors.contains_key(id) || self.scan_only.contains_key(id))
+            .collect()
+    }
 
-        for chain_id in chain_ids {
-            if let Some(client) = self.chains.clients.get(&chain_id).cloned() {
-                self.scan_network(&client).await?;
-            }
-        }
-        Ok(())
+    /// Сети в режиме scan-only (без исполнителя) с причиной
+    pub fn scan_only_networks(&self) -> &HashMap<u64, String> {
+        &self.scan_only
     }
 
-    /// per-network override slippage_bps
-    fn network_slippage_bps(&self, chain_id: u64) -> u32 {
-        let default_slip = self.planner.cfg.global.quote.slippage_bps_default;
-        self.planner
+    /// Прогрев (`execution.warmup_pools`): до первого скана параллельно резолвит адреса
+    /// пулов всех ног cross-dex маршрутов в кэш клиентов, сводку по сетям пишет в лог.
+    /// Отсутствующий пул — не ошибка старта: маршрут и так пропустится на котировке.
+    pub async fn warmup(&self) -> HashMap<u64, PoolWarmup> {
+        if !self.cfg.global.execution.warmup_pools {
+            return HashMap::new();
+        }
+        let started = Instant::now();
+        let clients = self
             .cfg
             .networks
             .iter()
-            .find(|n| n.chain_id == chain_id)
-            .and_then(|n| n.strategy_overrides.as_ref())
-            .and_then(|ov| ov.slippage_bps)
-            .unwrap_or(default_slip)
+            .filter_map(|n| self.chains.clients.get(&n.chain_id).cloned());
+        let done = futures::future::join_all(
+            clients.map(|c| async move { (c.cfg.chain_id, warmup_network(&c).await) }),
+        )
+        .await;
+        for (chain_id, w) in &done {
+            for leg in &w.missing {
+                tracing::warn!(chain = chain_id, "прогрев: пул не найден {leg}");
+            }
+            tracing::info!(
+                chain = chain_id,
+                "прогрев пулов: найдено {}, нет пула {}, ошибок {}",
+                w.resolved,
+                w.missing.len(),
+                w.failed.len()
+            );
+        }
+        tracing::info!("прогрев пулов завершён за {} ms", started.elapsed().as_millis());
+        done.into_iter().collect()
     }
 
-    /// per-network override min_profit_bps
-    fn network_min_profit_bps(&self, chain_id: u64) -> u32 {
-        self.planner
-            .cfg
-            .networks
-            .iter()
-            .find(|n| n.chain_id == chain_id)
-            .and_then(|n| n.strategy_overrides.as_ref())
-            .and_then(|ov| ov.min_profit_bps)
-            .unwrap_or(0)
+    /// Подключает pre-send хук (вето/изменение размера сделки перед отправкой)
+    pub fn set_pre_send_hook(&mut self, hook: Arc<dyn PreSendHook>) {
+        self.pre_send = hook;
     }
 
-    async fn scan_network(&mut self, client: &ChainClient) -> Result<()> {
-        let cooldown_sec = self.cfg.safety.circuit_breaker.cooldown_sec;
-        if self.pnl.should_cooldown(cooldown_sec) {
-            let remaining = self
-                .pnl
-                .last_loss_ts
-                .map(|ts| cooldown_sec.saturating_sub(ts.elapsed().as_secs()))
-                .unwrap_or(cooldown_sec);
-            tracing::warn!(
-                chain = client.cfg.chain_id,
-                consec_losses = self.pnl.consec_losses,
-                remaining,
-                "cooldown active ({}s). Skip",
-                remaining
-            );
-            return Ok(());
-        }
+    /// Подключает получателя алертов вместо лога/вебхука по умолчанию
+    pub fn set_alert_sink(&mut self, sink: Arc<dyn AlertSink>) {
+        self.alerts = sink;
+    }
 
-        let max_losses = self.cfg.safety.circuit_breaker.max_losses_in_row;
-        if self.pnl.consec_losses >= max_losses {
-            tracing::warn!(
-                chain = client.cfg.chain_id,
-                consec_losses = self.pnl.consec_losses,
-                "circuit breaker: skipping network (max_losses_in_row={})",
-                max_losses
+    /// Общий дневной агрегат PnL (для задачи сводки)
+    pub fn daily_pnl(&self) -> Arc<Mutex<DailyPnl>> {
+        self.daily.clone()
+    }
+
+    /// Прибыль сессии в base_token (для сводки)
+    pub fn session_profit(&self) -> Arc<Mutex<SessionProfit>> {
+        self.session.clone()
+    }
+
+    /// Сделка в суточную сводку и прибыль сессии: `realized_out` — фактический выход
+    /// подтверждённой транзакции на вход `amount_in` (None — отправка не удалась или revert)
+    fn record_trade(
+        &self,
+        net: &Network,
+        token_in: &str,
+        qr: &QuoteResult,
+        amount_in: U256,
+        realized_out: Option<U256>,
+    ) {
+        let fill = settled_fill(qr, amount_in, realized_out, net.native_usd_hint);
+        if let Some(out) = realized_out
+            && net.base_token.is_some()
+        {
+            let dec = net.tokens.get(token_in).map(|t| t.decimals).unwrap_or(18);
+            let profit = signed_diff_decimals(out, amount_in, dec);
+            let mut session = self.session.lock().unwrap();
+            let added = session.record(
+                net,
+                &self.cfg.global.risk.stables,
+                token_in,
+                profit,
+                fill.gas_native,
             );
-            return Ok(());
+            match (added, session.get(net.chain_id)) {
+                (Some(added), Some(acc)) => tracing::debug!(
+                    chain = net.chain_id,
+                    "session profit +{:.6} {} (total {:.6})",
+                    added,
+                    acc.token,
+                    acc.amount
+                ),
+                _ => tracing::debug!(
+                    chain = net.chain_id,
+                    "no price hint to convert {} profit to base token",
+                    token_in
+                ),
+            }
         }
+        let mut daily = self.daily.lock().unwrap();
+        daily.record(net.chain_id, &fill);
+        let rep = &self.cfg.telemetry.pnl_report;
+        if rep.enabled && let Err(e) = daily.persist(&state_path(rep)) {
+            tracing::error!("pnl state persist error: {e:#}");
+        }
+    }
 
-        let slip_bps = self.network_slippage_bps(client.cfg.chain_id);
-        let min_profit_bps = self.network_min_profit_bps(client.cfg.chain_id);
-        let slip_frac = bps(slip_bps as f64);
-        let min_profit_frac = bps(min_profit_bps as f64);
+    /// Слот для hot-reload: кладём туда уже провалидированный конфиг
+    pub fn reload_handle(&self) -> Arc<Mutex<Option<Config>>> {
+        self.pending_cfg.clone()
+    }
 
-        let strategy = self.cfg.strategies.first();
+    /// Атомарно подменяет конфиг (стратегии/риск/квоты и настройки сетей в клиентах),
+    /// если он был перезагружен
+    fn apply_pending_config(&mut self) {
+        let next = self.pending_cfg.lock().unwrap().take();
+        if let Some(cfg) = next {
+            if let Err(e) = self.cfg.ensure_reload_compatible(&cfg) {
+                tracing::error!("config reload rejected: {e:#}");
+                return;
+            }
+            self.planner = Arc::new(RoutePlanner::from_config(&cfg));
+            self.chains = Arc::new(self.chains.reconfigured(&cfg));
+            crate::utils::set_checksum_output(cfg.telemetry.logs.checksum_addresses);
+            self.cfg = Arc::new(cfg);
+            tracing::info!("config reloaded: version={}", self.cfg.version);
+        }
+    }
 
-        tracing::debug!(
-            chain = client.cfg.chain_id,
-            slip_bps,
-            min_profit_bps,
-            slip_frac,
-            min_profit_frac,
-            "network overrides",
-        );
+    /// Остановка: новые маршруты больше не отправляются (уже начатые сканы доигрываются без send)
+    pub fn stop_accepting(&self) {
+        self.accepting.store(false, Ordering::SeqCst);
+    }
 
-        let mut any_success = false;
+    /// Ждёт receipts отправленных транзакций не дольше `timeout` и логирует их итог.
+    /// Возвращает число транзакций, оставшихся неподтверждёнными.
+    pub async fn drain_pending(&self, timeout: Duration) -> usize {
+        let pending = self.receipts.pending(None).len();
+        if pending == 0 {
+            return 0;
+        }
+        tracing::info!("shutdown: ждём receipts {} отправленных tx (до {:?})", pending, timeout);
+        let poll = Duration::from_millis(self.cfg.global.execution.poll_interval_ms.max(250) as u64);
+        self.receipts
+            .drain(
+                timeout,
+                poll,
+                |chain_id, tx| self.fetch_receipt(chain_id, tx),
+                |settled| self.book_settled(settled),
+            )
+            .await
+            .len()
+    }
 
-        if let Some(routes) = &client.cfg.routes_cross_dex {
-            for r in routes {
-                if let Some(strat) = strategy {
-                    if strat.only_stables.unwrap_or(false) {
-                        let stables = &self.cfg.global.risk.stables;
-                        let a_stable = stables.iter().any(|s| s.eq_ignore_ascii_case(&r.pair[0]));
-                        let b_stable = stables.iter().any(|s| s.eq_ignore_ascii_case(&r.pair[1]));
-                        if !a_stable && !b_stable {
-                            tracing::debug!("skip pair {}-{}: only_stables", r.pair[0], r.pair[1]);
-                            continue;
-                        }
-                    }
-                    if let Some(dexes) = &strat.whitelist_dexes {
-                        if !r
-                            .dexes
-                            .iter()
-                            .all(|d| dexes.iter().any(|w| w.eq_ignore_ascii_case(d)))
-                        {
-                            tracing::debug!(
-                                "skip pair {}-{}: dex not whitelisted",
-                                r.pair[0],
-                                r.pair[1]
-                            );
-                            continue;
-                        }
-                    }
-                    if let Some(pairs) = &strat.whitelist_pairs {
-                        let mut ok = false;
-                        for p in pairs {
-                            if (p[0].eq_ignore_ascii_case(&r.pair[0])
-                                && p[1].eq_ignore_ascii_case(&r.pair[1]))
-                                || (p[0].eq_ignore_ascii_case(&r.pair[1])
-                                    && p[1].eq_ignore_ascii_case(&r.pair[0]))
-                            {
-                                ok = true;
-                                break;
-                            }
-                        }
-                        if !ok {
-                            tracing::debug!(
-                                "skip pair {}-{}: not in whitelist",
-                                r.pair[0],
-                                r.pair[1]
-                            );
-                            continue;
-                        }
-                    }
-                }
-                if self.skip_pair_by_risk(&client.cfg, &r.pair[0], &r.pair[1]) {
-                    continue;
+    /// Сделки по receipts: подтверждённые — с фактическим выходом, revert — без прибыли
+    fn book_settled(&self, settled: &[(PendingTx, TxOutcome)]) {
+        for (p, outcome) in settled {
+            let Some(trade) = self.sent.lock().unwrap().remove(&p.tx) else {
+                continue;
+            };
+            let Some(client) = self.chains.clients.get(&p.chain_id) else {
+                continue;
+            };
+            let realized_out = match outcome {
+                TxOutcome::Confirmed { realized_out, .. } => {
+                    Some(realized_out.unwrap_or(trade.amount_out))
                 }
+                TxOutcome::Reverted { .. } => None,
+            };
+            self.record_trade(
+                &client.cfg,
+                &trade.token_in,
+                &trade.qr,
+                trade.amount_in,
+                realized_out,
+            );
+        }
+    }
 
-                METRIC_ROUTES_SCANNED.inc();
-                let a = addr_of(&client.cfg, &r.pair[0])?;
-                let b = addr_of(&client.cfg, &r.pair[1])?;
-
-                let _ok_liq = self.meets_min_liquidity_hint(
-                    &client.cfg,
-                    &r.pair[0],
-                    &r.pair[1],
-                    None,
-                    None,
-                    Some(a),
-                    Some(b),
+    /// Итоги бумажного журнала по сетям — в лог на завершении
+    pub fn log_paper_totals(&self) {
+        let Some(paper) = &self.paper else {
+            return;
+        };
+        for chain_id in self.chains.clients.keys() {
+            let t = paper.totals(*chain_id);
+            if t.fills > 0 {
+                tracing::info!(
+                    chain = chain_id,
+                    "paper trading: {} сделок, PnL ${:.2}, газ {:.6} native",
+                    t.fills,
+                    t.pnl_usd,
+                    t.gas_native
                 );
-
-                if r.dexes.len() >= 2 {
-                    let dex_a = match client.cfg.dexes.iter().find(|d| d.name == r.dexes[0]) {
-                        Some(d) => d,
-                        None => continue,
-                    };
-                    let dex_b = match client.cfg.dexes.iter().find(|d| d.name == r.dexes[1]) {
-                        Some(d) => d,
-                        None => continue,
-                    };
-                    let dec = client
-                        .cfg
-                        .tokens
-                        .get(&r.pair[0])
-                        .map(|t| t.decimals)
-                        .unwrap_or(18);
-                    let amount_in = u256_from_decimals(1.0, dec);
-                    if let Some(qr) = quote_cross_dex_pair(
-                        &client,
-                        &client.cfg,
-                        (&r.pair[0], &r.pair[1]),
-                        dex_a,
-                        dex_b,
-                        amount_in,
-                        slip_bps,
-                    )
-                    .await?
-                    {
-                        let chain_label = client.cfg.chain_id.to_string();
-                        METRIC_OPPS_FOUND.inc();
-                        METRIC_BEST_PNL_USD
-                            .with_label_values(&[&chain_label])
-                            .set(qr.pnl_usd);
-
-                        let profit = qr.amount_out.saturating_sub(qr.amount_in);
-                        let min_profit = qr.amount_in * U256::from(min_profit_bps as u64)
-                            / U256::from(10_000u64);
-                        if profit < min_profit {
-                            continue;
-                        }
-                        log_candidate(
-                            client.cfg.chain_id,
-                            &format!("{}-{}", r.pair[0], r.pair[1]),
-                            qr.legs.len(),
-                            &qr,
-                        );
-                        if let Some(exec) = self.executors.get(&client.cfg.chain_id) {
-                            let route_calldata =
-                                encode_route_calldata(&qr.legs, qr.amount_in, qr.amount_out)?;
-                            let _ = exec.simulate(route_calldata.clone()).await;
-                            METRIC_LAST_SIM_GAS
-                                .with_label_values(&[&chain_label])
-                                .set(qr.gas_estimate as f64);
-                            if let Some(mode) = run_mode() {
-                                tracing::info!(
-                                    chain = client.cfg.chain_id,
-                                    "{mode}: not sending tx"
-                                );
-                            } else {
-                                match exec.execute(route_calldata.clone(), U256::zero()).await {
-                                    Ok(_tx) => {
-                                        METRIC_TX_SENT.inc();
-                                        METRIC_PROFITABLE_FOUND.inc();
-                                        METRIC_EXEC_OK.with_label_values(&[&chain_label]).inc();
-                                        any_success = true;
-                                    }
-                                    Err(_e) => {
-                                        METRIC_EXEC_FAIL.with_label_values(&[&chain_label]).inc();
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
             }
         }
+    }
 
-        for tri in &client.cfg.triangles {
-            if let Some(strat) = strategy {
-                if strat.only_stables.unwrap_or(false) {
-                    let stables = &self.cfg.global.risk.stables;
-                    let non_stable = |a: &str, b: &str| {
-                        let a_stable = stables.iter().any(|s| s.eq_ignore_ascii_case(a));
-                        let b_stable = stables.iter().any(|s| s.eq_ignore_ascii_case(b));
-                        !a_stable && !b_stable
-                    };
-                    if non_stable(&tri[0], &tri[1])
-                        || non_stable(&tri[1], &tri[2])
-                        || non_stable(&tri[2], &tri[0])
-                    {
-                        tracing::debug!(
-                            "skip triangle {}-{}-{}: only_stables",
-                            tri[0],
-                            tri[1],
-                            tri[2]
-                        );
-                        continue;
-                    }
-                }
-                if let Some(pairs) = &strat.whitelist_pairs {
-                    let in_list = |a: &str, b: &str| {
-                        pairs.iter().any(|p| {
-                            (p[0].eq_ignore_ascii_case(a) && p[1].eq_ignore_ascii_case(b))
-                                || (p[0].eq_ignore_ascii_case(b) && p[1].eq_ignore_ascii_case(a))
-                        })
-                    };
-                    if !(in_list(&tri[0], &tri[1])
-                        && in_list(&tri[1], &tri[2])
-                        && in_list(&tri[2], &tri[0]))
-                    {
-                        tracing::debug!(
-                            "skip triangle {}-{}-{}: pair not whitelisted",
-                            tri[0],
-                            tri[1],
-                            tri[2]
-                        );
-                        continue;
-                    }
-                }
-            }
-            if self.skip_pair_by_risk(&client.cfg, &tri[0], &tri[1])
-                || self.skip_pair_by_risk(&client.cfg, &tri[1], &tri[2])
-            {
+    /// Фактический выход подтверждённых сделок против котировки: при проскальзывании
+    /// больше `safety.halt_on_large_slippage_bps` исполнение в сети останавливается на
+    /// `circuit_breaker.cooldown_sec` (сэндвич или устаревшая котировка)
+    async fn check_slippage(&self, chain_id: u64, settled: &[(PendingTx, TxOutcome)]) {
+        let threshold = self.cfg.safety.halt_on_large_slippage_bps;
+        for (p, outcome) in settled {
+            let Some(slip) = slippage_breach(threshold, p, outcome) else {
                 continue;
-            }
-
-            METRIC_ROUTES_SCANNED.inc();
-            let _a = addr_of(&client.cfg, &tri[0])?;
-            let _b = addr_of(&client.cfg, &tri[1])?;
-            let _c = addr_of(&client.cfg, &tri[2])?;
-            // TODO: котировка A→B→C→A
+            };
+            let cooldown = Duration::from_secs(self.cfg.safety.circuit_breaker.cooldown_sec);
+            self.halts
+                .lock()
+                .unwrap()
+                .insert(chain_id, Instant::now() + cooldown);
+            let label = chain_id.to_string();
+            METRIC_SLIPPAGE_HALTS.with_label_values(&[&label]).inc();
+            METRIC_NETWORK_HALTED.with_label_values(&[&label]).set(1.0);
+            let msg = format!(
+                "tx {:?} ({}): проскальзывание {} bps > {} bps; исполнение в сети остановлено на {}s",
+                p.tx,
+                p.route,
+                slip,
+                threshold,
+                cooldown.as_secs()
+            );
+            self.alerts.alert(chain_id, "slippage_halt", &msg).await;
         }
+    }
 
-        if any_success {
-            self.pnl.on_success();
-        } else {
-            self.pnl.on_loss();
+    /// Dead-man's switch: Prometheus не скрейпит `/metrics` дольше
+    /// `safety.halt_on_scrape_gap_secs` — исполнение на паузе. Возвращает текущий разрыв;
+    /// включение (алерт) и снятие паузы логируются один раз, какая бы сеть их ни заметила
+    async fn scrape_gap_halt(&self, chain_id: u64) -> Option<Duration> {
+        let window = self.cfg.safety.halt_on_scrape_gap_secs;
+        if window == 0 {
+            return None;
         }
-
-        Ok(())
+        let gap = scrape_gap(self.started_ms, Duration::from_secs(window));
+        match (self.scrape_halted.swap(gap.is_some(), Ordering::SeqCst), gap) {
+            (false, Some(gap)) => {
+                let msg = format!(
+                    "dead-man's switch: /metrics не скрейпили {}s (> {window}s) — \
+                     исполнение приостановлено до возобновления мониторинга",
+                    gap.as_secs()
+                );
+                self.alerts.alert(chain_id, "scrape_gap_halt", &msg).await;
+            }
+            (true, None) => tracing::info!(
+                "dead-man's switch: скрейп /metrics возобновился — исполнение снова разрешено"
+            ),
+            _ => {}
+        }
+        gap
     }
-}
 
-// ===== helpers =====
+    /// Обновляет индекс волатильности сети текущей ценой native и возвращает его
+    /// (None — цены нет или точек пока мало); значение уходит в `volatility_index{chain}`
+    async fn update_volatility(&self, client: &ChainClient) -> Option<f64> {
+        let price = client.native_usd().await?;
+        let chain_id = client.cfg.chain_id;
+        let window = self.cfg.safety.volatility_window;
+        let index = {
+            let mut vol = self.volatility.lock().unwrap();
+            let v = vol
+                .entry(chain_id)
+                .or_insert_with(|| VolatilityIndex::new(window));
+            v.push(price);
+            v.index()?
+        };
+        METRIC_VOLATILITY_INDEX
+            .with_label_values(&[&chain_id.to_string()])
+            .set(index);
+        Some(index)
+    }
 
-fn addr_of(n: &Network, sym: &str) -> Result<Address> {
-    let t = n
-        .tokens
-        .get(sym)
-        .ok_or_else(|| anyhow!("token not found: {sym}"))?;
-    parse_addr(&t.address).map_err(|e| anyhow!(e))
-}
+    /// USD-объём сделки по цене токена входа (стейблы без цены — 1:1)
+    async fn notional_usd(&self, client: &ChainClient, token_in: &str, amount: U256) -> Option<f64> {
+        let px = match client.usd_price(token_in).await {
+            Some(px) => px,
+            None => is_stable(&self.cfg.global.risk.stables, token_in).then_some(1.0)?,
+        };
+        let dec = client.cfg.tokens.get(token_in).map(|t| t.decimals).unwrap_or(18);
+        Some(usd_value(amount, dec, px))
+    }
 
-#[derive(Clone, Debug)]
-struct PnLTracker {
-    consec_losses: u32,
-    last_loss_ts: Option<Instant>,
-}
-impl PnLTracker {
-    fn new() -> Self {
-        Self {
-            consec_losses: 0,
-            last_loss_ts: None,
+    /// Остаток суточного лимита сети, если сделка на `notional` USD в него не помещается
+    /// (или её объём не оценить). None — можно отправлять либо лимит выключен.
+    fn notional_blocked(&self, net: &Network, notional: Option<f64>) -> Option<f64> {
+        let cap = net.daily_notional_cap_usd;
+        if cap <= 0.0 {
+            return None;
+        }
+        let remaining = self
+            .notional
+            .lock()
+            .unwrap()
+            .entry(net.chain_id)
+            .or_insert_with(|| NotionalWindow::new(NotionalWindow::DAY_SECS))
+            .remaining(cap, unix_now());
+        METRIC_NOTIONAL_REMAINING
+            .with_label_values(&[&net.chain_id.to_string()])
+            .set(remaining);
+        match notional {
+            Some(usd) if usd <= remaining => None,
+            _ => Some(remaining),
         }
     }
-    fn on_success(&mut self) {
-        self.consec_losses = 0;
-        self.last_loss_ts = None;
+
+    /// Успешная отправка расходует суточный лимит сети
+    fn record_notional(&self, net: &Network, usd: Option<f64>) {
+        let cap = net.daily_notional_cap_usd;
+        let Some(usd) = usd.filter(|_| cap > 0.0) else {
+            return;
+        };
+        let now = unix_now();
+        let mut notional = self.notional.lock().unwrap();
+        let w = notional
+            .entry(net.chain_id)
+            .or_insert_with(|| NotionalWindow::new(NotionalWindow::DAY_SECS));
+        w.record(now, usd);
+        METRIC_NOTIONAL_REMAINING
+            .with_label_values(&[&net.chain_id.to_string()])
+            .set(w.remaining(cap, now));
     }
-    fn on_loss(&mut self) {
-        self.consec_losses = self.consec_losses.saturating_add(1);
+
+    /// Маршрут не котируется: причина в метрику/лог, отложенный ретрай снимается
+    fn on_quote_skip<T>(
+        &self,
+        chain_id: u64,
+        pair: &str,
+        route: &str,
+        outcome: &QuoteOutcome<T>,
+    ) {
+        record_skip(chain_id, SkipReason::NoQuote);
+        let reason = outcome.reason();
+        METRIC_QUOTE_OUTCOMES
+            .with_label_values(&[&chain_id.to_string(), reason])
+            .inc();
+        if let QuoteOutcome::MissingConfig(e) = outcome {
+            tracing::warn!(chain = chain_id, "pair {pair}: {e}");
+        } else {
+            tracing::debug!(chain = chain_id, "pair {pair} skipped: {reason}");
+        }
+        self.cancel_retry(chain_id, route);
+    }
+
+    /// Маршрут после revert больше не прибылен: ожидающий повтор отменяется
+    fn cancel_retry(&self, chain_id: u64, route: &str) {
+        if let Some(n) = self.retries.cancel(chain_id, route) {
+            tracing::info!(
+                chain = chain_id,
+                "маршрут {} после revert больше не прибылен: повтор {} отменён",
+                route,
+                n
+            );
+        }
+    }
+
+    /// Убыток маршрута (revert, ошибка отправки): на `route_max_losses_in_row`-м подряд
+    /// маршрут уходит на паузу, остальные маршруты сети сканируются дальше
+    fn record_route_loss(&self, chain_id: u64, route: &str, why: &str) {
+        let cb = &self.cfg.safety.circuit_breaker;
+        if cb.route_max_losses_in_row == 0 {
+            return;
+        }
+        let n = self
+            .route_losses
+            .lock()
+            .unwrap()
+            .on_loss(chain_id, route, Instant::now());
+        if n >= cb.route_max_losses_in_row {
+            METRIC_ROUTE_COOLDOWN
+                .with_label_values(&[&chain_id.to_string(), route])
+                .set(1.0);
+            tracing::warn!(
+                chain = chain_id,
+                "маршрут {route}: {n} убытков подряд ({why}) — пауза {}s",
+                cb.route_cooldown().as_secs()
+            );
+        }
+    }
+
+    /// Подтверждённая сделка сбрасывает серию убытков маршрута
+    fn record_route_success(&self, chain_id: u64, route: &str) {
+        if self.route_losses.lock().unwrap().on_success(chain_id, route) {
+            METRIC_ROUTE_COOLDOWN
+                .with_label_values(&[&chain_id.to_string(), route])
+                .set(0.0);
+        }
+    }
+
+    /// Остаток паузы маршрута; None — маршрут доступен (истёкшая пауза снимает флаг метрики)
+    fn route_cooling(&self, chain_id: u64, route: &str) -> Option<Duration> {
+        let cb = &self.cfg.safety.circuit_breaker;
+        if cb.route_max_losses_in_row == 0 {
+            return None;
+        }
+        let left = self.route_losses.lock().unwrap().remaining(
+            chain_id,
+            route,
+            cb.route_max_losses_in_row,
+            cb.route_cooldown(),
+            Instant::now(),
+        );
+        if left.is_none()
+            && let Ok(g) = METRIC_ROUTE_COOLDOWN
+                .get_metric_with_label_values(&[&chain_id.to_string(), route])
+        {
+            g.set(0.0);
+        }
+        left
+    }
+
+    /// Сколько ещё действует остановка исполнения в сети; по истечении флаг снимается
+    fn halted(&self, chain_id: u64) -> Option<Duration> {
+        let mut halts = self.halts.lock().unwrap();
+        let until = *halts.get(&chain_id)?;
+        let now = Instant::now();
+        if until > now {
+            return Some(until - now);
+        }
+        halts.remove(&chain_id);
+        METRIC_NETWORK_HALTED
+            .with_label_values(&[&chain_id.to_string()])
+            .set(0.0);
+        None
+    }
+
+    async fn fetch_receipt(&self, chain_id: u64, tx: TxHash) -> Result<Option<TransactionReceipt>> {
+        let client = self
+            .chains
+            .clients
+            .get(&chain_id)
+            .ok_or_else(|| anyhow!("unknown chain_id {chain_id}"))?;
+        client
+            .with_failover_exec("getTransactionReceipt", |p| async move {
+                p.get_transaction_receipt(tx).await
+            })
+            .await
+    }
+
+    /// Сканирует все сети параллельно (не больше `max_parallel_networks` одновременно):
+    /// медленная сеть не задерживает остальные
+    pub async fn scan_and_execute(&mut self) -> Result<()> {
+        if !self.accepting.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.apply_pending_config();
+        let engine = Arc::new(self.clone());
+        let limit = Arc::new(Semaphore::new(
+            self.cfg.global.execution.max_parallel_networks.max(1) as usize,
+        ));
+
+        let adaptive = self.cfg.global.execution.adaptive_poll.clone();
+        let mut deferred = 0usize;
+        let mut tasks = Vec::new();
+        for net in &self.cfg.networks {
+            let Some(client) = self.chains.clients.get(&net.chain_id).cloned() else {
+                continue;
+            };
+            // адаптивный режим: сеть ждёт своего интервала, главный цикл тикает по min_ms
+            if adaptive.enabled {
+                let due = self.poll.lock().unwrap().get(&net.chain_id).map(|(_, due)| *due);
+                if due.is_some_and(|due| Instant::now() < due) {
+                    deferred += 1;
+                    continue;
+                }
+            }
+            let engine = engine.clone();
+            let limit = limit.clone();
+            tasks.push((
+                net.chain_id,
+                tokio::spawn(async move {
+                    let _slot = limit.acquire_owned().await?;
+                    let _timer = METRIC_SCAN_DURATION
+                        .with_label_values(&[&client.cfg.chain_id.to_string()])
+                        .start_timer();
+                    engine.scan_network(&client).await
+                }),
+            ));
+        }
+
+        let mut failed = Vec::new();
+        let scanned = tasks.len();
+        for (chain_id, task) in tasks {
+            let res = task.await.map_err(anyhow::Error::from).and_then(|r| r);
+            match res {
+                Ok(opportunities) if adaptive.enabled => {
+                    self.schedule_next_scan(chain_id, opportunities, &adaptive)
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(chain = chain_id, "scan failed: {e:#}");
+                    failed.push(chain_id);
+                }
+            }
+        }
+        // живость для /healthz: хотя бы одна сеть отсканирована (или все ждут интервала)
+        if failed.len() < scanned || (scanned == 0 && deferred > 0) {
+            scan_heartbeat();
+        }
+        if !failed.is_empty() {
+            return Err(anyhow!("scan failed for chains {:?}", failed));
+        }
+        Ok(())
+    }
+
+    /// Следующий скан сети через адаптивный интервал по итогу текущего
+    fn schedule_next_scan(&self, chain_id: u64, opportunities: usize, cfg: &AdaptivePollCfg) {
+        let mut poll = self.poll.lock().unwrap();
+        let (iv, due) = poll.entry(chain_id).or_insert_with(|| {
+            let base = self.cfg.global.execution.poll_interval_ms as u64;
+            (AdaptiveInterval::new(base, cfg.min_ms, cfg.max_ms), Instant::now())
+        });
+        let before = iv.current_ms();
+        let ms = iv.on_scan(opportunities);
+        *due = Instant::now() + Duration::from_millis(ms);
+        if ms != before {
+            tracing::debug!(chain = chain_id, opportunities, "adaptive poll: {before} → {ms} ms");
+        }
+    }
+
+    /// Стратегии, под которые подходит маршрут: первой — самая строгая, дальше по конфигу.
+    /// Ok([None]) — стратегий в конфиге нет (фильтров нет), Err — ни одна не подошла.
+    fn strategies_for<T: std::fmt::Debug + ?Sized>(
+        &self,
+        route: &T,
+        matches: impl Fn(&Strategy) -> bool,
+    ) -> std::result::Result<Vec<Option<&Strategy>>, ()> {
+        if self.cfg.strategies.is_empty() {
+            return Ok(vec![None]);
+        }
+        let matched: Vec<&Strategy> = self.cfg.strategies.iter().filter(|st| matches(st)).collect();
+        let Some(strictest) = strictest_strategy(&matched) else {
+            tracing::debug!("skip {:?}: no strategy matched", route);
+            return Err(());
+        };
+        let rest = matched.into_iter().filter(|st| !std::ptr::eq(*st, strictest));
+        Ok(std::iter::once(strictest).chain(rest).map(Some).collect())
+    }
+
+    /// Предел ног маршрута его стратегии (`route_hop_limit`)
+    fn max_route_legs(&self, strategy: Option<&str>) -> usize {
+        let st = self.cfg.strategies.iter().find(|st| Some(st.name.as_str()) == strategy);
+        route_hop_limit(st, &self.cfg.routing.route_templates)
+    }
+
+    /// Оставляет стратегии, в чей предел ног укладывается маршрут из `legs` свопов;
+    /// отказ каждой — в debug-лог с её лимитом. Пусто — маршрут длиннее всех пределов.
+    fn retain_within_hops(
+        &self,
+        route: &str,
+        legs: usize,
+        strategies: &mut Vec<Option<&Strategy>>,
+    ) {
+        strategies.retain(|st| {
+            let limit = route_hop_limit(*st, &self.cfg.routing.route_templates);
+            if legs > limit {
+                tracing::debug!(
+                    "skip {route} для стратегии {:?}: {legs} ног > max_route_hops {limit}",
+                    st.map(|s| s.name.as_str())
+                );
+            }
+            legs <= limit
+        });
+    }
+
+    /// `min_profit_bps` стратегии маршрута (0 — без стратегии)
+    fn strategy_min_profit_bps(&self, strategy: Option<&str>) -> u32 {
+        self.cfg
+            .strategies
+            .iter()
+            .find(|st| Some(st.name.as_str()) == strategy)
+            .map_or(0, |st| st.min_profit_bps)
+    }
+
+    /// Лимит газа транзакции маршрута: `gas_limit` его стратегии (None — дефолт экзекутора)
+    fn strategy_gas_limit(&self, strategy: Option<&str>) -> Option<u64> {
+        self.cfg
+            .strategies
+            .iter()
+            .find(|st| Some(st.name.as_str()) == strategy)
+            .map(|st| st.gas_limit)
+    }
+
+    /// Гипотетическая сделка в бумажный журнал (если он включён); сбой записи не мешает скану
+    fn record_paper(&self, chain_id: u64, route: &str, strategy: Option<&str>, qr: &QuoteResult) {
+        let Some(paper) = &self.paper else {
+            return;
+        };
+        let fill = PaperFill::from_quote(unix_now(), chain_id, route, strategy, qr);
+        if let Err(e) = paper.record(&fill) {
+            tracing::warn!(chain = chain_id, "paper ledger: {e:#}");
+        }
+    }
+
+    /// Точная котировка маршрута на размере `amount_in` (как в скане)
+    async fn quote_route(
+        &self,
+        client: &ChainClient,
+        route: ScanRoute<'_>,
+        amount_in: U256,
+        params: &QuoteParams,
+    ) -> Result<QuoteOutcome<QuoteResult>> {
+        let r = match route {
+            ScanRoute::Cross(r) => r,
+            ScanRoute::Triangle(tri) => {
+                // ноги — на первом DEX сети с котировкой, в порядке конфига
+                return quote_triangle(
+                    client,
+                    &client.cfg,
+                    (&tri[0], &tri[1], &tri[2]),
+                    &[],
+                    amount_in,
+                    params.slip_bps,
+                    params.leg_timeout,
+                    params.tick_samples,
+                    params.gas_mode,
+                )
+                .await;
+            }
+        };
+        let dex = |name: &String| {
+            client
+                .cfg
+                .dexes
+                .iter()
+                .find(|d| &d.name == name)
+                .ok_or_else(|| anyhow!("unknown dex '{name}'"))
+        };
+        let (dex_a, dex_b) = match r.dexes.as_slice() {
+            [a, b, ..] => (dex(a)?, dex(b)?),
+            _ => bail!("route {}-{}: need two dexes", r.pair[0], r.pair[1]),
+        };
+        let via: Vec<&str> = r.via.iter().map(String::as_str).collect();
+        quote_cross_dex_pair(
+            client,
+            &client.cfg,
+            (&r.pair[0], &r.pair[1]),
+            &via,
+            dex_a,
+            dex_b,
+            amount_in,
+            params.slip_bps,
+            params.leg_timeout,
+            params.tick_samples,
+            params.gas_mode,
+        )
+        .await
+    }
+
+    /// Котировка маршрута под совпавшие стратегии: каждая — со своим размером входа,
+    /// котировка одна на размер, стратегии с тем же notional делят её. Возвращает
+    /// выбранную стратегию (`best_strategy_quote`) и её котировку. Если не прошёл никто —
+    /// котировка первой (самой строгой) стратегии для причины пропуска; заполненная
+    /// котировка при этом не прошла порог стратегии и становится `None` (пропуск
+    /// `BelowMinProfit` у вызывающего)
+    async fn quote_for_strategies(
+        &self,
+        client: &ChainClient,
+        route: ScanRoute<'_>,
+        strategies: &[Option<&Strategy>],
+        params: &QuoteParams,
+    ) -> Result<Option<(Option<String>, QuoteOutcome<QuoteResult>)>> {
+        let start = route.start();
+        let dec = client.cfg.tokens.get(start).map(|t| t.decimals).unwrap_or(18);
+        let sized = strategies
+            .iter()
+            .any(|st| self.route_notional_usd(st.map(|s| s.name.as_str())).is_some());
+        let token_usd = if sized { client.usd_price(start).await } else { None };
+        let mut amounts: Vec<U256> = Vec::new();
+        let mut cands: Vec<(u32, u64, usize)> = Vec::with_capacity(strategies.len());
+        for st in strategies {
+            let notional = self.route_notional_usd(st.map(|s| s.name.as_str()));
+            let amount_in = amount_for_notional(notional, token_usd, dec);
+            let idx = amounts.iter().position(|a| *a == amount_in).unwrap_or_else(|| {
+                amounts.push(amount_in);
+                amounts.len() - 1
+            });
+            cands.push((
+                st.map_or(0, |s| s.min_profit_bps),
+                st.map_or(u64::MAX, |s| s.gas_limit),
+                idx,
+            ));
+        }
+        let mut outcomes = Vec::with_capacity(amounts.len());
+        for amount_in in amounts {
+            outcomes.push(self.quote_route(client, route, amount_in, params).await?);
+        }
+        let Some(best) = best_strategy_quote(&cands, &outcomes) else {
+            let outcome = outcomes.swap_remove(cands[0].2);
+            if matches!(outcome, QuoteOutcome::Filled(_)) {
+                tracing::debug!(
+                    chain = client.cfg.chain_id,
+                    "{}: ни одна из {} стратегий не прошла свой min_profit_bps / gas_limit",
+                    route.tokens_label(),
+                    strategies.len()
+                );
+                return Ok(None);
+            }
+            return Ok(Some((strategies[0].map(|s| s.name.clone()), outcome)));
+        };
+        let strategy = strategies[best].map(|s| s.name.clone());
+        if strategies.len() > 1 {
+            tracing::debug!(
+                chain = client.cfg.chain_id,
+                "{}: оценено стратегий {}, котировок {}; выбрана {:?}",
+                route.tokens_label(),
+                strategies.len(),
+                outcomes.len(),
+                strategy
+            );
+        }
+        Ok(Some((strategy, outcomes.swap_remove(cands[best].2))))
+    }
+
+    /// Котировка старше `max_quote_age_blocks` перед отправкой: перекотировка на текущем
+    /// блоке (`requote_stale`) с повтором всех порогов прибыли, иначе пропуск.
+    /// Some((котировка для симуляции и отправки, её возраст в блоках)); возраст None —
+    /// проверка выключена или head не получен. None — маршрут пропущен, причина записана
+    async fn requote_before_send(
+        &self,
+        client: &ChainClient,
+        r: ScanRoute<'_>,
+        route: &str,
+        qr: QuoteResult,
+        params: &QuoteParams,
+        gates: &ProfitGates,
+    ) -> Option<(QuoteResult, Option<u64>)> {
+        let chain_id = client.cfg.chain_id;
+        let max_age = self.cfg.global.execution.max_quote_age_blocks;
+        let Some(quoted_at) = qr.quote_block.filter(|_| max_age > 0) else {
+            return Some((qr, None));
+        };
+        let head = match client
+            .with_failover_exec("blockNumber", |p| async move { p.get_block_number().await })
+            .await
+        {
+            Ok(head) => head.as_u64(),
+            Err(e) => {
+                tracing::debug!(
+                    chain = chain_id,
+                    "blockNumber: {e:#}; возраст котировки не проверен"
+                );
+                return Some((qr, None));
+            }
+        };
+        let age = quote_age_blocks(quoted_at, head);
+        METRIC_QUOTE_AGE_BLOCKS
+            .with_label_values(&[&chain_id.to_string()])
+            .observe(age as f64);
+        if !quote_is_stale(age, max_age) {
+            return Some((qr, Some(age)));
+        }
+        let fresh = if self.cfg.global.execution.requote_stale {
+            let pinned = client.pinned_at(head);
+            match self.quote_route(&pinned, r, qr.amount_in, params).await {
+                Ok(outcome) => outcome.filled(),
+                Err(e) => {
+                    tracing::debug!(chain = chain_id, "{route}: перекотировка: {e:#}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        match fresh {
+            Some(f) if gates.check(&f).is_none() => {
+                tracing::debug!(
+                    chain = chain_id,
+                    "{route}: котировка блока {quoted_at} устарела на {age}, \
+                     перекотирована на {head}"
+                );
+                Some((f, Some(0)))
+            }
+            _ => {
+                tracing::debug!(
+                    chain = chain_id,
+                    "{route}: котировка блока {quoted_at} устарела на {age} (> {max_age}), пропуск"
+                );
+                record_skip(chain_id, SkipReason::StaleQuote);
+                self.cancel_retry(chain_id, route);
+                None
+            }
+        }
+    }
+
+    /// Порог чистой PnL в USD: строже из `min_profit_usd` стратегии и сети
+    fn min_profit_usd(&self, net: &Network, strategy: Option<&str>) -> Option<f64> {
+        let st = self
+            .cfg
+            .strategies
+            .iter()
+            .find(|st| Some(st.name.as_str()) == strategy)
+            .and_then(|st| st.min_profit_usd);
+        let network = net.strategy_overrides.as_ref().and_then(|ov| ov.min_profit_usd);
+        st.into_iter().chain(network).reduce(f64::max)
+    }
+
+    /// Размер входа в USD: `quote.notional_usd`, но не больше `max_notional_usd` стратегии
+    fn route_notional_usd(&self, strategy: Option<&str>) -> Option<f64> {
+        let cap = self
+            .cfg
+            .strategies
+            .iter()
+            .find(|st| Some(st.name.as_str()) == strategy)
+            .and_then(|st| st.max_notional_usd);
+        match (self.cfg.global.quote.notional_usd, cap) {
+            (Some(usd), Some(cap)) => Some(usd.min(cap)),
+            (usd, _) => usd,
+        }
+    }
+
+    /// Снимок PnL-трекера сети
+    fn tracker(&self, chain_id: u64) -> PnLTracker {
+        self.pnl
+            .lock()
+            .unwrap()
+            .get(&chain_id)
+            .cloned()
+            .unwrap_or_else(PnLTracker::new)
+    }
+
+    /// per-network override slippage_bps
+    fn network_slippage_bps(&self, chain_id: u64) -> u32 {
+        let default_slip = self.planner.cfg.global.quote.slippage_bps_default;
+        self.planner
+            .cfg
+            .network_by_chain_id(chain_id)
+            .and_then(|n| n.strategy_overrides.as_ref())
+            .and_then(|ov| ov.slippage_bps)
+            .unwrap_or(default_slip)
+    }
+
+    /// per-network override min_profit_bps
+    fn network_min_profit_bps(&self, chain_id: u64) -> u32 {
+        self.planner
+            .cfg
+            .network_by_chain_id(chain_id)
+            .and_then(|n| n.strategy_overrides.as_ref())
+            .and_then(|ov| ov.min_profit_bps)
+            .unwrap_or(0)
+    }
+
+    /// Внеочередной скан маршрутов, задетых крупным свопом из mempool (`execution.mempool`):
+    /// тот же путь, что и у poll, но только по этим маршрутам; каждый — не чаще
+    /// `route_cooldown_ms`. Ok — число найденных возможностей.
+    #[cfg(feature = "mempool")]
+    pub async fn scan_triggered(&self, swap: &PendingSwap) -> Result<usize> {
+        if !self.accepting.load(Ordering::SeqCst) {
+            return Ok(0);
+        }
+        let Some(client) = self.chains.clients.get(&swap.chain_id) else {
+            return Ok(0);
+        };
+        let affected = affected_routes(&client.cfg, swap);
+        let routes =
+            self.mempool_throttle.lock().unwrap().admit(swap.chain_id, &affected, Instant::now());
+        if routes.is_empty() {
+            return Ok(0);
+        }
+        tracing::info!(
+            chain = swap.chain_id,
+            dex = %swap.dex,
+            "mempool: крупный своп {:?} — внеочередной скан маршрутов {:?}",
+            swap.tx,
+            routes
+        );
+        self.scan_routes(client, Some(&routes)).await
+    }
+
+    /// Кандидат скана с котировкой: пороги прибыли, дедупликация за poll, перекотировка,
+    /// симуляция, гейты исполнения и отправка через pre-send хук. Ok(true) — транзакция
+    /// отправлена
+    async fn execute_candidate(
+        &self,
+        scan: &ScanCtx<'_>,
+        dedup: &mut PollDedup,
+        dry_report: &mut Option<DryRunReport>,
+        r: ScanRoute<'_>,
+        strategy: Option<String>,
+        qr: QuoteResult,
+    ) -> Result<bool> {
+        let ScanCtx { client, min_profit_bps, allow_revert, scrape_gap, volatile, gas_spike, .. } =
+            *scan;
+        let chain_id = client.cfg.chain_id;
+        let gas_mode = scan.params.gas_mode;
+        let route = r.label();
+        let pair = r.tokens_label();
+        let chain_label = client.cfg.chain_id.to_string();
+        METRIC_OPPS_FOUND.inc();
+        METRIC_STRATEGY_SELECTED
+            .with_label_values(&[&chain_label, strategy.as_deref().unwrap_or("none")])
+            .inc();
+        METRIC_BEST_PNL_USD
+            .with_label_values(&[&chain_label])
+            .set(qr.pnl_usd);
+
+        // порог выбранной стратегии, override сети может только поднять его
+        let min_profit_bps =
+            min_profit_bps.max(self.strategy_min_profit_bps(strategy.as_deref()));
+        let profit = qr.amount_out.saturating_sub(qr.amount_in);
+        let min_profit = qr.amount_in * U256::from(min_profit_bps as u64)
+            / U256::from(10_000u64);
+        if profit < min_profit {
+            record_skip(chain_id, SkipReason::BelowMinProfit);
+            self.cancel_retry(chain_id, &route);
+            return Ok(false);
+        }
+        let min_usd = self.min_profit_usd(&client.cfg, strategy.as_deref());
+        if !meets_min_profit_usd(&qr, min_usd) {
+            let pnl = if qr.pnl_usd_priced {
+                format!("${:.4}", qr.pnl_usd)
+            } else {
+                "n/a".to_string()
+            };
+            tracing::debug!(
+                chain = chain_id,
+                "{route}: PnL {pnl} < min_profit_usd {min_usd:?}"
+            );
+            record_skip(chain_id, SkipReason::BelowMinProfit);
+            self.cancel_retry(chain_id, &route);
+            return Ok(false);
+        }
+        let gas_limit = self.strategy_gas_limit(strategy.as_deref());
+        if let Some(limit) = gas_limit.filter(|l| qr.gas_estimate > *l) {
+            tracing::debug!(
+                chain = chain_id,
+                "{route}: газ {} > gas_limit {limit} стратегии {:?}",
+                qr.gas_estimate,
+                strategy
+            );
+            record_skip(chain_id, SkipReason::OverGasLimit);
+            self.cancel_retry(chain_id, &route);
+            return Ok(false);
+        }
+        // V4 экзекутор не исполняет: с исполнителем маршрут не отправляется и не занимает
+        // пару/цикл у исполнимых; без него (бумажный журнал, DRY_RUN) — как обычно
+        if quote_only(&qr.legs) && self.executors.contains_key(&chain_id) {
+            tracing::debug!(chain = chain_id, "skip {pair}: нога V4 — только котировки");
+            record_skip(chain_id, SkipReason::QuoteOnly);
+            self.cancel_retry(chain_id, &route);
+            return Ok(false);
+        }
+        // из пересекающихся маршрутов (та же пара, тот же цикл, общее ребро с треугольником)
+        // исполняется первый — самый прибыльный; бумажный журнал видит тот же выбор
+        if !r.claim(dedup, chain_id) {
+            tracing::debug!("skip {pair}: already executed this poll");
+            record_skip(chain_id, SkipReason::Duplicate);
+            return Ok(false);
+        }
+        let wallets = self.executors.get(&client.cfg.chain_id);
+        // симуляции и адрес экзекутора — от первого кошелька, отправка — по кругу
+        let exec = wallets.map(|w| w.primary());
+        // с экзекутором кандидат логируется после симуляции (с её прибылью)
+        if exec.is_none() {
+            log_candidate(
+                &self.cfg.telemetry.logs.candidates,
+                &Candidate {
+                    chain_id: client.cfg.chain_id,
+                    pair_or_tri: &pair,
+                    strategy: strategy.as_deref(),
+                    qr: &qr,
+                    sim_profit: None,
+                    quote_age: None,
+                },
+            );
+            // без исполнителя отправки не будет — кандидат уходит в бумажный журнал
+            self.record_paper(chain_id, &route, strategy.as_deref(), &qr);
+        }
+        if let Some(rep) = dry_report.as_mut() {
+            rep.on_candidate(DryRunCandidate {
+                pair: pair.clone(),
+                strategy: strategy.clone(),
+                legs: r.dexes(),
+                amount_in: qr.amount_in.to_string(),
+                amount_out: qr.amount_out.to_string(),
+                pnl_usd: qr.pnl_usd,
+            });
+        }
+        if let Some(exec) = exec {
+            // пока котировались остальные маршруты скана, блок мог уйти вперёд
+            let gates = ProfitGates { min_profit_bps, min_usd, gas_limit };
+            let Some((qr, quote_age)) = self
+                .requote_before_send(client, r, &route, qr, &scan.params, &gates)
+                .await
+            else {
+                return Ok(false);
+            };
+            let deadline_seconds = self.cfg.global.quote.deadline_seconds;
+            // котировки ног — на вход qr.amount_in; хук может изменить размер
+            let leg_slippage = self
+                .cfg
+                .global
+                .quote
+                .leg_slippage_bps
+                .map(|bps| LegSlippage { bps, quoted_in: qr.amount_in });
+            let max_legs = self.max_route_legs(strategy.as_deref());
+            let route_calldata = encode_route_calldata(
+                &qr.legs,
+                qr.amount_in,
+                qr.amount_out,
+                leg_slippage,
+                route_deadline(unix_now(), deadline_seconds),
+                max_legs,
+            )?;
+            // eth_call simulate() (execute() на форке): revert или прибыль ниже порога —
+            // не отправляем
+            let sim_gas = simulate_gas_limit(
+                qr.legs.len(),
+                self.cfg.global.execution.simulate_gas_limit,
+            );
+            let sim = {
+                // симуляция идёт мимо ChainClient: слот исполнения берётся здесь и
+                // отпускается до следующих RPC, чтобы не ждать второй слот, держа первый
+                let budget = client.budget();
+                let _permit = budget.acquire_exec().await;
+                match self.fork_sims.get(&chain_id) {
+                    Some(fork) => {
+                        fork.simulate(
+                            &client.provider(),
+                            route_calldata.clone(),
+                            funding_for(&client.cfg, r.start()),
+                            qr.amount_in,
+                            sim_gas,
+                        )
+                        .await
+                    }
+                    None => exec.simulate(route_calldata.clone(), sim_gas).await,
+                }
+            };
+            let sim_profit = match &sim {
+                Ok(outcome) => outcome.profit(),
+                Err(e) => {
+                    tracing::debug!(
+                        chain = chain_id,
+                        "simulate {} failed: {e:#}",
+                        pair
+                    );
+                    None
+                }
+            };
+            log_candidate(
+                &self.cfg.telemetry.logs.candidates,
+                &Candidate {
+                    chain_id: client.cfg.chain_id,
+                    pair_or_tri: &pair,
+                    strategy: strategy.as_deref(),
+                    qr: &qr,
+                    sim_profit,
+                    quote_age,
+                },
+            );
+            METRIC_LAST_SIM_GAS
+                .with_label_values(&[&chain_label])
+                .set(qr.gas_estimate as f64);
+            if let Some(p) = sim_profit {
+                let dec =
+                    client.cfg.tokens.get(r.start()).map_or(18, |t| t.decimals);
+                METRIC_SIM_PROFIT
+                    .with_label_values(&[&chain_label])
+                    .set(u256_to_f64(p) / 10f64.powi(dec as i32));
+            }
+            let min_sim_bps = self.cfg.safety.min_simulated_profit_bps;
+            if !simulation_passes(sim_profit, qr.amount_in, min_sim_bps) {
+                tracing::info!(
+                    chain = chain_id,
+                    "simulation: маршрут {} не отправляется (прибыль {} < {} bps)",
+                    pair,
+                    match &sim {
+                        Ok(SimOutcome::Profit(p)) => p.to_string(),
+                        Ok(SimOutcome::Reverted(reason)) => {
+                            format!("revert: {reason}")
+                        }
+                        Err(_) => "ошибка вызова".to_string(),
+                    },
+                    min_sim_bps
+                );
+                record_skip(chain_id, SkipReason::SimulationFailed);
+                if let Ok(SimOutcome::Reverted(_)) = &sim {
+                    self.record_route_loss(chain_id, &route, "revert в simulate");
+                }
+                self.cancel_retry(chain_id, &route);
+                return Ok(false);
+            }
+            // исполнение идёт вне квоты котировок (приоритетные слоты бюджета)
+            let notional =
+                self.notional_usd(&client.for_exec(), r.start(), qr.amount_in).await;
+            if let Some(mode) = run_mode() {
+                tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "{mode}: not sending tx"
+                );
+                self.record_paper(chain_id, &route, strategy.as_deref(), &qr);
+            } else if let Some(gap) = scrape_gap {
+                tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "dead-man's switch: маршрут {} не отправляется (нет скрейпа {}s)",
+                    pair,
+                    gap.as_secs()
+                );
+            } else if volatile {
+                tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "volatility pause: маршрут {} не отправляется",
+                    pair
+                );
+            } else if gas_spike {
+                tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "gas ceiling: маршрут {} не отправляется",
+                    pair
+                );
+            } else if let Some(left) = self.halted(client.cfg.chain_id) {
+                tracing::warn!(
+                    chain = client.cfg.chain_id,
+                    "slippage halt: маршрут {} не отправляется (ещё {}s)",
+                    pair,
+                    left.as_secs()
+                );
+            } else if let Some(left) = self.notional_blocked(&client.cfg, notional) {
+                tracing::warn!(
+                    chain = client.cfg.chain_id,
+                    "daily notional cap: маршрут {} на {} не отправляется (остаток ${:.2})",
+                    pair,
+                    notional.map_or("n/a".to_string(), |usd| format!("${usd:.2}")),
+                    left
+                );
+            } else if !self.accepting.load(Ordering::SeqCst) {
+                tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "shutdown: маршрут {} не отправляется",
+                    pair
+                );
+            } else {
+                let ctx = TradeContext {
+                    chain_id: client.cfg.chain_id,
+                    route: pair.clone(),
+                    amount_in: qr.amount_in,
+                    amount_out: qr.amount_out,
+                    gas_estimate: qr.gas_estimate,
+                    pnl_usd: qr.pnl_usd,
+                };
+                let legs = &qr.legs;
+                // повтор после revert: свежая котировка, газ чуть выше
+                let retry = self.retries.pending(chain_id, &route);
+                let retry_gas = retry.map(|n| retry_gas_price(qr.gas_price, n));
+                // вход по подписи Permit2 вместо on-chain approve экзекутору;
+                // native-вход (нога Wrap) — не ERC20, подпись не нужна
+                let wallet = wallets.map_or(exec, |w| w.next_wallet());
+                let permit = (self.cfg.global.execution.permit2_signatures
+                    && !client.cfg.is_unwrapped_native(r.start()))
+                    .then(|| {
+                        let p2 = parse_addr(&client.cfg.permit2).ok()?;
+                        let token = addr_of(&client.cfg, r.start()).ok()?;
+                        Some((p2, token))
+                    })
+                    .flatten();
+                let sent = send_through_hook(
+                    self.pre_send.as_ref(),
+                    &ctx,
+                    |amount_in, amount_out| async move {
+                        let deadline = route_deadline(unix_now(), deadline_seconds);
+                        let mut calldata = encode_route_calldata(
+                            legs,
+                            amount_in,
+                            amount_out,
+                            leg_slippage,
+                            deadline,
+                            max_legs,
+                        )?;
+                        if let Some((p2, token)) = permit {
+                            calldata = wallet
+                                .attach_permit(calldata, p2, token, amount_in, deadline)
+                                .await?;
+                        }
+                        let opts = route_tx_opts(gas_limit, gas_mode, retry_gas);
+                        let min_profit =
+                            exec_min_profit(allow_revert, amount_in, min_profit_bps);
+                        let tx = wallet
+                            .execute_with_opts(calldata, min_profit, opts)
+                            .await?;
+                        Ok((tx, amount_in, amount_out))
+                    },
+                )
+                .await;
+                match sent {
+                    Ok(None) => {}
+                    Ok(Some((tx, amount_in, amount_out))) => {
+                        // pre-send хук мог изменить размер: учитываем фактический
+                        let sent_usd = match notional {
+                            Some(usd) if amount_in != qr.amount_in => {
+                                Some(usd * u256_to_f64(amount_in) / u256_to_f64(qr.amount_in))
+                            }
+                            other => other,
+                        };
+                        self.record_notional(&client.cfg, sent_usd);
+                        // маршрут круговой: выход — тот же токен, на экзекутор
+                        let expected = client
+                            .cfg
+                            .tokens
+                            .get(r.start())
+                            .and_then(|t| parse_addr(&t.address).ok())
+                            .map(|token| ExpectedOut {
+                                token,
+                                recipient: exec.address,
+                                payer: wallet.payer(),
+                                amount: amount_out,
+                            });
+                        self.receipts.track(
+                            client.cfg.chain_id,
+                            tx,
+                            &route,
+                            expected,
+                        );
+                        // в сводку и прибыль сессии — по receipt, с фактическим выходом
+                        self.sent.lock().unwrap().insert(
+                            tx,
+                            SentTrade {
+                                token_in: r.start().to_string(),
+                                qr: qr.clone(),
+                                amount_in,
+                                amount_out,
+                            },
+                        );
+                        if retry.is_some() {
+                            self.retries.mark_sent(chain_id, &route);
+                            METRIC_REVERT_RETRIES
+                                .with_label_values(&[&chain_label])
+                                .inc();
+                        }
+                        METRIC_TX_SENT.inc();
+                        METRIC_PROFITABLE_FOUND.inc();
+                        METRIC_EXEC_OK.with_label_values(&[&chain_label]).inc();
+                        return Ok(true);
+                    }
+                    Err(_e) => {
+                        METRIC_EXEC_FAIL.with_label_values(&[&chain_label]).inc();
+                        self.record_route_loss(chain_id, &route, "ошибка отправки");
+                        self.record_trade(&client.cfg, r.start(), &qr, qr.amount_in, None);
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Скан сети; Ok — число найденных возможностей (котировок с прибылью)
+    async fn scan_network(&self, client: &ChainClient) -> Result<usize> {
+        self.scan_routes(client, None).await
+    }
+
+    /// Скан окна маршрутов сети (`max_routes_per_poll`) или только `only` (индексы
+    /// `routes_cross_dex`) без сдвига окна
+    async fn scan_routes(&self, client: &ChainClient, only: Option<&[usize]>) -> Result<usize> {
+        let chain_id = client.cfg.chain_id;
+        if !self.receipts.pending(Some(chain_id)).is_empty() {
+            let settled = self
+                .receipts
+                .settle(Some(chain_id), &|c, tx| self.fetch_receipt(c, tx))
+                .await;
+            self.check_slippage(chain_id, &settled).await;
+            self.book_settled(&settled);
+            let max_retry = self.cfg.global.execution.revert_retry;
+            for (p, outcome) in &settled {
+                match outcome {
+                    TxOutcome::Reverted { .. } => {
+                        self.record_route_loss(chain_id, &p.route, "revert")
+                    }
+                    TxOutcome::Confirmed { .. } => self.record_route_success(chain_id, &p.route),
+                }
+                if let Some(n) = self.retries.on_outcome(chain_id, &p.route, outcome, max_retry) {
+                    tracing::info!(
+                        chain = chain_id,
+                        "revert {:?}: маршрут {} будет переотправлен после новой котировки (попытка {}/{})",
+                        p.tx,
+                        p.route,
+                        n,
+                        max_retry
+                    );
+                }
+            }
+        }
+
+        let cooldown_sec = self.cfg.safety.circuit_breaker.cooldown_sec;
+        let pnl = self.tracker(client.cfg.chain_id);
+        if pnl.should_cooldown(cooldown_sec) {
+            let remaining = pnl
+                .last_loss_ts
+                .map(|ts| cooldown_sec.saturating_sub(ts.elapsed().as_secs()))
+                .unwrap_or(cooldown_sec);
+            tracing::warn!(
+                chain = client.cfg.chain_id,
+                consec_losses = pnl.consec_losses,
+                remaining,
+                "cooldown active ({}s). Skip",
+                remaining
+            );
+            self.retries.cancel_chain(chain_id);
+            return Ok(0);
+        }
+
+        let max_losses = self.cfg.safety.circuit_breaker.max_losses_in_row;
+        if pnl.consec_losses >= max_losses {
+            tracing::warn!(
+                chain = client.cfg.chain_id,
+                consec_losses = pnl.consec_losses,
+                "circuit breaker: skipping network (max_losses_in_row={})",
+                max_losses
+            );
+            self.retries.cancel_chain(chain_id);
+            return Ok(0);
+        }
+
+        // высокая волатильность: сканируем и логируем, но не исполняем, пока не утихнет
+        let vol_threshold = self.cfg.safety.halt_on_volatility_index;
+        let volatile = match self.update_volatility(client).await {
+            Some(index) if vol_threshold > 0.0 && index > vol_threshold => {
+                tracing::warn!(
+                    chain = client.cfg.chain_id,
+                    index,
+                    "volatility index {:.4} > {}: исполнение приостановлено",
+                    index,
+                    vol_threshold
+                );
+                true
+            }
+            _ => false,
+        };
+
+        let scrape_gap = self.scrape_gap_halt(chain_id).await;
+
+        let gas_mode = GasPriceMode::parse(&self.cfg.global.quote.gas_price_mode)?;
+        // все ноги скана котируются на одном блоке, иначе кросс-dex спред может быть артефактом;
+        // с rpc.batch_requests блок и цена газа скана приходят одним HTTP-запросом
+        let pin = self.cfg.global.quote.pin_block;
+        let scoped;
+        let client = if self.cfg.rpc.batch_requests {
+            match client.scan_reads(gas_mode).await {
+                Ok(reads) => {
+                    scoped = client.with_scan_reads(reads, pin);
+                    &scoped
+                }
+                Err(e) => {
+                    tracing::warn!(chain = chain_id, "scan reads batch: {e:#}; читаем по одному");
+                    client
+                }
+            }
+        } else if pin {
+            match client
+                .with_failover("blockNumber", |p| async move { p.get_block_number().await })
+                .await
+            {
+                Ok(n) => {
+                    scoped = client.pinned_at(n.as_u64());
+                    &scoped
+                }
+                Err(e) => {
+                    tracing::warn!(chain = chain_id, "blockNumber: {e:#}; котируем на latest");
+                    client
+                }
+            }
+        } else {
+            client
+        };
+
+        // всплеск газа: как и при волатильности, сканируем, но не исполняем
+        let gas_spike = match gas_ceiling_breach(client, gas_mode).await {
+            Ok(Some(gwei)) => {
+                tracing::warn!(
+                    chain = chain_id,
+                    "газ {gwei:.2} gwei > max_gas_price_gwei {}: исполнение приостановлено",
+                    client.cfg.max_gas_price_gwei.unwrap_or_default()
+                );
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                tracing::warn!(chain = chain_id, "gas ceiling: {e:#}; потолок не проверен");
+                false
+            }
+        };
+        if client.cfg.max_gas_price_gwei.is_some() {
+            METRIC_GAS_CEILING_HALT
+                .with_label_values(&[&chain_id.to_string()])
+                .set(if gas_spike { 1.0 } else { 0.0 });
+        }
+
+        let slip_bps = self.network_slippage_bps(client.cfg.chain_id);
+        let min_profit_bps = self.network_min_profit_bps(client.cfg.chain_id);
+        let slip_frac = bps(slip_bps as f64);
+        let leg_timeout = Duration::from_millis(self.cfg.global.quote.leg_quote_timeout_ms);
+        let tick_samples = self.cfg.global.quote.tick_liquidity_sample.unwrap_or(0);
+        let min_profit_frac = bps(min_profit_bps as f64);
+        let allow_revert = self.cfg.safety.allow_revert_on_no_profit;
+        let params = QuoteParams { slip_bps, leg_timeout, tick_samples, gas_mode };
+
+        tracing::debug!(
+            chain = client.cfg.chain_id,
+            slip_bps,
+            min_profit_bps,
+            slip_frac,
+            min_profit_frac,
+            "network overrides",
+        );
+
+        let mut any_success = false;
+        let mut opportunities = 0usize;
+        // одна отправка на (сеть, пара, направление) за poll, сколько бы стратегий ни совпало
+        let mut dedup = PollDedup::default();
+        // сводка «что было бы отправлено» (только DRY_RUN/SAFE_LAUNCH)
+        let dry_cfg = &self.cfg.telemetry.dry_run_report;
+        let mut dry_report =
+            (dry_cfg.enabled && run_mode().is_some()).then(|| DryRunReport::new(client.cfg.chain_id));
+
+        let n_cross = client.cfg.routes_cross_dex.as_ref().map_or(0, |r| r.len());
+        let total = n_cross + client.cfg.triangles.len();
+        let max_routes = self.cfg.global.execution.max_routes_per_poll as usize;
+        let window: Vec<bool> = match only {
+            Some(only) => (0..total).map(|i| only.contains(&i)).collect(),
+            None => {
+                let (window, next_offset) = {
+                    let mut windows = self.route_windows.lock().unwrap();
+                    let w = windows.entry(client.cfg.chain_id).or_default();
+                    (w.advance(total, max_routes), w.offset())
+                };
+                if max_routes > 0 && max_routes < total {
+                    tracing::debug!(
+                        chain = client.cfg.chain_id,
+                        total,
+                        max_routes,
+                        "route window (next offset {})",
+                        next_offset
+                    );
+                }
+                window
+            }
+        };
+
+        // сначала котируем все маршруты окна (точные дубли — один раз), потом исполняем
+        let mut quoted = Vec::new();
+        if let Some(routes) = &client.cfg.routes_cross_dex {
+            for (i, r) in routes.iter().enumerate() {
+                if !window[i] {
+                    if only.is_none() {
+                        record_skip(chain_id, SkipReason::OutOfWindow);
+                    }
+                    continue;
+                }
+                if let Some(left) = self.route_cooling(chain_id, &route_label(&r.pair, &r.dexes)) {
+                    tracing::debug!(
+                        chain = chain_id,
+                        "маршрут {}-{} на паузе после убытков (ещё {}s)",
+                        r.pair[0],
+                        r.pair[1],
+                        left.as_secs()
+                    );
+                    record_skip(chain_id, SkipReason::RouteCooldown);
+                    continue;
+                }
+                let path = r.path();
+                let via: Vec<&str> = r.via.iter().map(String::as_str).collect();
+                if !dedup.first_quote(&path, &r.dexes) {
+                    record_skip(chain_id, SkipReason::Duplicate);
+                    continue;
+                }
+                let Ok(mut strategies) = self.strategies_for(&r.pair, |st| {
+                    pair_matches_strategy(st, &self.cfg.global.risk.stables, &r.pair, &r.dexes)
+                }) else {
+                    record_skip(chain_id, SkipReason::NoStrategy);
+                    continue;
+                };
+                let label = route_label(&r.pair, &r.dexes);
+                // cross-dex пара — две ноги (dex_a, dex_b), каждая по всем хопам пути
+                self.retain_within_hops(&label, 2 * (path.len() - 1), &mut strategies);
+                if strategies.is_empty() {
+                    record_skip(chain_id, SkipReason::TooManyHops);
+                    continue;
+                }
+                if let Some(reason) = self.skip_pair_by_risk(&client.cfg, &r.pair[0], &r.pair[1]) {
+                    record_skip(chain_id, reason);
+                    continue;
+                }
+
+                METRIC_ROUTES_SCANNED.inc();
+                if let Some(rep) = dry_report.as_mut() {
+                    rep.on_route();
+                }
+                if r.dexes.len() >= 2 {
+                    let dex_a = match client.cfg.dexes.iter().find(|d| d.name == r.dexes[0]) {
+                        Some(d) => d,
+                        None => {
+                            record_skip(chain_id, SkipReason::UnknownDex);
+                            continue;
+                        }
+                    };
+                    let dex_b = match client.cfg.dexes.iter().find(|d| d.name == r.dexes[1]) {
+                        Some(d) => d,
+                        None => {
+                            record_skip(chain_id, SkipReason::UnknownDex);
+                            continue;
+                        }
+                    };
+                    if !self.pair_liquidity_ok(client, &r.pair, &[dex_a, dex_b]).await? {
+                        record_skip(chain_id, SkipReason::LowLiquidity);
+                        continue;
+                    }
+                    let dec = client
+                        .cfg
+                        .tokens
+                        .get(&r.pair[0])
+                        .map(|t| t.decimals)
+                        .unwrap_or(18);
+                    if let Some(min_spread) = self.cfg.global.quote.prescreen_min_spread_bps {
+                        let notional = self.cfg.global.quote.prescreen_notional_usd;
+                        let token_usd = match notional {
+                            Some(_) => client.usd_price(&r.pair[0]).await,
+                            None => None,
+                        };
+                        let spread = prescreen_cross_dex_pair(
+                            client,
+                            &client.cfg,
+                            (&r.pair[0], &r.pair[1]),
+                            &via,
+                            dex_a,
+                            dex_b,
+                            amount_for_notional(notional, token_usd, dec),
+                            leg_timeout,
+                            tick_samples,
+                        )
+                        .await?;
+                        if let Some(spread) = spread.filter(|s| *s < min_spread) {
+                            // точной котировки не будет — спред пары по предотсеву
+                            record_pair_spread(chain_id, (&r.pair[0], &r.pair[1]), spread);
+                            tracing::debug!(
+                                chain = chain_id,
+                                "{}: спред {spread:.1} bps < {min_spread} bps, пропуск",
+                                route_label(&r.pair, &r.dexes)
+                            );
+                            record_skip(chain_id, SkipReason::NoSpread);
+                            continue;
+                        }
+                    }
+                    let route = ScanRoute::Cross(r);
+                    match self.quote_for_strategies(client, route, &strategies, &params).await? {
+                        Some((strategy, outcome)) => quoted.push((route, strategy, outcome)),
+                        None => record_skip(chain_id, SkipReason::BelowMinProfit),
+                    }
+                }
+            }
+        }
+
+        for (i, tri) in client.cfg.triangles.iter().enumerate() {
+            if !window[n_cross + i] {
+                if only.is_none() {
+                    record_skip(chain_id, SkipReason::OutOfWindow);
+                }
+                continue;
+            }
+            let label = tri.join("-");
+            if let Some(left) = self.route_cooling(chain_id, &label) {
+                tracing::debug!(
+                    chain = chain_id,
+                    "маршрут {label} на паузе после убытков (ещё {}s)",
+                    left.as_secs()
+                );
+                record_skip(chain_id, SkipReason::RouteCooldown);
+                continue;
+            }
+            let Ok(mut strategies) = self.strategies_for(tri, |st| {
+                triangle_matches_strategy(st, &self.cfg.global.risk.stables, tri)
+            }) else {
+                record_skip(chain_id, SkipReason::NoStrategy);
+                continue;
+            };
+            self.retain_within_hops(&label, tri.len(), &mut strategies);
+            if strategies.is_empty() {
+                record_skip(chain_id, SkipReason::TooManyHops);
+                continue;
+            }
+            let risk = self
+                .skip_pair_by_risk(&client.cfg, &tri[0], &tri[1])
+                .or_else(|| self.skip_pair_by_risk(&client.cfg, &tri[1], &tri[2]));
+            if let Some(reason) = risk {
+                record_skip(chain_id, reason);
+                continue;
+            }
+
+            METRIC_ROUTES_SCANNED.inc();
+            if let Some(rep) = dry_report.as_mut() {
+                rep.on_route();
+            }
+            let route = ScanRoute::Triangle(tri);
+            match self.quote_for_strategies(client, route, &strategies, &params).await? {
+                Some((strategy, outcome)) => quoted.push((route, strategy, outcome)),
+                None => record_skip(chain_id, SkipReason::BelowMinProfit),
+            }
+        }
+
+        // исполнение — от большей PnL к меньшей: из пересекающихся маршрутов (та же пара,
+        // тот же цикл в другом порядке, общее ребро с треугольником) уходит самый прибыльный
+        sort_by_pnl_desc(&mut quoted, |(_, _, outcome)| match outcome {
+            QuoteOutcome::Filled(qr) => Some(qr.pnl_usd),
+            _ => None,
+        });
+        let scan = ScanCtx {
+            client,
+            params,
+            min_profit_bps,
+            allow_revert,
+            scrape_gap,
+            volatile,
+            gas_spike,
+        };
+        for (route, strategy, outcome) in quoted {
+            let QuoteOutcome::Filled(qr) = outcome else {
+                self.on_quote_skip(chain_id, &route.tokens_label(), &route.label(), &outcome);
+                continue;
+            };
+            opportunities += 1;
+            if self
+                .execute_candidate(&scan, &mut dedup, &mut dry_report, route, strategy, qr)
+                .await?
+            {
+                any_success = true;
+            }
+        }
+
+        if let Some(rep) = dry_report {
+            match rep.finish(&dry_cfg.dir, dry_cfg.top_n) {
+                Ok(path) => tracing::info!(
+                    chain = client.cfg.chain_id,
+                    "dry-run report written to {}",
+                    path.display()
+                ),
+                Err(e) => tracing::error!("dry-run report error: {e:#}"),
+            }
+        }
+
+        record_scan_result(&mut self.pnl.lock().unwrap(), client.cfg.chain_id, any_success);
+
+        Ok(opportunities)
+    }
+}
+
+// ===== helpers =====
+
+/// Цена газа (wei) выше `max_gwei`: Some(цена в gwei), иначе None
+pub fn gas_over_ceiling(price: U256, max_gwei: Option<f64>) -> Option<f64> {
+    let gwei = u256_to_f64(price) / 1e9;
+    max_gwei.filter(|max| gwei > *max).map(|_| gwei)
+}
+
+/// Проверка `max_gas_price_gwei` сети по цене газа котировок (`current_gas_price_legacy`,
+/// снимок начала скана, если есть); без потолка RPC не трогается
+pub async fn gas_ceiling_breach(client: &ChainClient, mode: GasPriceMode) -> Result<Option<f64>> {
+    let Some(max_gwei) = client.cfg.max_gas_price_gwei else {
+        return Ok(None);
+    };
+    let price = match client.scan_gas_price() {
+        Some(price) => price,
+        None => {
+            let gas = client.cfg.gas;
+            client
+                .with_failover("gas_price", |p| current_gas_price_legacy(p, gas, mode))
+                .await?
+        }
+    };
+    Ok(gas_over_ceiling(price, Some(max_gwei)))
+}
+
+/// Параллельных резолвов пулов на сеть при прогреве
+const WARMUP_CONCURRENCY: usize = 8;
+
+/// Итог прогрева кэша пулов одной сети; ноги — `dex:IN-OUT`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PoolWarmup {
+    pub resolved: usize,
+    /// Пул (ни один tier) не найден
+    pub missing: Vec<String>,
+    /// Не резолвится: dex/токен не в конфиге или сбой RPC
+    pub failed: Vec<String>,
+}
+
+/// Уникальные хопы (dex, вход, выход) cross-dex маршрутов сети — как их котирует
+/// `quote_cross_dex_pair`: dex_a A→via…→B, dex_b B→…via→A, native через wrapped
+pub fn warmup_legs(net: &Network) -> Vec<(String, String, String)> {
+    let mut legs = Vec::new();
+    for r in net.routes_cross_dex.iter().flatten() {
+        if r.dexes.len() < 2 {
+            continue;
+        }
+        let tokens = r.path();
+        let path: Vec<&str> = tokens.iter().map(|s| net.quote_symbol(s)).collect();
+        let forward = path.windows(2).map(|w| (&r.dexes[0], w[0], w[1]));
+        let back = path.windows(2).rev().map(|w| (&r.dexes[1], w[1], w[0]));
+        for leg in forward.chain(back) {
+            let leg = (leg.0.clone(), leg.1.to_string(), leg.2.to_string());
+            if !legs.contains(&leg) {
+                legs.push(leg);
+            }
+        }
+    }
+    legs
+}
+
+async fn warmup_network(client: &ChainClient) -> PoolWarmup {
+    let net = &client.cfg;
+    let results: Vec<(String, Result<bool>)> = stream::iter(warmup_legs(net))
+        .map(|(dex, a, b)| async move {
+            let label = format!("{dex}:{a}-{b}");
+            let res = match net.dexes.iter().find(|d| d.name == dex) {
+                Some(d) => resolve_leg_pools(client, net, d, &a, &b).await,
+                None => Err(anyhow!("unknown dex '{dex}'")),
+            };
+            (label, res)
+        })
+        .buffer_unordered(WARMUP_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut w = PoolWarmup::default();
+    for (label, res) in results {
+        match res {
+            Ok(true) => w.resolved += 1,
+            Ok(false) => w.missing.push(label),
+            Err(e) => {
+                tracing::warn!(chain = net.chain_id, "прогрев: {label}: {e:#}");
+                w.failed.push(label);
+            }
+        }
+    }
+    w.missing.sort();
+    w.failed.sort();
+    w
+}
+
+fn is_stable(stables: &[String], sym: &str) -> bool {
+    stables.iter().any(|s| s.eq_ignore_ascii_case(sym))
+}
+
+fn pair_in_list(pairs: &[[String; 2]], a: &str, b: &str) -> bool {
+    pairs.iter().any(|p| {
+        (p[0].eq_ignore_ascii_case(a) && p[1].eq_ignore_ascii_case(b))
+            || (p[0].eq_ignore_ascii_case(b) && p[1].eq_ignore_ascii_case(a))
+    })
+}
+
+/// Фильтры стратегии (only_stables / whitelist_dexes / whitelist_pairs) для cross-dex пары
+pub fn pair_matches_strategy(
+    strat: &Strategy,
+    stables: &[String],
+    pair: &[String; 2],
+    dexes: &[String],
+) -> bool {
+    if strat.only_stables.unwrap_or(false)
+        && !is_stable(stables, &pair[0])
+        && !is_stable(stables, &pair[1])
+    {
+        return false;
+    }
+    if let Some(wl) = &strat.whitelist_dexes
+        && !dexes.iter().all(|d| wl.iter().any(|w| w.eq_ignore_ascii_case(d)))
+    {
+        return false;
+    }
+    if let Some(pairs) = &strat.whitelist_pairs && !pair_in_list(pairs, &pair[0], &pair[1]) {
+        return false;
+    }
+    true
+}
+
+/// То же для треугольника: каждое ребро должно пройти only_stables / whitelist_pairs
+pub fn triangle_matches_strategy(strat: &Strategy, stables: &[String], tri: &[String; 3]) -> bool {
+    let edges = [(&tri[0], &tri[1]), (&tri[1], &tri[2]), (&tri[2], &tri[0])];
+    if strat.only_stables.unwrap_or(false)
+        && edges
+            .iter()
+            .any(|(a, b)| !is_stable(stables, a) && !is_stable(stables, b))
+    {
+        return false;
+    }
+    if let Some(pairs) = &strat.whitelist_pairs
+        && !edges.iter().all(|(a, b)| pair_in_list(pairs, a, b))
+    {
+        return false;
+    }
+    true
+}
+
+/// Предел ног (свопов, без Wrap/Unwrap) маршрута: `max_route_hops` стратегии; без него —
+/// самый длинный шаблон `routing.route_templates`, без шаблонов — `MAX_ROUTE_LEGS`
+pub fn route_hop_limit(strategy: Option<&Strategy>, templates: &[RouteTemplate]) -> usize {
+    strategy
+        .and_then(|st| st.max_route_hops)
+        .or_else(|| templates.iter().map(|t| t.max_hops).max())
+        .map_or(MAX_ROUTE_LEGS, |n| n as usize)
+}
+
+/// Отправленная сделка до receipt: котировка и фактический размер (после pre-send хука)
+struct SentTrade {
+    token_in: String,
+    qr: QuoteResult,
+    amount_in: U256,
+    amount_out: U256,
+}
+
+/// Итог сделки для суточной сводки: `realized_out` — фактический выход на вход `amount_in`
+/// (None — не исполнена). Валовая прибыль в native — котировочная, пересчитанная
+/// на фактическую прибыль в токене входа (выход ниже входа — убыток)
+pub fn settled_fill(
+    qr: &QuoteResult,
+    amount_in: U256,
+    realized_out: Option<U256>,
+    native_usd: Option<f64>,
+) -> TradeFill {
+    let quoted = qr.amount_out.saturating_sub(qr.amount_in);
+    let gross_native = match realized_out {
+        Some(out) if !quoted.is_zero() => {
+            qr.profit_native * signed_diff_decimals(out, amount_in, 0) / u256_to_f64(quoted)
+        }
+        _ => 0.0,
+    };
+    TradeFill {
+        success: realized_out.is_some(),
+        gross_native,
+        gas_native: gas_cost_native(qr.gas_estimate, qr.gas_price),
+        native_usd,
+    }
+}
+
+/// Чистая PnL (USD, после газа) не ниже `min_usd`; PnL без цены порог не проходит
+pub fn meets_min_profit_usd(qr: &QuoteResult, min_usd: Option<f64>) -> bool {
+    min_usd.is_none_or(|min| qr.pnl_usd_priced && qr.pnl_usd >= min)
+}
+
+/// Самая строгая стратегия — с наибольшим min_profit_bps (при равенстве — первая в конфиге)
+pub fn strictest_strategy<'a>(matched: &[&'a Strategy]) -> Option<&'a Strategy> {
+    matched
+        .iter()
+        .copied()
+        .reduce(|best, st| if st.min_profit_bps > best.min_profit_bps { st } else { best })
+}
+
+/// Выбор стратегии маршрута по котировкам под каждую совпавшую. `cands[i]` — порог
+/// `min_profit_bps` стратегии, её `gas_limit` и индекс котировки её размера входа в `quotes`.
+/// Побеждает наибольшая PnL среди прошедших свой порог и уложившихся в лимит газа.
+/// Возвращает индекс в `cands`; None — не прошёл никто.
+pub fn best_strategy_quote(
+    cands: &[(u32, u64, usize)],
+    quotes: &[QuoteOutcome<QuoteResult>],
+) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (i, (min_bps, gas_limit, q)) in cands.iter().enumerate() {
+        let QuoteOutcome::Filled(qr) = &quotes[*q] else {
+            continue;
+        };
+        if qr.gas_estimate > *gas_limit {
+            continue;
+        }
+        let profit = qr.amount_out.saturating_sub(qr.amount_in);
+        if profit < qr.amount_in * U256::from(*min_bps) / U256::from(10_000u64) {
+            continue;
+        }
+        if best.is_none_or(|(_, pnl)| qr.pnl_usd > pnl) {
+            best = Some((i, qr.pnl_usd));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Маршрут как цикл ног (токен, DEX): регистр не важен, начало цикла — наименьшая нога.
+/// WETH-USDC через [UniswapV3, AerodromeV2] и USDC-WETH через [AerodromeV2, UniswapV3] —
+/// один и тот же цикл
+pub fn route_key(tokens: &[String], dexes: &[String]) -> String {
+    let legs: Vec<String> = tokens
+        .iter()
+        .zip(dexes)
+        .map(|(t, d)| format!("{}@{}", t.to_uppercase(), d.to_lowercase()))
+        .collect();
+    (0..legs.len().max(1))
+        .map(|start| {
+            let mut rot = legs.clone();
+            rot.rotate_left(start);
+            rot.join(">")
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Точное представление маршрута (без поворота цикла): такие дубли котируются один раз
+fn route_repr(tokens: &[String], dexes: &[String]) -> String {
+    format!("{}|{}", tokens.join("-").to_uppercase(), dexes.join(",").to_lowercase())
+}
+
+/// Дедупликация маршрутов за poll: котировки (точные дубли маршрута из разных
+/// стратегий/шаблонов) и отправки — по паре и по циклу (`route_key`)
+#[derive(Debug, Default)]
+pub struct PollDedup {
+    sent: HashSet<(u64, String, String)>,
+    routes: HashSet<(u64, String)>,
+    quoted: HashSet<String>,
+}
+impl PollDedup {
+    /// true — маршрут ещё не исполнялся в этом poll (и теперь занят)
+    pub fn claim(&mut self, chain_id: u64, token_in: &str, token_out: &str) -> bool {
+        self.sent
+            .insert((chain_id, token_in.to_uppercase(), token_out.to_uppercase()))
+    }
+
+    /// true — ни одно из направленных рёбер ещё не исполнялось в этом poll (и теперь заняты
+    /// все): треугольник не уходит вместе с парой или треугольником с общим ребром
+    pub fn claim_edges(&mut self, chain_id: u64, edges: &[(&str, &str)]) -> bool {
+        let keys: Vec<_> = edges
+            .iter()
+            .map(|(a, b)| (chain_id, a.to_uppercase(), b.to_uppercase()))
+            .collect();
+        if keys.iter().any(|k| self.sent.contains(k)) {
+            return false;
+        }
+        self.sent.extend(keys);
+        true
+    }
+
+    /// true — цикл маршрута ещё не исполнялся в этом poll (в любом порядке ног)
+    pub fn claim_route(&mut self, chain_id: u64, tokens: &[String], dexes: &[String]) -> bool {
+        self.routes.insert((chain_id, route_key(tokens, dexes)))
+    }
+
+    /// true — маршрут в этом виде ещё не котировался в этом скане
+    pub fn first_quote(&mut self, tokens: &[String], dexes: &[String]) -> bool {
+        self.quoted.insert(route_repr(tokens, dexes))
+    }
+}
+
+/// Кандидаты по убыванию PnL; без PnL (котировка не заполнена) — в конце, порядок сохраняется
+pub fn sort_by_pnl_desc<T>(items: &mut [T], pnl: impl Fn(&T) -> Option<f64>) {
+    items.sort_by(|a, b| match (pnl(a), pnl(b)) {
+        (Some(x), Some(y)) => y.total_cmp(&x),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
+/// Почему маршрут пропущен в скане; фиксированный набор — метки `routes_skipped_total`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Маршрут вне окна `max_routes_per_poll` этого poll
+    OutOfWindow,
+    /// Ни одна стратегия не подошла (only_stables / whitelist_dexes / whitelist_pairs)
+    NoStrategy,
+    /// Токен из `risk.blacklist_tokens`
+    Blacklisted,
+    /// Токен вне непустого `risk.allowlist_tokens`
+    NotAllowlisted,
+    /// DEX маршрута не описан в сети
+    UnknownDex,
+    /// Котировка без результата (причина — в `quote_outcomes_total`)
+    NoQuote,
+    /// Прибыль ниже `min_profit_bps` или чистая PnL ниже `min_profit_usd`
+    BelowMinProfit,
+    /// Дубль в этом poll: маршрут уже котировался, либо пара/цикл уже исполнялись
+    Duplicate,
+    /// simulate() откатился или показал прибыль ниже `min_simulated_profit_bps`
+    SimulationFailed,
+    /// Маршрут на паузе после убытков подряд (`route_max_losses_in_row`)
+    RouteCooldown,
+    /// Оценка газа маршрута выше `gas_limit` его стратегии
+    OverGasLimit,
+    /// Ног больше, чем `max_route_hops` любой совпавшей стратегии
+    TooManyHops,
+    /// Котировка старше `max_quote_age_blocks`, перекотировка не дала прибыли (или выключена)
+    StaleQuote,
+    /// Предотсев: спред пары между DEX ниже `prescreen_min_spread_bps`
+    NoSpread,
+    /// В маршруте нога только для котировок (V4): экзекутор её не исполняет
+    QuoteOnly,
+    /// USD-ликвидность v2-пары по стейбл-стороне ниже `risk.min_liquidity_usd`
+    LowLiquidity,
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 16] = [
+        SkipReason::OutOfWindow,
+        SkipReason::NoStrategy,
+        SkipReason::Blacklisted,
+        SkipReason::NotAllowlisted,
+        SkipReason::UnknownDex,
+        SkipReason::NoQuote,
+        SkipReason::BelowMinProfit,
+        SkipReason::Duplicate,
+        SkipReason::SimulationFailed,
+        SkipReason::RouteCooldown,
+        SkipReason::OverGasLimit,
+        SkipReason::TooManyHops,
+        SkipReason::StaleQuote,
+        SkipReason::NoSpread,
+        SkipReason::QuoteOnly,
+        SkipReason::LowLiquidity,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SkipReason::OutOfWindow => "out_of_window",
+            SkipReason::NoStrategy => "no_strategy",
+            SkipReason::Blacklisted => "blacklisted",
+            SkipReason::NotAllowlisted => "not_allowlisted",
+            SkipReason::UnknownDex => "unknown_dex",
+            SkipReason::NoQuote => "no_quote",
+            SkipReason::BelowMinProfit => "below_min_profit",
+            SkipReason::Duplicate => "duplicate",
+            SkipReason::SimulationFailed => "simulation_failed",
+            SkipReason::RouteCooldown => "route_cooldown",
+            SkipReason::OverGasLimit => "over_gas_limit",
+            SkipReason::TooManyHops => "too_many_hops",
+            SkipReason::StaleQuote => "stale_quote",
+            SkipReason::NoSpread => "no_spread",
+            SkipReason::QuoteOnly => "quote_only",
+            SkipReason::LowLiquidity => "low_liquidity",
+        }
+    }
+}
+
+/// Сколько блоков прошло с котировки на `quote_block` до `head`
+pub fn quote_age_blocks(quote_block: u64, head: u64) -> u64 {
+    head.saturating_sub(quote_block)
+}
+
+/// Маршрут скана: cross-dex пара из `routes_cross_dex` или треугольник из `triangles`
+#[derive(Clone, Copy, Debug)]
+enum ScanRoute<'a> {
+    Cross(&'a RouteDex),
+    Triangle(&'a [String; 3]),
+}
+
+impl ScanRoute<'_> {
+    /// Токен входа (и выхода) кругового маршрута
+    fn start(&self) -> &str {
+        match self {
+            ScanRoute::Cross(r) => &r.pair[0],
+            ScanRoute::Triangle(tri) => &tri[0],
+        }
+    }
+
+    /// Токены маршрута для логов: `A-B` / `A-B-C`
+    fn tokens_label(&self) -> String {
+        match self {
+            ScanRoute::Cross(r) => r.pair.join("-"),
+            ScanRoute::Triangle(tri) => tri.join("-"),
+        }
+    }
+
+    /// Ключ маршрута для пауз после убытков и повторов: `route_label` / `A-B-C`
+    fn label(&self) -> String {
+        match self {
+            ScanRoute::Cross(r) => route_label(&r.pair, &r.dexes),
+            ScanRoute::Triangle(tri) => tri.join("-"),
+        }
+    }
+
+    /// DEX маршрута (треугольник выбирает их при котировке)
+    fn dexes(&self) -> Vec<String> {
+        match self {
+            ScanRoute::Cross(r) => r.dexes.clone(),
+            ScanRoute::Triangle(_) => Vec::new(),
+        }
+    }
+
+    /// Занимает маршрут для исполнения в этом poll: цикл и направленные рёбра.
+    /// Цикл пары — по её концам: путь через via по тем же DEX двигает те же токены
+    fn claim(&self, dedup: &mut PollDedup, chain_id: u64) -> bool {
+        match self {
+            ScanRoute::Cross(r) => {
+                dedup.claim_route(chain_id, &r.pair, &r.dexes)
+                    && dedup.claim(chain_id, &r.pair[0], &r.pair[1])
+            }
+            ScanRoute::Triangle(tri) => {
+                let edges = [(&tri[0], &tri[1]), (&tri[1], &tri[2]), (&tri[2], &tri[0])];
+                let any_dex = [String::new(), String::new(), String::new()];
+                dedup.claim_route(chain_id, &tri[..], &any_dex)
+                    && dedup.claim_edges(chain_id, &edges.map(|(a, b)| (a.as_str(), b.as_str())))
+            }
+        }
+    }
+}
+
+/// Общее для кандидатов одного скана сети: клиент, параметры котировки и гейты исполнения
+struct ScanCtx<'a> {
+    client: &'a ChainClient,
+    params: QuoteParams,
+    // override сети; порог стратегии кандидата добавляется в `execute_candidate`
+    min_profit_bps: u32,
+    allow_revert: bool,
+    scrape_gap: Option<Duration>,
+    volatile: bool,
+    gas_spike: bool,
+}
+
+/// Параметры котировки маршрута в скане (общие для всех маршрутов сети)
+#[derive(Clone, Copy)]
+struct QuoteParams {
+    slip_bps: u32,
+    leg_timeout: Duration,
+    tick_samples: u32,
+    gas_mode: GasPriceMode,
+}
+
+/// Пороги прибыли котировки перед отправкой: `min_profit_bps` (больший из порога
+/// стратегии маршрута и override сети), `min_profit_usd` и `gas_limit` стратегии
+#[derive(Clone, Copy, Debug)]
+pub struct ProfitGates {
+    pub min_profit_bps: u32,
+    pub min_usd: Option<f64>,
+    pub gas_limit: Option<u64>,
+}
+
+impl ProfitGates {
+    /// Причина пропуска котировки, не прошедшей пороги; None — проходит все
+    pub fn check(&self, qr: &QuoteResult) -> Option<SkipReason> {
+        let profit = qr.amount_out.saturating_sub(qr.amount_in);
+        let min_profit =
+            qr.amount_in * U256::from(self.min_profit_bps as u64) / U256::from(10_000u64);
+        if profit < min_profit || !meets_min_profit_usd(qr, self.min_usd) {
+            return Some(SkipReason::BelowMinProfit);
+        }
+        if self.gas_limit.is_some_and(|l| qr.gas_estimate > l) {
+            return Some(SkipReason::OverGasLimit);
+        }
+        None
+    }
+}
+
+/// Котировка устарела: возраст больше `max_age` (0 — проверка выключена)
+pub fn quote_is_stale(age: u64, max_age: u64) -> bool {
+    max_age > 0 && age > max_age
+}
+
+/// Параметры отправки маршрута: лимит газа стратегии (`Strategy.gas_limit`), тип
+/// транзакции по `gas_price_mode` и цена газа повтора после revert
+pub fn route_tx_opts(
+    gas_limit: Option<u64>,
+    gas_mode: GasPriceMode,
+    retry_gas: Option<U256>,
+) -> TxOpts {
+    TxOpts {
+        gas_limit,
+        legacy_tx: gas_mode == GasPriceMode::Legacy,
+        legacy_gas_price: retry_gas,
+        ..TxOpts::default()
+    }
+}
+
+/// +1 к `routes_skipped_total{chain,reason}`
+pub fn record_skip(chain_id: u64, reason: SkipReason) {
+    METRIC_ROUTES_SKIPPED
+        .with_label_values(&[&chain_id.to_string(), reason.label()])
+        .inc();
+}
+
+/// Имя маршрута в ретраях, receipts и метриках: пара и DEX ног
+/// (`WETH-USDC@UniswapV3,AerodromeV2`)
+pub fn route_label(pair: &[String; 2], dexes: &[String]) -> String {
+    format!("{}-{}@{}", pair[0], pair[1], dexes.join(","))
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RouteLosses {
+    consec: u32,
+    last_loss: Instant,
+}
+
+/// Убытки отдельных маршрутов (по `route_label`): после `max_losses` подряд маршрут
+/// отдыхает `cooldown`, остальные маршруты сети работают. Сетевой circuit breaker
+/// (`PnLTracker`) остаётся страховкой поверх
+#[derive(Debug, Default)]
+pub struct RouteCooldowns {
+    routes: HashMap<(u64, String), RouteLosses>,
+}
+
+impl RouteCooldowns {
+    /// Убыток маршрута; возвращает число убытков подряд
+    pub fn on_loss(&mut self, chain_id: u64, route: &str, now: Instant) -> u32 {
+        let e = self
+            .routes
+            .entry((chain_id, route.to_string()))
+            .or_insert(RouteLosses {
+                consec: 0,
+                last_loss: now,
+            });
+        e.consec = e.consec.saturating_add(1);
+        e.last_loss = now;
+        e.consec
+    }
+
+    /// Успех маршрута сбрасывает серию; true — маршрут был в учёте
+    pub fn on_success(&mut self, chain_id: u64, route: &str) -> bool {
+        self.routes.remove(&(chain_id, route.to_string())).is_some()
+    }
+
+    /// Остаток паузы маршрута; None — маршрут доступен
+    pub fn remaining(
+        &self,
+        chain_id: u64,
+        route: &str,
+        max_losses: u32,
+        cooldown: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        let e = self.routes.get(&(chain_id, route.to_string()))?;
+        if max_losses == 0 || e.consec < max_losses {
+            return None;
+        }
+        (e.last_loss + cooldown)
+            .checked_duration_since(now)
+            .filter(|left| !left.is_zero())
+    }
+}
+
+/// Итог скана сети → трекер именно этой сети (другие сети не затрагиваются)
+pub fn record_scan_result(trackers: &mut HashMap<u64, PnLTracker>, chain_id: u64, success: bool) {
+    let tracker = trackers.entry(chain_id).or_default();
+    if success {
+        tracker.on_success();
+    } else {
+        tracker.on_loss();
+    }
+}
+
+/// Режим исполнения сети в `executor_mode{chain,mode}`: 1 у текущего, 0 у другого
+fn set_executor_mode(chain_id: u64, executing: bool) {
+    let chain = chain_id.to_string();
+    for (mode, on) in [("execute", executing), ("scan_only", !executing)] {
+        METRIC_EXECUTOR_MODE.with_label_values(&[&chain, mode]).set(if on { 1.0 } else { 0.0 });
+    }
+}
+
+/// Сеть остаётся без исполнителя: с `execution.require_executors` — ошибка старта,
+/// иначе scan-only (маршруты котируются и логируются, транзакции не отправляются)
+fn mark_scan_only(
+    require: bool,
+    chain_id: u64,
+    reason: String,
+    scan_only: &mut HashMap<u64, String>,
+) -> Result<()> {
+    if require {
+        bail!(
+            "chain_id={chain_id}: нет исполнителя ({reason}), а execution.require_executors = true"
+        );
+    }
+    tracing::warn!(
+        "chain_id={chain_id}: scan-only — {reason}; маршруты котируются, но не исполняются"
+    );
+    set_executor_mode(chain_id, false);
+    scan_only.insert(chain_id, reason);
+    Ok(())
+}
+
+/// Адрес токена; native (ETH) — адрес его wrapped-токена
+fn addr_of(n: &Network, sym: &str) -> Result<Address> {
+    let t = n
+        .tokens
+        .get(n.quote_symbol(sym))
+        .ok_or_else(|| anyhow!("token not found: {sym}"))?;
+    parse_addr(&t.address).map_err(|e| anyhow!(e))
+}
+
+/// Серия убытков одной сети (circuit breaker / cooldown)
+#[derive(Clone, Debug)]
+pub struct PnLTracker {
+    pub consec_losses: u32,
+    pub last_loss_ts: Option<Instant>,
+}
+impl PnLTracker {
+    pub fn new() -> Self {
+        Self {
+            consec_losses: 0,
+            last_loss_ts: None,
+        }
+    }
+    pub fn on_success(&mut self) {
+        self.consec_losses = 0;
+        self.last_loss_ts = None;
+    }
+    pub fn on_loss(&mut self) {
+        self.consec_losses = self.consec_losses.saturating_add(1);
         self.last_loss_ts = Some(Instant::now());
     }
-    fn should_cooldown(&self, cooldown_sec: u64) -> bool {
+    pub fn should_cooldown(&self, cooldown_sec: u64) -> bool {
         if self.consec_losses == 0 {
             return false;
         }
@@ -494,68 +2829,91 @@ impl PnLTracker {
             .unwrap_or(false)
     }
 }
+impl Default for PnLTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Токен сети в списке `risk.*_tokens`: элемент списка — адрес или символ
+fn token_listed(net: &Network, list: &[String], sym: &str) -> bool {
+    let addr = net.tokens.get(sym).map(|t| t.address.as_str());
+    list.iter()
+        .any(|x| x.eq_ignore_ascii_case(sym) || addr.is_some_and(|a| x.eq_ignore_ascii_case(a)))
+}
+
+/// Риск-фильтр токенов пары. Непустой `allowlist_tokens` решает первым: токен вне него —
+/// `NotAllowlisted`; внутри allowlist `blacklist_tokens` всё равно действует (`Blacklisted`)
+pub fn pair_risk_skip(net: &Network, risk: &Risk, a_sym: &str, b_sym: &str) -> Option<SkipReason> {
+    let pair = [a_sym, b_sym];
+    let allow = &risk.allowlist_tokens;
+    if !allow.is_empty() && pair.iter().any(|sym| !token_listed(net, allow, sym)) {
+        return Some(SkipReason::NotAllowlisted);
+    }
+    pair.iter()
+        .any(|sym| token_listed(net, &risk.blacklist_tokens, sym))
+        .then_some(SkipReason::Blacklisted)
+}
 
 impl StrategyEngine {
-    // Проверка "чёрного списка" токенов
-    fn skip_pair_by_risk(&self, net: &Network, a_sym: &str, b_sym: &str) -> bool {
-        let bl = &self.cfg.global.risk.blacklist_tokens;
-        let has_black = |sym: &str| {
-            net.tokens
-                .get(sym)
-                .map(|t| bl.iter().any(|x| x.eq_ignore_ascii_case(&t.address)))
-                .unwrap_or(false)
-        };
-        if has_black(a_sym) || has_black(b_sym) {
-            tracing::warn!("skip pair {}-{}: blacklisted token", a_sym, b_sym);
-            return true;
+    // Проверка allowlist и "чёрного списка" токенов
+    fn skip_pair_by_risk(&self, net: &Network, a_sym: &str, b_sym: &str) -> Option<SkipReason> {
+        let reason = pair_risk_skip(net, &self.cfg.global.risk, a_sym, b_sym)?;
+        tracing::warn!("skip pair {}-{}: {}", a_sym, b_sym, reason.label());
+        Some(reason)
+    }
+
+    /// `risk.min_liquidity_usd` по прямым v2-парам `pair` на `dexes`: резервы читаются
+    /// с пары (адрес — из кэша пулов). Пары других типов DEX, без пары или без
+    /// стейбл-стороны не оцениваются; порог 0 — проверка выключена, RPC не трогается
+    async fn pair_liquidity_ok(
+        &self,
+        client: &ChainClient,
+        pair: &[String; 2],
+        dexes: &[&DexConfig],
+    ) -> Result<bool> {
+        if self.cfg.global.risk.min_liquidity_usd == 0 {
+            return Ok(true);
+        }
+        let a = addr_of(&client.cfg, &pair[0])?;
+        let b = addr_of(&client.cfg, &pair[1])?;
+        for dex in dexes.iter().filter(|d| d.dex_type.eq_ignore_ascii_case("v2")) {
+            let Some(factory) = dex.factory.as_deref().and_then(|f| parse_addr(f).ok()) else {
+                continue;
+            };
+            let pool = client
+                .pool_address("getPair", (factory, a, b, 0), |p| v2_get_pair(p, factory, a, b))
+                .await?;
+            if pool == Address::zero() {
+                continue;
+            }
+            let (t0, t1) = client
+                .with_failover("pairTokens", |p| v2_pair_tokens(p.clone(), pool))
+                .await?;
+            let pair_obj = V2Pair { pair: pool };
+            let (r0, r1) = client
+                .with_failover_at("getReserves", |p, block| pair_obj.get_reserves(p.clone(), block))
+                .await?;
+            if !self.meets_min_liquidity_hint(&client.cfg, &pair[0], &pair[1], (r0, t0), (r1, t1)) {
+                return Ok(false);
+            }
         }
-        false
+        Ok(true)
     }
 
     // Эвристика USD-ликвидности
-    #[allow(clippy::too_many_arguments)]
     fn meets_min_liquidity_hint(
         &self,
         net: &Network,
         a_sym: &str,
         b_sym: &str,
-        reserve0: Option<U256>,
-        reserve1: Option<U256>,
-        token0: Option<Address>,
-        token1: Option<Address>,
+        (r0, t0): (U256, Address),
+        (r1, t1): (U256, Address),
     ) -> bool {
         let min_usd = self.cfg.global.risk.min_liquidity_usd as f64;
 
-        // стейблы задаём в конфиге по СИМВОЛАМ (USDC, USDT, DAI, ...)
-        let stable_syms: Vec<String> = self
-            .cfg
-            .global
-            .risk
-            .stables
-            .iter()
-            .map(|s| s.to_uppercase())
-            .collect();
-
-        let (r0, r1, t0, t1) = match (reserve0, reserve1, token0, token1) {
-            (Some(r0), Some(r1), Some(t0), Some(t1)) => (r0, r1, t0, t1),
-            _ => return true, // нет данных — пропускаем проверку
-        };
-
-        // адрес -> является ли стейблом по символу
-        let is_stable_addr = |addr: &Address| {
-            net.tokens.iter().any(|(sym, tk)| {
-                parse_addr(&tk.address)
-                    .ok()
-                    .map(|a| a == *addr && stable_syms.iter().any(|s| s == sym))
-                    .unwrap_or(false)
-            })
-        };
-
-        let usd = if is_stable_addr(&t0) {
-            (r0.as_u128() as f64) / 1e18
-        } else if is_stable_addr(&t1) {
-            (r1.as_u128() as f64) / 1e18
-        } else {
+        let stables = &self.cfg.global.risk.stables;
+        let Some(usd) = stable_liquidity_usd(net, stables, (r0, t0), (r1, t1)) else {
             return true;
         };
 
@@ -573,19 +2931,40 @@ impl StrategyEngine {
     }
 }
 
+/// USD-ликвидность пула по стейбл-стороне (стейблы — символы `risk.stables`, 1:1 к USD);
+/// резерв делится на 10^decimals токена. Обе стороны стейблы — сумма обеих,
+/// ни одной — None (оценить нечем).
+pub fn stable_liquidity_usd(
+    net: &Network,
+    stables: &[String],
+    (r0, t0): (U256, Address),
+    (r1, t1): (U256, Address),
+) -> Option<f64> {
+    // адрес -> decimals, если токен — стейбл по символу
+    let stable_decimals = |addr: Address| {
+        net.tokens
+            .iter()
+            .filter(|(sym, _)| stables.iter().any(|s| s.eq_ignore_ascii_case(sym)))
+            .find(|(_, tk)| parse_addr(&tk.address).is_ok_and(|a| a == addr))
+            .map(|(_, tk)| tk.decimals)
+    };
+    let usd = |reserve: U256, dec: u8| u256_to_f64(reserve) / 10f64.powi(dec as i32);
+    match (stable_decimals(t0), stable_decimals(t1)) {
+        (Some(d0), Some(d1)) => Some(usd(r0, d0) + usd(r1, d1)),
+        (Some(d0), None) => Some(usd(r0, d0)),
+        (None, Some(d1)) => Some(usd(r1, d1)),
+        (None, None) => None,
+    }
+}
+
 // Создаёт SignerMiddleware<Provider<Http>, LocalWallet> для указанной сети.
-// Ключ берём из ENV: PRIVATE_KEY_<chain_id> или PRIVATE_KEY.
+// Ключ: keystore (PRIVATE_KEY_FILE_<chain_id> + пароль) или hex PRIVATE_KEY_<chain_id> / PRIVATE_KEY.
 fn signer_middleware_for_chain(
     provider: Arc<Provider<Http>>,
+    key: &KeySource,
     chain_id: u64,
 ) -> Result<Arc<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-    let pk_env_specific = format!("PRIVATE_KEY_{}", chain_id);
-    let pk = std::env::var(&pk_env_specific)
-        .or_else(|_| std::env::var("PRIVATE_KEY"))
-        .map_err(|_| anyhow!("PRIVATE_KEY (или {pk_env_specific}) не задан"))?;
-
-    let wallet: LocalWallet = pk.parse()?;
-    let wallet = wallet.with_chain_id(chain_id);
+    let wallet = key.wallet()?.with_chain_id(chain_id);
     let sm = SignerMiddleware::new(provider.as_ref().clone(), wallet);
     Ok(Arc::new(sm))
 }
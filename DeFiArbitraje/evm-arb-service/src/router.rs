@@ -1,33 +1,121 @@
 use anyhow::{anyhow, Result};
 use ethers::types::{Address, U256};
+use std::future::Future;
+use std::time::Duration;
 use tracing::debug;
 
+use crate::metrics::{METRIC_LEG_QUOTE_TIMEOUTS, METRIC_PAIR_SPREAD_BPS, METRIC_QUOTE_DURATION};
+use crate::error::ArbError;
 use crate::network::ChainClient;
 
-use crate::calldata::{LegKind, LegQuote};
-use crate::config::{DexConfig, Network};
+use crate::calldata::{LegKind, LegQuote, V4PoolKey, encode_route_calldata};
+use crate::config::{DexConfig, Network, V2ReserveGuard};
 use crate::dex::{
-    amount_out_v2, ensure_not_zero, min_out_bps, solidly_get_pair, solidly_pair_get_amount_out,
-    v2_get_pair, v2_pair_tokens, v3_get_pool, v3_quote_exact_input_single, V2Pair,
+    algebra_pool_by_pair, algebra_quote_exact_input_single, amount_out_v2_path, min_out_bps,
+    solidly_get_pair, solidly_pair_get_amount_out, v2_get_pair, v4_default_tick_spacing,
+    v4_pool_initialized, v4_quote_exact_input_single, v2_pair_tokens, v3_get_pool,
+    v3_input_capacity, v3_quote_exact_input_single, v3_sample_liquidity, v3_slot0_liquidity,
+    V2Pair,
+};
+use crate::utils::{parse_addr, u256_to_f64};
+use crate::utils_gas::{
+    GasPriceMode, covers_gas, current_gas_price_legacy, gas_cost_native, l1_data_fee,
+    net_pnl_usd, token_to_native, token_transfer_overhead, wei_to_native,
 };
-use crate::utils::parse_addr;
-use crate::utils_gas::{current_gas_price_legacy, gas_cost_native, gas_cost_usd};
 
 /// Результат квотинга маршрута
+#[derive(Clone)]
 pub struct QuoteResult {
     pub amount_in: U256,
     pub amount_out: U256,
     pub gas_estimate: u64,
     pub gas_price: U256,
     pub legs: Vec<LegQuote>,
+    /// Чистый PnL в USD; 0.0 если цен не хватило (см. `pnl_usd_priced`)
     pub pnl_usd: f64,
+    /// false — у токена входа или native нет USD-цены, `pnl_usd` не оценка
+    pub pnl_usd_priced: bool,
+    /// Валовая прибыль в native (до газа); 0 если вход не native и его нельзя пересчитать по ценам
+    pub profit_native: f64,
+    /// Блок, на котором котировались ноги (`quote.pin_block`); None — latest
+    pub quote_block: Option<u64>,
+}
+
+/// Итог котировки ноги или маршрута: `Filled` — есть результат, остальное — причина пропуска
+#[derive(Debug)]
+pub enum QuoteOutcome<T> {
+    Filled(T),
+    /// Пул/пара не найдены (только для optional-токенов; для обязательных — ошибка)
+    NoPool,
+    /// Пул есть, но выход нулевой (нет ликвидности, не прошёл сэмпл тиков v3
+    /// или v2-пул вне `v2_reserve_guard`)
+    ZeroOutput,
+    /// После слиппеджа выход не покрывает вход или прибыль не окупает газ
+    BelowMinOut,
+    /// В конфиге DEX не хватает адреса (factory/router/quoter) или тип не поддерживается
+    MissingConfig(ArbError),
+    /// Нога не уложилась в `leg_quote_timeout_ms`
+    Timeout,
+}
+
+impl<T> QuoteOutcome<T> {
+    /// Метка причины для логов/метрик
+    pub fn reason(&self) -> &'static str {
+        match self {
+            QuoteOutcome::Filled(_) => "filled",
+            QuoteOutcome::NoPool => "no_pool",
+            QuoteOutcome::ZeroOutput => "zero_output",
+            QuoteOutcome::BelowMinOut => "below_min_out",
+            QuoteOutcome::MissingConfig(_) => "missing_config",
+            QuoteOutcome::Timeout => "timeout",
+        }
+    }
+
+    pub fn filled(self) -> Option<T> {
+        match self {
+            QuoteOutcome::Filled(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Причина пропуска с другим типом результата (для проброса из ноги в маршрут).
+    /// `Filled` сюда не попадает.
+    pub fn skip<U>(self) -> QuoteOutcome<U> {
+        match self {
+            QuoteOutcome::Filled(_) => unreachable!("skip() on a filled quote"),
+            QuoteOutcome::NoPool => QuoteOutcome::NoPool,
+            QuoteOutcome::ZeroOutput => QuoteOutcome::ZeroOutput,
+            QuoteOutcome::BelowMinOut => QuoteOutcome::BelowMinOut,
+            QuoteOutcome::MissingConfig(e) => QuoteOutcome::MissingConfig(e),
+            QuoteOutcome::Timeout => QuoteOutcome::Timeout,
+        }
+    }
 }
 
-// helper: проверка, является ли символ native-токеном (ETH/WETH и т.п.)
-fn is_native_symbol(net: &Network, sym: &str) -> bool {
-    let s = sym.to_uppercase();
-    let native = net.native_symbol.to_uppercase();
-    s == native || s == format!("W{}", native)
+/// Пул/пара для токенов не найдены. Если в паре есть `optional`-токен — маршрут
+/// молча пропускается (Ok), для обязательных токенов — явная ошибка.
+pub fn unresolved_pool(net: &Network, token_in: &str, token_out: &str, what: &str) -> Result<()> {
+    let optional = [token_in, token_out].iter().any(|sym| {
+        net.tokens
+            .get(&sym.to_uppercase())
+            .map(|t| t.optional)
+            .unwrap_or(false)
+    });
+    if optional {
+        debug!(
+            chain = net.chain_id,
+            "skip {}-{}: {} not found (optional token)", token_in, token_out, what
+        );
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "chain {}: {} not found for required pair {}-{}",
+            net.chain_id,
+            what,
+            token_in,
+            token_out
+        ))
+    }
 }
 
 fn addr_of(net: &Network, sym: &str) -> Result<Address> {
@@ -45,104 +133,426 @@ fn decimals_of(net: &Network, sym: &str) -> u8 {
         .unwrap_or(18)
 }
 
-async fn quote_on_dex(
+/// Котировка одной ноги с таймаутом: зависшее чтение пула считается неудачей ноги
+/// (`QuoteOutcome::Timeout` → маршрут пропускается). `timeout == 0` — без ограничения.
+pub async fn with_leg_timeout<T>(
+    chain_id: u64,
+    leg: &str,
+    timeout: Duration,
+    quote: impl Future<Output = Result<QuoteOutcome<T>>>,
+) -> Result<QuoteOutcome<T>> {
+    if timeout.is_zero() {
+        return quote.await;
+    }
+    match tokio::time::timeout(timeout, quote).await {
+        Ok(res) => res,
+        Err(_) => {
+            METRIC_LEG_QUOTE_TIMEOUTS
+                .with_label_values(&[&chain_id.to_string()])
+                .inc();
+            debug!(chain = chain_id, "leg {} quote timed out after {:?}", leg, timeout);
+            Ok(QuoteOutcome::Timeout)
+        }
+    }
+}
+
+/// Проверка v3-пула по сэмплу тиков (`quote.tick_liquidity_sample`): если `amount_in`
+/// пересекает больше ликвидности, чем есть в ближайших `tick_samples` тиках, fee tier отбрасывается.
+#[allow(clippy::too_many_arguments)]
+async fn v3_fits_sampled_liquidity(
+    client: &ChainClient,
+    net: &Network,
+    pool: Address,
+    fee: u32,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    tick_samples: u32,
+) -> Result<bool> {
+    let zero_for_one = token_in < token_out;
+    let (sqrt_price_x96, tick, _) = client
+        .with_failover_at("slot0", |p, block| v3_slot0_liquidity(p.clone(), pool, block))
+        .await?;
+    let sampled = client
+        .with_failover_at("tickBitmap", |p, block| {
+            v3_sample_liquidity(p.clone(), pool, tick, tick_samples, zero_for_one, block)
+        })
+        .await?;
+    let capacity = v3_input_capacity(sqrt_price_x96, &sampled, zero_for_one);
+    let fits = u256_to_f64(amount_in) <= capacity;
+    if !fits {
+        debug!(
+            chain = net.chain_id,
+            "v3 pool {:#x} fee {}: amount_in {} > ~{:.0} ликвидности в {} тиках, пропуск",
+            pool,
+            fee,
+            amount_in,
+            capacity,
+            sampled.len()
+        );
+    }
+    Ok(fits)
+}
+
+/// Котировка одной ноги на DEX: выход, нога для calldata и газ ноги
+pub type LegFill = (U256, LegQuote, u64);
+
+/// Котировка ноги по пути `path` (вход, промежуточные токены…, выход). Путь длиннее
+/// двух токенов — одна многохоповая v2-нога; на DEX другого типа — `MissingConfig`
+pub async fn quote_on_dex(
+    client: &ChainClient,
+    net: &Network,
+    dex: &DexConfig,
+    path: &[&str],
+    amount_in: U256,
+    tick_samples: u32,
+) -> Result<QuoteOutcome<LegFill>> {
+    if path.len() < 2 {
+        return Err(anyhow!("leg path needs at least 2 tokens, got {}", path.len()));
+    }
+    // таймер пишет в гистограмму и при drop (в т.ч. когда ногу отменил таймаут)
+    let _timer = METRIC_QUOTE_DURATION
+        .with_label_values(&[&net.chain_id.to_string(), &dex.name])
+        .start_timer();
+    if let [tin, tout] = path {
+        return quote_on_dex_inner(client, net, dex, tin, tout, amount_in, tick_samples).await;
+    }
+    if !dex.dex_type.eq_ignore_ascii_case("v2") {
+        return Ok(QuoteOutcome::MissingConfig(ArbError::Config(format!(
+            "dex '{}': multi-hop path {} is v2-only",
+            dex.name,
+            path.join("→")
+        ))));
+    }
+    quote_v2_multihop(client, net, dex, path, amount_in).await
+}
+
+/// Газ ноги по типу DEX, если в `gas_hints` сети нет записи
+pub const V2_LEG_GAS: u64 = 110_000;
+pub const V3_LEG_GAS: u64 = 140_000;
+pub const ALGEBRA_LEG_GAS: u64 = 150_000;
+pub const V4_LEG_GAS: u64 = 160_000;
+pub const SOLIDLY_LEG_GAS: u64 = 110_000;
+/// Каждый следующий хоп v2-пути в том же `swapExactTokensForTokens`
+const V2_EXTRA_HOP_GAS: u64 = 60_000;
+/// `WETH.deposit` / `WETH.withdraw` ноги Wrap/Unwrap
+pub const WRAP_LEG_GAS: u64 = 30_000;
+
+/// Маршрут, начинающийся с native (ETH-USDC): котировался по wrapped-токену, здесь
+/// к нему добавляются Wrap (первой ногой) и Unwrap (последней). Возвращает их газ;
+/// для маршрута из ERC20 — 0 и ноги без изменений
+pub fn wrap_native_ends(
+    net: &Network,
+    start_sym: &str,
+    legs: &mut Vec<LegQuote>,
+    amount_in: U256,
+    amount_out: U256,
+) -> Result<u64> {
+    if !net.is_unwrapped_native(start_sym) {
+        return Ok(0);
+    }
+    let weth = addr_of(net, net.quote_symbol(start_sym))?;
+    legs.insert(
+        0,
+        LegQuote {
+            kind: LegKind::Wrap { weth },
+            quoted_out: amount_in,
+        },
+    );
+    legs.push(LegQuote {
+        kind: LegKind::Unwrap { weth },
+        quoted_out: amount_out,
+    });
+    Ok(2 * WRAP_LEG_GAS)
+}
+
+/// Многохоповая v2-нога на одном router (например USDC→WETH→WBTC): выход считается
+/// по резервам каждой пары пути, в calldata уходит одна `V2`-нога с полным путём
+async fn quote_v2_multihop(
+    client: &ChainClient,
+    net: &Network,
+    dex: &DexConfig,
+    path: &[&str],
+    amount_in: U256,
+) -> Result<QuoteOutcome<LegFill>> {
+    let factory = match dex_addr(dex, "v2 factory", dex.factory.as_ref()) {
+        Ok(a) => a,
+        Err(e) => return Ok(QuoteOutcome::MissingConfig(e)),
+    };
+    let router = match dex_addr(dex, "v2 router", dex.router.as_ref()) {
+        Ok(a) => a,
+        Err(e) => return Ok(QuoteOutcome::MissingConfig(e)),
+    };
+    let tokens = path
+        .iter()
+        .map(|sym| addr_of(net, sym))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut hops = Vec::with_capacity(tokens.len() - 1);
+    for (i, w) in tokens.windows(2).enumerate() {
+        let (token_in, token_out) = (w[0], w[1]);
+        let pair_addr = client
+            .pool_address("getPair", (factory, token_in, token_out, 0), |p| {
+                v2_get_pair(p, factory, token_in, token_out)
+            })
+            .await?;
+        if pair_addr == Address::zero() {
+            unresolved_pool(net, path[i], path[i + 1], "v2 pair")?;
+            return Ok(QuoteOutcome::NoPool);
+        }
+        let (t0, _t1) = client
+            .with_failover("pairTokens", |p| v2_pair_tokens(p.clone(), pair_addr))
+            .await?;
+        let pair_obj = V2Pair { pair: pair_addr };
+        let (r0, r1) = client
+            .with_failover_at("getReserves", |p, block| pair_obj.get_reserves(p.clone(), block))
+            .await?;
+        let reserves = if token_in == t0 { (r0, r1) } else { (r1, r0) };
+        if net.v2_reserve_guard.enabled() {
+            let usd = [
+                reserve_usd(client, net, path[i], reserves.0).await,
+                reserve_usd(client, net, path[i + 1], reserves.1).await,
+            ];
+            if let Some(why) = v2_reserve_violation(&net.v2_reserve_guard, usd) {
+                debug!(
+                    chain = net.chain_id,
+                    "v2 pair {:#x} {}-{}: резервы {} / {} ({}), пропуск",
+                    pair_addr,
+                    path[i],
+                    path[i + 1],
+                    reserves.0,
+                    reserves.1,
+                    why
+                );
+                return Ok(QuoteOutcome::ZeroOutput);
+            }
+        }
+        hops.push(reserves);
+    }
+    let fee_bps = if dex.name.to_lowercase().contains("pancakev2") {
+        25
+    } else {
+        30
+    };
+    let out = amount_out_v2_path(amount_in, &hops, fee_bps);
+    let gas = net.leg_gas(dex, V2_LEG_GAS) + V2_EXTRA_HOP_GAS * (hops.len() as u64 - 1);
+    let leg = LegQuote {
+        kind: LegKind::V2 { router, path: tokens },
+        quoted_out: out,
+    };
+    Ok(leg_outcome(out, leg, gas))
+}
+
+/// Резерв токена `sym` в USD; None — нет цены или decimals
+async fn reserve_usd(client: &ChainClient, net: &Network, sym: &str, reserve: U256) -> Option<f64> {
+    let sym = net.quote_symbol(sym);
+    let decimals = net.tokens.get(sym)?.decimals;
+    let price = client.usd_price(sym).await?;
+    Some(u256_to_f64(reserve) / 10f64.powi(decimals as i32) * price)
+}
+
+/// Нарушение `v2_reserve_guard` резервами пары в USD: причина для лога или None.
+/// Сторона без цены не оценивается; перекос считается только при обеих ценах.
+pub fn v2_reserve_violation(guard: &V2ReserveGuard, usd: [Option<f64>; 2]) -> Option<String> {
+    let known: Vec<f64> = usd.iter().flatten().copied().collect();
+    let smaller = known.iter().copied().reduce(f64::min)?;
+    if guard.min_reserve_usd > 0.0 && smaller < guard.min_reserve_usd {
+        return Some(format!("${smaller:.0} < min_reserve_usd ${}", guard.min_reserve_usd));
+    }
+    if let [Some(a), Some(b)] = usd {
+        let ratio = a.max(b) / a.min(b).max(f64::MIN_POSITIVE);
+        if guard.max_ratio > 0.0 && ratio > guard.max_ratio {
+            return Some(format!("перекос {ratio:.1}x > max_ratio {}", guard.max_ratio));
+        }
+    }
+    None
+}
+
+/// Адрес из конфига DEX; отсутствие или мусор — `MissingConfig`
+fn dex_addr(dex: &DexConfig, what: &str, value: Option<&String>) -> Result<Address, ArbError> {
+    let v = value.ok_or_else(|| ArbError::Config(format!("dex '{}': {what} missing", dex.name)))?;
+    parse_addr(v).map_err(|e| ArbError::Config(format!("dex '{}': {what}: {e}", dex.name)))
+}
+
+/// Резолв адресов пулов ноги `token_in_sym → token_out_sym` на `dex` в кэш клиента —
+/// те же ключи и вызовы, что у котировки (`quote_on_dex`), но без квотеров.
+/// true — нашёлся хотя бы один пул (tier / stable-вариант); Err — нет адреса в конфиге
+/// dex/токена или сбой RPC.
+pub async fn resolve_leg_pools(
     client: &ChainClient,
     net: &Network,
     dex: &DexConfig,
     token_in_sym: &str,
     token_out_sym: &str,
-    amount_in: U256,
-) -> Result<Option<(U256, LegQuote, u64)>> {
+) -> Result<bool> {
     let token_in = addr_of(net, token_in_sym)?;
     let token_out = addr_of(net, token_out_sym)?;
-
+    let fee_tiers: Vec<u32> = dex.fee_tiers_bps.clone().unwrap_or_else(|| vec![3000]);
+    let mut any_pool = false;
     match dex.dex_type.to_lowercase().as_str() {
         "v2" => {
-            let factory = parse_addr(
-                dex.factory
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("v2 factory missing"))?,
-            )
-            .map_err(|e| anyhow!(e))?;
-            let pair_addr = ensure_not_zero(
-                client
-                    .with_failover(|p| v2_get_pair(p.clone(), factory, token_in, token_out))
-                    .await?,
-                "v2_get_pair",
-            )?;
-            let (t0, _t1) = client
-                .with_failover(|p| v2_pair_tokens(p.clone(), pair_addr))
+            let factory = dex_addr(dex, "v2 factory", dex.factory.as_ref())?;
+            let pair = client
+                .pool_address("getPair", (factory, token_in, token_out, 0), |p| {
+                    v2_get_pair(p, factory, token_in, token_out)
+                })
                 .await?;
-            let pair_obj = V2Pair { pair: pair_addr };
-            let (r0, r1) = client
-                .with_failover(|p| pair_obj.get_reserves(p.clone()))
+            any_pool = pair != Address::zero();
+        }
+        "v3" => {
+            let factory = dex_addr(dex, "v3 factory", dex.factory.as_ref())?;
+            for fee in fee_tiers {
+                let pool = client
+                    .pool_address("getPool", (factory, token_in, token_out, fee), |p| {
+                        v3_get_pool(p, factory, token_in, token_out, fee)
+                    })
+                    .await?;
+                any_pool |= pool != Address::zero();
+            }
+        }
+        "v3_algebra" => {
+            let factory = dex_addr(dex, "algebra factory", dex.factory.as_ref())?;
+            let pool = client
+                .pool_address("poolByPair", (factory, token_in, token_out, 0), |p| {
+                    algebra_pool_by_pair(p, factory, token_in, token_out)
+                })
                 .await?;
-            let (res_in, res_out) = if token_in == t0 { (r0, r1) } else { (r1, r0) };
-            let fee_bps = if dex.name.to_lowercase().contains("pancakev2") {
-                25
-            } else {
-                30
+            any_pool = pool != Address::zero();
+        }
+        "v4" => {
+            let pool_manager = dex_addr(dex, "v4 poolManager", dex.pool_manager.as_ref())?;
+            let hooks = match dex.hooks.as_ref() {
+                Some(_) => dex_addr(dex, "v4 hooks", dex.hooks.as_ref())?,
+                None => Address::zero(),
             };
-            let out = amount_out_v2(amount_in, res_in, res_out, fee_bps);
-            if out.is_zero() {
-                return Ok(None);
+            for fee in fee_tiers {
+                let Some(tick_spacing) = v4_default_tick_spacing(fee) else {
+                    continue;
+                };
+                let key = V4PoolKey::new(token_in, token_out, fee, tick_spacing, hooks);
+                let pool = client
+                    .pool_address("v4PoolState", (pool_manager, token_in, token_out, fee), |p| {
+                        async move {
+                            let initialized = v4_pool_initialized(p, pool_manager, &key).await?;
+                            Ok(if initialized { pool_manager } else { Address::zero() })
+                        }
+                    })
+                    .await?;
+                any_pool |= pool != Address::zero();
             }
-            let router = parse_addr(
-                dex.router
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("v2 router missing"))?,
-            )
-            .map_err(|e| anyhow!(e))?;
-            let leg = LegQuote {
-                kind: LegKind::V2 {
-                    router,
-                    path: vec![token_in, token_out],
-                },
-            };
-            Ok(Some((out, leg, 110_000)))
         }
+        t if t.starts_with("solidly") => {
+            let factory = dex_addr(dex, "solidly factory", dex.factory.as_ref())?;
+            let volatile = client
+                .pool_address("getPair", (factory, token_in, token_out, 0), |p| {
+                    solidly_get_pair(p, factory, token_in, token_out, false)
+                })
+                .await?;
+            any_pool = volatile != Address::zero();
+            // stable-пара котируется только если нет volatile — так же и резолвится
+            if !any_pool && dex.stable_pools.unwrap_or(false) {
+                let stable = client
+                    .pool_address("getPair", (factory, token_in, token_out, 1), |p| {
+                        solidly_get_pair(p, factory, token_in, token_out, true)
+                    })
+                    .await?;
+                any_pool = stable != Address::zero();
+            }
+        }
+        other => {
+            return Err(ArbError::Config(format!(
+                "dex '{}': unsupported type '{other}'",
+                dex.name
+            ))
+            .into());
+        }
+    }
+    Ok(any_pool)
+}
+
+/// Цена газа котировки: снимок начала скана (`rpc.batch_requests`), иначе запрос
+async fn quote_gas_price(client: &ChainClient, net: &Network, mode: GasPriceMode) -> Result<U256> {
+    match client.scan_gas_price() {
+        Some(price) => Ok(price),
+        None => {
+            client
+                .with_failover("gas_price", |p| current_gas_price_legacy(p, net.gas, mode))
+                .await
+        }
+    }
+}
+
+/// Нулевой выход пула — `ZeroOutput`, иначе нога заполнена
+pub fn leg_outcome(out: U256, leg: LegQuote, gas: u64) -> QuoteOutcome<LegFill> {
+    if out.is_zero() {
+        QuoteOutcome::ZeroOutput
+    } else {
+        QuoteOutcome::Filled((out, leg, gas))
+    }
+}
+
+async fn quote_on_dex_inner(
+    client: &ChainClient,
+    net: &Network,
+    dex: &DexConfig,
+    token_in_sym: &str,
+    token_out_sym: &str,
+    amount_in: U256,
+    tick_samples: u32,
+) -> Result<QuoteOutcome<LegFill>> {
+    let token_in = addr_of(net, token_in_sym)?;
+    let token_out = addr_of(net, token_out_sym)?;
+    macro_rules! cfg_addr {
+        ($what:expr, $value:expr) => {
+            match dex_addr(dex, $what, $value) {
+                Ok(a) => a,
+                Err(e) => return Ok(QuoteOutcome::MissingConfig(e)),
+            }
+        };
+    }
+
+    match dex.dex_type.to_lowercase().as_str() {
+        "v2" => quote_v2_multihop(client, net, dex, &[token_in_sym, token_out_sym], amount_in).await,
         "v3" => {
-            let factory = parse_addr(
-                dex.factory
+            let factory = cfg_addr!("v3 factory", dex.factory.as_ref());
+            let router = cfg_addr!(
+                "v3 router",
+                dex.swap_router02
                     .as_ref()
-                    .ok_or_else(|| anyhow!("v3 factory missing"))?,
-            )
-            .map_err(|e| anyhow!(e))?;
-            let router_str = dex
-                .swap_router02
-                .as_ref()
-                .or(dex.universal_router.as_ref())
-                .or(dex.router.as_ref())
-                .ok_or_else(|| anyhow!("v3 router missing"))?;
-            let router = parse_addr(router_str).map_err(|e| anyhow!(e))?;
-
-            let quoter_addr = if dex.quoter_v2_hint.unwrap_or(false) {
-                parse_addr(
-                    dex.swap_router02
-                        .as_ref()
-                        .or(dex.universal_router.as_ref())
-                        .ok_or_else(|| anyhow!("v3 quoter unknown"))?,
-                )
-                .ok()
-            } else {
-                None
-            };
-
-            if quoter_addr.is_none() {
-                return Ok(None);
+                    .or(dex.universal_router.as_ref())
+                    .or(dex.router.as_ref())
+            );
+            if !dex.quoter_v2_hint.unwrap_or(false) {
+                return Ok(QuoteOutcome::MissingConfig(ArbError::Config(format!(
+                    "dex '{}': v3 quoter unknown (quoter_v2_hint)",
+                    dex.name
+                ))));
             }
-            let quoter = quoter_addr.unwrap();
+            let quoter = cfg_addr!(
+                "v3 quoter",
+                dex.swap_router02.as_ref().or(dex.universal_router.as_ref())
+            );
 
             let fee_tiers: Vec<u32> = dex.fee_tiers_bps.clone().unwrap_or_else(|| vec![3000]);
+            let mut any_pool = false;
             for fee in fee_tiers {
                 let pool = client
-                    .with_failover(|p| v3_get_pool(p.clone(), factory, token_in, token_out, fee))
+                    .pool_address("getPool", (factory, token_in, token_out, fee), |p| {
+                        v3_get_pool(p, factory, token_in, token_out, fee)
+                    })
                     .await?;
                 if pool == Address::zero() {
                     continue;
                 }
+                any_pool = true;
+                if tick_samples > 0
+                    && !v3_fits_sampled_liquidity(client, net, pool, fee, token_in, token_out, amount_in, tick_samples)
+                        .await?
+                {
+                    continue;
+                }
                 let (out, _) = client
-                    .with_failover(|p| {
+                    .with_failover_at("quoteExactInputSingle", |p, block| {
                         v3_quote_exact_input_single(
                             p.clone(),
                             quoter,
@@ -150,6 +560,7 @@ async fn quote_on_dex(
                             token_out,
                             fee,
                             amount_in,
+                            block,
                         )
                     })
                     .await?;
@@ -163,48 +574,156 @@ async fn quote_on_dex(
                         token_out,
                         fee_bps: fee,
                     },
+                    quoted_out: out,
+                };
+                return Ok(QuoteOutcome::Filled((out, leg, net.leg_gas(dex, V3_LEG_GAS))));
+            }
+            if !any_pool {
+                unresolved_pool(net, token_in_sym, token_out_sym, "v3 pool")?;
+                return Ok(QuoteOutcome::NoPool);
+            }
+            // пулы есть, но ни один tier не дал выхода (или не прошёл сэмпл тиков)
+            Ok(QuoteOutcome::ZeroOutput)
+        }
+        "v3_algebra" => {
+            let factory = cfg_addr!("algebra factory", dex.factory.as_ref());
+            let router = cfg_addr!(
+                "algebra router",
+                dex.swap_router02.as_ref().or(dex.router.as_ref())
+            );
+            let quoter = cfg_addr!("algebra quoter", dex.quoter.as_ref());
+            // один пул на пару, fee tier'ов нет: ключ кэша с fee = 0
+            let pool = client
+                .pool_address("poolByPair", (factory, token_in, token_out, 0), |p| {
+                    algebra_pool_by_pair(p, factory, token_in, token_out)
+                })
+                .await?;
+            if pool == Address::zero() {
+                unresolved_pool(net, token_in_sym, token_out_sym, "algebra pool")?;
+                return Ok(QuoteOutcome::NoPool);
+            }
+            let (out, fee) = client
+                .with_failover_at("algebraQuoteExactInputSingle", |p, block| {
+                    algebra_quote_exact_input_single(
+                        p.clone(),
+                        quoter,
+                        token_in,
+                        token_out,
+                        amount_in,
+                        block,
+                    )
+                })
+                .await?;
+            debug!(
+                chain = net.chain_id,
+                "{}: {}-{} dynamic fee {} (1e-6)",
+                dex.name,
+                token_in_sym,
+                token_out_sym,
+                fee
+            );
+            let leg = LegQuote {
+                kind: LegKind::AlgebraV3 {
+                    router,
+                    token_in,
+                    token_out,
+                },
+                quoted_out: out,
+            };
+            Ok(leg_outcome(out, leg, net.leg_gas(dex, ALGEBRA_LEG_GAS)))
+        }
+        "v4" => {
+            let pool_manager = cfg_addr!("v4 poolManager", dex.pool_manager.as_ref());
+            let router = cfg_addr!(
+                "v4 router",
+                dex.universal_router.as_ref().or(dex.router.as_ref())
+            );
+            let quoter = cfg_addr!("v4 quoter", dex.quoter.as_ref());
+            let hooks = match dex.hooks.as_ref() {
+                Some(_) => cfg_addr!("v4 hooks", dex.hooks.as_ref()),
+                None => Address::zero(),
+            };
+
+            let fee_tiers: Vec<u32> = dex.fee_tiers_bps.clone().unwrap_or_else(|| vec![3000]);
+            let mut any_pool = false;
+            for fee in fee_tiers {
+                let Some(tick_spacing) = v4_default_tick_spacing(fee) else {
+                    debug!("{}: no default tick spacing for fee {}, skip", dex.name, fee);
+                    continue;
+                };
+                let key = V4PoolKey::new(token_in, token_out, fee, tick_spacing, hooks);
+                // отдельного контракта пула нет: в кэше «адрес» пула — PoolManager
+                // (инициализирован) или ноль
+                let cache_key = (pool_manager, token_in, token_out, fee);
+                let pool = client
+                    .pool_address("v4PoolState", cache_key, |p| async move {
+                        let initialized = v4_pool_initialized(p, pool_manager, &key).await?;
+                        Ok(if initialized { pool_manager } else { Address::zero() })
+                    })
+                    .await?;
+                if pool == Address::zero() {
+                    continue;
+                }
+                any_pool = true;
+                let zero_for_one = token_in == key.currency0;
+                let out = client
+                    .with_failover_at("v4QuoteExactInputSingle", |p, block| {
+                        v4_quote_exact_input_single(
+                            p.clone(),
+                            quoter,
+                            &key,
+                            zero_for_one,
+                            amount_in,
+                            block,
+                        )
+                    })
+                    .await?;
+                if out.is_zero() {
+                    continue;
+                }
+                let leg = LegQuote {
+                    kind: LegKind::V4 {
+                        router,
+                        pool_key: key,
+                        zero_for_one,
+                    },
+                    quoted_out: out,
                 };
-                return Ok(Some((out, leg, 140_000)));
+                return Ok(QuoteOutcome::Filled((out, leg, net.leg_gas(dex, V4_LEG_GAS))));
+            }
+            if !any_pool {
+                unresolved_pool(net, token_in_sym, token_out_sym, "v4 pool")?;
+                return Ok(QuoteOutcome::NoPool);
             }
-            Ok(None)
+            Ok(QuoteOutcome::ZeroOutput)
         }
         t if t.starts_with("solidly") => {
-            let factory = parse_addr(
-                dex.factory
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("solidly factory missing"))?,
-            )
-            .map_err(|e| anyhow!(e))?;
-            let router = parse_addr(
-                dex.router
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("solidly router missing"))?,
-            )
-            .map_err(|e| anyhow!(e))?;
+            let factory = cfg_addr!("solidly factory", dex.factory.as_ref());
+            let router = cfg_addr!("solidly router", dex.router.as_ref());
             // сначала volatile
             let mut stable = false;
             let mut pair_addr = client
-                .with_failover(|p| solidly_get_pair(p.clone(), factory, token_in, token_out, false))
+                .pool_address("getPair", (factory, token_in, token_out, 0), |p| {
+                    solidly_get_pair(p, factory, token_in, token_out, false)
+                })
                 .await?;
             if pair_addr == Address::zero() && dex.stable_pools.unwrap_or(false) {
                 stable = true;
                 pair_addr = client
-                    .with_failover(|p| {
-                        solidly_get_pair(p.clone(), factory, token_in, token_out, true)
+                    .pool_address("getPair", (factory, token_in, token_out, 1), |p| {
+                        solidly_get_pair(p, factory, token_in, token_out, true)
                     })
                     .await?;
             }
             if pair_addr == Address::zero() {
-                return Ok(None);
+                unresolved_pool(net, token_in_sym, token_out_sym, "solidly pair")?;
+                return Ok(QuoteOutcome::NoPool);
             }
             let out = client
-                .with_failover(|p| {
-                    solidly_pair_get_amount_out(p.clone(), pair_addr, amount_in, token_in)
+                .with_failover_at("getAmountOut", |p, block| {
+                    solidly_pair_get_amount_out(p.clone(), pair_addr, amount_in, token_in, block)
                 })
                 .await?;
-            if out.is_zero() {
-                return Ok(None);
-            }
             let leg = LegQuote {
                 kind: LegKind::Solidly {
                     router,
@@ -212,105 +731,256 @@ async fn quote_on_dex(
                     stable,
                     token_in,
                 },
+                quoted_out: out,
             };
-            Ok(Some((out, leg, 110_000)))
+            Ok(leg_outcome(out, leg, net.leg_gas(dex, SOLIDLY_LEG_GAS)))
         }
-        _ => Ok(None),
+        other => Ok(QuoteOutcome::MissingConfig(ArbError::Config(format!(
+            "dex '{}': unsupported type '{other}'",
+            dex.name
+        )))),
+    }
+}
+
+/// L1-плата за данные транзакции маршрута в native (`l2_data_fees` сети), иначе 0.
+/// Адрес экзекутора роутеру не известен — размер calldata от него не зависит.
+async fn l1_data_cost_native(
+    client: &ChainClient,
+    net: &Network,
+    legs: &[LegQuote],
+    amount_in: U256,
+    amount_out: U256,
+    gas_units: u64,
+) -> Result<f64> {
+    // убыточный маршрут отсеется ниже — не тратим на него RPC
+    if !net.l2_data_fees || amount_out <= amount_in {
+        return Ok(0.0);
+    }
+    // только размер calldata: минимумы ног на него не влияют
+    let data =
+        encode_route_calldata(legs, amount_in, amount_out, None, U256::zero(), legs.len())?;
+    let fee = client
+        .with_failover("getL1Fee", |p| {
+            l1_data_fee(p, net.chain_id, Address::zero(), data.clone(), gas_units)
+        })
+        .await?;
+    Ok(wei_to_native(fee))
+}
+
+/// Спред (bps) выходов одной ноги A→B на двух DEX: насколько `out_a` больше `out_b`.
+/// Круг A→B на dex_a, B→A на dex_b окупается примерно при положительном спреде.
+pub fn implied_spread_bps(out_a: U256, out_b: U256) -> f64 {
+    if out_b.is_zero() {
+        return 0.0;
     }
+    let (a, b) = (u256_to_f64(out_a), u256_to_f64(out_b));
+    (a - b) / b * 10_000.0
+}
+
+/// Валовой спред круга (bps): выход против входа после комиссий пулов, до газа
+pub fn round_trip_spread_bps(amount_in: U256, amount_out: U256) -> f64 {
+    implied_spread_bps(amount_out, amount_in)
+}
+
+/// `pair_spread_bps{chain,pair}`: пара — как в конфиге (`A-B`)
+pub fn record_pair_spread(chain_id: u64, pair: (&str, &str), spread_bps: f64) {
+    METRIC_PAIR_SPREAD_BPS
+        .with_label_values(&[&chain_id.to_string(), &format!("{}-{}", pair.0, pair.1)])
+        .set(spread_bps);
+}
+
+/// Путь ноги A→via…→B в символах котировки (native — через wrapped)
+fn quote_path<'a>(net: &'a Network, pair: (&'a str, &'a str), via: &[&'a str]) -> Vec<&'a str> {
+    let mut path = vec![net.quote_symbol(pair.0)];
+    path.extend(via.iter().map(|s| net.quote_symbol(s)));
+    path.push(net.quote_symbol(pair.1));
+    path
+}
+
+/// Предотсев кросс-dex пары: A→B (через `via`) на dex_a и на dex_b котируются
+/// параллельно на размере `amount_in`. Some(спред в bps) или None, если какая-то нога
+/// не котируется — тогда решает точная котировка `quote_cross_dex_pair`.
+#[allow(clippy::too_many_arguments)]
+pub async fn prescreen_cross_dex_pair(
+    client: &ChainClient,
+    net: &Network,
+    pair: (&str, &str),
+    via: &[&str],
+    dex_a: &DexConfig,
+    dex_b: &DexConfig,
+    amount_in: U256,
+    leg_timeout: Duration,
+    tick_samples: u32,
+) -> Result<Option<f64>> {
+    let path = quote_path(net, pair, via);
+    let leg = |dex: &DexConfig| format!("{}:{}", dex.name, path.join("-"));
+    let (leg_a, leg_b) = (leg(dex_a), leg(dex_b));
+    let (a, b) = tokio::try_join!(
+        with_leg_timeout(
+            net.chain_id,
+            &leg_a,
+            leg_timeout,
+            quote_on_dex(client, net, dex_a, &path, amount_in, tick_samples),
+        ),
+        with_leg_timeout(
+            net.chain_id,
+            &leg_b,
+            leg_timeout,
+            quote_on_dex(client, net, dex_b, &path, amount_in, tick_samples),
+        ),
+    )?;
+    Ok(match (a.filled(), b.filled()) {
+        (Some((out_a, ..)), Some((out_b, ..))) => Some(implied_spread_bps(out_a, out_b)),
+        _ => None,
+    })
 }
 
+/// Круг A→(via…)→B на dex_a и обратно B→(…via)→A на dex_b
+#[allow(clippy::too_many_arguments)]
 pub async fn quote_cross_dex_pair(
     client: &ChainClient,
     net: &Network,
     pair: (&str, &str),
+    via: &[&str],
     dex_a: &DexConfig,
     dex_b: &DexConfig,
     amount_in: U256,
     slip_bps: u32,
-) -> Result<Option<QuoteResult>> {
-    let (sym_a, sym_b) = pair;
+    leg_timeout: Duration,
+    tick_samples: u32,
+    gas_mode: GasPriceMode,
+) -> Result<QuoteOutcome<QuoteResult>> {
+    // native на концах (ETH) котируется через wrapped-токен, см. `wrap_native_ends`
+    let forward = quote_path(net, pair, via);
+    let back: Vec<&str> = forward.iter().rev().copied().collect();
     let mut legs: Vec<LegQuote> = Vec::new();
     let mut gas_total = 0u64;
 
     let mut amount = amount_in;
-    let (out1, leg1, gas1) = match quote_on_dex(client, net, dex_a, sym_a, sym_b, amount).await? {
-        Some(v) => v,
-        None => return Ok(None),
+    let leg_a = format!("{}:{}", dex_a.name, forward.join("-"));
+    let (out1, leg1, gas1) = match with_leg_timeout(
+        net.chain_id,
+        &leg_a,
+        leg_timeout,
+        quote_on_dex(client, net, dex_a, &forward, amount, tick_samples),
+    )
+    .await?
+    {
+        QuoteOutcome::Filled(v) => v,
+        skip => return Ok(skip.skip()),
     };
     legs.push(leg1);
     gas_total += gas1;
     amount = out1;
 
-    let (out2, leg2, gas2) = match quote_on_dex(client, net, dex_b, sym_b, sym_a, amount).await? {
-        Some(v) => v,
-        None => return Ok(None),
+    let leg_b = format!("{}:{}", dex_b.name, back.join("-"));
+    let (out2, leg2, gas2) = match with_leg_timeout(
+        net.chain_id,
+        &leg_b,
+        leg_timeout,
+        quote_on_dex(client, net, dex_b, &back, amount, tick_samples),
+    )
+    .await?
+    {
+        QuoteOutcome::Filled(v) => v,
+        skip => return Ok(skip.skip()),
     };
     legs.push(leg2);
     gas_total += gas2;
     amount = out2;
+    // спред пишется до газового гейта: виден и круг, который не окупает газ
+    record_pair_spread(net.chain_id, pair, round_trip_spread_bps(amount_in, amount));
+    let hops: Vec<(&str, &str)> =
+        forward.windows(2).chain(back.windows(2)).map(|w| (w[0], w[1])).collect();
+    gas_total += token_transfer_overhead(net, &hops);
+    finish_quote(client, net, pair.0, legs, amount_in, amount, gas_total, slip_bps, gas_mode).await
+}
+
+/// Общий хвост котировки кругового маршрута: ноги Wrap/Unwrap для native на концах,
+/// газ (+L1-плата), газовый гейт, PnL и порог min_out. `start` — символ входа маршрута
+/// (как в конфиге), `amount` — выход последней ноги, `gas_total` — газ свопов и доплаты
+/// за токены
+#[allow(clippy::too_many_arguments)]
+async fn finish_quote(
+    client: &ChainClient,
+    net: &Network,
+    start: &str,
+    mut legs: Vec<LegQuote>,
+    amount_in: U256,
+    amount: U256,
+    mut gas_total: u64,
+    slip_bps: u32,
+    gas_mode: GasPriceMode,
+) -> Result<QuoteOutcome<QuoteResult>> {
+    let sym_a = net.quote_symbol(start);
+    gas_total += wrap_native_ends(net, start, &mut legs, amount_in, amount)?;
 
     let gas_estimate = ((gas_total as f64) * 1.15).ceil() as u64;
-    let gas_price = client
-        .with_failover(|p| current_gas_price_legacy(p.clone()))
-        .await?;
-    let gas_cost_native = gas_cost_native(gas_estimate, gas_price);
-
-    let mut profit_native = 0.0f64;
-    if is_native_symbol(net, sym_a) {
-        let dec = decimals_of(net, sym_a) as i32;
-        let diff = if amount > amount_in {
-            amount - amount_in
-        } else {
-            U256::zero()
-        };
-        profit_native = (diff.as_u128() as f64) / 10f64.powi(dec);
+    let gas_price = quote_gas_price(client, net, gas_mode).await?;
+    let l1_fee_native =
+        l1_data_cost_native(client, net, &legs, amount_in, amount, gas_estimate).await?;
+    let gas_cost_native = gas_cost_native(gas_estimate, gas_price) + l1_fee_native;
+
+    // валовая прибыль в токене входа (USDC-круг считается в USDC) и её пересчёт в native
+    let profit_tokens = u256_to_f64(amount.saturating_sub(amount_in))
+        / 10f64.powi(decimals_of(net, sym_a) as i32);
+    let token_usd = client.usd_price(sym_a).await;
+    let native_usd = client.native_usd().await;
+    let profit_in_native = token_to_native(net, sym_a, profit_tokens, token_usd, native_usd);
+    let profit_native = profit_in_native.unwrap_or(0.0);
+    // газовый гейт — когда прибыль известна в native (native-вход или есть цены для пересчёта)
+    if profit_in_native.is_some()
+        && !covers_gas(profit_native, gas_cost_native, net.min_net_profit_native)
+    {
+        debug!(
+            chain = net.chain_id,
+            "route rejected: profit {:.6} {} does not cover gas {:.6} + min_net {:.6}",
+            profit_native,
+            net.native_symbol,
+            gas_cost_native,
+            net.min_net_profit_native
+        );
+        return Ok(QuoteOutcome::BelowMinOut);
     }
-    let pnl_native = profit_native - gas_cost_native;
-    let (pnl_usd, gas_cost_usd_opt) = net
-        .native_usd_hint
-        .map(|price| {
-            (
-                gas_cost_usd(pnl_native, price),
-                Some(gas_cost_usd(gas_cost_native, price)),
-            )
-        })
-        .unwrap_or((0.0, None));
+    let pnl = net_pnl_usd(profit_tokens, token_usd, gas_cost_native, native_usd);
     let min_out = min_out_bps(amount, slip_bps);
-    if min_out <= amount_in {
-        return Ok(None);
-    }
-    if amount <= amount_in {
-        return Ok(None);
+    if min_out <= amount_in || amount <= amount_in {
+        return Ok(QuoteOutcome::BelowMinOut);
     }
-    if let Some(cost_usd) = gas_cost_usd_opt {
-        debug!(
-            "candidate pnl_usd={:.4}, gas={}, gas_price={}, gas_cost_usd={:.4}, legs={}",
+    match pnl {
+        Some(pnl_usd) => debug!(
+            "candidate pnl_usd={:.4}, gas={}, gas_price={}, gas_native={:.6} (l1 {:.6}), legs={}",
             pnl_usd,
             gas_estimate,
             gas_price,
-            cost_usd,
+            gas_cost_native,
+            l1_fee_native,
             legs.len()
-        );
-    } else {
-        debug!(
-            "candidate pnl_usd={:.4}, gas={}, gas_price={}, legs={}",
-            pnl_usd,
+        ),
+        // прежнее поведение (pnl_usd = 0), но явно помечаем, что это не оценка
+        None => debug!(
+            "candidate pnl_usd=n/a (нет USD-цены {} или native), gas={}, gas_price={}, legs={}",
+            sym_a,
             gas_estimate,
             gas_price,
             legs.len()
-        );
+        ),
     }
 
-    Ok(Some(QuoteResult {
+    Ok(QuoteOutcome::Filled(QuoteResult {
         amount_in,
         amount_out: amount,
         gas_estimate,
         gas_price,
         legs,
-        pnl_usd,
+        pnl_usd: pnl.unwrap_or(0.0),
+        pnl_usd_priced: pnl.is_some(),
+        profit_native,
+        quote_block: client.pinned_block(),
     }))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn quote_triangle(
     client: &ChainClient,
     net: &Network,
@@ -318,8 +988,11 @@ pub async fn quote_triangle(
     preferred_dexes: &[String],
     amount_in: U256,
     slip_bps: u32,
-) -> Result<Option<QuoteResult>> {
-    let (a, b, c) = tri;
+    leg_timeout: Duration,
+    tick_samples: u32,
+    gas_mode: GasPriceMode,
+) -> Result<QuoteOutcome<QuoteResult>> {
+    let (a, b, c) = (net.quote_symbol(tri.0), net.quote_symbol(tri.1), net.quote_symbol(tri.2));
     let mut amount = amount_in;
     let mut legs: Vec<LegQuote> = Vec::new();
     let mut gas_total = 0u64;
@@ -338,80 +1011,25 @@ pub async fn quote_triangle(
                 dex_order.push(d);
             }
         }
-        let mut quoted = None;
+        // первый DEX с котировкой; иначе — причина от последнего опрошенного
+        let mut quoted = QuoteOutcome::NoPool;
         for d in dex_order {
-            if let Some(res) = quote_on_dex(client, net, d, tin, tout, amount).await? {
-                quoted = Some((res.0, res.1, res.2));
+            let leg = format!("{}:{}-{}", d.name, tin, tout);
+            let path = [*tin, *tout];
+            let quote = quote_on_dex(client, net, d, &path, amount, tick_samples);
+            quoted = with_leg_timeout(net.chain_id, &leg, leg_timeout, quote).await?;
+            if matches!(quoted, QuoteOutcome::Filled(_)) {
                 break;
             }
         }
         let (out, leg, gas) = match quoted {
-            Some(v) => v,
-            None => return Ok(None),
+            QuoteOutcome::Filled(v) => v,
+            skip => return Ok(skip.skip()),
         };
         amount = out;
         legs.push(leg);
         gas_total += gas;
     }
-
-    let gas_estimate = ((gas_total as f64) * 1.15).ceil() as u64;
-    let gas_price = client
-        .with_failover(|p| current_gas_price_legacy(p.clone()))
-        .await?;
-    let gas_cost_native = gas_cost_native(gas_estimate, gas_price);
-
-    let mut profit_native = 0.0f64;
-    if is_native_symbol(net, a) {
-        let dec = decimals_of(net, a) as i32;
-        let diff = if amount > amount_in {
-            amount - amount_in
-        } else {
-            U256::zero()
-        };
-        profit_native = (diff.as_u128() as f64) / 10f64.powi(dec);
-    }
-    let pnl_native = profit_native - gas_cost_native;
-    let (pnl_usd, gas_cost_usd_opt) = net
-        .native_usd_hint
-        .map(|price| {
-            (
-                gas_cost_usd(pnl_native, price),
-                Some(gas_cost_usd(gas_cost_native, price)),
-            )
-        })
-        .unwrap_or((0.0, None));
-    let min_out = min_out_bps(amount, slip_bps);
-    if min_out <= amount_in {
-        return Ok(None);
-    }
-    if amount <= amount_in {
-        return Ok(None);
-    }
-    if let Some(cost_usd) = gas_cost_usd_opt {
-        debug!(
-            "candidate pnl_usd={:.4}, gas={}, gas_price={}, gas_cost_usd={:.4}, legs={}",
-            pnl_usd,
-            gas_estimate,
-            gas_price,
-            cost_usd,
-            legs.len()
-        );
-    } else {
-        debug!(
-            "candidate pnl_usd={:.4}, gas={}, gas_price={}, legs={}",
-            pnl_usd,
-            gas_estimate,
-            gas_price,
-            legs.len()
-        );
-    }
-
-    Ok(Some(QuoteResult {
-        amount_in,
-        amount_out: amount,
-        gas_estimate,
-        gas_price,
-        legs,
-        pnl_usd,
-    }))
+    gas_total += token_transfer_overhead(net, &pairs);
+    finish_quote(client, net, tri.0, legs, amount_in, amount, gas_total, slip_bps, gas_mode).await
 }
@@ -0,0 +1,156 @@
+//! Сверка токенов конфига с on-chain `decimals()`/`symbol()` на старте
+//! (`global.verify_tokens`): кривые decimals портят всю арифметику сумм токена.
+//! Все токены сети читаются одним `aggregate3` Multicall3.
+
+use anyhow::{Result, anyhow, bail};
+use ethers::abi::{self, ParamType, Token};
+use ethers::contract::multicall_contract::{Call3, Multicall3};
+use ethers::contract::MULTICALL_ADDRESS;
+use ethers::providers::{Http, Provider};
+use ethers::types::{Address, Bytes};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::config::Token as TokenCfg;
+use crate::network::ChainClient;
+use crate::utils::parse_addr;
+
+/// decimals()
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+/// symbol()
+const SYMBOL_SELECTOR: [u8; 4] = [0x95, 0xd8, 0x9b, 0x41];
+
+/// Что токен вернул on-chain (None — вызов откатился или ответ не читается)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenMeta {
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenIssue {
+    /// Ошибка конфига: суммы токена считаются не в тех единицах
+    Decimals { configured: u8, onchain: u8 },
+    /// Символ не совпал с ключом конфига (после нормализации) — только предупреждение
+    Symbol { onchain: String },
+    /// decimals() не прочитан: не токен, не та сеть или адрес пустой
+    Unreadable,
+}
+
+/// Символ для сравнения: верхний регистр, только буквы и цифры ("USDC.e" → "USDCE")
+fn normalize_symbol(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Расхождения токена `key` конфига с on-chain метаданными
+pub fn compare_token(key: &str, cfg: &TokenCfg, meta: &TokenMeta) -> Vec<TokenIssue> {
+    let mut issues = Vec::new();
+    match meta.decimals {
+        Some(d) if d != cfg.decimals => issues.push(TokenIssue::Decimals {
+            configured: cfg.decimals,
+            onchain: d,
+        }),
+        Some(_) => {}
+        None => issues.push(TokenIssue::Unreadable),
+    }
+    if let Some(sym) = &meta.symbol && normalize_symbol(sym) != normalize_symbol(key) {
+        issues.push(TokenIssue::Symbol { onchain: sym.clone() });
+    }
+    issues
+}
+
+/// `string symbol()`; старые токены (MKR и т.п.) отдают `bytes32`
+pub fn decode_symbol(data: &[u8]) -> Option<String> {
+    if let Ok(mut t) = abi::decode(&[ParamType::String], data)
+        && let Some(Token::String(s)) = t.pop()
+    {
+        return Some(s);
+    }
+    if data.len() == 32 {
+        let end = data.iter().position(|b| *b == 0).unwrap_or(32);
+        return String::from_utf8(data[..end].to_vec()).ok().filter(|s| !s.is_empty());
+    }
+    None
+}
+
+pub fn decode_decimals(data: &[u8]) -> Option<u8> {
+    match abi::decode(&[ParamType::Uint(8)], data).ok()?.pop()? {
+        Token::Uint(v) if v <= u8::MAX.into() => Some(v.as_u32() as u8),
+        _ => None,
+    }
+}
+
+async fn fetch_token_meta(p: Arc<Provider<Http>>, tokens: &[Address]) -> Result<Vec<TokenMeta>> {
+    let mc = Multicall3::new(MULTICALL_ADDRESS, p);
+    let calls: Vec<Call3> = tokens
+        .iter()
+        .flat_map(|t| {
+            [SYMBOL_SELECTOR, DECIMALS_SELECTOR].map(|sel| Call3 {
+                target: *t,
+                allow_failure: true,
+                call_data: Bytes::from(sel.to_vec()),
+            })
+        })
+        .collect();
+    let results = mc.aggregate_3(calls).call().await?;
+    if results.len() != tokens.len() * 2 {
+        bail!("multicall returned {} results for {} calls", results.len(), tokens.len() * 2);
+    }
+    Ok(results
+        .chunks(2)
+        .map(|r| TokenMeta {
+            symbol: r[0].success.then(|| decode_symbol(&r[0].return_data)).flatten(),
+            decimals: r[1].success.then(|| decode_decimals(&r[1].return_data)).flatten(),
+        })
+        .collect())
+}
+
+/// Сверяет токены сети с on-chain: расхождение decimals — ошибка старта,
+/// символ и нечитаемые токены — предупреждения (для optional — debug)
+pub async fn verify_tokens(client: &ChainClient) -> Result<()> {
+    let net = &client.cfg;
+    let mut entries: Vec<(&String, &TokenCfg, Address)> = Vec::new();
+    for (key, t) in &net.tokens {
+        let addr = parse_addr(&t.address).map_err(|e| anyhow!("token {key}: {e}"))?;
+        entries.push((key, t, addr));
+    }
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let addrs: Vec<Address> = entries.iter().map(|e| e.2).collect();
+    let metas = client
+        .with_failover("multicall token meta", |p| {
+            let addrs = addrs.clone();
+            async move { fetch_token_meta(p, &addrs).await }
+        })
+        .await?;
+
+    let mut bad = Vec::new();
+    for ((key, t, _), meta) in entries.iter().zip(&metas) {
+        for issue in compare_token(key, t, meta) {
+            match issue {
+                TokenIssue::Decimals { configured, onchain } => {
+                    bad.push(format!("{key}: decimals {configured} в конфиге, {onchain} on-chain"))
+                }
+                TokenIssue::Symbol { onchain } => warn!(
+                    chain = net.chain_id,
+                    "token {key}: on-chain symbol '{onchain}' не совпадает с ключом конфига"
+                ),
+                TokenIssue::Unreadable if t.optional => {
+                    debug!(chain = net.chain_id, "optional token {key}: decimals() не прочитан")
+                }
+                TokenIssue::Unreadable => warn!(
+                    chain = net.chain_id,
+                    "token {key} ({}): decimals() не прочитан — проверьте адрес",
+                    t.address
+                ),
+            }
+        }
+    }
+    if !bad.is_empty() {
+        bail!("network '{}': token config mismatch: {}", net.name, bad.join("; "));
+    }
+    info!(chain = net.chain_id, "tokens verified: {}", entries.len());
+    Ok(())
+}
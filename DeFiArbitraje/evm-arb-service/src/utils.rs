@@ -1,5 +1,9 @@
 use ethers::types::{U256, Address};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Формат адресов в выводе (логи, candidate-файлы): EIP-55 или lowercase
+static CHECKSUM_OUTPUT: AtomicBool = AtomicBool::new(false);
 
 /// Парсит Ethereum-адрес из строки.
 /// Возвращает Result вместо panic.
@@ -15,8 +19,63 @@ pub fn u256_from_decimals(amount: f64, decimals: u8) -> U256 {
     U256::from(v)
 }
 
+/// U256 в f64 по 64-битным словам: без паники на значениях выше u128 (точность — f64)
+pub fn u256_to_f64(v: U256) -> f64 {
+    v.0.iter().rev().fold(0.0, |acc, &w| acc * 18_446_744_073_709_551_616.0 + w as f64)
+}
+
+/// Разность `a - b` в целых единицах токена с учётом decimals; отрицательная — убыток
+pub fn signed_diff_decimals(a: U256, b: U256, decimals: u8) -> f64 {
+    let diff = if a >= b { u256_to_f64(a - b) } else { -u256_to_f64(b - a) };
+    diff / 10f64.powi(decimals as i32)
+}
+
+/// Размер входа в единицах токена: `notional_usd` по USD-цене токена; без `notional_usd`
+/// или без цены — одна целая единица токена (прежнее поведение)
+pub fn amount_for_notional(notional_usd: Option<f64>, token_usd: Option<f64>, decimals: u8) -> U256 {
+    match (notional_usd, token_usd) {
+        (Some(usd), Some(price)) if usd > 0.0 && price > 0.0 => {
+            u256_from_decimals(usd / price, decimals)
+        }
+        _ => u256_from_decimals(1.0, decimals),
+    }
+}
+
+/// USD-объём `amount` (в минимальных единицах токена) по цене `price`; суммы выше
+/// u128 не паникуют
+pub fn usd_value(amount: U256, decimals: u8, price: f64) -> f64 {
+    u256_to_f64(amount) / 10f64.powi(decimals as i32) * price
+}
+
 /// Переводит число в долях процента (basis points) в обычный коэффициент.
 /// Например: 50 bps → 0.005
 pub fn bps(v: f64) -> f64 {
     v / 10_000.0
-}
\ No newline at end of file
+}
+
+/// EIP-55 checksum-представление адреса (0xAbC...)
+pub fn to_checksum_address(addr: &Address) -> String {
+    ethers::utils::to_checksum(addr, None)
+}
+
+/// Включает/выключает checksum-адреса в выводе (telemetry.logs.checksum_addresses)
+pub fn set_checksum_output(enabled: bool) {
+    CHECKSUM_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// Директива фильтра логов: RUST_LOG, если задан и не пуст, иначе telemetry.logs.level
+pub fn log_directive(rust_log: Option<String>, level: &str) -> String {
+    rust_log
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| level.trim().to_string())
+}
+
+/// Адрес для вывода: checksum, если включено, иначе lowercase 0x...
+/// Внутренние сравнения адресов от этого не зависят.
+pub fn fmt_addr(addr: &Address) -> String {
+    if CHECKSUM_OUTPUT.load(Ordering::Relaxed) {
+        to_checksum_address(addr)
+    } else {
+        format!("{:#x}", addr)
+    }
+}
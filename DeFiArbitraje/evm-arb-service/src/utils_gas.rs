@@ -1,43 +1,208 @@
-use anyhow::Result;
+use crate::config::{Network, NetworkGas};
+use crate::utils::u256_to_f64;
+use anyhow::{anyhow, Result};
+use ethers::prelude::abigen;
 use ethers::providers::Middleware;
-use ethers::types::{BlockNumber, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockNumber, Bytes, Eip1559TransactionRequest, U256};
 use std::cmp::min;
-use std::env;
 use std::sync::Arc;
 
-/// Get current legacy gas price (wei) taking into account EIP-1559 fields if available
-pub async fn current_gas_price_legacy<M>(mw: Arc<M>) -> Result<U256>
+abigen!(
+    IGasPriceOracle,
+    r#"[ function getL1Fee(bytes _data) external view returns (uint256) ]"#
+);
+
+abigen!(
+    INodeInterface,
+    r#"[ function gasEstimateL1Component(address to, bool contractCreation, bytes data) external payable returns (uint64 gasEstimateForL1, uint256 baseFee, uint256 l1BaseFeeEstimate) ]"#
+);
+
+/// Predeploy GasPriceOracle OP-stack (Optimism, Base, ...)
+pub const OP_GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+/// Виртуальный NodeInterface Arbitrum (доступен только через eth_call)
+pub const ARB_NODE_INTERFACE: &str = "0x00000000000000000000000000000000000000C8";
+
+fn gwei(v: f64) -> U256 {
+    U256::from((v.max(0.0) * 1e9) as u128)
+}
+
+/// Цена газа из base fee по настройкам сети: base * multiplier + tip,
+/// не выше оценки EIP-1559 (если есть) и не выше потолка max_fee_gwei
+pub fn gas_price_from_base_fee(base_fee: U256, eip1559_max: Option<U256>, gas: &NetworkGas) -> U256 {
+    let mult_ppm = (gas.base_fee_multiplier.max(0.0) * 1e6) as u64;
+    let scaled = base_fee * U256::from(mult_ppm) / U256::from(1_000_000u64);
+    let mut price = scaled + gwei(gas.tip_gwei);
+    if let Some(est) = eip1559_max {
+        price = min(price, est);
+    }
+    cap_gas_price(price, gas)
+}
+
+/// Потолок max_fee_gwei (если задан)
+pub fn cap_gas_price(price: U256, gas: &NetworkGas) -> U256 {
+    match gas.max_fee_gwei {
+        Some(cap) => min(price, gwei(cap)),
+        None => price,
+    }
+}
+
+/// Источник цены газа (`quote.gas_price_mode`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GasPriceMode {
+    /// base fee последнего блока + tip (с оценкой EIP-1559), без base fee — eth_gasPrice
+    #[default]
+    Eip1559,
+    /// Сразу eth_gasPrice: для сетей без EIP-1559 или с некорректными fee-оценками
+    Legacy,
+}
+
+impl GasPriceMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            // auto — прежнее поведение: EIP-1559, без base fee откат на eth_gasPrice
+            "eip1559" | "auto" => Ok(Self::Eip1559),
+            "legacy" => Ok(Self::Legacy),
+            other => Err(anyhow!("gas_price_mode must be eip1559, legacy or auto, got '{other}'")),
+        }
+    }
+}
+
+/// Get current legacy gas price (wei): в режиме eip1559 — из base fee и EIP-1559 оценки (если есть),
+/// в режиме legacy — eth_gasPrice
+pub async fn current_gas_price_legacy<M>(mw: Arc<M>, gas: NetworkGas, mode: GasPriceMode) -> Result<U256>
 where
     M: Middleware + 'static,
     M::Error: 'static,
 {
-    let tip_gwei: u64 = env::var("GAS_TIP_GWEI")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(2);
-    let tip = U256::from(tip_gwei) * U256::exp10(9);
-
-    if let Some(block) = mw.get_block(BlockNumber::Latest).await? {
-        if let Some(base_fee) = block.base_fee_per_gas {
-            let base_plus_tip = base_fee + tip;
-            if let Ok((max_fee_per_gas, _)) = mw.estimate_eip1559_fees(None).await {
-                return Ok(min(max_fee_per_gas, base_plus_tip));
-            }
-            return Ok(base_plus_tip);
+    if mode == GasPriceMode::Eip1559
+        && let Some(block) = mw.get_block(BlockNumber::Latest).await?
+        && let Some(base_fee) = block.base_fee_per_gas
+    {
+        let est = mw.estimate_eip1559_fees(None).await.ok().map(|(max_fee, _)| max_fee);
+        return Ok(gas_price_from_base_fee(base_fee, est, &gas));
+    }
+
+    Ok(cap_gas_price(mw.get_gas_price().await?, &gas))
+}
+
+/// Как L2 берёт плату за публикацию данных транзакции в L1 (`l2_data_fees` сети)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum L1FeeModel {
+    /// `GasPriceOracle.getL1Fee(rlp(tx))` — отдельная плата в wei сверх газа L2
+    OpStack,
+    /// `NodeInterface.gasEstimateL1Component` — L1-часть в единицах газа L2 по base fee
+    Arbitrum,
+}
+
+impl L1FeeModel {
+    pub fn for_chain(chain_id: u64) -> Self {
+        match chain_id {
+            42161 | 42170 | 421614 => L1FeeModel::Arbitrum,
+            _ => L1FeeModel::OpStack,
         }
     }
+}
 
-    Ok(mw.get_gas_price().await?)
+/// Неподписанная EIP-1559 транзакция маршрута (RLP) — вход `getL1Fee`; цены газа не важны,
+/// плата зависит от размера и состава байтов
+pub fn l1_fee_tx_bytes(chain_id: u64, to: Address, data: Bytes, gas_units: u64) -> Bytes {
+    let tx = Eip1559TransactionRequest::new()
+        .chain_id(chain_id)
+        .to(to)
+        .data(data)
+        .gas(gas_units);
+    TypedTransaction::Eip1559(tx).rlp()
+}
+
+/// L1-плата за данные транзакции `to`/`data` в wei
+pub async fn l1_data_fee<M: Middleware + 'static>(
+    mw: Arc<M>,
+    chain_id: u64,
+    to: Address,
+    data: Bytes,
+    gas_units: u64,
+) -> Result<U256> {
+    match L1FeeModel::for_chain(chain_id) {
+        L1FeeModel::OpStack => {
+            let oracle = IGasPriceOracle::new(OP_GAS_PRICE_ORACLE.parse::<Address>()?, mw);
+            let tx = l1_fee_tx_bytes(chain_id, to, data, gas_units);
+            Ok(oracle.get_l1_fee(tx).call().await?)
+        }
+        L1FeeModel::Arbitrum => {
+            let node = INodeInterface::new(ARB_NODE_INTERFACE.parse::<Address>()?, mw);
+            let (l1_gas, base_fee, _) =
+                node.gas_estimate_l1_component(to, false, data).call().await?;
+            Ok(U256::from(l1_gas) * base_fee)
+        }
+    }
+}
+
+/// Сумма в wei → native
+pub fn wei_to_native(wei: U256) -> f64 {
+    u256_to_f64(wei) / 1e18f64
 }
 
 /// Calculate gas cost in native tokens
 pub fn gas_cost_native(gas_units: u64, gas_price: U256) -> f64 {
-    let price_native = (gas_price.as_u128() as f64) / 1e18f64;
+    let price_native = u256_to_f64(gas_price) / 1e18f64;
     price_native * gas_units as f64
 }
 
-/// Convert native token amount to USD
-pub fn gas_cost_usd(native_amount: f64, native_usd: f64) -> f64 {
-    native_amount * native_usd
+/// Газовый гейт: валовая прибыль в native должна окупать газ и давать
+/// не меньше `min_net_native` сверху (независимо от наличия USD-цены)
+pub fn covers_gas(profit_native: f64, gas_cost_native: f64, min_net_native: f64) -> bool {
+    profit_native > gas_cost_native && profit_native - gas_cost_native >= min_net_native
 }
 
+/// Пересчёт суммы в native в USD по цене native
+pub fn native_to_usd(amount_native: f64, native_usd: f64) -> f64 {
+    amount_native * native_usd
+}
+
+/// Стоимость газа (в native) в USD
+pub fn gas_cost_usd(gas_native: f64, native_usd: f64) -> f64 {
+    native_to_usd(gas_native, native_usd)
+}
+
+/// Сумма токена `sym` в native: native/W-native — как есть, иначе через USD-цены
+/// токена и native. None — цен не хватает.
+pub fn token_to_native(
+    net: &Network,
+    sym: &str,
+    amount: f64,
+    token_usd: Option<f64>,
+    native_usd: Option<f64>,
+) -> Option<f64> {
+    if net.is_native(sym) {
+        return Some(amount);
+    }
+    let native_usd = native_usd.filter(|p| *p > 0.0)?;
+    Some(amount * token_usd? / native_usd)
+}
+
+/// Чистый PnL маршрута в USD: прибыль в токене входа по его USD-цене минус газ
+/// по цене native. None — не хватает одной из цен (PnL неизвестен, а не нулевой).
+pub fn net_pnl_usd(
+    profit_tokens: f64,
+    token_usd: Option<f64>,
+    gas_native: f64,
+    native_usd: Option<f64>,
+) -> Option<f64> {
+    Some(profit_tokens * token_usd? - gas_cost_usd(gas_native, native_usd?))
+}
+
+
+/// Суммарный доп. газ за transfer-ы «дорогих» токенов по хопам маршрута:
+/// каждый хоп — это transfer token_in и token_out
+pub fn token_transfer_overhead(net: &Network, hops: &[(&str, &str)]) -> u64 {
+    let extra = |sym: &str| {
+        net.tokens
+            .get(&sym.to_uppercase())
+            .and_then(|t| t.extra_gas)
+            .unwrap_or(0)
+    };
+    hops.iter()
+        .map(|(tin, tout)| extra(tin).saturating_add(extra(tout)))
+        .fold(0u64, |acc, g| acc.saturating_add(g))
+}
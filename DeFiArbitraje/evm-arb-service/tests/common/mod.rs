@@ -0,0 +1,218 @@
+//! Общие фикстуры интеграционных тестов: конфиг репозитория, котировки, движок,
+//! HTTP/JSON-RPC заглушки. Каждый тестовый бинарник берёт свою часть.
+#![allow(dead_code)]
+
+use DeFiArbitraje::config::{Config, DexConfig, Network, RouteDex, V2ReserveGuard};
+use DeFiArbitraje::network::{ChainClient, MultiChain, PoolKey};
+use DeFiArbitraje::route::{RoutePlanner, StrategyEngine};
+use DeFiArbitraje::router::QuoteResult;
+use anyhow::Result;
+use ethers::types::{Address, U256};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Конфиг репозитория
+pub const CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../config/defi_config.json");
+
+pub fn config() -> Config {
+    Config::load(CONFIG).expect("load config")
+}
+
+/// Конфиг с одной сетью `chain_id`, без pools_file и файла кэша allowance
+pub fn single_network(chain_id: u64) -> Config {
+    let mut cfg = config();
+    cfg.networks.retain(|n| n.chain_id == chain_id);
+    cfg.pools_file = None;
+    cfg.global.execution.allowance_cache_file.clear();
+    cfg
+}
+
+/// Сеть `chain_id` из конфига репозитория
+pub fn network(chain_id: u64) -> Network {
+    config()
+        .networks
+        .into_iter()
+        .find(|n| n.chain_id == chain_id)
+        .expect("network in config")
+}
+
+/// Клиент сети `chain_id` (конфиг репозитория, без pools_file)
+pub async fn client(chain_id: u64) -> ChainClient {
+    let chains = MultiChain::from_config(&single_network(chain_id)).await.expect("clients");
+    chains.clients[&chain_id].clone()
+}
+
+/// Адрес токена `sym` сети
+pub fn token(net: &Network, sym: &str) -> Address {
+    net.tokens[sym].address.parse().unwrap()
+}
+
+/// Котировка круга `amount_in` → `amount_out` без ног и газа; `pnl_usd` None — без цены
+pub fn quote(amount_in: u64, amount_out: u64, pnl_usd: Option<f64>) -> QuoteResult {
+    QuoteResult {
+        amount_in: U256::from(amount_in),
+        amount_out: U256::from(amount_out),
+        gas_estimate: 0,
+        gas_price: U256::zero(),
+        legs: Vec::new(),
+        pnl_usd: pnl_usd.unwrap_or(0.0),
+        pnl_usd_priced: pnl_usd.is_some(),
+        profit_native: 0.0,
+        quote_block: None,
+    }
+}
+
+/// v2-пара заглушки: адрес, token0, token1, резервы
+pub type StubPair = (Address, Address, Address, U256, U256);
+
+/// Рынок заглушки: v2-пары и их ключи для `ChainClient::prewarm_pools`
+pub struct V2Market {
+    pub pairs: Vec<StubPair>,
+    pub pools: Vec<(PoolKey, Address)>,
+}
+
+/// DEX типа v2 с фабрикой `factory` и роутером по соседнему адресу
+pub fn v2_dex(name: &str, factory: u64) -> DexConfig {
+    let mut dex: DexConfig = serde_json::from_value(json!({ "name": name, "type": "v2" })).unwrap();
+    dex.factory = Some(format!("{:#x}", Address::from_low_u64_be(factory)));
+    dex.router = Some(format!("{:#x}", Address::from_low_u64_be(factory + 1)));
+    dex
+}
+
+/// Единственная сеть `cfg` — два v2 DEX (V2A, V2B) с фиксированными ценами, без прогрева,
+/// батчей и внешних цен. Пара WETH→USDC@V2A→WETH@V2B даёт ~1.5%, треугольник
+/// WETH-USDC-USDBC через V2A — ~5%; оба начинаются с ребра WETH→USDC@V2A
+pub fn v2_market(cfg: &mut Config) -> V2Market {
+    cfg.global.execution.warmup_pools = false;
+    cfg.global.quote.gas_price_mode = "legacy".into();
+    cfg.rpc.batch_requests = false;
+    cfg.telemetry.logs.candidates.enabled = false;
+
+    let net = &mut cfg.networks[0];
+    net.l2_data_fees = false;
+    net.native_usd_hint = Some(2_000.0);
+    net.v2_reserve_guard = V2ReserveGuard::default();
+    net.dexes = vec![v2_dex("V2A", 0xa0), v2_dex("V2B", 0xb0)];
+    net.routes_cross_dex = Some(vec![RouteDex {
+        pair: ["WETH".into(), "USDC".into()],
+        dexes: vec!["V2A".into(), "V2B".into()],
+        via: Vec::new(),
+    }]);
+    net.triangles = vec![["WETH".into(), "USDC".into(), "USDBC".into()]];
+    for (sym, px) in [("WETH", 2_000.0), ("USDC", 1.0), ("USDBC", 1.0)] {
+        let t = net.tokens.get_mut(sym).unwrap();
+        t.usd_price = Some(px);
+        t.price_feed = None;
+    }
+    let (weth, usdc, usdbc) = (token(net, "WETH"), token(net, "USDC"), token(net, "USDBC"));
+
+    // V2A: WETH по 2000, USDBC на 6% дешевле USDC; V2B: WETH по 1960
+    let e = |n: u64, dec: usize| U256::from(n) * U256::exp10(dec);
+    let pair = |n: u64| Address::from_low_u64_be(0x1000 + n);
+    let pairs = vec![
+        (pair(1), weth, usdc, e(1_000, 18), e(2_000_000, 6)),
+        (pair(2), usdc, usdbc, e(1_000_000, 6), e(1_060_000, 6)),
+        (pair(3), weth, usdbc, e(1_000, 18), e(2_000_000, 6)),
+        (pair(4), weth, usdc, e(1_000, 18), e(1_960_000, 6)),
+    ];
+    let factory = Address::from_low_u64_be;
+    let mut pools = Vec::new();
+    for (f, p, a, b) in [
+        (0xa0, pair(1), weth, usdc),
+        (0xa0, pair(2), usdc, usdbc),
+        (0xa0, pair(3), weth, usdbc),
+        (0xb0, pair(4), weth, usdc),
+    ] {
+        pools.push(((factory(f), a, b, 0), p));
+        pools.push(((factory(f), b, a, 0), p));
+    }
+    V2Market { pairs, pools }
+}
+
+/// Ответ v2-пары из `pairs` на eth_call `tx`: token0/token1/getReserves; None — не пара
+pub fn v2_pair_call(pairs: &[StubPair], tx: &Value) -> Option<String> {
+    let word = |v: U256| format!("{v:064x}");
+    let addr = |a: Address| format!("0x{}", word(U256::from(a.as_bytes())));
+    let to: Address = tx["to"].as_str()?.parse().ok()?;
+    let data = tx["data"].as_str().or(tx["input"].as_str())?;
+    let &(_, t0, t1, r0, r1) = pairs.iter().find(|p| p.0 == to)?;
+    match data.get(..10)? {
+        "0x0dfe1681" => Some(addr(t0)),
+        "0xd21220a7" => Some(addr(t1)),
+        // getReserves: reserve0, reserve1, blockTimestampLast
+        "0x0902f1ac" => Some(format!("0x{}{}{}", word(r0), word(r1), word(1.into()))),
+        _ => None,
+    }
+}
+
+/// RPC-заглушка v2-пар: token0/token1/getReserves по адресу пары, прочие eth_call — revert
+pub async fn v2_node(pairs: Vec<StubPair>) -> SocketAddr {
+    rpc_stub(move |call| match call["method"].as_str().unwrap() {
+        "eth_chainId" => Ok(json!("0x2105")),
+        "eth_call" => v2_pair_call(&pairs, &call["params"][0]).map(Value::from).ok_or_else(reverted),
+        _ => Ok(json!("0x1")),
+    })
+    .await
+}
+
+/// Ошибка JSON-RPC откатившегося вызова
+pub fn reverted() -> Value {
+    json!({ "code": 3, "message": "execution reverted" })
+}
+
+/// Движок над клиентами сетей `cfg` (ключи и экзекуторы — из окружения, как в бинарнике)
+pub async fn engine(cfg: &Config) -> Result<StrategyEngine> {
+    let chains = Arc::new(MultiChain::from_config(cfg).await?);
+    engine_on(cfg, chains).await
+}
+
+/// Движок над готовыми клиентами (кэш пулов предзагружен тестом)
+pub async fn engine_on(cfg: &Config, chains: Arc<MultiChain>) -> Result<StrategyEngine> {
+    let planner = Arc::new(RoutePlanner::from_config(cfg));
+    StrategyEngine::new(cfg.clone(), chains, planner, false).await
+}
+
+/// HTTP-заглушка на 127.0.0.1: `handle(путь, тело)` на каждый запрос
+pub async fn http_stub<F>(handle: F) -> SocketAddr
+where
+    F: Fn(&str, &[u8]) -> Response<Body> + Send + Sync + 'static,
+{
+    let handle = Arc::new(handle);
+    let make = make_service_fn(move |_| {
+        let handle = handle.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let handle = handle.clone();
+                async move {
+                    let path = req.uri().path().to_string();
+                    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                    Ok::<_, Infallible>(handle(&path, &body))
+                }
+            }))
+        }
+    });
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    addr
+}
+
+/// JSON-RPC заглушка (по вызову на запрос): `answer(вызов)` — Ok(result) или Err(error)
+pub async fn rpc_stub<F>(answer: F) -> SocketAddr
+where
+    F: Fn(&Value) -> Result<Value, Value> + Send + Sync + 'static,
+{
+    http_stub(move |_, body| {
+        let call: Value = serde_json::from_slice(body).unwrap();
+        let resp = match answer(&call) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": call["id"], "error": error }),
+        };
+        Response::new(Body::from(resp.to_string()))
+    })
+    .await
+}
@@ -0,0 +1,21 @@
+use DeFiArbitraje::utils::{fmt_addr, parse_addr, set_checksum_output, to_checksum_address};
+
+#[test]
+fn checksum_output_is_eip55() {
+    // эталонные векторы из EIP-55
+    for expected in [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    ] {
+        let addr = parse_addr(&expected.to_lowercase()).unwrap();
+        assert_eq!(to_checksum_address(&addr), expected);
+    }
+
+    let addr = parse_addr("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913").unwrap();
+    set_checksum_output(false);
+    assert_eq!(fmt_addr(&addr), "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913");
+    set_checksum_output(true);
+    assert_eq!(fmt_addr(&addr), "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+    set_checksum_output(false);
+}
@@ -0,0 +1,83 @@
+use DeFiArbitraje::config::{Config, SUPPORTED_CONFIG_VERSION};
+use crate::common::{self, CONFIG};
+use std::path::PathBuf;
+
+fn fixture_value() -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(CONFIG).unwrap()).unwrap()
+}
+
+fn write_tmp(name: &str, body: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("defi-arb-{}-{}", std::process::id(), name));
+    std::fs::write(&path, body).unwrap();
+    path
+}
+
+fn chain_ids(cfg: &Config) -> Vec<u64> {
+    cfg.networks.iter().map(|n| n.chain_id).collect()
+}
+
+#[test]
+fn yaml_and_toml_load_like_json() {
+    let json = common::config();
+    let value = fixture_value();
+
+    let yaml = write_tmp("cfg.yaml", &serde_yaml::to_string(&value).unwrap());
+    let from_yaml = Config::load(yaml.to_str().unwrap()).unwrap();
+    assert_eq!(chain_ids(&from_yaml), chain_ids(&json));
+    // нормализация применяется так же, как для JSON
+    assert_eq!(from_yaml.networks[0].tokens.keys().len(), json.networks[0].tokens.len());
+
+    let toml_path = write_tmp("cfg.toml", &toml::to_string(&value).unwrap());
+    let from_toml = Config::load(toml_path.to_str().unwrap()).unwrap();
+    assert_eq!(chain_ids(&from_toml), chain_ids(&json));
+    assert_eq!(from_toml.rpc.budget_total, json.rpc.budget_total);
+
+    let _ = std::fs::remove_file(yaml);
+    let _ = std::fs::remove_file(toml_path);
+}
+
+#[test]
+fn unknown_extension_falls_back_to_json() {
+    let raw = std::fs::read_to_string(CONFIG).unwrap();
+    let ok = write_tmp("cfg.conf", &raw);
+    assert!(Config::load(ok.to_str().unwrap()).is_ok());
+
+    let bad = write_tmp("cfg.ini", "networks = []");
+    let err = Config::load(bad.to_str().unwrap()).unwrap_err().to_string();
+    assert!(err.contains("unknown extension 'ini'"), "{err}");
+
+    let _ = std::fs::remove_file(ok);
+    let _ = std::fs::remove_file(bad);
+}
+
+#[test]
+fn previous_major_is_migrated_other_majors_rejected() {
+    let mut value = fixture_value();
+    value["version"] = "1.3.0".into();
+    value["rpc"].as_object_mut().unwrap().remove("max_block_lag");
+    let v1 = write_tmp("cfg-v1.json", &value.to_string());
+    let cfg = Config::load(v1.to_str().unwrap()).unwrap();
+    assert_eq!(cfg.version, SUPPORTED_CONFIG_VERSION);
+    // поведение 1.x: проверки отставания RPC и свежести скана выключены
+    assert_eq!(cfg.rpc.max_block_lag, 0);
+    assert_eq!(cfg.telemetry.prometheus.health_stale_secs, 0);
+
+    value["version"] = "3.0.0".into();
+    let v3 = write_tmp("cfg-v3.json", &value.to_string());
+    let err = format!("{:#}", Config::load(v3.to_str().unwrap()).unwrap_err());
+    assert!(err.contains("major 3"), "{err}");
+
+    value["version"] = "latest".into();
+    let bad = write_tmp("cfg-vbad.json", &value.to_string());
+    let err = format!("{:#}", Config::load(bad.to_str().unwrap()).unwrap_err());
+    assert!(err.contains("expected x.y.z"), "{err}");
+
+    // текущий major грузится как есть
+    let current = common::config();
+    assert_eq!(current.version, SUPPORTED_CONFIG_VERSION);
+    assert_eq!(current.rpc.max_block_lag, 3);
+
+    for p in [v1, v3, bad] {
+        let _ = std::fs::remove_file(p);
+    }
+}
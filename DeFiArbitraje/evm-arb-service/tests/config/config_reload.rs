@@ -0,0 +1,42 @@
+use DeFiArbitraje::network::MultiChain;
+use crate::common;
+
+#[test]
+fn reload_accepts_strategy_changes() {
+    let cur = common::config();
+    let mut next = cur.clone();
+    next.version = "1.3.1".into();
+    next.global.quote.slippage_bps_default = 10;
+    next.global.risk.blacklist_tokens.push("0xdead".into());
+    assert!(cur.ensure_reload_compatible(&next).is_ok());
+}
+
+#[test]
+fn reload_rejects_network_changes() {
+    let cur = common::config();
+    let mut next = cur.clone();
+    next.networks[0].chain_id = 1;
+    assert!(cur.ensure_reload_compatible(&next).is_err());
+
+    let mut next = cur.clone();
+    next.networks.pop();
+    assert!(cur.ensure_reload_compatible(&next).is_err());
+}
+
+#[tokio::test]
+async fn reload_updates_client_networks() {
+    let cur = common::single_network(8453);
+    let chains = MultiChain::from_config(&cur).await.unwrap();
+
+    // правки сети доходят до клиента, смена RPC — только после рестарта
+    let mut next = cur.clone();
+    next.networks[0].dexes.pop();
+    next.networks[0].native_usd_hint = Some(1_234.0);
+    next.networks[0].rpc = vec!["http://127.0.0.1:1".into()];
+    let chains = chains.reconfigured(&next);
+
+    let net = &chains.clients[&8453].cfg;
+    assert_eq!(net.dexes.len(), cur.networks[0].dexes.len() - 1);
+    assert_eq!(net.native_usd_hint, Some(1_234.0));
+    assert_eq!(net.rpc, cur.networks[0].rpc);
+}
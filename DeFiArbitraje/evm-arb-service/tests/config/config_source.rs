@@ -0,0 +1,57 @@
+use DeFiArbitraje::config::{Config, config_source_label, is_config_url};
+use crate::common::{self, CONFIG, http_stub};
+use hyper::{Body, Response, StatusCode};
+use std::net::SocketAddr;
+
+/// Локальный HTTP: /defi_config.json — фикстура, остальное — 404
+async fn serve_fixture() -> SocketAddr {
+    let body = std::fs::read_to_string(CONFIG).unwrap();
+    http_stub(move |path, _| {
+        if path == "/defi_config.json" {
+            Response::new(Body::from(body.clone()))
+        } else {
+            let mut r = Response::new(Body::empty());
+            *r.status_mut() = StatusCode::NOT_FOUND;
+            r
+        }
+    })
+    .await
+}
+
+#[test]
+fn url_sources_are_detected_and_labelled_without_query() {
+    assert!(is_config_url("https://bucket.s3.amazonaws.com/defi.yaml?X-Amz-Signature=abc"));
+    assert!(is_config_url("HTTP://host/cfg.json"));
+    assert!(!is_config_url("config/defi_config.json"));
+    assert!(!is_config_url("./http/cfg.json"));
+    assert_eq!(
+        config_source_label("https://bucket.s3.amazonaws.com/defi.yaml?X-Amz-Signature=abc"),
+        "bucket.s3.amazonaws.com/defi.yaml"
+    );
+    assert_eq!(config_source_label("config/defi_config.json"), "config/defi_config.json");
+}
+
+#[tokio::test]
+async fn loads_config_over_http_like_local_file() {
+    let addr = serve_fixture().await;
+    let local = common::config();
+    let remote = Config::load_source(&format!("http://{addr}/defi_config.json?token=x"))
+        .await
+        .unwrap();
+    assert_eq!(remote.version, local.version);
+    assert_eq!(remote.networks.len(), local.networks.len());
+    assert!(remote.network_by_chain_id(local.networks[0].chain_id).is_some());
+
+    // локальный путь через load_source — то же, что Config::load
+    let same = Config::load_source(CONFIG).await.unwrap();
+    assert_eq!(same.networks.len(), local.networks.len());
+}
+
+#[tokio::test]
+async fn http_error_status_fails_the_load() {
+    let addr = serve_fixture().await;
+    let err = Config::load_source(&format!("http://{addr}/missing.json"))
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("404"), "{err:#}");
+}
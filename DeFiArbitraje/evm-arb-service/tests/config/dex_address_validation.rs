@@ -0,0 +1,21 @@
+use crate::common;
+
+#[test]
+fn typo_in_router_fails_validation_with_dex_and_field() {
+    let mut cfg = common::config();
+    let dex = &mut cfg.networks[0].dexes[0];
+    let dex_name = dex.name.clone();
+    dex.router = Some("0x12345".into());
+
+    let err = cfg.validate().unwrap_err().to_string();
+    assert!(err.contains(&format!("dex '{dex_name}'")), "{err}");
+    assert!(err.contains("field router"), "{err}");
+}
+
+#[test]
+fn bad_universal_router_is_rejected() {
+    let mut cfg = common::config();
+    cfg.networks[1].dexes[0].universal_router = Some("not-an-address".into());
+    let err = cfg.validate().unwrap_err().to_string();
+    assert!(err.contains("field universalRouter"), "{err}");
+}
@@ -0,0 +1,11 @@
+use DeFiArbitraje::utils::log_directive;
+
+#[test]
+fn rust_log_overrides_config_level() {
+    assert_eq!(log_directive(None, "debug"), "debug");
+    assert_eq!(log_directive(Some("  ".into()), "warn"), "warn");
+    assert_eq!(
+        log_directive(Some("info,DeFiArbitraje=trace".into()), "warn"),
+        "info,DeFiArbitraje=trace"
+    );
+}
@@ -0,0 +1,14 @@
+//! Конфиг (`config`): форматы и источники, валидация, перезагрузка
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod checksum;
+mod config_formats;
+mod config_reload;
+mod config_source;
+mod dex_address_validation;
+mod log_level;
+mod network_lookup;
+mod notional_sizing;
+mod token_meta;
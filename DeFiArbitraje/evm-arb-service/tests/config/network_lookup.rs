@@ -0,0 +1,29 @@
+use crate::common;
+
+#[test]
+fn chain_id_lookup_survives_network_edits() {
+    let mut cfg = common::config();
+    assert_eq!(cfg.network_by_chain_id(10).unwrap().id, "optimism");
+    assert!(cfg.network_by_chain_id(1).is_none());
+
+    // индекс устарел после retain — поиск всё равно находит нужную сеть
+    cfg.networks.retain(|n| n.chain_id != 8453);
+    assert_eq!(cfg.network_by_chain_id(10).unwrap().chain_id, 10);
+    assert!(cfg.network_by_chain_id(8453).is_none());
+    cfg.index_networks();
+    assert_eq!(cfg.network_by_chain_id(137).unwrap().id, "polygon");
+}
+
+#[test]
+fn duplicate_chain_id_or_ambiguous_name_is_rejected() {
+    let mut cfg = common::config();
+    cfg.networks[1].chain_id = cfg.networks[0].chain_id;
+    let err = cfg.validate().unwrap_err().to_string();
+    assert!(err.contains("duplicate chain_id"), "{err}");
+
+    let mut cfg = common::config();
+    // name одной сети совпадает с id другой (без учёта регистра)
+    cfg.networks[1].name = cfg.networks[0].id.to_uppercase();
+    let err = cfg.validate().unwrap_err().to_string();
+    assert!(err.contains("ambiguous"), "{err}");
+}
@@ -0,0 +1,28 @@
+use DeFiArbitraje::utils::amount_for_notional;
+use crate::common;
+use ethers::types::U256;
+
+#[test]
+fn notional_converts_through_usd_price() {
+    // $100 в WBTC по $50k = 0.002 WBTC (8 знаков)
+    assert_eq!(amount_for_notional(Some(100.0), Some(50_000.0), 8), U256::from(200_000u64));
+    // $100 в USDC по $1 = 100 USDC (6 знаков)
+    assert_eq!(amount_for_notional(Some(100.0), Some(1.0), 6), U256::from(100_000_000u64));
+}
+
+#[test]
+fn missing_price_or_notional_keeps_one_unit() {
+    let one_weth = U256::exp10(18);
+    assert_eq!(amount_for_notional(Some(100.0), None, 18), one_weth);
+    assert_eq!(amount_for_notional(None, Some(3_000.0), 18), one_weth);
+    assert_eq!(amount_for_notional(Some(100.0), Some(0.0), 18), one_weth);
+}
+
+#[test]
+fn non_positive_notional_is_rejected() {
+    let mut cfg = common::config();
+    cfg.global.quote.notional_usd = Some(0.0);
+    assert!(cfg.validate().is_err());
+    cfg.global.quote.notional_usd = Some(250.0);
+    assert!(cfg.validate().is_ok());
+}
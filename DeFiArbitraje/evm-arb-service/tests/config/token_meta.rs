@@ -0,0 +1,49 @@
+use DeFiArbitraje::tokens::{TokenIssue, TokenMeta, compare_token, decode_decimals, decode_symbol};
+use crate::common;
+use ethers::abi::{Token, encode};
+use ethers::types::U256;
+
+fn usdc() -> DeFiArbitraje::config::Token {
+    common::network(8453).tokens["USDC"].clone()
+}
+
+#[test]
+fn decimals_mismatch_is_reported() {
+    let mut t = usdc();
+    assert_eq!(t.decimals, 6);
+    let ok = TokenMeta { symbol: Some("USDC".into()), decimals: Some(6) };
+    assert!(compare_token("USDC", &t, &ok).is_empty());
+
+    // классика: USDC записан как 18 decimals
+    t.decimals = 18;
+    assert_eq!(
+        compare_token("USDC", &t, &ok),
+        vec![TokenIssue::Decimals { configured: 18, onchain: 6 }]
+    );
+}
+
+#[test]
+fn symbol_is_compared_loosely() {
+    let t = usdc();
+    let meta = |s: &str| TokenMeta { symbol: Some(s.into()), decimals: Some(6) };
+    assert!(compare_token("USDCE", &t, &meta("USDC.e")).is_empty());
+    assert_eq!(
+        compare_token("USDC", &t, &meta("USDbC")),
+        vec![TokenIssue::Symbol { onchain: "USDbC".into() }]
+    );
+    let unreadable = TokenMeta { symbol: None, decimals: None };
+    assert_eq!(compare_token("USDC", &t, &unreadable), vec![TokenIssue::Unreadable]);
+}
+
+#[test]
+fn decodes_string_and_bytes32_symbols() {
+    let s = encode(&[Token::String("cbBTC".into())]);
+    assert_eq!(decode_symbol(&s).as_deref(), Some("cbBTC"));
+    let mut b32 = [0u8; 32];
+    b32[..3].copy_from_slice(b"MKR");
+    assert_eq!(decode_symbol(&b32).as_deref(), Some("MKR"));
+    assert_eq!(decode_symbol(&[]), None);
+
+    assert_eq!(decode_decimals(&encode(&[Token::Uint(U256::from(6))])), Some(6));
+    assert_eq!(decode_decimals(&encode(&[Token::Uint(U256::from(300))])), None);
+}
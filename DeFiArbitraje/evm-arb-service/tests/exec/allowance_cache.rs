@@ -0,0 +1,56 @@
+use DeFiArbitraje::approvals::AllowanceCache;
+use ethers::types::{Address, U256};
+
+fn addr(b: u8) -> Address {
+    Address::repeat_byte(b)
+}
+
+#[test]
+fn cached_allowance_covers_threshold() {
+    let mut c = AllowanceCache::default();
+    let (owner, token, spender) = (addr(1), addr(2), addr(3));
+    c.bind_executor(8453, addr(9));
+    assert!(!c.covers(8453, owner, token, spender, U256::from(100u64)));
+
+    c.record(8453, owner, token, spender, U256::from(150u64));
+    assert!(c.covers(8453, owner, token, spender, U256::from(100u64)));
+    // порог выше кэшированного — проверяем on-chain
+    assert!(!c.covers(8453, owner, token, spender, U256::from(200u64)));
+    // другая сеть — свой ключ
+    assert!(!c.covers(10, owner, token, spender, U256::from(100u64)));
+}
+
+#[test]
+fn executor_change_invalidates_chain() {
+    let mut c = AllowanceCache::default();
+    let (owner, token, spender) = (addr(1), addr(2), addr(3));
+    assert!(!c.bind_executor(8453, addr(9)));
+    assert!(!c.bind_executor(10, addr(9)));
+    c.record(8453, owner, token, spender, U256::MAX);
+    c.record(10, owner, token, spender, U256::MAX);
+
+    assert!(!c.bind_executor(8453, addr(9)));
+    assert!(c.covers(8453, owner, token, spender, U256::MAX));
+
+    assert!(c.bind_executor(8453, addr(8)));
+    assert!(!c.covers(8453, owner, token, spender, U256::one()));
+    assert!(c.covers(10, owner, token, spender, U256::MAX));
+}
+
+#[test]
+fn persists_and_reloads() {
+    let dir = std::env::temp_dir().join(format!("allowance-cache-{}", std::process::id()));
+    let path = dir.join("cache.json");
+    let mut c = AllowanceCache::default();
+    c.bind_executor(42161, addr(9));
+    c.record(42161, addr(1), addr(2), addr(3), U256::from(10u64).pow(U256::from(24u64)));
+    c.persist(&path).unwrap();
+
+    let loaded = AllowanceCache::load_or_default(&path);
+    assert!(loaded.covers(42161, addr(1), addr(2), addr(3), U256::from(10u64).pow(24u64.into())));
+
+    std::fs::write(&path, "{not json").unwrap();
+    let broken = AllowanceCache::load_or_default(&path);
+    assert!(!broken.covers(42161, addr(1), addr(2), addr(3), U256::one()));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,12 @@
+use DeFiArbitraje::exec::exec_min_profit;
+use ethers::types::U256;
+
+#[test]
+fn min_profit_depends_on_revert_flag() {
+    let amount_in = U256::exp10(18);
+    // 30 bps от 1e18
+    let strict = exec_min_profit(false, amount_in, 30);
+    assert_eq!(strict, U256::from(3_000_000_000_000_000u64));
+    assert_eq!(exec_min_profit(true, amount_in, 30), U256::zero());
+    assert_ne!(strict, exec_min_profit(true, amount_in, 30));
+}
@@ -0,0 +1,84 @@
+use DeFiArbitraje::config::ExecutorAbiCfg;
+use DeFiArbitraje::exec::{ExecutorMethods, load_executor_abi, profit_from_token};
+use DeFiArbitraje::forksim::decode_profit;
+use ethers::abi::{self, Abi, Token};
+use ethers::types::{I256, U256};
+use ethers::utils::id;
+
+/// Экзекутор другой версии: `executeRoute(bytes,uint256) -> uint256`, `quoteRoute(bytes) -> int256`
+fn route_abi() -> Abi {
+    serde_json::from_value(serde_json::json!([
+        {"type": "function", "name": "executeRoute", "stateMutability": "nonpayable",
+         "inputs": [{"name": "route", "type": "bytes"}, {"name": "minProfit", "type": "uint256"}],
+         "outputs": [{"name": "profit", "type": "uint256"}]},
+        {"type": "function", "name": "quoteRoute", "stateMutability": "view",
+         "inputs": [{"name": "route", "type": "bytes"}],
+         "outputs": [{"name": "profit", "type": "int256"}]}
+    ]))
+    .unwrap()
+}
+
+fn route_cfg() -> ExecutorAbiCfg {
+    ExecutorAbiCfg {
+        execute_method: "executeRoute".into(),
+        execute_returns: "uint256".into(),
+        simulate_method: "quoteRoute".into(),
+        simulate_returns: "int256".into(),
+        ..ExecutorAbiCfg::default()
+    }
+}
+
+#[test]
+fn default_config_matches_bundled_abi() {
+    let cfg = ExecutorAbiCfg::default();
+    let methods = ExecutorMethods::from_abi(&load_executor_abi(&cfg).unwrap(), &cfg).unwrap();
+    assert_eq!(methods, ExecutorMethods::default());
+}
+
+#[test]
+fn contract_abi_serves_default_methods() {
+    // ABI контракта из onchain-executor: маршрут сервиса рядом с Hop[]-перегрузками
+    let cfg = ExecutorAbiCfg {
+        abi_file: concat!(env!("CARGO_MANIFEST_DIR"), "/../onchain-executor/abi/Executor.abi.json")
+            .into(),
+        ..ExecutorAbiCfg::default()
+    };
+    let methods = ExecutorMethods::from_abi(&load_executor_abi(&cfg).unwrap(), &cfg).unwrap();
+    assert_eq!(methods, ExecutorMethods::default());
+}
+
+#[test]
+fn custom_methods_resolved_from_abi() {
+    let methods = ExecutorMethods::from_abi(&route_abi(), &route_cfg()).unwrap();
+    assert_eq!(methods.execute_selector, id("executeRoute(bytes,uint256)"));
+    assert_eq!(methods.simulate_selector, id("quoteRoute(bytes)"));
+    assert!(!methods.execute_signed && methods.simulate_signed);
+}
+
+#[test]
+fn misconfigured_methods_fail_fast() {
+    // встроенный ABI не знает executeRoute
+    let bundled = load_executor_abi(&ExecutorAbiCfg::default()).unwrap();
+    let err = ExecutorMethods::from_abi(&bundled, &route_cfg()).unwrap_err().to_string();
+    assert!(err.contains("executeRoute") && err.contains("execute_method"), "{err}");
+    assert!(err.contains("simulate"), "known methods listed: {err}");
+
+    let mut cfg = route_cfg();
+    cfg.execute_returns = "int256".into();
+    let err = ExecutorMethods::from_abi(&route_abi(), &cfg).unwrap_err().to_string();
+    assert!(err.contains("uint256") && err.contains("int256"), "{err}");
+
+    cfg.execute_returns = "bool".into();
+    assert!(ExecutorMethods::from_abi(&route_abi(), &cfg).is_err());
+}
+
+#[test]
+fn profit_decoded_by_return_type() {
+    let loss = I256::from(-5).into_raw();
+    assert_eq!(profit_from_token(Token::Int(loss)).unwrap(), U256::zero());
+    assert_eq!(profit_from_token(Token::Uint(U256::from(7u64))).unwrap(), U256::from(7u64));
+    // тот же word: как uint256 — огромная прибыль, как int256 — убыток
+    let word = abi::encode(&[Token::Uint(loss)]);
+    assert_eq!(decode_profit(&word, false).unwrap(), loss);
+    assert_eq!(decode_profit(&word, true).unwrap(), U256::zero());
+}
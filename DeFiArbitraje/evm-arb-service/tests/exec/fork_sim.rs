@@ -0,0 +1,115 @@
+use DeFiArbitraje::config::ForkSimCfg;
+use DeFiArbitraje::exec::SimOutcome;
+use DeFiArbitraje::forksim::{
+    ForkSimulator, Funding, balance_slot_key, execute_calldata, funding_for,
+};
+use crate::common::{self, rpc_stub};
+use ethers::abi::{self, Token};
+use ethers::providers::{Http, Provider};
+use ethers::types::{Address, Bytes, I256, U256};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// JSON-RPC заглушка форка: запоминает последний запрос и отвечает `reply`
+/// (объект с "result" или "error")
+async fn fork_stub(
+    reply: serde_json::Value,
+) -> (SocketAddr, Arc<Mutex<Option<serde_json::Value>>>) {
+    let seen = Arc::new(Mutex::new(None));
+    let log = seen.clone();
+    let addr = rpc_stub(move |call| {
+        *log.lock().unwrap() = Some(call.clone());
+        match reply.get("error") {
+            Some(error) => Err(error.clone()),
+            None => Ok(reply["result"].clone()),
+        }
+    })
+    .await;
+    (addr, seen)
+}
+
+fn simulator(code: Option<Bytes>) -> ForkSimulator {
+    let mut sim = ForkSimulator::new(
+        &ForkSimCfg::default(),
+        Address::from_low_u64_be(0xE1),
+        Address::from_low_u64_be(0xF0),
+    )
+    .unwrap();
+    sim.executor_code = code;
+    sim
+}
+
+#[test]
+fn funding_from_token_balance_slots() {
+    let base = &common::network(8453);
+    let usdc: Address = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".parse().unwrap();
+    assert_eq!(funding_for(base, "USDC"), Funding::Erc20 { token: usdc, slot: 9 });
+    assert_eq!(funding_for(base, "ETH"), Funding::Native);
+    // слот баланса не задан — вход не подменяется
+    assert_eq!(funding_for(base, "DAI"), Funding::None);
+}
+
+#[test]
+fn state_override_funds_executor() {
+    let sim = simulator(Some(Bytes::from(vec![0x60, 0x00])));
+    let token = Address::from_low_u64_be(0x70);
+    let state = sim.state_override(Funding::Erc20 { token, slot: 9 }, U256::from(1_000_000u64));
+    let json = serde_json::to_value(&state).unwrap();
+
+    assert_eq!(json[format!("{:?}", sim.executor)]["code"], "0x6000");
+    let key = format!("{:?}", balance_slot_key(sim.executor, 9));
+    let stored = &json[format!("{token:?}")]["stateDiff"][&key];
+    assert_eq!(stored, &format!("{:#066x}", 1_000_000u64));
+
+    let native = serde_json::to_value(sim.state_override(Funding::Native, U256::from(5u64)))
+        .unwrap();
+    assert_eq!(native[format!("{:?}", sim.executor)]["balance"], "0x5");
+    // слот mapping'а зависит от держателя
+    assert_ne!(balance_slot_key(sim.executor, 9), balance_slot_key(token, 9));
+}
+
+#[tokio::test]
+async fn execute_on_fork_returns_profit() {
+    let profit = abi::encode(&[Token::Int(I256::from(42_000).into_raw())]);
+    let (addr, seen) =
+        fork_stub(serde_json::json!({ "result": format!("0x{}", hex::encode(profit)) })).await;
+    let provider = Provider::<Http>::try_from(format!("http://{addr}")).unwrap();
+    let sim = simulator(None);
+    let route = Bytes::from(vec![0xAB; 4]);
+
+    let out = sim
+        .simulate(&provider, route.clone(), Funding::Native, U256::from(7u64), 900_000)
+        .await
+        .unwrap();
+    assert_eq!(out, SimOutcome::Profit(U256::from(42_000u64)));
+
+    let call = seen.lock().unwrap().take().unwrap();
+    assert_eq!(call["method"], "eth_call");
+    let params = call["params"].as_array().unwrap();
+    assert_eq!(params[0]["to"], format!("{:?}", sim.executor));
+    assert_eq!(params[0]["from"], format!("{:?}", sim.from));
+    let data = execute_calldata(sim.methods.execute_selector, route, U256::zero());
+    assert_eq!(params[0]["data"], format!("0x{}", hex::encode(&data)));
+    // третий параметр — stateOverride с подменённым балансом экзекутора
+    assert_eq!(params[2][format!("{:?}", sim.executor)]["balance"], "0x7");
+}
+
+#[tokio::test]
+async fn revert_on_fork_is_decoded() {
+    let mut data = ethers::utils::id("Error(string)").to_vec();
+    data.extend(abi::encode(&[Token::String("MIN_PROFIT".into())]));
+    let (addr, _) = fork_stub(serde_json::json!({
+        "error": {
+            "code": 3,
+            "message": "execution reverted: MIN_PROFIT",
+            "data": format!("0x{}", hex::encode(data)),
+        }
+    }))
+    .await;
+    let provider = Provider::<Http>::try_from(format!("http://{addr}")).unwrap();
+    let out = simulator(None)
+        .simulate(&provider, Bytes::default(), Funding::None, U256::one(), 900_000)
+        .await
+        .unwrap();
+    assert_eq!(out, SimOutcome::Reverted("MIN_PROFIT".into()));
+}
@@ -0,0 +1,64 @@
+use DeFiArbitraje::keys::{KeySource, resolve_key_source};
+use ethers::signers::{LocalWallet, Signer};
+use std::collections::HashMap;
+
+const PK: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |k| map.get(k).cloned()
+}
+
+#[test]
+fn keystore_file_decrypts_to_same_wallet() {
+    let dir = std::env::temp_dir().join(format!("defi-arb-keystore-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pk_bytes = hex::decode(PK.trim_start_matches("0x")).unwrap();
+    let mut rng = ethers::core::rand::thread_rng();
+    let (expected, _) =
+        LocalWallet::encrypt_keystore(&dir, &mut rng, pk_bytes, "secret", Some("key.json")).unwrap();
+    let path = dir.join("key.json");
+
+    let src = resolve_key_source(
+        8453,
+        env(&[
+            ("PRIVATE_KEY_FILE_8453", path.to_str().unwrap()),
+            ("PRIVATE_KEY_PASSWORD", "secret"),
+        ]),
+    )
+    .unwrap();
+    assert!(matches!(src, KeySource::Keystore { .. }));
+    assert_eq!(src.wallet().unwrap().address(), expected.address());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn raw_hex_is_still_supported() {
+    let src = resolve_key_source(10, env(&[("PRIVATE_KEY", PK)])).unwrap();
+    assert_eq!(src, KeySource::RawHex(PK.to_string()));
+    let src = resolve_key_source(10, env(&[("PRIVATE_KEY_10", PK), ("PRIVATE_KEY", "0x01")])).unwrap();
+    assert_eq!(src, KeySource::RawHex(PK.to_string()));
+}
+
+#[test]
+fn exactly_one_key_source_per_chain() {
+    let err = resolve_key_source(
+        56,
+        env(&[
+            ("PRIVATE_KEY_FILE_56", "/tmp/k.json"),
+            ("PRIVATE_KEY_56", PK),
+            ("PRIVATE_KEY_PASSWORD", "x"),
+        ]),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("PRIVATE_KEY_FILE_56"));
+
+    // keystore без пароля
+    assert!(resolve_key_source(56, env(&[("PRIVATE_KEY_FILE_56", "/tmp/k.json")])).is_err());
+    // вообще без ключа
+    assert!(resolve_key_source(56, env(&[])).is_err());
+}
@@ -0,0 +1,17 @@
+//! Исполнение (`exec`, `calldata`, `keys`): calldata маршрута, симуляция, отправка и receipts
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod allowance_cache;
+mod exec_min_profit;
+mod executor_abi;
+mod fork_sim;
+mod keystore;
+mod permit2_sign;
+mod receipt_drain;
+mod revert_retry;
+mod route_calldata;
+mod safe_exec;
+mod slippage_halt;
+mod wallet_rotation;
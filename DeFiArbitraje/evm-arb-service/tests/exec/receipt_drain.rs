@@ -0,0 +1,89 @@
+use DeFiArbitraje::exec::{ReceiptTracker, TxOutcome};
+use ethers::types::{TransactionReceipt, TxHash, U64};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn receipt(status: u64, block: u64) -> TransactionReceipt {
+    TransactionReceipt {
+        status: Some(U64::from(status)),
+        block_number: Some(U64::from(block)),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn settle_removes_only_mined_transactions() {
+    let tracker = ReceiptTracker::default();
+    let (ok, reverted, mempool) = (
+        TxHash::from_low_u64_be(1),
+        TxHash::from_low_u64_be(2),
+        TxHash::from_low_u64_be(3),
+    );
+    tracker.track(8453, ok, "WETH-USDC", None);
+    tracker.track(8453, reverted, "USDC-WETH", None);
+    tracker.track(10, mempool, "WETH-USDC", None);
+
+    let fetch = |_chain: u64, tx: TxHash| async move {
+        Ok(match tx {
+            t if t == ok => Some(receipt(1, 100)),
+            t if t == reverted => Some(receipt(0, 101)),
+            _ => None,
+        })
+    };
+    let done = tracker.settle(Some(8453), &fetch).await;
+    assert_eq!(done.len(), 2);
+    assert_eq!(
+        done[0].1,
+        TxOutcome::Confirmed {
+            block: 100,
+            realized_out: None
+        }
+    );
+    assert_eq!(done[1].1, TxOutcome::Reverted { block: 101 });
+    assert_eq!(tracker.pending(None).len(), 1);
+    assert_eq!(tracker.pending(Some(10))[0].tx, mempool);
+}
+
+#[tokio::test]
+async fn drain_waits_for_late_receipt() {
+    let tracker = ReceiptTracker::default();
+    let tx = TxHash::from_low_u64_be(7);
+    tracker.track(8453, tx, "WETH-USDC", None);
+
+    // receipt появляется только на третьем опросе
+    let polls = AtomicUsize::new(0);
+    let settled = Mutex::new(Vec::new());
+    let left = tracker
+        .drain(
+            Duration::from_secs(5),
+            Duration::from_millis(5),
+            |_, _| {
+                let n = polls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok((n >= 2).then(|| receipt(1, 5))) }
+            },
+            |s| settled.lock().unwrap().extend(s.iter().map(|(p, _)| p.tx)),
+        )
+        .await;
+    assert!(left.is_empty());
+    assert_eq!(*settled.lock().unwrap(), [tx]);
+    assert_eq!(polls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn drain_gives_up_after_timeout() {
+    let tracker = ReceiptTracker::default();
+    let tx = TxHash::from_low_u64_be(9);
+    tracker.track(56, tx, "WBNB-USDT", None);
+
+    let left = tracker
+        .drain(
+            Duration::from_millis(30),
+            Duration::from_millis(5),
+            |_, _| async { Ok(None) },
+            |_| {},
+        )
+        .await;
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].tx, tx);
+}
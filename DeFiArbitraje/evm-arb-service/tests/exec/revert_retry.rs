@@ -0,0 +1,48 @@
+use DeFiArbitraje::exec::{retry_gas_price, RevertRetries, TxOutcome};
+use ethers::types::U256;
+
+const REVERTED: TxOutcome = TxOutcome::Reverted { block: 1 };
+
+#[test]
+fn retries_stop_after_limit() {
+    let r = RevertRetries::default();
+    assert_eq!(r.on_outcome(8453, "WETH-USDC", &REVERTED, 2), Some(1));
+    assert_eq!(r.pending(8453, "WETH-USDC"), Some(1));
+    r.mark_sent(8453, "WETH-USDC");
+    assert_eq!(r.pending(8453, "WETH-USDC"), None);
+
+    assert_eq!(r.on_outcome(8453, "WETH-USDC", &REVERTED, 2), Some(2));
+    r.mark_sent(8453, "WETH-USDC");
+    // третий revert — лимит исчерпан
+    assert_eq!(r.on_outcome(8453, "WETH-USDC", &REVERTED, 2), None);
+    assert_eq!(r.pending(8453, "WETH-USDC"), None);
+    // следующий revert того же маршрута — снова с первой попытки
+    assert_eq!(r.on_outcome(8453, "WETH-USDC", &REVERTED, 2), Some(1));
+}
+
+#[test]
+fn confirmation_and_cancel_clear_state() {
+    let r = RevertRetries::default();
+    assert_eq!(r.on_outcome(10, "WETH-USDC", &REVERTED, 0), None);
+
+    r.on_outcome(10, "WETH-USDC", &REVERTED, 3);
+    r.mark_sent(10, "WETH-USDC");
+    let ok = TxOutcome::Confirmed { block: 2, realized_out: None };
+    assert_eq!(r.on_outcome(10, "WETH-USDC", &ok, 3), None);
+    assert_eq!(r.on_outcome(10, "WETH-USDC", &REVERTED, 3), Some(1));
+
+    assert_eq!(r.cancel(10, "WETH-USDC"), Some(1));
+    assert_eq!(r.pending(10, "WETH-USDC"), None);
+
+    r.on_outcome(10, "WETH-USDC", &REVERTED, 3);
+    r.on_outcome(56, "WBNB-USDT", &REVERTED, 3);
+    assert_eq!(r.cancel_chain(10), 1);
+    assert_eq!(r.pending(56, "WBNB-USDT"), Some(1));
+}
+
+#[test]
+fn gas_bumped_per_attempt() {
+    let gp = U256::from(1_000_000_000u64);
+    assert_eq!(retry_gas_price(gp, 1), U256::from(1_100_000_000u64));
+    assert_eq!(retry_gas_price(gp, 2), U256::from(1_200_000_000u64));
+}
@@ -0,0 +1,183 @@
+use DeFiArbitraje::calldata::{
+    encode_route_calldata, leg_min_outs, route_deadline, validate_route, LegKind, LegQuote,
+    LegSlippage,
+};
+use ethers::abi::{decode, ParamType, Token};
+use ethers::types::{Address, U256};
+
+#[test]
+fn deadline_is_trailing_field() {
+    let router = Address::from_low_u64_be(0x1);
+    let path = vec![Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb)];
+    let legs = vec![LegQuote {
+        kind: LegKind::V2 { router, path: path.clone() },
+        quoted_out: U256::from(1020),
+    }];
+    let deadline = route_deadline(1_700_000_000, 120);
+    assert_eq!(deadline, U256::from(1_700_000_120u64));
+
+    let data = encode_route_calldata(&legs, U256::from(1000), U256::from(1010), None, deadline, 3)
+        .unwrap();
+    let out = decode(
+        &[
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::Array(Box::new(ParamType::Address)),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+        ],
+        &data,
+    )
+    .unwrap();
+    assert_eq!(out[0], Token::Uint(U256::from(1000)));
+    assert_eq!(out[2], Token::Uint(U256::one()));
+    assert_eq!(out[4], Token::Address(router));
+    // единственная нога — её минимум совпадает с min_out маршрута
+    assert_eq!(out[6], Token::Uint(U256::from(1010)));
+    assert_eq!(out[7], Token::Uint(deadline));
+}
+
+#[test]
+fn zero_seconds_disables_deadline() {
+    assert_eq!(route_deadline(1_700_000_000, 0), U256::zero());
+}
+
+#[test]
+fn algebra_leg_has_no_fee() {
+    let router = Address::from_low_u64_be(0x4);
+    let (token_in, token_out) = (Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb));
+    let legs = vec![LegQuote {
+        kind: LegKind::AlgebraV3 { router, token_in, token_out },
+        quoted_out: U256::zero(),
+    }];
+    assert_eq!(legs[0].router(), router);
+
+    let data = encode_route_calldata(
+        &legs,
+        U256::from(1000),
+        U256::from(1010),
+        None,
+        U256::zero(),
+        3,
+    )
+    .unwrap();
+    let out = decode(
+        &[
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+        ],
+        &data,
+    )
+    .unwrap();
+    assert_eq!(out[3], Token::Uint(U256::from(4)));
+    assert_eq!(out[5], Token::Address(token_in));
+    assert_eq!(out[6], Token::Address(token_out));
+    // котировка ноги неизвестна — минимум ноги 0 (дальше дедлайн)
+    assert_eq!(out[7], Token::Uint(U256::zero()));
+}
+
+#[test]
+fn rejects_losing_empty_and_long_routes() {
+    let leg = || LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(0x1),
+            path: vec![Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb)],
+        },
+        quoted_out: U256::from(1020),
+    };
+    let (amount_in, min_out) = (U256::from(1000), U256::from(1010));
+    assert!(validate_route(&[leg(), leg()], amount_in, min_out, 2).is_ok());
+
+    // min_out не выше входа — круговой маршрут в убыток
+    let err =
+        encode_route_calldata(&[leg()], amount_in, amount_in, None, U256::zero(), 2).unwrap_err();
+    assert!(err.to_string().contains("min_out"), "{err}");
+    assert!(validate_route(&[leg()], amount_in, U256::from(990), 2).is_err());
+
+    let err = encode_route_calldata(&[], amount_in, min_out, None, U256::zero(), 2).unwrap_err();
+    assert!(err.to_string().contains("no legs"), "{err}");
+
+    let err = validate_route(&[leg(), leg(), leg()], amount_in, min_out, 2).unwrap_err();
+    assert!(err.to_string().contains("max 2"), "{err}");
+}
+
+#[test]
+fn intermediate_legs_carry_scaled_minimums() {
+    let leg = |quoted: u64| LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(0x1),
+            path: vec![Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb)],
+        },
+        quoted_out: U256::from(quoted),
+    };
+    // USDC 1000 → 5e17 WETH → 1020 USDC; min_out 1010 — запас ~0.98% на каждой ноге
+    let legs = [leg(500_000_000_000_000_000), leg(1020)];
+    let mins = leg_min_outs(&legs, U256::from(1000), U256::from(1010), None);
+    assert_eq!(mins[0], U256::from(495_098_039_215_686_274u64));
+    assert_eq!(mins[1], U256::from(1010));
+
+    let data = encode_route_calldata(
+        &legs,
+        U256::from(1000),
+        U256::from(1010),
+        None,
+        U256::zero(),
+        3,
+    )
+    .unwrap();
+    let leg_params = [
+        ParamType::Uint(256),
+        ParamType::Address,
+        ParamType::Array(Box::new(ParamType::Address)),
+        ParamType::Uint(256),
+    ];
+    let mut params = vec![ParamType::Uint(256); 3];
+    params.extend(leg_params.clone());
+    params.extend(leg_params);
+    params.push(ParamType::Uint(256));
+    let out = decode(&params, &data).unwrap();
+    assert_eq!(out[6], Token::Uint(mins[0]));
+    assert_eq!(out[10], Token::Uint(mins[1]));
+    assert_eq!(out[11], Token::Uint(U256::zero()));
+
+    // без котировки последней ноги минимумов нет
+    let unquoted = leg_min_outs(&[leg(7), leg(0)], U256::from(1000), U256::from(1010), None);
+    assert!(unquoted.iter().all(|m| m.is_zero()));
+}
+
+#[test]
+fn leg_slippage_applies_to_each_leg_quote() {
+    let leg = |quoted: u64| LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(0x1),
+            path: vec![Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb)],
+        },
+        quoted_out: U256::from(quoted),
+    };
+    // USDC 1000 → 2_000_000 X → 40_000 Y → 1020 USDC; 30 bps на промежуточных ногах
+    let legs = [leg(2_000_000), leg(40_000), leg(1020)];
+    let slip = Some(LegSlippage { bps: 30, quoted_in: U256::from(1000) });
+    let mins = leg_min_outs(&legs, U256::from(1000), U256::from(1010), slip);
+    assert_eq!(mins[0], U256::from(1_994_000));
+    assert_eq!(mins[1], U256::from(39_880));
+    // последняя нога — финальный min_out, а не её котировка минус 30 bps
+    assert_eq!(mins[2], U256::from(1010));
+
+    // минимумы не зависят от запаса финального min_out
+    let loose = leg_min_outs(&legs, U256::from(1000), U256::from(1001), slip);
+    assert_eq!(loose[..2], mins[..2]);
+    assert_ne!(leg_min_outs(&legs, U256::from(1000), U256::from(1001), None)[0], mins[0]);
+
+    // pre-send хук уменьшил вход вдвое: котировки промежуточных ног — тоже
+    let half = leg_min_outs(&legs, U256::from(500), U256::from(505), slip);
+    assert_eq!(half, vec![U256::from(997_000), U256::from(19_940), U256::from(505)]);
+}
@@ -0,0 +1,60 @@
+use DeFiArbitraje::exec::{
+    prevalidated_signature, safe_calldata, safe_mode, ExecTransactionCall,
+    ExecTransactionFromModuleCall, SafeMode,
+};
+use DeFiArbitraje::keys::{resolve_safe_module_key, KeySource};
+use ethers::abi::AbiDecode;
+use ethers::types::{Address, Bytes, U256};
+
+fn addr(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+#[test]
+fn single_owner_needs_threshold_one() {
+    let me = addr(1);
+    assert_eq!(safe_mode(8453, me, U256::one(), true, None).unwrap(), SafeMode::Owner);
+    let err = safe_mode(8453, me, U256::from(2), true, None).unwrap_err().to_string();
+    assert!(err.contains("порог Safe 2"), "{err}");
+    assert!(err.contains("SAFE_MODULE_KEY_8453"), "{err}");
+    assert!(safe_mode(8453, me, U256::one(), false, None).is_err());
+}
+
+#[test]
+fn module_path_ignores_threshold() {
+    let me = addr(1);
+    assert_eq!(
+        safe_mode(10, me, U256::from(3), false, Some(true)).unwrap(),
+        SafeMode::Module
+    );
+    assert!(safe_mode(10, me, U256::from(3), true, Some(false)).is_err());
+}
+
+#[test]
+fn owner_calldata_wraps_execute_with_prevalidated_signature() {
+    let (executor, owner) = (addr(0xe0), addr(0xab));
+    let inner = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+    let data = safe_calldata(SafeMode::Owner, executor, inner.clone(), owner);
+    let call = ExecTransactionCall::decode(data.as_ref()).unwrap();
+    assert_eq!(call.to, executor);
+    assert_eq!(call.data, inner);
+    assert_eq!(call.operation, 0);
+    assert_eq!(call.signatures, prevalidated_signature(owner));
+    assert_eq!(call.signatures.len(), 65);
+    assert_eq!(&call.signatures[12..32], owner.as_bytes());
+    assert_eq!(call.signatures[64], 1);
+
+    let data = safe_calldata(SafeMode::Module, executor, inner.clone(), owner);
+    let call = ExecTransactionFromModuleCall::decode(data.as_ref()).unwrap();
+    assert_eq!((call.to, call.data), (executor, inner));
+}
+
+#[test]
+fn module_key_read_per_chain() {
+    let get = |k: &str| (k == "SAFE_MODULE_KEY_8453").then(|| "0xabc".to_string());
+    assert_eq!(
+        resolve_safe_module_key(8453, get),
+        Some(KeySource::RawHex("0xabc".into()))
+    );
+    assert_eq!(resolve_safe_module_key(10, get), None);
+}
@@ -0,0 +1,78 @@
+use DeFiArbitraje::exec::{
+    realized_output, slippage_breach, slippage_bps, ExpectedOut, PendingTx, TxOutcome,
+};
+use ethers::types::{Address, Bytes, Log, TxHash, H256, U256};
+
+fn transfer(token: Address, from: Address, to: Address, amount: u64) -> Log {
+    let sig = H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)"));
+    let mut data = [0u8; 32];
+    U256::from(amount).to_big_endian(&mut data);
+    Log {
+        address: token,
+        topics: vec![sig, H256::from(from), H256::from(to)],
+        data: Bytes::from(data.to_vec()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn realized_output_sums_transfers_to_recipient() {
+    let (token, other, exec) = (
+        Address::from_low_u64_be(1),
+        Address::from_low_u64_be(2),
+        Address::from_low_u64_be(3),
+    );
+    let (pool, wallet) = (Address::from_low_u64_be(0xdead), Address::from_low_u64_be(4));
+    let logs = vec![
+        transfer(token, pool, exec, 600),
+        transfer(token, pool, Address::from_low_u64_be(9), 1_000),
+        transfer(other, pool, exec, 1_000),
+        transfer(token, pool, exec, 400),
+    ];
+    assert_eq!(realized_output(&logs, token, exec, wallet), U256::from(1_000));
+}
+
+#[test]
+fn input_pull_is_not_realized_output() {
+    let (token, pool, wallet, exec) = (
+        Address::from_low_u64_be(1),
+        Address::from_low_u64_be(2),
+        Address::from_low_u64_be(3),
+        Address::from_low_u64_be(4),
+    );
+    // круговой маршрут: экзекутор забирает вход с кошелька и получает выход тем же токеном
+    let logs = vec![
+        transfer(token, wallet, exec, 10_000),
+        transfer(token, exec, pool, 10_000),
+        transfer(token, pool, exec, 10_050),
+        transfer(token, exec, wallet, 10_050),
+    ];
+    assert_eq!(realized_output(&logs, token, exec, wallet), U256::from(10_050));
+}
+
+#[test]
+fn breach_only_above_threshold() {
+    assert_eq!(slippage_bps(U256::from(10_000), U256::from(9_850)), 150);
+    assert_eq!(slippage_bps(U256::from(10_000), U256::from(10_100)), 0);
+
+    let pending = PendingTx {
+        chain_id: 8453,
+        tx: TxHash::from_low_u64_be(1),
+        route: "WETH-USDC".into(),
+        expected: Some(ExpectedOut {
+            token: Address::from_low_u64_be(1),
+            recipient: Address::from_low_u64_be(3),
+            payer: Address::from_low_u64_be(4),
+            amount: U256::from(10_000),
+        }),
+    };
+    let confirmed = |out: u64| TxOutcome::Confirmed {
+        block: 1,
+        realized_out: Some(U256::from(out)),
+    };
+    assert_eq!(slippage_breach(120, &pending, &confirmed(9_850)), Some(150));
+    assert_eq!(slippage_breach(120, &pending, &confirmed(9_900)), None);
+    // порог 0 — проверка выключена
+    assert_eq!(slippage_breach(0, &pending, &confirmed(5_000)), None);
+    assert_eq!(slippage_breach(120, &pending, &TxOutcome::Reverted { block: 1 }), None);
+}
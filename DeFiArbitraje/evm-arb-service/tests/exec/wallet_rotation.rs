@@ -0,0 +1,133 @@
+use DeFiArbitraje::exec::{
+    DEFAULT_EXECUTOR_ABI, Executor, ExecutorMethods, ExecutorPool, NONCE_STALL_RESYNC,
+    NonceManager,
+};
+use DeFiArbitraje::keys::{KeySource, resolve_key_sources};
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> =
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    move |k| map.get(k).cloned()
+}
+
+#[test]
+fn indexed_keys_are_read_in_order() {
+    let keys = resolve_key_sources(
+        8453,
+        env(&[
+            ("PRIVATE_KEY_8453_0", "0xaa"),
+            ("PRIVATE_KEY_FILE_8453_1", "/keys/w1.json"),
+            ("PRIVATE_KEY_PASSWORD_8453_1", "pw1"),
+            ("PRIVATE_KEY_8453_2", "0xcc"),
+            // разрыв нумерации: _4 без _3 не подхватывается
+            ("PRIVATE_KEY_8453_4", "0xee"),
+            ("PRIVATE_KEY", "0xff"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        keys,
+        vec![
+            KeySource::RawHex("0xaa".into()),
+            KeySource::Keystore { path: "/keys/w1.json".into(), password: "pw1".into() },
+            KeySource::RawHex("0xcc".into()),
+        ]
+    );
+}
+
+#[test]
+fn single_key_without_index_still_works() {
+    let keys = resolve_key_sources(10, env(&[("PRIVATE_KEY_10", "0xaa")])).unwrap();
+    assert_eq!(keys, vec![KeySource::RawHex("0xaa".into())]);
+}
+
+#[test]
+fn shared_password_and_conflicts() {
+    let keys = resolve_key_sources(
+        10,
+        env(&[("PRIVATE_KEY_FILE_10_0", "/k0.json"), ("PRIVATE_KEY_PASSWORD", "shared")]),
+    )
+    .unwrap();
+    assert_eq!(
+        keys,
+        vec![KeySource::Keystore { path: "/k0.json".into(), password: "shared".into() }]
+    );
+
+    let both = env(&[("PRIVATE_KEY_10", "0xaa"), ("PRIVATE_KEY_10_0", "0xbb")]);
+    assert!(resolve_key_sources(10, both).is_err());
+    // keystore кошелька без пароля — ошибка с номером кошелька
+    let err = resolve_key_sources(10, env(&[("PRIVATE_KEY_FILE_10_0", "/k0.json")]))
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("кошелёк 0"));
+}
+
+#[test]
+fn nonces_advance_locally_until_reset() {
+    let nonces = NonceManager::default();
+    let t0 = Instant::now();
+    assert_eq!(nonces.take(U256::from(5), t0), U256::from(5));
+    // сеть ещё не видит отправленную — следующий nonce локальный
+    assert_eq!(nonces.take(U256::from(5), t0), U256::from(6));
+    // транзакции в обход бота: pending-nonce сети выше
+    assert_eq!(nonces.take(U256::from(9), t0), U256::from(9));
+    nonces.reset();
+    assert_eq!(nonces.take(U256::from(7), t0), U256::from(7));
+}
+
+#[test]
+fn stalled_pending_nonce_resyncs_counter() {
+    let nonces = NonceManager::default();
+    let t0 = Instant::now();
+    assert_eq!(nonces.take(U256::from(5), t0), U256::from(5));
+    assert_eq!(nonces.take(U256::from(5), t0 + Duration::from_secs(1)), U256::from(6));
+    // tx с nonce 5 выпала: pending стоит на 5 дольше порога — снова 5
+    let later = t0 + NONCE_STALL_RESYNC;
+    assert_eq!(nonces.take(U256::from(5), later), U256::from(5));
+    // дальше снова локально, отсчёт — с момента пересинхронизации
+    assert_eq!(nonces.take(U256::from(5), later + Duration::from_secs(1)), U256::from(6));
+    // pending сдвинулся — не застой
+    assert_eq!(nonces.take(U256::from(6), later + NONCE_STALL_RESYNC), U256::from(7));
+}
+
+#[test]
+fn empty_pool_is_an_error() {
+    let wallets: Vec<Arc<Executor<Provider<Http>, LocalWallet>>> = Vec::new();
+    assert!(ExecutorPool::new(wallets).is_err());
+}
+
+#[test]
+fn pool_rotates_wallets_round_robin() {
+    let keys = [
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    ];
+    let wallets = keys
+        .iter()
+        .map(|k| {
+            let provider = Provider::<Http>::try_from("http://127.0.0.1:1").unwrap();
+            let wallet: LocalWallet = k.parse().unwrap();
+            Arc::new(Executor {
+                client: Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(8453u64))),
+                address: Address::from_low_u64_be(0xe),
+                abi: serde_json::from_str(DEFAULT_EXECUTOR_ABI).unwrap(),
+                methods: ExecutorMethods::default(),
+                nonces: NonceManager::default(),
+                safe: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    let addrs: Vec<Address> = wallets.iter().map(|w| w.client.address()).collect();
+    let pool = ExecutorPool::new(wallets).unwrap();
+
+    let sent: Vec<Address> = (0..4).map(|_| pool.next_wallet().client.address()).collect();
+    assert_eq!(sent, vec![addrs[0], addrs[1], addrs[0], addrs[1]]);
+    // симуляции всегда от первого кошелька
+    assert_eq!(pool.primary().client.address(), addrs[0]);
+}
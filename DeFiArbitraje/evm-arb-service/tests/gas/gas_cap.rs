@@ -0,0 +1,32 @@
+use DeFiArbitraje::config::NetworkGas;
+use DeFiArbitraje::utils_gas::{cap_gas_price, gas_price_from_base_fee};
+use ethers::types::U256;
+
+fn gwei(v: u64) -> U256 {
+    U256::from(v) * U256::exp10(9)
+}
+
+#[test]
+fn cap_is_respected_when_base_fee_spikes() {
+    let gas = NetworkGas {
+        tip_gwei: 2.0,
+        max_fee_gwei: Some(50.0),
+        base_fee_multiplier: 1.25,
+    };
+    // обычный режим: 20 * 1.25 + 2 = 27 gwei
+    assert_eq!(gas_price_from_base_fee(gwei(20), None, &gas), gwei(27));
+    // всплеск base fee до 400 gwei → упираемся в потолок 50
+    assert_eq!(gas_price_from_base_fee(gwei(400), None, &gas), gwei(50));
+    assert_eq!(gas_price_from_base_fee(gwei(400), Some(gwei(600)), &gas), gwei(50));
+    // legacy-цепочки без base fee тоже ограничены
+    assert_eq!(cap_gas_price(gwei(120), &gas), gwei(50));
+}
+
+#[test]
+fn defaults_match_previous_behaviour() {
+    let gas = NetworkGas::default();
+    assert_eq!(gas_price_from_base_fee(gwei(10), None, &gas), gwei(12));
+    // оценка EIP-1559 ниже base + tip — берём её
+    assert_eq!(gas_price_from_base_fee(gwei(10), Some(gwei(11)), &gas), gwei(11));
+    assert_eq!(cap_gas_price(gwei(1_000), &gas), gwei(1_000));
+}
@@ -0,0 +1,27 @@
+use DeFiArbitraje::utils_gas::{covers_gas, gas_cost_native};
+use crate::common;
+use ethers::types::U256;
+
+#[test]
+fn gas_dominated_route_is_rejected() {
+    // 0.0005 ETH валовой прибыли против 300k газа по 5 gwei = 0.0015 ETH
+    let gas = gas_cost_native(300_000, U256::from(5_000_000_000u64));
+    assert!((gas - 0.0015).abs() < 1e-12);
+    assert!(!covers_gas(0.0005, gas, 0.0));
+    // ровно в ноль — тоже отказ
+    assert!(!covers_gas(gas, gas, 0.0));
+    assert!(covers_gas(0.002, gas, 0.0));
+}
+
+#[test]
+fn min_net_profit_native_raises_the_bar() {
+    let gas = 0.001;
+    assert!(covers_gas(0.0015, gas, 0.0005));
+    assert!(!covers_gas(0.0014, gas, 0.0005));
+}
+
+#[test]
+fn min_net_profit_native_defaults_to_zero() {
+    let cfg = common::config();
+    assert!(cfg.networks.iter().all(|n| n.min_net_profit_native == 0.0));
+}
@@ -0,0 +1,44 @@
+use DeFiArbitraje::config::NetworkGas;
+use DeFiArbitraje::utils_gas::{current_gas_price_legacy, GasPriceMode};
+use ethers::providers::Provider;
+use ethers::types::{Block, TxHash, U256};
+use std::sync::Arc;
+
+fn gwei(v: u64) -> U256 {
+    U256::from(v) * U256::exp10(9)
+}
+
+#[tokio::test]
+async fn legacy_mode_reads_gas_price_only() {
+    let (provider, mock) = Provider::mocked();
+    mock.push(gwei(7)).unwrap();
+    let price = current_gas_price_legacy(Arc::new(provider), NetworkGas::default(), GasPriceMode::Legacy)
+        .await
+        .unwrap();
+    assert_eq!(price, gwei(7));
+    // блок не запрашивался: единственный ответ ушёл на eth_gasPrice
+    mock.assert_request("eth_gasPrice", ()).unwrap();
+}
+
+#[tokio::test]
+async fn eip1559_mode_uses_base_fee() {
+    let (provider, mock) = Provider::mocked();
+    let block = Block::<TxHash> {
+        base_fee_per_gas: Some(gwei(10)),
+        ..Default::default()
+    };
+    mock.push(block).unwrap();
+    // оценка EIP-1559 без ответов падает → берём base * 1.0 + tip 2
+    let price = current_gas_price_legacy(Arc::new(provider), NetworkGas::default(), GasPriceMode::Eip1559)
+        .await
+        .unwrap();
+    assert_eq!(price, gwei(12));
+}
+
+#[test]
+fn mode_parsing() {
+    assert_eq!(GasPriceMode::parse("EIP1559").unwrap(), GasPriceMode::Eip1559);
+    assert_eq!(GasPriceMode::parse("auto").unwrap(), GasPriceMode::Eip1559);
+    assert_eq!(GasPriceMode::parse("legacy").unwrap(), GasPriceMode::Legacy);
+    assert!(GasPriceMode::parse("fast").is_err());
+}
@@ -0,0 +1,34 @@
+use DeFiArbitraje::utils_gas::{L1FeeModel, l1_fee_tx_bytes, wei_to_native};
+use crate::common;
+use ethers::types::{Address, Bytes, U256};
+use ethers::utils::rlp::Rlp;
+
+#[test]
+fn fee_model_follows_chain() {
+    assert_eq!(L1FeeModel::for_chain(42161), L1FeeModel::Arbitrum);
+    assert_eq!(L1FeeModel::for_chain(10), L1FeeModel::OpStack);
+    assert_eq!(L1FeeModel::for_chain(8453), L1FeeModel::OpStack);
+}
+
+#[test]
+fn l1_fee_input_is_typed_tx_with_route_data() {
+    let data = Bytes::from(vec![0xab; 200]);
+    let tx = l1_fee_tx_bytes(10, Address::zero(), data.clone(), 300_000);
+    // EIP-1559: тип 0x02, затем RLP-список полей
+    assert_eq!(tx[0], 0x02);
+    let fields = Rlp::new(&tx[1..]);
+    assert_eq!(fields.val_at::<U256>(0).unwrap(), U256::from(10));
+    assert_eq!(fields.val_at::<Vec<u8>>(7).unwrap(), data.to_vec());
+    // больше calldata — больше байтов для L1
+    let longer = l1_fee_tx_bytes(10, Address::zero(), Bytes::from(vec![0xab; 400]), 300_000);
+    assert!(longer.len() - tx.len() >= 200);
+}
+
+#[test]
+fn l2_networks_enable_data_fees() {
+    let cfg = common::config();
+    let enabled = |id: u64| cfg.networks.iter().find(|n| n.chain_id == id).unwrap().l2_data_fees;
+    assert!(enabled(10) && enabled(8453) && enabled(42161));
+    assert!(!enabled(56) && !enabled(137));
+    assert!((wei_to_native(U256::exp10(15)) - 0.001).abs() < 1e-15);
+}
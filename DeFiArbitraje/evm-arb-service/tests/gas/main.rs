@@ -0,0 +1,11 @@
+//! Газ (`utils_gas`): режимы цены, потолки, L2 data fee, PnL в USD
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod gas_cap;
+mod gas_guard;
+mod gas_price_mode;
+mod l2_data_fee;
+mod pnl_usd;
+mod token_gas;
@@ -0,0 +1,43 @@
+use DeFiArbitraje::utils_gas::{native_to_usd, net_pnl_usd};
+use crate::common;
+
+#[test]
+fn native_profit_converts_at_native_price() {
+    assert_eq!(native_to_usd(0.5, 3000.0), 1500.0);
+    // 0.01 ETH прибыли, 0.002 ETH газа, ETH = 2500 → 20 USD
+    let pnl = net_pnl_usd(0.01, Some(2500.0), 0.002, Some(2500.0)).unwrap();
+    assert!((pnl - 20.0).abs() < 1e-9);
+}
+
+#[test]
+fn stable_profit_uses_token_price_not_native() {
+    let mut net = common::network(8453);
+    net.native_usd_hint = Some(2000.0);
+    // USDC в risk.stables → цена 1.0 без явного usd_price
+    assert_eq!(net.token_usd_price("usdc"), Some(1.0));
+    assert_eq!(net.token_usd_price("WETH"), Some(2000.0));
+    // 12 USDC прибыли минус 0.001 ETH газа (2 USD) = 10 USD
+    let pnl = net_pnl_usd(12.0, net.token_usd_price("USDC"), 0.001, net.native_usd_hint).unwrap();
+    assert!((pnl - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn missing_price_is_unknown_not_zero() {
+    assert_eq!(net_pnl_usd(5.0, None, 0.001, Some(2000.0)), None);
+    assert_eq!(net_pnl_usd(5.0, Some(1.0), 0.001, None), None);
+    let mut net = common::network(8453);
+    net.tokens.get_mut("AERO").unwrap().usd_price = Some(0.8);
+    assert_eq!(net.token_usd_price("AERO"), Some(0.8));
+}
+
+#[test]
+fn stable_round_trip_profit_converts_to_native() {
+    use DeFiArbitraje::utils_gas::token_to_native;
+    let net = common::network(8453);
+    assert_eq!(token_to_native(&net, "WETH", 0.25, None, None), Some(0.25));
+    // без цены native стейбл в native не пересчитать
+    assert_eq!(token_to_native(&net, "USDC", 30.0, Some(1.0), None), None);
+    let native = token_to_native(&net, "USDC", 30.0, Some(1.0), Some(3000.0)).unwrap();
+    assert!((native - 0.01).abs() < 1e-12);
+    assert_eq!(token_to_native(&net, "CBBTC", 1.0, None, Some(3000.0)), None);
+}
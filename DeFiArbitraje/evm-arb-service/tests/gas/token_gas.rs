@@ -0,0 +1,38 @@
+use DeFiArbitraje::utils_gas::token_transfer_overhead;
+use crate::common;
+
+#[test]
+fn expensive_token_adds_extra_gas() {
+    let mut cfg = common::config();
+    let net = cfg.networks.iter_mut().find(|n| n.chain_id == 8453).unwrap();
+
+    let hops = [("WETH", "AERO"), ("AERO", "WETH")];
+    assert_eq!(token_transfer_overhead(net, &hops), 0);
+
+    net.tokens.get_mut("AERO").unwrap().extra_gas = Some(25_000);
+    // AERO участвует в двух transfer-ах: выход 1-го хопа и вход 2-го
+    assert_eq!(token_transfer_overhead(net, &hops), 50_000);
+
+    // маршрут без дорогого токена не меняется
+    assert_eq!(token_transfer_overhead(net, &[("WETH", "USDC"), ("USDC", "WETH")]), 0);
+}
+
+#[test]
+fn gas_hints_override_leg_gas_by_name_then_type() {
+    let mut cfg = common::config();
+    let arb = cfg.networks.iter().find(|n| n.chain_id == 42161).unwrap();
+    let v3 = arb.dexes.iter().find(|d| d.dex_type == "v3").unwrap();
+    assert_eq!(arb.leg_gas(v3, 140_000), 190_000);
+
+    let net = cfg.networks.iter_mut().find(|n| n.chain_id == 8453).unwrap();
+    let dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap().clone();
+    // без подсказок — встроенная оценка
+    assert_eq!(net.leg_gas(&dex, 140_000), 140_000);
+    net.gas_hints.insert("V3".into(), 170_000);
+    assert_eq!(net.leg_gas(&dex, 140_000), 170_000);
+    net.gas_hints.insert("uniswapv3".into(), 155_000);
+    assert_eq!(net.leg_gas(&dex, 140_000), 155_000);
+
+    net.gas_hints.insert("v2".into(), 0);
+    assert!(cfg.validate().is_err());
+}
@@ -0,0 +1,80 @@
+use DeFiArbitraje::hooks::{
+    ALERT_BACKOFF_MAX, AlertQueue, AlertSink, WebhookAlert, alert_backoff,
+};
+use DeFiArbitraje::metrics::METRIC_ALERTS_DROPPED;
+use crate::common::{self, http_stub};
+use hyper::{Body, Response, StatusCode};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Вебхук, который отвечает 503 на первые `failures` запросов и собирает доставленные тела
+async fn flaky_webhook(failures: u32) -> (SocketAddr, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let got = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::new(AtomicU32::new(0));
+    let log = got.clone();
+    let addr = http_stub(move |_, body| {
+        let mut resp = Response::new(Body::empty());
+        if seen.fetch_add(1, Ordering::SeqCst) < failures {
+            *resp.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+        } else {
+            log.lock().unwrap().push(serde_json::from_slice(body).unwrap());
+        }
+        resp
+    })
+    .await;
+    (addr, got)
+}
+
+#[test]
+fn full_queue_drops_oldest() {
+    let mut q = AlertQueue::new(2);
+    assert!(!q.push(serde_json::json!(1)));
+    assert!(!q.push(serde_json::json!(2)));
+    let (seq, body) = q.front().unwrap();
+    assert_eq!(body, 1);
+
+    // пока первый алерт в полёте, третий вытесняет его
+    assert!(q.push(serde_json::json!(3)));
+    assert_eq!(q.len(), 2);
+    // подтверждение вытесненного не снимает следующий
+    q.ack(seq);
+    assert_eq!(q.front().unwrap().1, 2);
+    let (seq, _) = q.front().unwrap();
+    q.ack(seq);
+    assert_eq!(q.front().unwrap().1, 3);
+}
+
+#[test]
+fn backoff_doubles_up_to_cap() {
+    let base = Duration::from_millis(500);
+    assert_eq!(alert_backoff(base, 0), base);
+    assert_eq!(alert_backoff(base, 2), Duration::from_secs(2));
+    assert_eq!(alert_backoff(base, 10), ALERT_BACKOFF_MAX);
+    assert_eq!(alert_backoff(base, 40), ALERT_BACKOFF_MAX);
+}
+
+#[tokio::test]
+async fn alert_survives_webhook_blip() {
+    let (addr, got) = flaky_webhook(2).await;
+    let mut alerts = common::config().telemetry.alerts;
+    alerts.webhook_url = Some(format!("http://{addr}"));
+    alerts.retry_backoff_ms = 10;
+    alerts.queue_size = 4;
+    let sink = WebhookAlert::new(&alerts);
+
+    let dropped = METRIC_ALERTS_DROPPED.get();
+    sink.alert(8453, "circuit_breaker", "3 убытка подряд").await;
+    for _ in 0..100 {
+        if !got.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let got = got.lock().unwrap();
+    assert_eq!(got.len(), 1, "alert not delivered after retries");
+    assert_eq!(got[0]["kind"], "circuit_breaker");
+    assert_eq!(got[0]["chain_id"], 8453);
+    assert_eq!(METRIC_ALERTS_DROPPED.get(), dropped);
+}
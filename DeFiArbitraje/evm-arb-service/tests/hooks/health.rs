@@ -0,0 +1,53 @@
+use DeFiArbitraje::metrics::{Readiness, scan_age, scan_is_fresh, scrape_stalled};
+use std::time::Duration;
+
+#[test]
+fn healthz_tracks_scan_staleness() {
+    let window = Duration::from_secs(60);
+    // сканов ещё не было — не готов
+    assert!(!scan_is_fresh(0, 1_000_000, window));
+    assert_eq!(scan_age(0, 1_000_000), None);
+
+    let last = 1_000_000;
+    assert!(scan_is_fresh(last, last + 59_000, window));
+    assert!(scan_is_fresh(last, last + 60_000, window));
+    assert!(!scan_is_fresh(last, last + 60_001, window));
+    assert_eq!(scan_age(last, last + 2_500), Some(Duration::from_millis(2_500)));
+    // часы ушли назад — возраст не отрицательный
+    assert_eq!(scan_age(last, last - 10), Some(Duration::ZERO));
+}
+
+#[test]
+fn readyz_waits_for_every_network() {
+    let mut r = Readiness::default();
+    // набор сетей ещё не объявлен — старт
+    assert!(!r.is_ready());
+    r.mark_ready(8453);
+    assert!(!r.is_ready());
+
+    r.expect([8453, 42161]);
+    assert!(!r.is_ready());
+    assert_eq!(r.pending(), vec![42161]);
+
+    r.mark_ready(42161);
+    assert!(r.is_ready());
+    assert!(r.pending().is_empty());
+}
+
+#[test]
+fn dead_mans_switch_engages_on_scrape_gap() {
+    let window = Duration::from_secs(120);
+    let started = 1_000_000;
+    // скрейпов ещё не было: грейс от старта движка
+    assert_eq!(scrape_stalled(0, started, started + 120_000, window), None);
+    assert_eq!(
+        scrape_stalled(0, started, started + 121_000, window),
+        Some(Duration::from_secs(121))
+    );
+
+    let scraped = started + 500_000;
+    assert_eq!(scrape_stalled(scraped, started, scraped + 30_000, window), None);
+    assert!(scrape_stalled(scraped, started, scraped + 180_000, window).is_some());
+    // скрейп возобновился — пауза снимается
+    assert_eq!(scrape_stalled(scraped + 179_000, started, scraped + 180_000, window), None);
+}
@@ -0,0 +1,8 @@
+//! Хуки и алерты (`hooks`), /healthz
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod alert_delivery;
+mod health;
+mod pre_send_hook;
@@ -0,0 +1,144 @@
+use DeFiArbitraje::hooks::{Decision, NoopHook, PreSendHook, TradeContext, send_through_hook};
+#[cfg(feature = "mempool")]
+use DeFiArbitraje::mempool::PendingSwap;
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::route::StrategyEngine;
+use crate::common::{self, v2_pair_call};
+use async_trait::async_trait;
+use ethers::types::{Address, U256};
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+
+struct Fixed(Decision);
+
+#[async_trait]
+impl PreSendHook for Fixed {
+    async fn should_send(&self, _ctx: &TradeContext) -> Decision {
+        self.0.clone()
+    }
+}
+
+fn ctx() -> TradeContext {
+    TradeContext {
+        chain_id: 8453,
+        route: "WETH-USDC".into(),
+        amount_in: U256::from(1_000u64),
+        amount_out: U256::from(1_010u64),
+        gas_estimate: 250_000,
+        pnl_usd: 1.5,
+    }
+}
+
+#[tokio::test]
+async fn vetoed_trade_is_not_sent() {
+    let sends = AtomicUsize::new(0);
+    let res = send_through_hook(&Fixed(Decision::Skip), &ctx(), |_, _| async {
+        sends.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert!(res.is_none());
+    assert_eq!(sends.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn noop_hook_sends_original_size() {
+    let res = send_through_hook(&NoopHook, &ctx(), |a, b| async move { Ok((a, b)) })
+        .await
+        .unwrap();
+    assert_eq!(res, Some((U256::from(1_000u64), U256::from(1_010u64))));
+}
+
+#[tokio::test]
+async fn modify_size_scales_amount_out() {
+    let hook = Fixed(Decision::ModifySize(U256::from(500u64)));
+    let res = send_through_hook(&hook, &ctx(), |a, b| async move { Ok((a, b)) })
+        .await
+        .unwrap();
+    assert_eq!(res, Some((U256::from(500u64), U256::from(505u64))));
+}
+
+/// Сеть движковых тестов хука: свой chain id, чтобы EXECUTOR_<id> не задевал Base
+const CHAIN: u64 = 31337;
+const EXECUTOR: &str = "0x00000000000000000000000000000000000000e0";
+
+/// Хук-наблюдатель: запоминает маршруты и ничего не отправляет
+#[derive(Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+#[async_trait]
+impl PreSendHook for Recorder {
+    async fn should_send(&self, ctx: &TradeContext) -> Decision {
+        self.0.lock().unwrap().push(ctx.route.clone());
+        Decision::Skip
+    }
+}
+
+/// Движок с экзекутором над v2-рынком заглушки: simulate() экзекутора всегда в плюсе
+async fn hooked_engine() -> (StrategyEngine, Arc<Recorder>) {
+    static ENV: Once = Once::new();
+    ENV.call_once(|| unsafe {
+        std::env::set_var(format!("EXECUTOR_{CHAIN}"), EXECUTOR);
+        std::env::set_var(
+            format!("PRIVATE_KEY_{CHAIN}"),
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        );
+    });
+    let mut cfg = common::single_network(8453);
+    let market = common::v2_market(&mut cfg);
+    cfg.global.execution.approve_spend_on_start = false;
+    cfg.global.execution.fork_sim.enabled = false;
+    cfg.networks[0].chain_id = CHAIN;
+    cfg.networks[0].safe_address = None;
+    let executor: Address = EXECUTOR.parse().unwrap();
+    let pairs = market.pairs;
+    let rpc = common::rpc_stub(move |call| match call["method"].as_str().unwrap() {
+        "eth_chainId" => Ok(json!(format!("{CHAIN:#x}"))),
+        "eth_call" => {
+            let tx = &call["params"][0];
+            if tx["to"].as_str().and_then(|to| to.parse().ok()) == Some(executor) {
+                return Ok(json!(format!("0x{:064x}", U256::exp10(18))));
+            }
+            v2_pair_call(&pairs, tx).map(Value::from).ok_or_else(common::reverted)
+        }
+        _ => Ok(json!("0x1")),
+    })
+    .await;
+    cfg.networks[0].rpc = vec![format!("http://{rpc}")];
+
+    let chains = Arc::new(MultiChain::from_config(&cfg).await.expect("clients"));
+    chains.clients[&CHAIN].prewarm_pools(market.pools);
+    let mut engine = common::engine_on(&cfg, chains).await.expect("engine");
+    assert!(engine.scan_only_networks().is_empty(), "{:?}", engine.scan_only_networks());
+    let hook = Arc::new(Recorder::default());
+    engine.set_pre_send_hook(hook.clone());
+    (engine, hook)
+}
+
+#[tokio::test]
+async fn triangle_goes_through_the_hook() {
+    let (mut engine, hook) = hooked_engine().await;
+    engine.scan_and_execute().await.expect("scan");
+    // треугольник прибыльнее пары и забирает общее ребро: хук видит только его
+    assert_eq!(*hook.0.lock().unwrap(), ["WETH-USDC-USDBC"]);
+}
+
+#[cfg(feature = "mempool")]
+#[tokio::test]
+async fn mempool_trigger_goes_through_the_hook() {
+    let (engine, hook) = hooked_engine().await;
+    // токены рынка — из Base, сеть которой тест переименовал в CHAIN
+    let net = &common::network(8453);
+    let swap = PendingSwap {
+        tx: ethers::types::H256::zero(),
+        chain_id: CHAIN,
+        dex: "V2A".into(),
+        token_in: common::token(net, "USDC"),
+        token_out: common::token(net, "WETH"),
+        amount_in: U256::exp10(12),
+    };
+    engine.scan_triggered(&swap).await.expect("scan");
+    assert_eq!(*hook.0.lock().unwrap(), ["WETH-USDC"]);
+}
@@ -0,0 +1,33 @@
+use DeFiArbitraje::network::{MultiChain, is_block_lag_error};
+use crate::common;
+use anyhow::anyhow;
+use ethers::types::Address;
+
+#[test]
+fn lagging_endpoint_errors_are_detected() {
+    assert!(is_block_lag_error(&anyhow!("(code: -32000, message: header not found, data: None)")));
+    assert!(is_block_lag_error(&anyhow!("Unknown block number")));
+    assert!(is_block_lag_error(&anyhow!("block not found").context("getReserves")));
+    assert!(!is_block_lag_error(&anyhow!("execution reverted")));
+}
+
+#[tokio::test]
+async fn pinned_client_keeps_shared_state() {
+    let cfg = common::single_network(8453);
+    let chains = MultiChain::from_config(&cfg).await.expect("clients");
+    let client = &chains.clients[&8453];
+    assert_eq!(client.pinned_block(), None);
+
+    let pinned = client.pinned_at(19_000_000);
+    assert_eq!(pinned.pinned_block(), Some(19_000_000));
+    assert_eq!(client.pinned_block(), None);
+    // кэш пулов общий: предзагрузка через закреплённую копию видна исходному клиенту
+    let a = Address::from_low_u64_be;
+    let key = (a(1), a(2), a(3), 0);
+    pinned.prewarm_pools([(key, a(4))]);
+    let addr = client
+        .pool_address("getPool", key, |_p| async { Err::<Address, _>(anyhow!("no rpc")) })
+        .await
+        .unwrap();
+    assert_eq!(addr, a(4));
+}
@@ -0,0 +1,15 @@
+//! RPC-клиенты сетей (`network`): выбор и проверка эндпоинтов, ретраи, батчи, кэш пулов
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod block_pin;
+mod pool_cache;
+mod pools_file;
+mod rpc_batch;
+mod rpc_budget;
+mod rpc_rate_limit;
+mod rpc_retry;
+mod rpc_selection;
+mod validate_endpoints;
+mod ws_endpoints;
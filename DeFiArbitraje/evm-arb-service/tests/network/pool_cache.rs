@@ -0,0 +1,32 @@
+use DeFiArbitraje::network::PoolCache;
+use ethers::types::Address;
+use std::time::Duration;
+
+fn key(n: u64) -> (Address, Address, Address, u32) {
+    (
+        Address::from_low_u64_be(1),
+        Address::from_low_u64_be(2),
+        Address::from_low_u64_be(3),
+        n as u32,
+    )
+}
+
+#[test]
+fn found_pool_is_cached_forever() {
+    let cache = PoolCache::new(Duration::from_millis(0));
+    let pool = Address::from_low_u64_be(42);
+    assert_eq!(cache.get(&key(3000)), None);
+    cache.insert(key(3000), pool);
+    assert_eq!(cache.get(&key(3000)), Some(pool));
+    assert_eq!(cache.get(&key(500)), None);
+    assert!((cache.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn missing_pool_expires_after_negative_ttl() {
+    let cache = PoolCache::new(Duration::from_millis(20));
+    cache.insert(key(0), Address::zero());
+    assert_eq!(cache.get(&key(0)), Some(Address::zero()));
+    std::thread::sleep(Duration::from_millis(30));
+    assert_eq!(cache.get(&key(0)), None);
+}
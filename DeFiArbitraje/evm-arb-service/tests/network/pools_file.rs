@@ -0,0 +1,84 @@
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::pools::PoolsFile;
+use crate::common;
+use ethers::types::Address;
+
+const FACTORY_V2: &str = "0x8909dc15e40173ff4699343b6eb8132c65e18ec6";
+const FACTORY_V3: &str = "0x33128a8fc17869897dce68ed026d694621f6fdfd";
+const WETH: &str = "0x4200000000000000000000000000000000000006";
+const USDC: &str = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913";
+
+// фрагмент реального вывода pool-discovery-cli (с резервами/slot0, которые движок игнорирует)
+fn discovery_output() -> String {
+    format!(
+        r#"{{
+  "generated_at": "2025-01-01T00:00:00Z",
+  "networks": [{{
+    "chain_id": 8453, "name": "base",
+    "dexes": [
+      {{ "type": "v2", "name": "UniV2", "factory": "{FACTORY_V2}", "pairs": [
+        {{ "pair": ["WETH", "USDC"], "address": "0x00000000000000000000000000000000000000a1",
+           "token0": "{WETH}", "token1": "{USDC}", "reserves0": "1", "reserves1": "2",
+           "decimals0": 18, "decimals1": 6, "suggested_amount_token0": "0", "suggested_amount_token1": "0" }} ] }},
+      {{ "type": "v3", "name": "UniswapV3", "factory": "{FACTORY_V3}", "pools": [
+        {{ "pair": ["WETH", "USDC"], "fee": 500, "address": "0x00000000000000000000000000000000000000b2",
+           "token0": "{WETH}", "token1": "{USDC}", "sqrt_price_x96": "1", "tick": 0, "liquidity": "1", "note": "" }} ] }}
+    ]
+  }}]
+}}"#
+    )
+}
+
+fn write_pools(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("defi-arb-pools-{}-{}", std::process::id(), name));
+    std::fs::write(&path, discovery_output()).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn a(s: &str) -> Address {
+    s.parse().unwrap()
+}
+
+#[test]
+fn discovery_output_maps_to_cache_keys_both_directions() {
+    let pools = PoolsFile::load(&write_pools("keys.json")).expect("load pools");
+    let entries = pools.cache_entries(8453).unwrap();
+    assert_eq!(entries.len(), 4);
+    let v3 = a("0x00000000000000000000000000000000000000b2");
+    assert!(entries.contains(&((a(FACTORY_V3), a(USDC), a(WETH), 500), v3)));
+    assert!(entries.contains(&((a(FACTORY_V3), a(WETH), a(USDC), 500), v3)));
+    assert!(pools.cache_entries(1).unwrap().is_empty());
+}
+
+#[test]
+fn pools_file_age_from_generated_at() {
+    let mut pools = PoolsFile::load(&write_pools("age.json")).expect("load pools");
+    // 2025-01-01T00:00:00Z
+    let generated = 1_735_689_600;
+    assert_eq!(pools.age_secs(generated + 90), Some(90));
+    pools.generated_at = "2025-01-01T03:00:00.123456+03:00".into();
+    assert_eq!(pools.age_secs(generated), Some(0));
+    pools.generated_at = "2024-02-29T23:59:59Z".into();
+    assert_eq!(pools.age_secs(generated), Some(generated - 1_709_251_199));
+    for bad in ["", "2025-01-01", "2025-13-01T00:00:00Z", "2025-01-01T00:00:00"] {
+        pools.generated_at = bad.into();
+        assert_eq!(pools.age_secs(generated), None, "{bad}");
+    }
+}
+
+#[tokio::test]
+async fn prewarmed_pool_skips_factory_lookup() {
+    let mut cfg = common::config();
+    cfg.pools_file = Some(write_pools("prewarm.json"));
+    let chains = MultiChain::from_config(&cfg).await.expect("clients");
+    let client = &chains.clients[&8453];
+
+    let key = (a(FACTORY_V2), a(USDC), a(WETH), 0);
+    let addr = client
+        .pool_address("getPair", key, |_| async {
+            Err(anyhow::anyhow!("factory must not be called"))
+        })
+        .await
+        .expect("cached pool");
+    assert_eq!(addr, a("0x00000000000000000000000000000000000000a1"));
+}
@@ -0,0 +1,125 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::network::{
+    MultiChain, ScanReads, batch_request_body, parse_batch_response, scan_reads_from_batch,
+};
+use DeFiArbitraje::utils_gas::GasPriceMode;
+use crate::common::{self, http_stub};
+use ethers::providers::HttpClientError;
+use ethers::types::U256;
+use hyper::{Body, Response, StatusCode};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+const GWEI: u64 = 1_000_000_000;
+
+fn fixture() -> Config {
+    let mut cfg = common::single_network(8453);
+    cfg.rpc.retry_base_delay_ms = 1;
+    cfg.rpc.retry_max_delay_ms = 5;
+    cfg.rpc.max_block_lag = 0;
+    cfg
+}
+
+/// JSON-RPC узел: `healthy = false` — 503 на всё; иначе отвечает на батч
+/// (в обратном порядке) и запоминает тела запросов
+async fn node(healthy: bool) -> (SocketAddr, Arc<Mutex<Vec<Value>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    let addr = http_stub(move |_, body| {
+        let calls: Value = serde_json::from_slice(body).unwrap();
+        log.lock().unwrap().push(calls.clone());
+        if !healthy {
+            let mut resp = Response::new(Body::empty());
+            *resp.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+            return resp;
+        }
+        let replies: Vec<Value> = calls
+            .as_array()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|c| {
+                let result = match c["method"].as_str().unwrap() {
+                    "eth_blockNumber" => json!("0x10"),
+                    "eth_gasPrice" => json!(format!("{:#x}", 7 * GWEI)),
+                    _ => json!({ "baseFeePerGas": format!("{:#x}", GWEI) }),
+                };
+                json!({ "jsonrpc": "2.0", "id": c["id"], "result": result })
+            })
+            .collect();
+        Response::new(Body::from(Value::from(replies).to_string()))
+    })
+    .await;
+    (addr, seen)
+}
+
+#[test]
+fn batch_replies_matched_by_id() {
+    let body = batch_request_body(&[("eth_blockNumber", json!([])), ("eth_gasPrice", json!([]))]);
+    assert_eq!(body[1]["id"], 1);
+    assert_eq!(body[1]["method"], "eth_gasPrice");
+
+    let reply = json!([
+        { "jsonrpc": "2.0", "id": 1, "result": "0x2" },
+        { "jsonrpc": "2.0", "id": 0, "result": "0x1" },
+    ]);
+    assert_eq!(parse_batch_response(reply, 2).unwrap(), vec![json!("0x1"), json!("0x2")]);
+
+    // ответ не на все вызовы — сбой всего батча
+    let partial = json!([{ "jsonrpc": "2.0", "id": 0, "result": "0x1" }]);
+    assert!(parse_batch_response(partial, 2).is_err());
+
+    let failed = json!([
+        { "jsonrpc": "2.0", "id": 0, "result": "0x1" },
+        { "jsonrpc": "2.0", "id": 1, "error": { "code": -32005, "message": "limit exceeded" } },
+    ]);
+    match parse_batch_response(failed, 2) {
+        Err(HttpClientError::JsonRpcError(e)) => assert_eq!(e.code, -32005),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn scan_reads_gas_by_mode() {
+    let mut net = fixture().networks.remove(0);
+    net.gas.tip_gwei = 1.0;
+    net.gas.base_fee_multiplier = 2.0;
+    net.gas.max_fee_gwei = Some(5.0);
+    let replies = [
+        json!("0x10"),
+        json!(format!("{:#x}", 7 * GWEI)),
+        json!({ "baseFeePerGas": format!("{:#x}", GWEI) }),
+    ];
+
+    let r = scan_reads_from_batch(&replies, &net, GasPriceMode::Eip1559).unwrap();
+    // base 1 * 2 + tip 1
+    assert_eq!(r, ScanReads { block: 16, gas_price: U256::from(3 * GWEI) });
+    // legacy: eth_gasPrice 7 под потолком 5
+    let r = scan_reads_from_batch(&replies[..2], &net, GasPriceMode::Legacy).unwrap();
+    assert_eq!(r.gas_price, U256::from(5 * GWEI));
+}
+
+#[tokio::test]
+async fn batch_fails_over_as_a_whole() {
+    let (down, down_seen) = node(false).await;
+    let (up, up_seen) = node(true).await;
+    let mut cfg = fixture();
+    cfg.networks[0].rpc = vec![format!("http://{down}"), format!("http://{up}")];
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let client = &chains.clients[&8453];
+
+    let reads = client.scan_reads(GasPriceMode::Eip1559).await.unwrap();
+    assert_eq!(reads.block, 16);
+
+    // весь снимок — одним HTTP-запросом на каждый эндпоинт
+    assert_eq!(down_seen.lock().unwrap().len(), 1);
+    let up_seen = up_seen.lock().unwrap();
+    assert_eq!(up_seen.len(), 1);
+    assert_eq!(up_seen[0].as_array().unwrap().len(), 3);
+
+    let scoped = client.with_scan_reads(reads, true);
+    assert_eq!(scoped.pinned_block(), Some(16));
+    assert_eq!(scoped.scan_gas_price(), Some(reads.gas_price));
+    assert_eq!(client.with_scan_reads(reads, false).pinned_block(), None);
+}
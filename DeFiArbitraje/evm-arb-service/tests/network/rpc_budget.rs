@@ -0,0 +1,61 @@
+use DeFiArbitraje::network::{MultiChain, RpcBudget};
+use crate::common;
+use ethers::providers::ProviderError;
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn exec_proceeds_when_quote_budget_saturated() {
+    // 4 слота на сеть, 1 зарезервирован под исполнение
+    let budget = RpcBudget::new(4, 1);
+
+    let mut quotes = Vec::new();
+    for _ in 0..3 {
+        quotes.push(budget.acquire_quote().await);
+    }
+
+    // котировки упёрлись в свой лимит
+    let blocked = timeout(Duration::from_millis(50), budget.acquire_quote()).await;
+    assert!(blocked.is_err(), "quote must wait for a free slot");
+
+    // а исполнение проходит сразу
+    let exec = timeout(Duration::from_millis(50), budget.acquire_exec()).await;
+    assert!(exec.is_ok(), "execution must not be starved by quotes");
+
+    // общий бюджет ограничен: второй exec ждёт
+    let exec2 = timeout(Duration::from_millis(50), budget.acquire_exec()).await;
+    assert!(exec2.is_err());
+
+    // освободили котировку → слот снова доступен
+    drop(quotes.pop());
+    let quote = timeout(Duration::from_millis(50), budget.acquire_quote()).await;
+    assert!(quote.is_ok());
+}
+
+#[tokio::test]
+async fn client_exec_calls_bypass_saturated_quotes() {
+    let mut cfg = common::single_network(8453);
+    cfg.rpc.budget_total = 3;
+    cfg.rpc.budget_exec_reserved = 1;
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let client = &chains.clients[&8453];
+
+    // котировки скана заняли всю свою квоту (2 слота)
+    let budget = client.budget();
+    let _quotes = (budget.acquire_quote().await, budget.acquire_quote().await);
+    let call = || async { Ok::<_, ProviderError>(7u64) };
+
+    let quote = timeout(Duration::from_millis(50), client.with_failover("q", |_p| call())).await;
+    assert!(quote.is_err(), "quote RPC must wait for the quote pool");
+
+    // receipt и чтения пути исполнения идут из резерва
+    let receipt = timeout(
+        Duration::from_millis(50),
+        client.with_failover_exec("getTransactionReceipt", |_p| call()),
+    )
+    .await;
+    assert_eq!(receipt.expect("receipt must not wait for quotes").unwrap(), 7);
+    let exec = client.for_exec();
+    let read = timeout(Duration::from_millis(50), exec.with_failover("price", |_p| call())).await;
+    assert_eq!(read.expect("exec client must not wait for quotes").unwrap(), 7);
+}
@@ -0,0 +1,59 @@
+use DeFiArbitraje::network::{EndpointHealth, TokenBucket, is_rate_limited};
+use ethers::providers::{HttpClientError, JsonRpcError, ProviderError};
+use std::time::{Duration, Instant};
+
+#[test]
+fn token_bucket_throttles_after_burst() {
+    let now = Instant::now();
+    let mut b = TokenBucket::new(10.0, 2, now);
+    assert!(b.try_take(now).is_ok());
+    assert!(b.try_take(now).is_ok());
+    let wait = b.try_take(now).unwrap_err();
+    assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+
+    // через 100 мс накапливается ровно один токен
+    let later = now + Duration::from_millis(100);
+    assert!(b.try_take(later).is_ok());
+    assert!(b.try_take(later).is_err());
+}
+
+#[test]
+fn zero_rate_means_unlimited() {
+    let now = Instant::now();
+    let mut b = TokenBucket::new(0.0, 0, now);
+    for _ in 0..1000 {
+        assert!(b.try_take(now).is_ok());
+    }
+}
+
+fn rpc_error(code: i64, message: &str) -> anyhow::Error {
+    let err = JsonRpcError { code, message: message.into(), data: None };
+    ProviderError::JsonRpcClientError(Box::new(HttpClientError::JsonRpcError(err))).into()
+}
+
+#[test]
+fn detects_rate_limit_errors() {
+    assert!(is_rate_limited(&rpc_error(-32005, "daily request limit exceeded")));
+    assert!(is_rate_limited(&rpc_error(429, "Too Many Requests")));
+    // с контекстом из with_failover
+    assert!(is_rate_limited(&rpc_error(-32005, "limit exceeded").context("chain 8453")));
+    assert!(!is_rate_limited(&rpc_error(3, "execution reverted")));
+    // только статус / код: текст ошибки не в счёт
+    assert!(!is_rate_limited(&rpc_error(-32000, "header not found (block 429)")));
+    assert!(!is_rate_limited(&anyhow::anyhow!("HTTP status 429 Too Many Requests")));
+}
+
+#[test]
+fn cool_down_does_not_shorten_quarantine() {
+    let now = Instant::now();
+    let mut h = EndpointHealth::default();
+    h.on_failure(now, Duration::from_secs(60), Duration::from_secs(60));
+    h.cool_down(now, Duration::from_secs(10));
+    assert_eq!(h.quarantined_until, Some(now + Duration::from_secs(60)));
+
+    let mut h = EndpointHealth::default();
+    h.cool_down(now, Duration::from_secs(10));
+    assert!(!h.is_available(now + Duration::from_secs(5)));
+    assert!(h.is_available(now + Duration::from_secs(10)));
+    assert_eq!(h.fails, 0);
+}
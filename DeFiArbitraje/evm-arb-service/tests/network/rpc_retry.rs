@@ -0,0 +1,91 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::metrics::{METRIC_RPC_CALLS, METRIC_RPC_FAILOVERS};
+use DeFiArbitraje::network::{MultiChain, retry_delay};
+use crate::common;
+use ethers::providers::{HttpClientError, ProviderError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn fixture() -> Config {
+    let mut cfg = common::config();
+    cfg.rpc.retry_base_delay_ms = 1;
+    cfg.rpc.retry_max_delay_ms = 5;
+    cfg.rpc.retry_jitter = 0.0;
+    // без сети проверка высоты после failover только тратит время на таймауты
+    cfg.rpc.max_block_lag = 0;
+    cfg
+}
+
+fn transient() -> ProviderError {
+    let err = serde_json::from_str::<u8>("<html>502</html>").unwrap_err();
+    ProviderError::JsonRpcClientError(Box::new(HttpClientError::SerdeJson {
+        err,
+        text: "bad gateway".into(),
+    }))
+}
+
+#[tokio::test]
+async fn failover_retries_then_succeeds() {
+    let chains = MultiChain::from_config(&fixture()).await.unwrap();
+    let client = &chains.clients[&8453];
+    let calls = AtomicUsize::new(0);
+
+    let before = METRIC_RPC_CALLS
+        .with_label_values(&["8453", "retry_then_ok"])
+        .get();
+    let out = client
+        .with_failover("retry_then_ok", |_p| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move { if n < 2 { Err(transient()) } else { Ok(42u64) } }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(out, 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    let after = METRIC_RPC_CALLS
+        .with_label_values(&["8453", "retry_then_ok"])
+        .get();
+    assert_eq!(after - before, 3.0);
+    assert!(METRIC_RPC_FAILOVERS.with_label_values(&["8453"]).get() >= 2.0);
+}
+
+#[tokio::test]
+async fn failover_cycles_endpoints_and_lists_failures() {
+    let mut cfg = fixture();
+    cfg.rpc.retry_cycles = 2;
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let client = &chains.clients[&8453];
+    let calls = AtomicUsize::new(0);
+
+    let err = client
+        .with_failover("always_fails", |_p| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err::<u64, _>(transient()) }
+        })
+        .await
+        .unwrap_err();
+
+    // 3 эндпоинта * 2 цикла
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    let msg = format!("{err:#}");
+    assert!(msg.contains("base-rpc.publicnode.com"), "{msg}");
+    assert!(msg.contains("mainnet.base.org"), "{msg}");
+}
+
+#[test]
+fn retry_delay_grows_and_caps() {
+    let mut rpc = fixture().rpc;
+    rpc.retry_base_delay_ms = 100;
+    rpc.retry_max_delay_ms = 1_000;
+    rpc.retry_multiplier = 2.0;
+    assert_eq!(retry_delay(&rpc, 0), Duration::from_millis(100));
+    assert_eq!(retry_delay(&rpc, 2), Duration::from_millis(400));
+    assert_eq!(retry_delay(&rpc, 10), Duration::from_millis(1_000));
+
+    rpc.retry_jitter = 0.5;
+    for _ in 0..50 {
+        let d = retry_delay(&rpc, 1).as_millis();
+        assert!((100..=300).contains(&d), "{d}");
+    }
+}
@@ -0,0 +1,62 @@
+use DeFiArbitraje::network::{EndpointHealth, lags_behind, pick_lowest_latency};
+use std::time::{Duration, Instant};
+
+#[test]
+fn picks_lowest_latency_healthy_endpoint() {
+    let now = Instant::now();
+    let mut h = vec![EndpointHealth::default(); 3];
+    h[0].on_success(120.0);
+    h[1].on_success(35.0);
+    h[2].on_success(80.0);
+    assert_eq!(pick_lowest_latency(&h, now), Some(1));
+
+    // самый быстрый упал → карантин, выбираем следующий по скорости
+    h[1].on_failure(now, Duration::from_secs(5), Duration::from_secs(60));
+    assert_eq!(pick_lowest_latency(&h, now), Some(2));
+
+    // после карантина снова доступен (но без замера — в конце очереди)
+    let later = now + Duration::from_secs(6);
+    assert!(h[1].is_available(later));
+    assert_eq!(pick_lowest_latency(&h, later), Some(2));
+}
+
+#[test]
+fn quarantine_backoff_is_exponential_and_capped() {
+    let now = Instant::now();
+    let base = Duration::from_secs(5);
+    let max = Duration::from_secs(30);
+    let mut h = EndpointHealth::default();
+
+    h.on_failure(now, base, max);
+    assert_eq!(h.quarantined_until, Some(now + Duration::from_secs(5)));
+    h.on_failure(now, base, max);
+    assert_eq!(h.quarantined_until, Some(now + Duration::from_secs(10)));
+    h.on_failure(now, base, max);
+    assert_eq!(h.quarantined_until, Some(now + Duration::from_secs(20)));
+    h.on_failure(now, base, max);
+    assert_eq!(h.quarantined_until, Some(now + max));
+
+    h.on_success(10.0);
+    assert_eq!(h.fails, 0);
+    assert!(h.is_available(now));
+}
+
+#[test]
+fn no_available_endpoints() {
+    let now = Instant::now();
+    let mut h = vec![EndpointHealth::default(); 2];
+    for e in h.iter_mut() {
+        e.on_failure(now, Duration::from_secs(5), Duration::from_secs(60));
+    }
+    assert_eq!(pick_lowest_latency(&h, now), None);
+}
+
+#[test]
+fn lagging_endpoint_beyond_max_lag() {
+    assert!(!lags_behind(1_000, 997, 3));
+    assert!(lags_behind(1_000, 996, 3));
+    // эндпоинт впереди лучшей высоты не отстаёт
+    assert!(!lags_behind(1_000, 1_005, 3));
+    // 0 — проверка выключена
+    assert!(!lags_behind(1_000, 10, 0));
+}
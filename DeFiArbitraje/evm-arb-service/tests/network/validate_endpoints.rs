@@ -0,0 +1,59 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::network::{EndpointStatus, MultiChain, endpoint_status, probe_endpoint};
+use crate::common;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// JSON-RPC заглушка: на любой запрос отвечает `eth_chainId` = `chain_id`
+async fn chain_stub(chain_id: u64) -> SocketAddr {
+    common::rpc_stub(move |_| Ok(format!("{chain_id:#x}").into())).await
+}
+
+#[test]
+fn status_from_chain_id_answer() {
+    assert!(endpoint_status(8453, Ok(8453), 12.0).is_ok());
+    assert_eq!(endpoint_status(8453, Ok(1), 12.0), EndpointStatus::WrongChain { got: 1 });
+    assert_eq!(
+        endpoint_status(8453, Err("refused".into()), 12.0),
+        EndpointStatus::Unreachable("refused".into())
+    );
+}
+
+#[tokio::test]
+async fn probe_catches_wrong_chain_and_dead_endpoint() {
+    let timeout = Duration::from_secs(2);
+    let base = chain_stub(8453).await;
+    let url = format!("http://{base}");
+    assert!(probe_endpoint(8453, &url, timeout).await.is_ok());
+    assert_eq!(
+        probe_endpoint(42161, &url, timeout).await,
+        EndpointStatus::WrongChain { got: 8453 }
+    );
+
+    // порт, на котором никто не слушает
+    let dead = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let status = probe_endpoint(8453, &format!("http://{dead}"), timeout).await;
+    assert!(matches!(status, EndpointStatus::Unreachable(_)), "{status:?}");
+}
+
+/// Фикстура с одной сетью, RPC которой — заглушка с chain id `served` (None — своим)
+async fn single_network(served: Option<u64>) -> Config {
+    let mut cfg = common::config();
+    cfg.networks.truncate(1);
+    let served = served.unwrap_or(cfg.networks[0].chain_id);
+    cfg.networks[0].rpc = vec![format!("http://{}", chain_stub(served).await)];
+    cfg
+}
+
+#[tokio::test]
+async fn startup_rejects_rpc_serving_another_chain() {
+    let cfg = single_network(None).await;
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    chains.verify_chain_ids().await.unwrap();
+
+    // mainnet-RPC, вставленный в запись другой сети
+    let cfg = single_network(Some(1)).await;
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let err = chains.verify_chain_ids().await.unwrap_err();
+    assert!(err.to_string().contains("serves chain_id 1"), "{err}");
+}
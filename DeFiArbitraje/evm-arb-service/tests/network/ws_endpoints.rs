@@ -0,0 +1,27 @@
+use DeFiArbitraje::network::{MultiChain, is_ws_url};
+use crate::common;
+
+#[test]
+fn detects_ws_scheme() {
+    assert!(is_ws_url("wss://base-rpc.publicnode.com"));
+    assert!(is_ws_url("WS://localhost:8546"));
+    assert!(!is_ws_url("https://mainnet.base.org"));
+}
+
+#[tokio::test]
+async fn http_only_networks_have_no_subscription() {
+    let chains = MultiChain::from_config(&common::config()).await.unwrap();
+    assert!(chains.subscribe_new_heads().is_none());
+}
+
+#[tokio::test]
+async fn ws_only_network_is_rejected() {
+    let mut cfg = common::config();
+    cfg.networks[0].rpc = vec!["wss://base-rpc.publicnode.com".into()];
+    assert!(MultiChain::from_config(&cfg).await.is_err());
+
+    cfg.networks[0]
+        .rpc
+        .push("https://base-rpc.publicnode.com".into());
+    assert!(MultiChain::from_config(&cfg).await.is_ok());
+}
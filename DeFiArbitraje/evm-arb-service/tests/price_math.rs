@@ -1,6 +1,5 @@
 use DeFiArbitraje::dex::amount_out_v2;
 use ethers::types::U256;
-use pretty_assertions::assert_eq;
 
 #[test]
 fn test_amount_out_v2_basic() {
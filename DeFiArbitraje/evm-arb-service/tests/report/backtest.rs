@@ -0,0 +1,72 @@
+use DeFiArbitraje::backtest::{
+    Backtest, Filters, Verdict, candidate_files, evaluate, read_candidates,
+};
+use crate::common;
+
+fn line(chain: u64, amount_in: u64, amount_out: u64, pnl: f64, priced: bool) -> String {
+    format!(
+        r#"{{"ts":1700000000,"chain_id":{chain},"pair_or_tri":"WETH-USDC","strategy":null,"legs":2,"routers":[],"amount_in":"{amount_in}","amount_out":"{amount_out}","gas_estimate":250000,"pnl_usd":{pnl},"pnl_usd_priced":{priced}}}"#
+    )
+}
+
+fn tmp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("defi-arb-bt-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn filters_decide_like_the_engine() {
+    let f = Some(Filters { slippage_bps: 50, min_profit_bps: 10 });
+    let c = |out: u64| serde_json::from_str(&line(8453, 10_000, out, 1.0, true)).unwrap();
+    // 10100 * (1 - 0.5%) = 10049.5 > 10000, прибыль 1% ≥ 0.1%
+    assert_eq!(evaluate(&c(10_100), f), Verdict::Execute);
+    // после слиппеджа меньше входа
+    assert_eq!(evaluate(&c(10_040), f), Verdict::BelowMinOut);
+    let strict = Some(Filters { slippage_bps: 0, min_profit_bps: 200 });
+    assert_eq!(evaluate(&c(10_100), strict), Verdict::BelowMinProfit);
+    assert_eq!(evaluate(&c(10_100), None), Verdict::Invalid);
+}
+
+#[test]
+fn summary_over_rotated_files() {
+    let dir = tmp_dir("files");
+    std::fs::write(
+        dir.join("candidates-8453.jsonl"),
+        [line(8453, 10_000, 10_100, 2.5, true), line(8453, 10_000, 10_010, 0.1, true)].join("\n"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("candidates-8453-1699990000.jsonl"),
+        format!("{}\nnot json\n", line(8453, 10_000, 10_200, 0.0, false)),
+    )
+    .unwrap();
+    std::fs::write(dir.join("candidates-42161.jsonl"), line(42161, 1, 2, 1.0, true)).unwrap();
+    std::fs::write(dir.join("pnl-summary.json"), "{}").unwrap();
+
+    let files = candidate_files(&dir, Some(8453)).unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(candidate_files(&dir, None).unwrap().len(), 3);
+
+    let cfg = common::config();
+    let filters = Some(Filters { slippage_bps: 50, min_profit_bps: 0 });
+    assert!(Filters::for_chain(&cfg, 8453).is_some());
+    assert!(Filters::for_chain(&cfg, 1).is_none());
+
+    let mut bt = Backtest::default();
+    let mut bad = 0;
+    for f in &files {
+        let (cands, b) = read_candidates(f).unwrap();
+        bad += b;
+        for c in &cands {
+            bt.add(c, evaluate(c, filters));
+        }
+    }
+    assert_eq!(bad, 1);
+    let s = &bt.chains[&8453];
+    assert_eq!((s.candidates, s.executed, s.below_min_out), (3, 2, 1));
+    assert_eq!(s.unpriced, 1);
+    assert!((s.pnl_usd - 2.5).abs() < 1e-9);
+    assert!(bt.table().lines().last().unwrap().starts_with("   total"));
+    std::fs::remove_dir_all(&dir).ok();
+}
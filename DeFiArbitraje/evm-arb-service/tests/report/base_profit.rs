@@ -0,0 +1,43 @@
+use DeFiArbitraje::config::Network;
+use DeFiArbitraje::report::{DailyPnl, SessionProfit, to_base_token};
+use crate::common;
+
+fn load() -> (Network, Vec<String>) {
+    let stables = common::config().global.risk.stables;
+    let mut net = common::network(8453);
+    net.base_token = Some("USDC".into());
+    net.native_usd_hint = Some(2000.0);
+    (net, stables)
+}
+
+#[test]
+fn converts_profit_to_base_token() {
+    let (net, stables) = load();
+    assert_eq!(to_base_token(&net, &stables, "USDC", 5.0), Some(5.0));
+    assert_eq!(to_base_token(&net, &stables, "WETH", 0.01), Some(20.0));
+    assert_eq!(to_base_token(&net, &stables, "USDBC", 3.0), Some(3.0));
+    // цены AERO в подсказках нет — пересчитать нельзя
+    assert_eq!(to_base_token(&net, &stables, "AERO", 1.0), None);
+}
+
+#[test]
+fn session_accumulates_trades_from_different_pairs() {
+    let (net, stables) = load();
+    let mut session = SessionProfit::default();
+
+    // WETH-USDC: +0.01 WETH, газ 0.0005 ETH → 20 - 1 = 19 USDC
+    assert_eq!(session.record(&net, &stables, "WETH", 0.01, 0.0005), Some(19.0));
+    // USDC-USDBC: +4 USDC, газ 0.0005 ETH → 3 USDC
+    assert_eq!(session.record(&net, &stables, "USDC", 4.0, 0.0005), Some(3.0));
+    // AERO без цены не учитывается
+    assert_eq!(session.record(&net, &stables, "AERO", 100.0, 0.0005), None);
+
+    let acc = session.get(8453).expect("base accumulator");
+    assert_eq!(acc.token, "USDC");
+    assert_eq!(acc.trades, 2);
+    assert!((acc.amount - 22.0).abs() < 1e-9);
+
+    let mut summary = DailyPnl::new(0).summary(1);
+    summary.session_profit_base = session.snapshot();
+    assert!((summary.session_profit_base[&8453].amount - 22.0).abs() < 1e-9);
+}
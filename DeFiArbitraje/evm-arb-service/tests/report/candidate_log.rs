@@ -0,0 +1,51 @@
+use DeFiArbitraje::config::CandidateLogCfg;
+use DeFiArbitraje::report::{append_candidate, rotation_due};
+
+fn cfg(dir: &str, max_bytes: u64) -> CandidateLogCfg {
+    let dir = std::env::temp_dir().join(format!("defi-arb-cand-{}-{}", std::process::id(), dir));
+    let _ = std::fs::remove_dir_all(&dir);
+    CandidateLogCfg {
+        enabled: true,
+        dir: dir.to_string_lossy().into_owned(),
+        max_bytes,
+        rotate_daily: true,
+    }
+}
+
+#[test]
+fn rotation_by_size_and_day() {
+    let c = cfg("due", 100);
+    let day = 20_000 * 86_400;
+    assert!(!rotation_due(&c, 50, 40, day, day + 10));
+    assert!(rotation_due(&c, 80, 40, day, day + 10));
+    // пустой файл не ротируем даже при длинной строке
+    assert!(!rotation_due(&c, 0, 400, day, day + 10));
+    assert!(rotation_due(&c, 10, 10, day - 1, day));
+    let unlimited = CandidateLogCfg { max_bytes: 0, rotate_daily: false, ..c };
+    assert!(!rotation_due(&unlimited, 1 << 40, 100, 0, day));
+}
+
+#[test]
+fn full_file_rolls_over_keeping_lines_intact() {
+    let c = cfg("roll", 64);
+    let line = r#"{"ts":1,"chain_id":8453,"pair_or_tri":"WETH-USDC"}"#;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    append_candidate(&c, 8453, line, now).unwrap();
+    append_candidate(&c, 8453, line, now).unwrap();
+
+    let mut files: Vec<_> = std::fs::read_dir(&c.dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files.len(), 2, "{files:?}");
+    assert_eq!(files[1], "candidates-8453.jsonl");
+    assert!(files[0].starts_with("candidates-8453-"));
+    for f in files {
+        let body = std::fs::read_to_string(std::path::Path::new(&c.dir).join(f)).unwrap();
+        assert_eq!(body, format!("{line}\n"));
+    }
+}
@@ -0,0 +1,102 @@
+use DeFiArbitraje::report::{DailyPnl, TradeFill};
+use DeFiArbitraje::route::settled_fill;
+use DeFiArbitraje::router::QuoteResult;
+use crate::common;
+use ethers::types::U256;
+
+fn fill(success: bool, gross: f64, gas: f64) -> TradeFill {
+    TradeFill {
+        success,
+        gross_native: gross,
+        gas_native: gas,
+        native_usd: Some(2_000.0),
+    }
+}
+
+#[test]
+fn daily_summary_totals() {
+    let mut day = DailyPnl::new(1_000);
+
+    // сутки фейковых сделок: 24 на Base (каждая 4-я — revert), 6 на Arbitrum
+    for i in 0..24 {
+        let ok = i % 4 != 0;
+        day.record(8453, &fill(ok, 0.01, 0.001));
+    }
+    for _ in 0..6 {
+        day.record(42161, &fill(true, 0.002, 0.0005));
+    }
+
+    let s = day.summary(1_000 + 86_400);
+    assert_eq!(s.period_start, 1_000);
+    assert_eq!(s.period_end, 87_400);
+    assert_eq!(s.total.trades, 30);
+    assert_eq!(s.total.successes, 24);
+    assert!((s.success_rate - 0.8).abs() < 1e-9);
+
+    let base = &s.chains[&8453];
+    assert_eq!(base.trades, 24);
+    assert!((base.gross_native - 0.18).abs() < 1e-9);
+    assert!((base.gas_native - 0.024).abs() < 1e-9);
+    assert!((base.net_native - 0.156).abs() < 1e-9);
+
+    // 0.18 + 0.012 = 0.192 ETH валовых, 0.024 + 0.003 = 0.027 ETH газа
+    assert!((s.total.gross_usd - 384.0).abs() < 1e-6);
+    assert!((s.total.gas_usd - 54.0).abs() < 1e-6);
+    assert!((s.total.net_usd - 330.0).abs() < 1e-6);
+
+    day.reset(87_400);
+    let s = day.summary(87_401);
+    assert_eq!(s.total.trades, 0);
+    assert_eq!(s.period_start, 87_400);
+}
+
+#[test]
+fn settled_trade_books_realized_profit() {
+    // котировка: 1000 → 1010, прибыль 0.01 native, газ 100k по 1 gwei
+    let qr = QuoteResult {
+        gas_estimate: 100_000,
+        gas_price: U256::exp10(9),
+        profit_native: 0.01,
+        ..common::quote(1_000, 1_010, Some(20.0))
+    };
+    let fill = settled_fill(&qr, qr.amount_in, Some(U256::from(1_005)), Some(2_000.0));
+    assert!(fill.success);
+    assert!((fill.gross_native - 0.005).abs() < 1e-12, "{fill:?}");
+    assert!((fill.gas_native - 0.0001).abs() < 1e-12);
+
+    // хук уменьшил вход вдвое: прибыль считается от фактического входа
+    let half = settled_fill(&qr, U256::from(500), Some(U256::from(505)), None);
+    assert!((half.gross_native - 0.005).abs() < 1e-12, "{half:?}");
+
+    // выход ниже входа — убыток, а не ноль
+    let loss = settled_fill(&qr, qr.amount_in, Some(U256::from(995)), None);
+    assert!(loss.success);
+    assert!((loss.gross_native + 0.005).abs() < 1e-12, "{loss:?}");
+
+    // revert: газ потрачен, прибыли нет
+    let reverted = settled_fill(&qr, qr.amount_in, None, Some(2_000.0));
+    assert!(!reverted.success);
+    assert_eq!(reverted.gross_native, 0.0);
+    assert!((reverted.gas_native - 0.0001).abs() < 1e-12);
+
+    let mut day = DailyPnl::new(0);
+    day.record(8453, &fill);
+    day.record(8453, &reverted);
+    let s = day.summary(1);
+    assert_eq!((s.total.trades, s.total.successes), (2, 1));
+    assert!((s.total.net_native - 0.0048).abs() < 1e-12);
+}
+
+#[test]
+fn settled_fill_above_u128() {
+    // суммы за пределами u128 не паникуют
+    let big = U256::from(u128::MAX) * 4;
+    let qr = QuoteResult {
+        amount_in: big,
+        amount_out: big * 2,
+        profit_native: 1.0,
+        ..common::quote(0, 0, None)
+    };
+    let fill = settled_fill(&qr, big, Some(big + big / 2), None);
+    assert!((fill.gross_native - 0.5).abs() < 1e-9, "{fill:?}");
+}
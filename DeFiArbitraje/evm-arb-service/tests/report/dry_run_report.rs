@@ -0,0 +1,37 @@
+use DeFiArbitraje::report::{DryRunCandidate, DryRunReport};
+
+fn cand(pair: &str, pnl: f64) -> DryRunCandidate {
+    DryRunCandidate {
+        pair: pair.into(),
+        strategy: Some("cross_dex_spread".into()),
+        legs: vec!["UniswapV3".into(), "AerodromeV2".into()],
+        amount_in: "1000".into(),
+        amount_out: "1010".into(),
+        pnl_usd: pnl,
+    }
+}
+
+#[test]
+fn report_keeps_top_n_and_totals() {
+    let dir = std::env::temp_dir().join(format!("defi-arb-dryrun-{}", std::process::id()));
+    let mut rep = DryRunReport::new(8453);
+    for _ in 0..5 {
+        rep.on_route();
+    }
+    rep.on_candidate(cand("WETH-USDC", 1.5));
+    rep.on_candidate(cand("WETH-USDBC", 4.0));
+    rep.on_candidate(cand("USDC-USDBC", 0.5));
+
+    let path = rep.finish(dir.to_str().unwrap(), 2).unwrap();
+    assert!(path.ends_with("dryrun-8453.json"));
+
+    let saved: DryRunReport =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved.routes_scanned, 5);
+    assert_eq!(saved.profitable, 3);
+    assert!((saved.total_pnl_usd - 6.0).abs() < 1e-9);
+    let pairs: Vec<_> = saved.top.iter().map(|c| c.pair.as_str()).collect();
+    assert_eq!(pairs, vec!["WETH-USDBC", "WETH-USDC"]);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,11 @@
+//! Отчёты (`report`, `paper`, `backtest`): кандидаты, PnL, бумажный журнал
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod backtest;
+mod base_profit;
+mod candidate_log;
+mod daily_pnl;
+mod dry_run_report;
+mod paper_ledger;
@@ -0,0 +1,91 @@
+use DeFiArbitraje::config::PaperTradingCfg;
+use DeFiArbitraje::metrics::{METRIC_PAPER_FILLS, METRIC_PAPER_PNL_USD};
+use DeFiArbitraje::paper::{JsonlLedger, PaperFill, PaperLedger, open_backend};
+use DeFiArbitraje::router::QuoteResult;
+use crate::common;
+use ethers::types::U256;
+use std::io::Write;
+
+fn quote(pnl_usd: Option<f64>) -> QuoteResult {
+    QuoteResult {
+        gas_estimate: 300_000,
+        gas_price: U256::from(2_000_000_000u64),
+        quote_block: Some(42),
+        ..common::quote(100_000_000, 100_400_000, pnl_usd)
+    }
+}
+
+fn ledger_path(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("defi-arb-paper-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("paper-ledger.jsonl")
+}
+
+#[test]
+fn fill_reuses_quote_data() {
+    let fill = PaperFill::from_quote(1_700_000_000, 8453, "WETH-USDC@A,B", Some("x"), &quote(None));
+    assert_eq!(fill.amount_out, "100400000");
+    assert_eq!(fill.gas_price, "2000000000");
+    // 300k газа по 2 gwei
+    assert!((fill.gas_native - 0.0006).abs() < 1e-12);
+    assert_eq!(fill.pnl_usd, None);
+    assert_eq!(fill.quote_block, Some(42));
+}
+
+#[test]
+fn totals_survive_restart() {
+    let path = ledger_path("restart");
+    let ledger = PaperLedger::new(Box::new(JsonlLedger::new(&path))).unwrap();
+    for (chain, pnl) in [(8453, Some(1.5)), (8453, None), (10, Some(-0.25)), (8453, Some(2.0))] {
+        let fill = PaperFill::from_quote(1, chain, "WETH-USDC@A,B", None, &quote(pnl));
+        ledger.record(&fill).unwrap();
+    }
+    assert_eq!(ledger.totals(8453).fills, 3);
+    assert_eq!(ledger.totals(8453).pnl_usd, 3.5);
+    assert_eq!(METRIC_PAPER_FILLS.with_label_values(&["8453"]).get(), 3.0);
+    assert_eq!(METRIC_PAPER_PNL_USD.with_label_values(&["10"]).get(), -0.25);
+
+    // падение посреди записи: оборванная строка пропускается
+    let mut f = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    write!(f, "{{\"ts\":1,\"chain_id\":8453,\"rou").unwrap();
+    drop(f);
+
+    let reopened = PaperLedger::new(Box::new(JsonlLedger::new(&path))).unwrap();
+    assert_eq!(reopened.totals(8453), ledger.totals(8453));
+    assert_eq!(reopened.totals(10).fills, 1);
+    assert_eq!(reopened.totals(1).fills, 0);
+}
+
+#[test]
+fn backend_is_chosen_by_config() {
+    let mut cfg = PaperTradingCfg {
+        path: ledger_path("backend").display().to_string(),
+        ..Default::default()
+    };
+    assert!(open_backend(&cfg).is_ok());
+    cfg.backend = "postgres".into();
+    assert!(open_backend(&cfg).is_err());
+
+    cfg.backend = "sqlite".into();
+    cfg.path = ledger_path("sqlite").with_extension("db").display().to_string();
+    // без feature `sqlite` — понятная ошибка старта, а не молчаливый jsonl
+    assert_eq!(open_backend(&cfg).is_ok(), cfg!(feature = "sqlite"));
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_backend_aggregates_totals() {
+    let path = ledger_path("sqlite-totals").with_extension("db");
+    let cfg = PaperTradingCfg {
+        backend: "sqlite".into(),
+        path: path.display().to_string(),
+        ..Default::default()
+    };
+    let ledger = PaperLedger::new(open_backend(&cfg).unwrap()).unwrap();
+    for pnl in [Some(1.0), None, Some(0.5)] {
+        ledger.record(&PaperFill::from_quote(1, 56, "WBNB-USDT@A,B", None, &quote(pnl))).unwrap();
+    }
+    let reopened = PaperLedger::new(open_backend(&cfg).unwrap()).unwrap();
+    assert_eq!(reopened.totals(56).fills, 3);
+    assert_eq!(reopened.totals(56).pnl_usd, 1.5);
+}
@@ -0,0 +1,26 @@
+use DeFiArbitraje::route::AdaptiveInterval;
+
+#[test]
+fn interval_shrinks_on_opportunities_and_grows_when_dry() {
+    let mut iv = AdaptiveInterval::new(1_500, 200, 10_000);
+    assert_eq!(iv.current_ms(), 1_500);
+
+    assert_eq!(iv.on_scan(3), 750);
+    assert_eq!(iv.on_scan(1), 375);
+    assert_eq!(iv.on_scan(1), 200);
+    // не ниже min
+    assert_eq!(iv.on_scan(5), 200);
+
+    // пустые сканы удлиняют в 1.5 раза до потолка
+    assert_eq!(iv.on_scan(0), 300);
+    for _ in 0..20 {
+        iv.on_scan(0);
+    }
+    assert_eq!(iv.current_ms(), 10_000);
+}
+
+#[test]
+fn base_interval_is_clamped() {
+    assert_eq!(AdaptiveInterval::new(50, 200, 10_000).current_ms(), 200);
+    assert_eq!(AdaptiveInterval::new(60_000, 200, 10_000).current_ms(), 10_000);
+}
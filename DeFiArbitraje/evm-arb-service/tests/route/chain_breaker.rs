@@ -0,0 +1,28 @@
+use DeFiArbitraje::route::{PnLTracker, record_scan_result};
+use std::collections::HashMap;
+
+#[test]
+fn losses_on_one_chain_do_not_cool_down_another() {
+    let mut trackers: HashMap<u64, PnLTracker> = HashMap::new();
+    record_scan_result(&mut trackers, 8453, false);
+    record_scan_result(&mut trackers, 8453, false);
+    record_scan_result(&mut trackers, 42161, true);
+
+    assert_eq!(trackers[&8453].consec_losses, 2);
+    assert!(trackers[&8453].should_cooldown(60));
+
+    assert_eq!(trackers[&42161].consec_losses, 0);
+    assert!(!trackers[&42161].should_cooldown(60));
+    // сеть без сканов тоже не в cooldown
+    assert!(!trackers.get(&56).cloned().unwrap_or_default().should_cooldown(60));
+}
+
+#[test]
+fn success_resets_only_its_chain() {
+    let mut trackers: HashMap<u64, PnLTracker> = HashMap::new();
+    record_scan_result(&mut trackers, 1, false);
+    record_scan_result(&mut trackers, 2, false);
+    record_scan_result(&mut trackers, 1, true);
+    assert_eq!(trackers[&1].consec_losses, 0);
+    assert_eq!(trackers[&2].consec_losses, 1);
+}
@@ -0,0 +1,55 @@
+use DeFiArbitraje::route::NotionalWindow;
+use DeFiArbitraje::utils::usd_value;
+use ethers::types::U256;
+use crate::common;
+
+#[test]
+fn window_slides_without_reset() {
+    let mut w = NotionalWindow::new(NotionalWindow::DAY_SECS);
+    let t0 = 1_700_000_000;
+    w.record(t0, 600.0);
+    w.record(t0 + 3_600, 300.0);
+    assert_eq!(w.spent(t0 + 3_600), 900.0);
+    assert_eq!(w.remaining(1_000.0, t0 + 3_600), 100.0);
+    assert_eq!(w.remaining(500.0, t0 + 3_600), 0.0);
+
+    // первая сделка выпала из окна, вторая ещё в нём
+    assert_eq!(w.spent(t0 + NotionalWindow::DAY_SECS), 300.0);
+    assert_eq!(w.spent(t0 + NotionalWindow::DAY_SECS + 3_600), 0.0);
+}
+
+#[test]
+fn invalid_amounts_are_ignored() {
+    let mut w = NotionalWindow::new(60);
+    w.record(10, f64::NAN);
+    w.record(10, -5.0);
+    w.record(10, 0.0);
+    assert_eq!(w.spent(10), 0.0);
+}
+
+#[test]
+fn cap_is_per_network_and_off_by_default() {
+    let cfg = common::config();
+    let cap = |chain: u64| {
+        cfg.networks
+            .iter()
+            .find(|n| n.chain_id == chain)
+            .map(|n| n.daily_notional_cap_usd)
+            .unwrap()
+    };
+    assert_eq!(cap(8453), 50_000.0);
+    assert_eq!(cap(42161), 0.0);
+}
+
+#[test]
+fn notional_above_u128_is_priced() {
+    // 1e21 WETH (18 decimals) > u128::MAX в wei: объём считается, а не паникует
+    let amount = U256::from(u128::MAX) * 3;
+    let usd = usd_value(amount, 18, 2_000.0);
+    let expected = 3.0 * u128::MAX as f64 / 1e18 * 2_000.0;
+    assert!((usd / expected - 1.0).abs() < 1e-12);
+
+    let mut w = NotionalWindow::new(NotionalWindow::DAY_SECS);
+    w.record(1_700_000_000, usd);
+    assert_eq!(w.remaining(50_000.0, 1_700_000_000), 0.0);
+}
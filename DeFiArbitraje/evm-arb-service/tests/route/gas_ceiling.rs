@@ -0,0 +1,64 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::network::{MultiChain, ScanReads};
+use DeFiArbitraje::route::{gas_ceiling_breach, gas_over_ceiling};
+use DeFiArbitraje::utils_gas::GasPriceMode;
+use crate::common;
+use ethers::types::U256;
+use serde_json::json;
+use std::net::SocketAddr;
+
+const GWEI: u64 = 1_000_000_000;
+
+fn fixture() -> Config {
+    let mut cfg = common::single_network(8453);
+    cfg.rpc.max_block_lag = 0;
+    cfg.networks[0].gas.max_fee_gwei = None;
+    cfg
+}
+
+/// JSON-RPC узел в час пик: eth_gasPrice = `gwei`
+async fn congested_node(gwei: u64) -> SocketAddr {
+    common::rpc_stub(move |call| {
+        Ok(match call["method"].as_str().unwrap() {
+            "eth_gasPrice" => json!(format!("{:#x}", gwei * GWEI)),
+            _ => json!("0x1"),
+        })
+    })
+    .await
+}
+
+#[test]
+fn ceiling_compares_in_gwei() {
+    let price = U256::from(30 * GWEI);
+    assert_eq!(gas_over_ceiling(price, Some(25.0)), Some(30.0));
+    assert_eq!(gas_over_ceiling(price, Some(30.0)), None);
+    assert_eq!(gas_over_ceiling(price, None), None);
+}
+
+#[tokio::test]
+async fn gas_spike_pauses_execution() {
+    let addr = congested_node(80).await;
+    let mut cfg = fixture();
+    cfg.networks[0].rpc = vec![format!("http://{addr}")];
+    cfg.networks[0].max_gas_price_gwei = Some(50.0);
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let client = &chains.clients[&8453];
+
+    let breach = gas_ceiling_breach(client, GasPriceMode::Legacy).await.unwrap();
+    assert_eq!(breach, Some(80.0));
+
+    // газ упал (снимок начала скана) — исполнение возобновляется сразу
+    let reads = ScanReads { block: 1, gas_price: U256::from(20 * GWEI) };
+    let calm = client.with_scan_reads(reads, false);
+    assert_eq!(gas_ceiling_breach(&calm, GasPriceMode::Legacy).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn no_ceiling_skips_the_check() {
+    // без max_gas_price_gwei RPC не опрашивается: недоступный узел не мешает
+    let mut cfg = fixture();
+    cfg.networks[0].rpc = vec!["http://127.0.0.1:1".into()];
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let breach = gas_ceiling_breach(&chains.clients[&8453], GasPriceMode::Legacy).await;
+    assert_eq!(breach.unwrap(), None);
+}
@@ -0,0 +1,80 @@
+use DeFiArbitraje::config::Network;
+use DeFiArbitraje::metrics::METRIC_ROUTES_SKIPPED;
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::route::stable_liquidity_usd;
+use crate::common::{self, token};
+use ethers::types::U256;
+use std::sync::Arc;
+
+fn base() -> (Network, Vec<String>) {
+    (common::network(8453), common::config().global.risk.stables)
+}
+
+#[test]
+fn six_decimal_stable_reserve() {
+    let (net, stables) = base();
+    // 250k USDC (6 знаков) против WETH
+    let usdc = (U256::from(250_000u64) * U256::exp10(6), token(&net, "USDC"));
+    let weth = (U256::exp10(20), token(&net, "WETH"));
+    assert_eq!(stable_liquidity_usd(&net, &stables, weth, usdc), Some(250_000.0));
+    assert_eq!(stable_liquidity_usd(&net, &stables, usdc, weth), Some(250_000.0));
+}
+
+#[test]
+fn eighteen_decimal_stable_reserve() {
+    let (net, stables) = base();
+    let dai = (U256::from(1_500u64) * U256::exp10(18), token(&net, "DAI"));
+    let weth = (U256::exp10(18), token(&net, "WETH"));
+    assert_eq!(stable_liquidity_usd(&net, &stables, dai, weth), Some(1_500.0));
+}
+
+#[test]
+fn both_sides_stable_are_summed() {
+    let (net, stables) = base();
+    let usdc = (U256::from(40_000u64) * U256::exp10(6), token(&net, "USDC"));
+    let dai = (U256::from(60_000u64) * U256::exp10(18), token(&net, "DAI"));
+    assert_eq!(stable_liquidity_usd(&net, &stables, usdc, dai), Some(100_000.0));
+}
+
+#[test]
+fn no_stable_side_is_unknown() {
+    let (net, stables) = base();
+    let weth = (U256::exp10(18), token(&net, "WETH"));
+    let aero = (U256::exp10(21), token(&net, "AERO"));
+    assert_eq!(stable_liquidity_usd(&net, &stables, weth, aero), None);
+}
+
+/// Кандидатов пары WETH-USDC рынка `v2_market` (без треугольника) при `min_liquidity_usd`
+/// и прирост пропусков `low_liquidity`
+async fn scan_pair_with_min_liquidity(min_usd: u64) -> (usize, f64) {
+    let mut cfg = common::single_network(8453);
+    let market = common::v2_market(&mut cfg);
+    cfg.networks[0].triangles.clear();
+    cfg.global.risk.min_liquidity_usd = min_usd;
+    let dir = std::env::temp_dir()
+        .join(format!("defi-arb-min-liq-{}-{min_usd}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let ledger = dir.join("paper.jsonl");
+    cfg.telemetry.paper_trading.enabled = true;
+    cfg.telemetry.paper_trading.backend = "jsonl".into();
+    cfg.telemetry.paper_trading.path = ledger.to_string_lossy().into_owned();
+    cfg.networks[0].rpc = vec![format!("http://{}", common::v2_node(market.pairs).await)];
+
+    let chains = Arc::new(MultiChain::from_config(&cfg).await.expect("clients"));
+    chains.clients[&8453].prewarm_pools(market.pools);
+    let mut engine = common::engine_on(&cfg, chains).await.expect("engine");
+    let low = METRIC_ROUTES_SKIPPED.with_label_values(&["8453", "low_liquidity"]);
+    let before = low.get();
+    engine.scan_and_execute().await.expect("scan");
+    let fills = std::fs::read_to_string(&ledger).unwrap_or_default();
+    (fills.lines().count(), low.get() - before)
+}
+
+#[tokio::test]
+async fn thin_pair_is_skipped_before_quoting() {
+    // стейбл-сторона пар: 2M и 1.96M USDC
+    let (fills, low) = scan_pair_with_min_liquidity(20_000).await;
+    assert_eq!((fills, low), (1, 0.0));
+    let (fills, low) = scan_pair_with_min_liquidity(5_000_000).await;
+    assert_eq!((fills, low), (0, 1.0));
+}
@@ -0,0 +1,26 @@
+//! Движок скана (`route`): окно и дедупликация маршрутов, гейты прибыли и исполнения
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod adaptive_poll;
+mod chain_breaker;
+mod daily_notional;
+mod gas_ceiling;
+mod liquidity_hint;
+#[cfg(feature = "mempool")]
+mod mempool_trigger;
+mod min_profit_usd;
+mod pool_warmup;
+mod route_cooldown;
+mod route_hops;
+mod route_window;
+mod scan_only;
+mod simulation_gate;
+mod skip_reasons;
+mod stale_quote;
+mod strategy_dedup;
+mod strategy_gas_limit;
+mod token_allowlist;
+mod triangle_dedup;
+mod volatility_index;
@@ -0,0 +1,114 @@
+use DeFiArbitraje::mempool::{
+    PendingSwap, TriggerQueue, TriggerThrottle, affected_routes, decode_pending_swap, swap_usd,
+};
+use crate::common::{self, token};
+use ethers::abi::{Token, encode};
+use ethers::types::{Address, H256, Transaction, U256};
+use std::time::{Duration, Instant};
+
+/// SwapRouter02.exactInputSingle(tokenIn, tokenOut, fee, recipient, amountIn, 0, 0)
+fn exact_input_single(
+    to: Address,
+    token_in: Address,
+    token_out: Address,
+    amount: U256,
+) -> Transaction {
+    let sig = "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))";
+    let args = Token::Tuple(vec![
+        Token::Address(token_in),
+        Token::Address(token_out),
+        Token::Uint(U256::from(500u64)),
+        Token::Address(Address::repeat_byte(0x11)),
+        Token::Uint(amount),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+    ]);
+    let mut input = ethers::utils::id(sig)[..4].to_vec();
+    input.extend(encode(&[args]));
+    Transaction {
+        hash: H256::repeat_byte(0xab),
+        to: Some(to),
+        input: input.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn large_router_swap_targets_pair_routes() {
+    let (cfg, net) = (common::config(), common::network(8453));
+    let router = "0x2626664c2603336e57b271c5c0b26f421741e481".parse().unwrap();
+    // $50k USDC → WETH через SwapRouter02 UniswapV3
+    let amount = U256::from(50_000u64) * U256::exp10(6);
+    let tx = exact_input_single(router, token(&net, "USDC"), token(&net, "WETH"), amount);
+
+    let swap = decode_pending_swap(&net, &tx).unwrap();
+    assert_eq!(swap.dex, "UniswapV3");
+    assert_eq!(swap.amount_in, amount);
+    assert_eq!(swap_usd(&net, &cfg.global.risk.stables, &swap), Some(50_000.0));
+    // WETH-USDC: пара в обратном направлении тоже задета
+    assert_eq!(affected_routes(&net, &swap), vec![0]);
+}
+
+#[test]
+fn unrelated_txs_are_ignored() {
+    let net = common::network(8453);
+    let (usdc, weth) = (token(&net, "USDC"), token(&net, "WETH"));
+    let amount = U256::exp10(6);
+    // не роутер DEX сети
+    let tx = exact_input_single(Address::repeat_byte(0x22), usdc, weth, amount);
+    assert_eq!(decode_pending_swap(&net, &tx), None);
+
+    // роутер, но не своп
+    let router = "0x2626664c2603336e57b271c5c0b26f421741e481".parse().unwrap();
+    let mut tx = exact_input_single(router, usdc, weth, amount);
+    tx.input = ethers::utils::id("multicall(bytes[])")[..4].to_vec().into();
+    assert_eq!(decode_pending_swap(&net, &tx), None);
+
+    // своп по паре без маршрута
+    let tx = exact_input_single(router, token(&net, "AERO"), token(&net, "DAI"), amount);
+    let swap = decode_pending_swap(&net, &tx).unwrap();
+    assert!(affected_routes(&net, &swap).is_empty());
+}
+
+#[test]
+fn throttle_limits_rescans_per_route() {
+    let mut throttle = TriggerThrottle::new(Duration::from_millis(1_000));
+    let t0 = Instant::now();
+    assert_eq!(throttle.admit(8453, &[0, 3], t0), vec![0, 3]);
+    assert_eq!(throttle.admit(8453, &[0, 1], t0 + Duration::from_millis(200)), vec![1]);
+    // другая сеть — свой учёт
+    assert_eq!(throttle.admit(10, &[0], t0), vec![0]);
+    assert_eq!(throttle.admit(8453, &[0], t0 + Duration::from_millis(1_000)), vec![0]);
+}
+
+#[test]
+fn queue_coalesces_pairs_and_drops_stale_swaps() {
+    let net = common::network(8453);
+    let (usdc, weth) = (token(&net, "USDC"), token(&net, "WETH"));
+    let swap = |n: u8, dex: &str, token_in, token_out| PendingSwap {
+        tx: H256::repeat_byte(n),
+        chain_id: 8453,
+        dex: dex.into(),
+        token_in,
+        token_out,
+        amount_in: U256::exp10(12),
+    };
+    let mut queue = TriggerQueue::new();
+    queue.on_head(8453, 100);
+
+    // поток свопов по одной паре и DEX за долгий скан — один, последний
+    queue.push(swap(1, "UniswapV3", usdc, weth));
+    queue.push(swap(2, "UniswapV3", weth, usdc));
+    queue.push(swap(3, "Aerodrome", usdc, weth));
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.pop().map(|s| s.tx), Some(H256::repeat_byte(2)));
+    assert_eq!(queue.pop().map(|s| s.tx), Some(H256::repeat_byte(3)));
+    assert_eq!(queue.pop(), None);
+
+    // своп, замеченный до нового блока, устарел; после блока — свежий
+    queue.push(swap(4, "UniswapV3", usdc, weth));
+    queue.on_head(8453, 101);
+    queue.push(swap(5, "Aerodrome", usdc, weth));
+    assert_eq!(queue.pop().map(|s| s.tx), Some(H256::repeat_byte(5)));
+    assert!(queue.is_empty());
+}
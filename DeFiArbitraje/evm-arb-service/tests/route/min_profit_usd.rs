@@ -0,0 +1,48 @@
+use DeFiArbitraje::config::{Strategy, StrategyOverrides};
+use DeFiArbitraje::route::meets_min_profit_usd;
+use DeFiArbitraje::router::QuoteResult;
+use crate::common;
+use ethers::types::U256;
+
+/// Круг с `amount_in` → `amount_out` (USDC, 6 знаков) и чистой PnL `pnl_usd`
+fn quote(amount_in: u64, amount_out: u64, pnl_usd: Option<f64>) -> QuoteResult {
+    QuoteResult { gas_estimate: 250_000, ..common::quote(amount_in, amount_out, pnl_usd) }
+}
+
+#[test]
+fn high_bps_but_tiny_usd_is_rejected() {
+    // 50 bps на $20: проходит min_profit_bps, но $0.06 после газа — мимо $1
+    let tiny = quote(20_000_000, 20_100_000, Some(0.06));
+    let profit = tiny.amount_out - tiny.amount_in;
+    assert!(profit >= tiny.amount_in * U256::from(25u64) / U256::from(10_000u64));
+    assert!(!meets_min_profit_usd(&tiny, Some(1.0)));
+
+    // те же 50 bps на $20k
+    let sized = quote(20_000_000_000, 20_100_000_000, Some(85.0));
+    assert!(meets_min_profit_usd(&sized, Some(1.0)));
+}
+
+#[test]
+fn floor_is_optional_and_needs_a_price() {
+    assert!(meets_min_profit_usd(&quote(1, 2, Some(0.0)), None));
+    assert!(meets_min_profit_usd(&quote(1, 2, None), None));
+    // PnL не оценить в USD — порог не доказан
+    assert!(!meets_min_profit_usd(&quote(1, 2, None), Some(1.0)));
+}
+
+#[test]
+fn strategies_and_networks_parse_floor() {
+    let st: Strategy = serde_json::from_value(serde_json::json!({
+        "name": "cross_dex_spread",
+        "description": "",
+        "min_profit_bps": 18,
+        "slippage_bps": 6,
+        "gas_limit": 1_500_000,
+        "min_profit_usd": 2.0
+    }))
+    .unwrap();
+    assert_eq!(st.min_profit_usd, Some(2.0));
+    let ov: StrategyOverrides =
+        serde_json::from_value(serde_json::json!({ "min_profit_bps": 25 })).unwrap();
+    assert_eq!(ov.min_profit_usd, None);
+}
@@ -0,0 +1,83 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::route::{StrategyEngine, warmup_legs};
+use crate::common;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// RPC-заглушка: на любой eth_call отвечает словом `word` (адрес пула / slot0), считает вызовы
+async fn pool_stub(word: &'static str) -> (SocketAddr, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let seen = calls.clone();
+    let addr = common::rpc_stub(move |call| {
+        let result = match call["method"].as_str().unwrap() {
+            "eth_chainId" => "0x2105".to_string(),
+            "eth_call" => {
+                seen.fetch_add(1, Ordering::SeqCst);
+                format!("0x{word:0>64}")
+            }
+            _ => "0x1".to_string(),
+        };
+        Ok(result.into())
+    })
+    .await;
+    (addr, calls)
+}
+
+async fn base_engine(rpc: SocketAddr, warmup: bool) -> (StrategyEngine, Config) {
+    let mut cfg = common::single_network(8453);
+    cfg.networks[0].rpc = vec![format!("http://{rpc}")];
+    cfg.global.execution.warmup_pools = warmup;
+    let engine = common::engine(&cfg).await.expect("engine");
+    (engine, cfg)
+}
+
+#[test]
+fn legs_follow_route_directions() {
+    let base = &common::network(8453);
+    let legs = warmup_legs(base);
+    let r = &base.routes_cross_dex.as_ref().unwrap()[0];
+    let (a, b) = (base.quote_symbol(&r.pair[0]), base.quote_symbol(&r.pair[1]));
+    assert!(legs.contains(&(r.dexes[0].clone(), a.to_string(), b.to_string())));
+    assert!(legs.contains(&(r.dexes[1].clone(), b.to_string(), a.to_string())));
+    // ноги общие для нескольких маршрутов — один раз
+    let mut uniq = legs.clone();
+    uniq.sort();
+    uniq.dedup();
+    assert_eq!(uniq.len(), legs.len());
+}
+
+#[tokio::test]
+async fn warmup_fills_pool_cache() {
+    let (addr, calls) = pool_stub("beef").await;
+    let (engine, cfg) = base_engine(addr, true).await;
+    let legs = warmup_legs(&cfg.networks[0]);
+
+    let w = &engine.warmup().await[&8453];
+    assert!(w.resolved > 0);
+    assert!(w.missing.is_empty(), "{:?}", w.missing);
+    assert_eq!(w.resolved + w.failed.len(), legs.len());
+
+    // повторный прогрев целиком из кэша
+    let before = calls.load(Ordering::SeqCst);
+    assert!(before > 0);
+    assert_eq!(engine.warmup().await[&8453].resolved, w.resolved);
+    assert_eq!(calls.load(Ordering::SeqCst), before);
+}
+
+#[tokio::test]
+async fn warmup_reports_missing_pools() {
+    let (addr, _) = pool_stub("0").await;
+    let (engine, _) = base_engine(addr, true).await;
+    let w = &engine.warmup().await[&8453];
+    assert_eq!(w.resolved, 0);
+    assert!(!w.missing.is_empty());
+}
+
+#[tokio::test]
+async fn warmup_disabled_is_noop() {
+    let (addr, calls) = pool_stub("beef").await;
+    let (engine, _) = base_engine(addr, false).await;
+    assert!(engine.warmup().await.is_empty());
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,45 @@
+use DeFiArbitraje::route::{RouteCooldowns, route_label};
+use crate::common;
+use std::time::{Duration, Instant};
+
+fn s(v: &[&str]) -> Vec<String> {
+    v.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn one_route_cooldown_does_not_block_sibling() {
+    let pair = ["WETH".to_string(), "USDC".to_string()];
+    let bad = route_label(&pair, &s(&["UniswapV3", "AerodromeV2"]));
+    let good = route_label(&pair, &s(&["UniswapV3", "SushiV2"]));
+    assert_eq!(bad, "WETH-USDC@UniswapV3,AerodromeV2");
+    assert_ne!(bad, good);
+
+    let cooldown = Duration::from_secs(600);
+    let now = Instant::now();
+    let mut c = RouteCooldowns::default();
+    assert_eq!(c.on_loss(8453, &bad, now), 1);
+    assert_eq!(c.remaining(8453, &bad, 2, cooldown, now), None);
+    assert_eq!(c.on_loss(8453, &bad, now), 2);
+
+    // сломанный маршрут на паузе, соседний по той же паре и тот же в другой сети — нет
+    assert_eq!(c.remaining(8453, &bad, 2, cooldown, now), Some(cooldown));
+    assert_eq!(c.remaining(8453, &good, 2, cooldown, now), None);
+    assert_eq!(c.remaining(42161, &bad, 2, cooldown, now), None);
+
+    // пауза истекает; успех сбрасывает серию
+    let later = now + cooldown;
+    assert_eq!(c.remaining(8453, &bad, 2, cooldown, later), None);
+    assert!(c.on_success(8453, &bad));
+    assert_eq!(c.on_loss(8453, &bad, later), 1);
+    // 0 — выключено
+    assert_eq!(c.remaining(8453, &bad, 0, cooldown, later), None);
+}
+
+#[test]
+fn route_cooldown_falls_back_to_network_cooldown() {
+    let cfg = common::config();
+    let mut cb = cfg.safety.circuit_breaker.clone();
+    assert_eq!(cb.route_cooldown(), Duration::from_secs(cb.route_cooldown_sec));
+    cb.route_cooldown_sec = 0;
+    assert_eq!(cb.route_cooldown(), Duration::from_secs(cb.cooldown_sec));
+}
@@ -0,0 +1,48 @@
+use DeFiArbitraje::calldata::{LegKind, LegQuote, MAX_ROUTE_LEGS, validate_route};
+use DeFiArbitraje::route::{SkipReason, route_hop_limit, triangle_matches_strategy};
+use crate::common;
+use ethers::types::{Address, U256};
+
+#[test]
+fn triangle_exceeding_strategy_hops_is_rejected() {
+    let cfg = common::config();
+    let templates = &cfg.routing.route_templates;
+    let cross = cfg.strategies.iter().find(|s| s.name == "cross_dex_spread").unwrap();
+    let tri = ["WETH".to_string(), "USDC".to_string(), "USDBC".to_string()];
+
+    // фильтры стратегии треугольник пропускают, но 3 ноги > max_route_hops 2
+    assert!(triangle_matches_strategy(cross, &cfg.global.risk.stables, &tri));
+    assert_eq!(route_hop_limit(Some(cross), templates), 2);
+    assert!(tri.len() > route_hop_limit(Some(cross), templates));
+    // cross-dex пара в пределе
+    assert!(2 <= route_hop_limit(Some(cross), templates));
+    assert_eq!(SkipReason::TooManyHops.label(), "too_many_hops");
+
+    // тот же предел — и при кодировании calldata
+    let leg = |n: u64| LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(n),
+            path: vec![Address::from_low_u64_be(1), Address::from_low_u64_be(2)],
+        },
+        quoted_out: U256::from(1_010u64),
+    };
+    let legs = [leg(1), leg(2), leg(3)];
+    let (amount_in, min_out) = (U256::from(1_000u64), U256::from(1_005u64));
+    let limit = route_hop_limit(Some(cross), templates);
+    let err = validate_route(&legs, amount_in, min_out, limit).unwrap_err();
+    assert!(err.to_string().contains("max 2"), "{err}");
+    assert!(validate_route(&legs[..2], amount_in, min_out, limit).is_ok());
+}
+
+#[test]
+fn hop_limit_falls_back_to_templates() {
+    let mut cfg = common::config();
+    // стратегия без max_route_hops — самый длинный шаблон (triangular, 3)
+    let tri = cfg.strategies.iter().find(|s| s.name == "triangular_arbitrage").unwrap();
+    assert_eq!(tri.max_route_hops, None);
+    assert_eq!(route_hop_limit(Some(tri), &cfg.routing.route_templates), 3);
+
+    cfg.routing.route_templates.retain(|t| t.max_hops < 3);
+    assert_eq!(route_hop_limit(Some(tri), &cfg.routing.route_templates), 1);
+    assert_eq!(route_hop_limit(None, &[]), MAX_ROUTE_LEGS);
+}
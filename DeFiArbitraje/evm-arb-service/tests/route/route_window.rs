@@ -0,0 +1,41 @@
+use DeFiArbitraje::route::RouteWindow;
+use std::collections::HashSet;
+
+fn selected(mask: &[bool]) -> Vec<usize> {
+    mask.iter()
+        .enumerate()
+        .filter(|(_, on)| **on)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[test]
+fn window_rotates_and_covers_all_routes() {
+    let mut w = RouteWindow::default();
+    let total = 7;
+    let max = 3;
+
+    let first = selected(&w.advance(total, max));
+    assert_eq!(first, vec![0, 1, 2]);
+    assert_eq!(w.offset(), 3);
+
+    let second = selected(&w.advance(total, max));
+    assert_eq!(second, vec![3, 4, 5]);
+
+    // третий poll переходит через конец списка
+    let third = selected(&w.advance(total, max));
+    assert_eq!(third, vec![0, 1, 6]);
+    assert_eq!(w.offset(), 2);
+
+    let covered: HashSet<usize> = first.into_iter().chain(second).chain(third).collect();
+    assert_eq!(covered.len(), total);
+}
+
+#[test]
+fn zero_or_large_limit_scans_everything() {
+    let mut w = RouteWindow::default();
+    assert!(w.advance(5, 0).iter().all(|on| *on));
+    assert!(w.advance(5, 10).iter().all(|on| *on));
+    assert_eq!(w.offset(), 0);
+    assert!(w.advance(0, 3).is_empty());
+}
@@ -0,0 +1,55 @@
+use DeFiArbitraje::config::Config;
+use DeFiArbitraje::metrics::METRIC_EXECUTOR_MODE;
+use crate::common;
+
+/// Фикстура с одной сетью Base без EXECUTOR_8453 в окружении
+fn base_only(require_executors: bool) -> Config {
+    assert!(std::env::var("EXECUTOR_8453").is_err(), "тест ожидает окружение без EXECUTOR_8453");
+    let mut cfg = common::single_network(8453);
+    cfg.global.execution.require_executors = require_executors;
+    cfg
+}
+
+#[tokio::test]
+async fn missing_executor_is_explicit_scan_only() {
+    let engine = common::engine(&base_only(false)).await.expect("engine");
+    let reason = &engine.scan_only_networks()[&8453];
+    assert!(reason.contains("EXECUTOR_8453"), "{reason}");
+    assert_eq!(METRIC_EXECUTOR_MODE.with_label_values(&["8453", "scan_only"]).get(), 1.0);
+    assert_eq!(METRIC_EXECUTOR_MODE.with_label_values(&["8453", "execute"]).get(), 0.0);
+}
+
+#[tokio::test]
+async fn require_executors_fails_fast() {
+    let err = common::engine(&base_only(true)).await.err().expect("must fail");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("chain_id=8453") && msg.contains("require_executors"), "{msg}");
+}
+
+#[tokio::test]
+async fn scan_only_network_is_ready() {
+    let engine = common::engine(&base_only(false)).await.expect("engine");
+    assert_eq!(engine.ready_networks(), vec![8453]);
+}
+
+/// Свой chain id: EXECUTOR_<id> задан, но ключ не разбирается — подписант не поднимется
+const BROKEN_SIGNER_CHAIN: u64 = 31338;
+
+#[tokio::test]
+async fn failed_signer_init_is_scan_only_and_ready() {
+    unsafe {
+        std::env::set_var(
+            format!("EXECUTOR_{BROKEN_SIGNER_CHAIN}"),
+            "0x00000000000000000000000000000000000000e0",
+        );
+        std::env::set_var(format!("PRIVATE_KEY_{BROKEN_SIGNER_CHAIN}"), "0xnot-a-key");
+    }
+    let mut cfg = base_only(false);
+    cfg.networks[0].chain_id = BROKEN_SIGNER_CHAIN;
+    cfg.networks[0].safe_address = None;
+    let engine = common::engine(&cfg).await.expect("engine");
+    let reason = &engine.scan_only_networks()[&BROKEN_SIGNER_CHAIN];
+    assert!(reason.contains("signer init failed"), "{reason}");
+    // без require_executors деградированная сеть не держит /readyz в 503
+    assert_eq!(engine.ready_networks(), vec![BROKEN_SIGNER_CHAIN]);
+}
@@ -0,0 +1,79 @@
+use DeFiArbitraje::exec::{
+    DEFAULT_EXECUTOR_ABI, Executor, ExecutorMethods, SIMULATE_GAS_PER_LEG, SimOutcome,
+    decode_revert_reason, simulate_gas_limit, simulation_passes,
+};
+use DeFiArbitraje::route::SkipReason;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Bytes, U256};
+use std::sync::Arc;
+
+#[test]
+fn reverted_simulation_blocks_send() {
+    assert!(!simulation_passes(None, U256::exp10(18), 0));
+}
+
+#[test]
+fn simulated_profit_must_reach_threshold() {
+    let amount_in = U256::from(1_000_000u64);
+    // 20 bps от 1e6 = 2000
+    assert!(simulation_passes(Some(U256::from(2_000)), amount_in, 20));
+    assert!(!simulation_passes(Some(U256::from(1_999)), amount_in, 20));
+    // порог 0: достаточно, чтобы simulate() не откатился
+    assert!(simulation_passes(Some(U256::zero()), amount_in, 0));
+}
+
+#[test]
+fn simulation_failure_is_a_skip_reason() {
+    assert!(SkipReason::ALL.contains(&SkipReason::SimulationFailed));
+    assert_eq!(SkipReason::SimulationFailed.label(), "simulation_failed");
+}
+
+#[test]
+fn simulate_gas_scales_with_legs_unless_configured() {
+    let two = simulate_gas_limit(2, None);
+    let three = simulate_gas_limit(3, None);
+    assert!(three > two && three > 200_000);
+    assert_eq!(three - two, SIMULATE_GAS_PER_LEG);
+    assert_eq!(simulate_gas_limit(3, Some(2_000_000)), 2_000_000);
+}
+
+#[test]
+fn simulate_call_carries_gas_limit() {
+    let provider = Provider::<Http>::try_from("http://127.0.0.1:1").unwrap();
+    let wallet: LocalWallet = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        .parse()
+        .unwrap();
+    let exec = Executor {
+        client: Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(8453u64))),
+        address: Address::from_low_u64_be(0xe),
+        abi: serde_json::from_str(DEFAULT_EXECUTOR_ABI).unwrap(),
+        methods: ExecutorMethods::default(),
+        nonces: Default::default(),
+        safe: None,
+    };
+    let gas = simulate_gas_limit(3, None);
+    let call = exec.simulate_call(Bytes::from(vec![1, 2, 3]), gas).unwrap();
+    assert_eq!(call.tx.gas(), Some(&U256::from(gas)));
+    assert_eq!(call.tx.to_addr(), Some(&exec.address));
+}
+
+#[test]
+fn revert_reasons_are_decoded() {
+    // Error(string) — require(..., "STF") из TransferHelper Uniswap
+    let mut data = hex::decode("08c379a0").unwrap();
+    data.extend(ethers::abi::encode(&[ethers::abi::Token::String("STF".into())]));
+    assert_eq!(decode_revert_reason(&data), "STF");
+
+    // Panic(0x11) — переполнение
+    let mut panic = hex::decode("4e487b71").unwrap();
+    panic.extend(ethers::abi::encode(&[ethers::abi::Token::Uint(U256::from(0x11))]));
+    assert_eq!(decode_revert_reason(&panic), "panic 0x11");
+
+    assert_eq!(decode_revert_reason(&hex::decode("e450d38c").unwrap()), "custom error 0xe450d38c");
+    assert_eq!(decode_revert_reason(&[]), "revert without reason");
+
+    assert_eq!(SimOutcome::Reverted("STF".into()).profit(), None);
+    assert_eq!(SimOutcome::Profit(U256::one()).profit(), Some(U256::one()));
+}
@@ -0,0 +1,25 @@
+use DeFiArbitraje::metrics::METRIC_ROUTES_SKIPPED;
+use DeFiArbitraje::route::{SkipReason, record_skip};
+use std::collections::HashSet;
+
+#[test]
+fn skip_reason_labels_are_bounded_and_unique() {
+    let labels: HashSet<_> = SkipReason::ALL.iter().map(|r| r.label()).collect();
+    assert_eq!(labels.len(), SkipReason::ALL.len());
+    assert!(labels.iter().all(|l| l.chars().all(|c| c.is_ascii_lowercase() || c == '_')));
+}
+
+#[test]
+fn record_skip_counts_per_chain_and_reason() {
+    let get = |reason: SkipReason| {
+        METRIC_ROUTES_SKIPPED
+            .with_label_values(&["4242", reason.label()])
+            .get()
+    };
+    let before = (get(SkipReason::Blacklisted), get(SkipReason::NoQuote));
+    record_skip(4242, SkipReason::Blacklisted);
+    record_skip(4242, SkipReason::Blacklisted);
+    record_skip(4242, SkipReason::NoQuote);
+    assert_eq!(get(SkipReason::Blacklisted) - before.0, 2.0);
+    assert_eq!(get(SkipReason::NoQuote) - before.1, 1.0);
+}
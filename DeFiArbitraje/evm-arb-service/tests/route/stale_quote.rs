@@ -0,0 +1,44 @@
+use DeFiArbitraje::route::{ProfitGates, SkipReason, quote_age_blocks, quote_is_stale};
+use DeFiArbitraje::router::QuoteResult;
+use crate::common;
+
+#[test]
+fn requote_triggers_past_max_age() {
+    let max_age = 2;
+    // котировка на блоке 100: на 102 ещё годна, на 103 — перекотировка
+    assert!(!quote_is_stale(quote_age_blocks(100, 102), max_age));
+    assert!(quote_is_stale(quote_age_blocks(100, 103), max_age));
+    // эндпоинт отстаёт от закреплённого блока — возраст 0, а не переполнение
+    assert_eq!(quote_age_blocks(100, 99), 0);
+    // 0 — проверка выключена
+    assert!(!quote_is_stale(50, 0));
+    assert_eq!(SkipReason::StaleQuote.label(), "stale_quote");
+}
+
+#[test]
+fn fixture_requotes_stale_quotes() {
+    let cfg = common::config();
+    let exec = &cfg.global.execution;
+    assert_eq!(exec.max_quote_age_blocks, 2);
+    assert!(exec.requote_stale);
+    // возраст считается от закреплённого блока котировки
+    assert!(cfg.global.quote.pin_block);
+}
+
+#[test]
+fn requoted_route_rechecks_every_profit_gate() {
+    let quote = |out: u64, pnl_usd: f64, gas: u64| QuoteResult {
+        gas_estimate: gas,
+        quote_block: Some(103),
+        ..common::quote(10_000, out, Some(pnl_usd))
+    };
+    let gates = ProfitGates { min_profit_bps: 10, min_usd: Some(1.0), gas_limit: Some(500_000) };
+    assert_eq!(gates.check(&quote(10_020, 2.0, 300_000)), None);
+    // свежая котировка сузилась ниже min_profit_bps
+    assert_eq!(gates.check(&quote(10_005, 2.0, 300_000)), Some(SkipReason::BelowMinProfit));
+    // bps проходит, но PnL после газа ниже min_profit_usd
+    assert_eq!(gates.check(&quote(10_020, 0.5, 300_000)), Some(SkipReason::BelowMinProfit));
+    let unpriced = QuoteResult { pnl_usd_priced: false, ..quote(10_020, 2.0, 300_000) };
+    assert_eq!(gates.check(&unpriced), Some(SkipReason::BelowMinProfit));
+    assert_eq!(gates.check(&quote(10_020, 2.0, 600_000)), Some(SkipReason::OverGasLimit));
+}
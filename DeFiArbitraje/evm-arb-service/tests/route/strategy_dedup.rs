@@ -0,0 +1,198 @@
+use DeFiArbitraje::config::Strategy;
+use DeFiArbitraje::metrics::METRIC_ROUTES_SKIPPED;
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::route::{
+    PollDedup, best_strategy_quote, pair_matches_strategy, route_key, sort_by_pnl_desc,
+    strictest_strategy,
+};
+use DeFiArbitraje::router::{QuoteOutcome, QuoteResult};
+use crate::common;
+use std::sync::Arc;
+
+fn strategies() -> (Vec<Strategy>, Vec<String>) {
+    let cfg = common::config();
+    let mut loose = cfg.strategies[0].clone();
+    loose.name = "loose".into();
+    loose.min_profit_bps = 5;
+    loose.whitelist_pairs = None;
+    loose.only_stables = None;
+    loose.whitelist_dexes = None;
+    let mut strict = loose.clone();
+    strict.name = "strict".into();
+    strict.min_profit_bps = 25;
+    strict.whitelist_pairs = Some(vec![["USDC".into(), "WETH".into()]]);
+    (vec![loose, strict], cfg.global.risk.stables)
+}
+
+#[test]
+fn two_matching_strategies_execute_once() {
+    let (strats, stables) = strategies();
+    let pair = ["WETH".to_string(), "USDC".to_string()];
+    let dexes = vec!["UniswapV3".to_string(), "AerodromeV2".to_string()];
+
+    let matched: Vec<&Strategy> = strats
+        .iter()
+        .filter(|s| pair_matches_strategy(s, &stables, &pair, &dexes))
+        .collect();
+    assert_eq!(matched.len(), 2);
+    assert_eq!(strictest_strategy(&matched).unwrap().name, "strict");
+
+    // по каждой совпавшей стратегии движок пытался бы отправить — проходит только первая
+    let mut dedup = PollDedup::default();
+    let sends = matched
+        .iter()
+        .filter(|_| dedup.claim(8453, &pair[0], &pair[1]))
+        .count();
+    assert_eq!(sends, 1);
+
+    // обратное направление — отдельный маршрут
+    assert!(dedup.claim(8453, "USDC", "WETH"));
+    assert!(!dedup.claim(8453, "weth", "usdc"));
+    // та же пара в другой сети
+    assert!(dedup.claim(42161, "WETH", "USDC"));
+}
+
+#[test]
+fn strict_strategy_filters_unlisted_pair() {
+    let (strats, stables) = strategies();
+    let pair = ["WETH".to_string(), "CBBTC".to_string()];
+    let matched: Vec<&Strategy> = strats
+        .iter()
+        .filter(|s| pair_matches_strategy(s, &stables, &pair, &[]))
+        .collect();
+    assert_eq!(matched.len(), 1);
+    assert_eq!(strictest_strategy(&matched).unwrap().name, "loose");
+}
+
+fn s(v: &[&str]) -> Vec<String> {
+    v.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn overlapping_routes_quoted_and_executed_once() {
+    // один и тот же маршрут из двух шаблонов + тот же цикл в обратном порядке ног
+    let routes = [
+        (s(&["WETH", "USDC"]), s(&["UniswapV3", "AerodromeV2"]), 4.0),
+        (s(&["weth", "usdc"]), s(&["uniswapv3", "aerodromev2"]), 4.0),
+        (s(&["USDC", "WETH"]), s(&["AerodromeV2", "UniswapV3"]), 7.5),
+        (s(&["WETH", "USDC"]), s(&["AerodromeV2", "UniswapV3"]), 1.0),
+    ];
+    assert_eq!(
+        route_key(&routes[0].0, &routes[0].1),
+        route_key(&routes[2].0, &routes[2].1)
+    );
+    assert_ne!(
+        route_key(&routes[0].0, &routes[0].1),
+        route_key(&routes[3].0, &routes[3].1)
+    );
+
+    let mut dedup = PollDedup::default();
+    let mut quoted: Vec<_> = routes
+        .iter()
+        .filter(|(t, d, _)| dedup.first_quote(t, d))
+        .collect();
+    assert_eq!(quoted.len(), 3);
+
+    sort_by_pnl_desc(&mut quoted, |r| Some(r.2));
+    let executed: Vec<f64> = quoted
+        .iter()
+        .filter(|(t, d, _)| dedup.claim_route(8453, t, d) && dedup.claim(8453, &t[0], &t[1]))
+        .map(|r| r.2)
+        .collect();
+    // цикл WETH@Uni→USDC@Aero уходит один раз — в самом прибыльном представлении (7.5);
+    // WETH@Aero→USDC@Uni — другой цикл, исполняется отдельно
+    assert_eq!(executed, vec![7.5, 1.0]);
+}
+
+#[test]
+fn unfilled_quotes_sort_last() {
+    let mut v = vec![None, Some(1.0), Some(3.0), None, Some(2.0)];
+    sort_by_pnl_desc(&mut v, |x| *x);
+    assert_eq!(v, vec![Some(3.0), Some(2.0), Some(1.0), None, None]);
+}
+
+fn filled(amount_in: u64, amount_out: u64, pnl_usd: f64) -> QuoteOutcome<QuoteResult> {
+    QuoteOutcome::Filled(common::quote(amount_in, amount_out, Some(pnl_usd)))
+}
+
+#[test]
+fn conservative_and_aggressive_strategies_pick_different_routes() {
+    let (strats, stables) = strategies();
+    let mut stable = strats[0].clone();
+    stable.name = "stable_conservative".into();
+    stable.only_stables = Some(true);
+    stable.min_profit_bps = 5;
+    stable.max_notional_usd = Some(5_000.0);
+    let mut aggressive = strats[0].clone();
+    aggressive.name = "aggressive".into();
+    aggressive.min_profit_bps = 20;
+    aggressive.max_notional_usd = Some(500.0);
+    let all = [stable, aggressive];
+    let dexes = s(&["UniswapV3", "AerodromeV2"]);
+    let matching = |pair: [&str; 2]| -> Vec<String> {
+        let pair = [pair[0].to_string(), pair[1].to_string()];
+        all.iter()
+            .filter(|st| pair_matches_strategy(st, &stables, &pair, &dexes))
+            .map(|st| st.name.clone())
+            .collect()
+    };
+    // стейбл-маршрут видят обе стратегии, WETH-AERO — только агрессивная
+    assert_eq!(matching(["USDC", "USDBC"]), ["stable_conservative", "aggressive"]);
+    assert_eq!(matching(["WETH", "AERO"]), ["aggressive"]);
+
+    // USDC-USDBC: большой вход консервативной даёт 8 bps — проходит её 5 bps;
+    // малый вход агрессивной газ не окупает и до 20 bps не дотягивает
+    let quotes = [filled(5_000, 5_004, 3.5), filled(500, 500, 0.4)];
+    let cands = [(5, u64::MAX, 0), (20, u64::MAX, 1)];
+    assert_eq!(best_strategy_quote(&cands, &quotes), Some(0));
+
+    // спред узкий по глубине: малый вход агрессивной прибыльнее
+    let quotes = [filled(5_000, 5_003, 0.8), filled(500, 502, 1.6)];
+    assert_eq!(best_strategy_quote(&cands, &quotes), Some(1));
+
+    // обе стратегии с одним размером делят котировку: побеждает та, чей порог пройден
+    let quotes = [filled(1_000, 1_001, 0.9)];
+    assert_eq!(best_strategy_quote(&[(20, u64::MAX, 0), (5, u64::MAX, 0)], &quotes), Some(1));
+
+    // многоногий маршрут дороже лимита газа консервативной — уходит с агрессивной
+    let mut heavy = filled(5_000, 5_010, 6.0);
+    if let QuoteOutcome::Filled(qr) = &mut heavy {
+        qr.gas_estimate = 900_000;
+    }
+    let quotes = [heavy, filled(500, 502, 1.6)];
+    assert_eq!(best_strategy_quote(&[(5, 600_000, 0), (20, 1_500_000, 1)], &quotes), Some(1));
+
+    // никто не прошёл свой порог — выбора нет
+    let quotes = [QuoteOutcome::ZeroOutput, filled(500, 500, -0.2)];
+    assert_eq!(best_strategy_quote(&[(20, u64::MAX, 1), (5, u64::MAX, 0)], &quotes), None);
+}
+
+#[tokio::test]
+async fn route_below_every_strategy_threshold_is_not_sent() {
+    let mut cfg = common::single_network(8453);
+    let market = common::v2_market(&mut cfg);
+    // пара даёт ~1.5%, треугольник ~5%: порог каждой стратегии — 100%, у сети порога нет
+    for st in &mut cfg.strategies {
+        st.min_profit_bps = 10_000;
+    }
+    cfg.networks[0].strategy_overrides = None;
+    let dir = std::env::temp_dir().join(format!("defi-arb-strategy-bps-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let ledger = dir.join("paper.jsonl");
+    cfg.telemetry.paper_trading.enabled = true;
+    cfg.telemetry.paper_trading.backend = "jsonl".into();
+    cfg.telemetry.paper_trading.path = ledger.to_string_lossy().into_owned();
+    cfg.networks[0].rpc = vec![format!("http://{}", common::v2_node(market.pairs).await)];
+
+    let chains = Arc::new(MultiChain::from_config(&cfg).await.expect("clients"));
+    chains.clients[&8453].prewarm_pools(market.pools);
+    let mut engine = common::engine_on(&cfg, chains).await.expect("engine");
+    let below = METRIC_ROUTES_SKIPPED.with_label_values(&["8453", "below_min_profit"]);
+    let before = below.get();
+    engine.scan_and_execute().await.expect("scan");
+
+    // ни кандидата в бумажном журнале, ни отправки: оба маршрута — below_min_profit
+    let fills = std::fs::read_to_string(&ledger).unwrap_or_default();
+    assert!(fills.trim().is_empty(), "{fills}");
+    assert!(below.get() - before >= 2.0);
+}
@@ -0,0 +1,30 @@
+use DeFiArbitraje::route::{SkipReason, route_tx_opts};
+use DeFiArbitraje::utils_gas::GasPriceMode;
+use crate::common;
+use ethers::types::U256;
+
+#[test]
+fn strategy_gas_limit_reaches_tx_opts() {
+    let cfg = common::config();
+    let stable = cfg
+        .strategies
+        .iter()
+        .find(|s| s.name == "stable_stable_mispricing")
+        .unwrap();
+    assert_eq!(stable.gas_limit, 1_200_000);
+
+    let opts = route_tx_opts(Some(stable.gas_limit), GasPriceMode::Eip1559, None);
+    assert_eq!(opts.gas_limit, Some(1_200_000));
+    assert!(!opts.legacy_tx);
+
+    // повтор после revert в legacy-режиме: лимит стратегии сохраняется
+    let retry = U256::from(3_000_000_000u64);
+    let opts = route_tx_opts(Some(stable.gas_limit), GasPriceMode::Legacy, Some(retry));
+    assert_eq!(opts.gas_limit, Some(1_200_000));
+    assert!(opts.legacy_tx);
+    assert_eq!(opts.legacy_gas_price, Some(retry));
+
+    // без стратегии — дефолт экзекутора
+    assert_eq!(route_tx_opts(None, GasPriceMode::Eip1559, None).gas_limit, None);
+    assert_eq!(SkipReason::OverGasLimit.label(), "over_gas_limit");
+}
@@ -0,0 +1,54 @@
+use DeFiArbitraje::config::{Network, Risk};
+use DeFiArbitraje::route::{SkipReason, pair_risk_skip};
+use crate::common;
+
+fn base() -> Network {
+    common::network(8453)
+}
+
+fn risk(allow: &[&str], deny: &[&str]) -> Risk {
+    serde_json::from_value(serde_json::json!({
+        "allowlist_tokens": allow,
+        "blacklist_tokens": deny,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn empty_allowlist_keeps_blacklist_only() {
+    let net = base();
+    assert_eq!(pair_risk_skip(&net, &risk(&[], &[]), "WETH", "AERO"), None);
+    // чёрный список — по символу или по адресу токена
+    let aero = net.tokens["AERO"].address.to_uppercase();
+    assert_eq!(
+        pair_risk_skip(&net, &risk(&[], &[&aero]), "WETH", "AERO"),
+        Some(SkipReason::Blacklisted)
+    );
+    assert_eq!(
+        pair_risk_skip(&net, &risk(&[], &["aero"]), "AERO", "WETH"),
+        Some(SkipReason::Blacklisted)
+    );
+}
+
+#[test]
+fn allowlist_skips_any_token_outside_it() {
+    let net = base();
+    let blue_chips = risk(&["WETH", "USDC", &net.tokens["CBBTC"].address], &[]);
+    assert_eq!(pair_risk_skip(&net, &blue_chips, "WETH", "USDC"), None);
+    assert_eq!(pair_risk_skip(&net, &blue_chips, "CBBTC", "USDC"), None);
+    assert_eq!(
+        pair_risk_skip(&net, &blue_chips, "WETH", "AERO"),
+        Some(SkipReason::NotAllowlisted)
+    );
+    assert_eq!(SkipReason::NotAllowlisted.label(), "not_allowlisted");
+}
+
+#[test]
+fn blacklist_still_applies_inside_allowlist() {
+    let net = base();
+    let both = risk(&["WETH", "USDC", "USDT"], &["USDT"]);
+    assert_eq!(pair_risk_skip(&net, &both, "USDC", "USDT"), Some(SkipReason::Blacklisted));
+    // вне allowlist — причина allowlist, даже если токен ещё и в чёрном списке
+    let outside = risk(&["WETH", "USDC"], &["AERO"]);
+    assert_eq!(pair_risk_skip(&net, &outside, "WETH", "AERO"), Some(SkipReason::NotAllowlisted));
+}
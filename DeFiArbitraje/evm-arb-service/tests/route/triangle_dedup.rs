@@ -0,0 +1,35 @@
+use DeFiArbitraje::metrics::METRIC_ROUTES_SKIPPED;
+use DeFiArbitraje::network::MultiChain;
+use crate::common;
+use serde_json::Value;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn triangle_and_pair_sharing_an_edge_fill_once() {
+    let mut cfg = common::single_network(8453);
+    let market = common::v2_market(&mut cfg);
+    let dir = std::env::temp_dir().join(format!("defi-arb-tri-dedup-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let ledger = dir.join("paper.jsonl");
+    cfg.telemetry.paper_trading.enabled = true;
+    cfg.telemetry.paper_trading.backend = "jsonl".into();
+    cfg.telemetry.paper_trading.path = ledger.to_string_lossy().into_owned();
+    cfg.networks[0].rpc = vec![format!("http://{}", common::v2_node(market.pairs).await)];
+
+    let chains = Arc::new(MultiChain::from_config(&cfg).await.expect("clients"));
+    chains.clients[&8453].prewarm_pools(market.pools);
+    let mut engine = common::engine_on(&cfg, chains).await.expect("engine");
+    let duplicates = METRIC_ROUTES_SKIPPED.with_label_values(&["8453", "duplicate"]);
+    let dup_before = duplicates.get();
+    engine.scan_and_execute().await.expect("scan");
+
+    // без экзекутора кандидат уходит в бумажный журнал — ровно один на пересекающиеся маршруты
+    let fills: Vec<Value> = std::fs::read_to_string(&ledger)
+        .expect("paper ledger")
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(fills.len(), 1, "{fills:?}");
+    assert_eq!(fills[0]["route"], "WETH-USDC-USDBC", "{fills:?}");
+    assert_eq!(duplicates.get() - dup_before, 1.0);
+}
@@ -0,0 +1,29 @@
+use DeFiArbitraje::route::VolatilityIndex;
+
+#[test]
+fn flat_price_has_zero_index() {
+    let mut v = VolatilityIndex::new(5);
+    v.push(3000.0);
+    assert_eq!(v.index(), None);
+    v.push(3000.0);
+    assert_eq!(v.index(), Some(0.0));
+}
+
+#[test]
+fn index_tracks_only_recent_window() {
+    let mut v = VolatilityIndex::new(4);
+    for p in [2000.0, 2600.0, 2000.0, 2600.0] {
+        v.push(p);
+    }
+    // mean 2300, stddev 300
+    let chaotic = v.index().unwrap();
+    assert!((chaotic - 300.0 / 2300.0).abs() < 1e-12);
+    for _ in 0..4 {
+        v.push(2300.0);
+    }
+    assert_eq!(v.index(), Some(0.0));
+    // мусорные цены не попадают в окно
+    v.push(f64::NAN);
+    v.push(0.0);
+    assert_eq!(v.index(), Some(0.0));
+}
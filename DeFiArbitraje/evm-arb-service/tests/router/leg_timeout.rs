@@ -0,0 +1,31 @@
+use DeFiArbitraje::metrics::METRIC_LEG_QUOTE_TIMEOUTS;
+use DeFiArbitraje::router::{QuoteOutcome, with_leg_timeout};
+use std::time::Duration;
+
+#[tokio::test]
+async fn hanging_leg_is_skipped_on_timeout() {
+    let before = METRIC_LEG_QUOTE_TIMEOUTS.with_label_values(&["999"]).get();
+    let hung = std::future::pending::<anyhow::Result<QuoteOutcome<u64>>>();
+    let res = with_leg_timeout(999, "uni:WETH-USDC", Duration::from_millis(20), hung)
+        .await
+        .expect("timeout is not an error");
+    assert_eq!(res.reason(), "timeout");
+    let after = METRIC_LEG_QUOTE_TIMEOUTS.with_label_values(&["999"]).get();
+    assert_eq!(after - before, 1.0);
+}
+
+#[tokio::test]
+async fn fast_leg_passes_through() {
+    let res = with_leg_timeout(999, "uni:WETH-USDC", Duration::from_millis(500), async {
+        Ok(QuoteOutcome::Filled(42u64))
+    })
+    .await
+    .unwrap();
+    assert_eq!(res.filled(), Some(42));
+
+    let err = with_leg_timeout(999, "uni:WETH-USDC", Duration::ZERO, async {
+        Err::<QuoteOutcome<u64>, _>(anyhow::anyhow!("boom"))
+    })
+    .await;
+    assert!(err.is_err());
+}
@@ -0,0 +1,16 @@
+//! Котировки (`router`, `dex`, `prices`): ноги по типам DEX, пути, предотсев, USD-цены
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod leg_timeout;
+mod native_wrap;
+mod optional_tokens;
+mod pair_spread;
+mod prescreen;
+mod price_oracle;
+mod quote_outcome;
+mod v2_multihop;
+mod v2_reserve_guard;
+mod v3_tick_sample;
+mod v4_pools;
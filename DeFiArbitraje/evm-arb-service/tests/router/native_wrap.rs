@@ -0,0 +1,89 @@
+use DeFiArbitraje::calldata::{LegKind, LegQuote, encode_route_calldata, validate_route};
+use DeFiArbitraje::router::{WRAP_LEG_GAS, wrap_native_ends};
+use crate::common;
+use ethers::abi::{ParamType, Token, decode};
+use ethers::types::{Address, U256};
+
+fn base() -> DeFiArbitraje::config::Network {
+    common::network(8453)
+}
+
+fn swap_legs() -> Vec<LegQuote> {
+    let (a, b) = (Address::from_low_u64_be(0xa), Address::from_low_u64_be(0xb));
+    [(a, b, 2_000_000u64), (b, a, 1_020)]
+        .into_iter()
+        .map(|(token_in, token_out, out)| LegQuote {
+            kind: LegKind::AlgebraV3 {
+                router: Address::from_low_u64_be(0x4),
+                token_in,
+                token_out,
+            },
+            quoted_out: U256::from(out),
+        })
+        .collect()
+}
+
+#[test]
+fn native_symbol_quotes_through_wrapped_token() {
+    let net = base();
+    assert!(net.is_unwrapped_native("ETH"));
+    assert_eq!(net.quote_symbol("ETH"), "WETH");
+    assert!(!net.is_unwrapped_native("WETH"));
+    assert_eq!(net.quote_symbol("USDC"), "USDC");
+}
+
+#[test]
+fn erc20_route_gets_no_wrap_legs() {
+    let mut legs = swap_legs();
+    let gas = wrap_native_ends(&base(), "USDC", &mut legs, U256::from(1000), U256::from(1020));
+    assert_eq!(gas.unwrap(), 0);
+    assert_eq!(legs.len(), 2);
+}
+
+#[test]
+fn native_route_is_wrapped_and_unwrapped() {
+    let net = base();
+    let weth: Address = net.tokens["WETH"].address.parse().unwrap();
+    let mut legs = swap_legs();
+    let gas = wrap_native_ends(&net, "ETH", &mut legs, U256::from(1000), U256::from(1020)).unwrap();
+    assert_eq!(gas, 2 * WRAP_LEG_GAS);
+    assert_eq!(legs.len(), 4);
+    assert!(matches!(legs[0].kind, LegKind::Wrap { weth: w } if w == weth));
+    assert!(matches!(legs[3].kind, LegKind::Unwrap { weth: w } if w == weth));
+    assert_eq!(legs[0].router(), weth);
+
+    // обёртки не считаются в лимит ног: две свап-ноги при max_legs = 2
+    validate_route(&legs, U256::from(1000), U256::from(1010), 2).unwrap();
+
+    let data = encode_route_calldata(
+        &legs,
+        U256::from(1000),
+        U256::from(1010),
+        None,
+        U256::zero(),
+        2,
+    )
+    .unwrap();
+    let algebra = [
+        ParamType::Uint(8),
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Uint(256),
+    ];
+    let mut types = vec![ParamType::Uint(256); 3];
+    types.extend([ParamType::Uint(8), ParamType::Address, ParamType::Uint(256)]);
+    types.extend(algebra.clone());
+    types.extend(algebra);
+    types.extend([ParamType::Uint(8), ParamType::Address, ParamType::Uint(256)]);
+    types.push(ParamType::Uint(256));
+    let out = decode(&types, &data).unwrap();
+    assert_eq!(out[2], Token::Uint(U256::from(4)));
+    // Wrap: тег 6 и адрес WETH
+    assert_eq!(out[3], Token::Uint(U256::from(6)));
+    assert_eq!(out[4], Token::Address(weth));
+    // Unwrap: тег 7 после двух algebra-ног, минимум = min_out маршрута
+    assert_eq!(out[16], Token::Uint(U256::from(7)));
+    assert_eq!(out[17], Token::Address(weth));
+    assert_eq!(out[18], Token::Uint(U256::from(1010)));
+}
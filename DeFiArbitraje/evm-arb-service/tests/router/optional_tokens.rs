@@ -0,0 +1,23 @@
+use DeFiArbitraje::router::unresolved_pool;
+use crate::common;
+
+fn base_network() -> DeFiArbitraje::config::Network {
+    common::network(8453)
+}
+
+#[test]
+fn optional_token_missing_pool_skips_route() {
+    let net = base_network();
+    assert!(net.tokens["AERO"].optional);
+    assert!(unresolved_pool(&net, "WETH", "AERO", "v2 pair").is_ok());
+    assert!(unresolved_pool(&net, "aero", "USDC", "v3 pool").is_ok());
+}
+
+#[test]
+fn required_token_missing_pool_errors() {
+    let net = base_network();
+    let err = unresolved_pool(&net, "WETH", "USDC", "v2 pair").unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("v2 pair not found"), "{msg}");
+    assert!(msg.contains("WETH-USDC"), "{msg}");
+}
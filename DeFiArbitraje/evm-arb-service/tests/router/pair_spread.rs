@@ -0,0 +1,22 @@
+use DeFiArbitraje::metrics::METRIC_PAIR_SPREAD_BPS;
+use DeFiArbitraje::router::{record_pair_spread, round_trip_spread_bps};
+use ethers::types::U256;
+
+#[test]
+fn round_trip_spread_can_be_negative() {
+    let amount_in = U256::from(1_000_000u64);
+    assert!((round_trip_spread_bps(amount_in, U256::from(1_003_000u64)) - 30.0).abs() < 1e-9);
+    // круг в минус (комиссии пулов съели разницу) — тоже near-miss для дашборда
+    assert!((round_trip_spread_bps(amount_in, U256::from(998_000u64)) + 20.0).abs() < 1e-9);
+}
+
+#[test]
+fn spread_gauge_is_per_chain_and_pair() {
+    record_pair_spread(8453, ("WETH", "USDC"), 12.5);
+    record_pair_spread(8453, ("USDC", "USDT"), -3.0);
+    record_pair_spread(8453, ("WETH", "USDC"), 7.5);
+
+    let get = |pair: &str| METRIC_PAIR_SPREAD_BPS.with_label_values(&["8453", pair]).get();
+    assert_eq!(get("WETH-USDC"), 7.5);
+    assert_eq!(get("USDC-USDT"), -3.0);
+}
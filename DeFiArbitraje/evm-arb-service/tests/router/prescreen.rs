@@ -0,0 +1,47 @@
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::route::SkipReason;
+use DeFiArbitraje::router::{implied_spread_bps, prescreen_cross_dex_pair};
+use crate::common;
+use ethers::types::{Address, U256};
+use std::time::Duration;
+
+#[test]
+fn spread_is_relative_to_second_dex() {
+    let b = U256::from(2_000_000u64);
+    // dex_a даёт на 0.5% больше B — круг через dex_b окупает ~50 bps
+    let spread = implied_spread_bps(U256::from(2_010_000u64), b);
+    assert!((spread - 50.0).abs() < 1e-9);
+    assert!(implied_spread_bps(U256::from(1_990_000u64), b) < 0.0);
+    assert_eq!(implied_spread_bps(b, U256::zero()), 0.0);
+    assert_eq!(SkipReason::NoSpread.label(), "no_spread");
+}
+
+#[tokio::test]
+async fn unquotable_leg_defers_to_exact_quote() {
+    let cfg = common::single_network(8453);
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    let client = &chains.clients[&8453];
+    let net = client.cfg.clone();
+    let dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap();
+    let factory: Address = dex.factory.as_ref().unwrap().parse().unwrap();
+    let weth: Address = net.tokens["WETH"].address.parse().unwrap();
+    let aero: Address = net.tokens["AERO"].address.parse().unwrap();
+    // пулов WETH-AERO нет ни в одном fee tier — RPC не трогается
+    let tiers = dex.fee_tiers_bps.clone().unwrap();
+    client.prewarm_pools(tiers.into_iter().map(|fee| ((factory, weth, aero, fee), Address::zero())));
+
+    let spread = prescreen_cross_dex_pair(
+        client,
+        &net,
+        ("WETH", "AERO"),
+        &[],
+        dex,
+        dex,
+        U256::exp10(18),
+        Duration::from_secs(1),
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(spread, None);
+}
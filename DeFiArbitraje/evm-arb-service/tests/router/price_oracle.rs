@@ -0,0 +1,88 @@
+use DeFiArbitraje::network::MultiChain;
+use DeFiArbitraje::prices::{chainlink_price, price_feed_of, round_is_stale, PriceCache};
+use DeFiArbitraje::report::unix_now;
+use crate::common;
+use ethers::types::{Address, I256, U256};
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+const ETH_USD_FEED: &str = "0x71041dddad3595f9ced3dccfbe3d1f4b0a16bb70";
+
+#[test]
+fn chainlink_answer_scaled_by_decimals() {
+    let px = chainlink_price(I256::from(345_612_000_000i64), 8).unwrap();
+    assert!((px - 3456.12).abs() < 1e-9);
+    assert_eq!(chainlink_price(I256::zero(), 8), None);
+    assert_eq!(chainlink_price(I256::from(-1), 8), None);
+}
+
+#[test]
+fn cache_expires_after_ttl() {
+    let cache = PriceCache::new(Duration::from_secs(30));
+    let t0 = Instant::now();
+    cache.insert(8453, "weth", 3000.0, t0);
+    assert_eq!(cache.get(8453, "WETH", t0 + Duration::from_secs(10)), Some(3000.0));
+    assert_eq!(cache.get(10, "WETH", t0), None);
+    assert_eq!(cache.get(8453, "WETH", t0 + Duration::from_secs(31)), None);
+}
+
+#[test]
+fn native_uses_wrapped_native_feed() {
+    let mut net = common::network(8453);
+    assert_eq!(price_feed_of(&net, "ETH"), None);
+    net.tokens.get_mut("WETH").unwrap().price_feed = Some(ETH_USD_FEED.into());
+    let feed: Address = ETH_USD_FEED.parse().unwrap();
+    assert_eq!(price_feed_of(&net, "ETH"), Some(feed));
+    assert_eq!(price_feed_of(&net, "weth"), Some(feed));
+    assert_eq!(price_feed_of(&net, "USDC"), None);
+}
+
+#[test]
+fn round_older_than_max_age_is_stale() {
+    let now = 1_700_000_000;
+    let updated = |ago: u64| U256::from(now - ago);
+    assert!(!round_is_stale(updated(600), now, 3_600));
+    assert!(!round_is_stale(updated(3_600), now, 3_600));
+    assert!(round_is_stale(updated(3_601), now, 3_600));
+    // 0 — проверка выключена
+    assert!(!round_is_stale(updated(86_400), now, 0));
+}
+
+/// Цена WETH из фида-заглушки (answer 3000, раунд `age` секунд назад);
+/// статическая подсказка — 2000
+async fn weth_price_with_round_age(age: u64) -> Option<f64> {
+    let updated = unix_now() - age;
+    let addr = common::rpc_stub(move |call| {
+        let data = call["params"][0]["data"].as_str().or(call["params"][0]["input"].as_str());
+        Ok(match data.map(|d| &d[..10]) {
+            // latestRoundData: roundId, answer, startedAt, updatedAt, answeredInRound
+            Some("0xfeaf968c") => json!(format!(
+                "0x{:064x}{:064x}{:064x}{:064x}{:064x}",
+                1,
+                300_000_000_000u64,
+                updated,
+                updated,
+                1
+            )),
+            Some("0x313ce567") => json!(format!("0x{:064x}", 8)),
+            _ => json!("0x1"),
+        })
+    })
+    .await;
+    let mut cfg = common::single_network(8453);
+    cfg.rpc.max_block_lag = 0;
+    cfg.global.quote.price_feed_max_age_secs = 3_600;
+    let net = &mut cfg.networks[0];
+    net.rpc = vec![format!("http://{addr}")];
+    let weth = net.tokens.get_mut("WETH").unwrap();
+    weth.price_feed = Some(ETH_USD_FEED.into());
+    weth.usd_price = Some(2_000.0);
+    let chains = MultiChain::from_config(&cfg).await.unwrap();
+    chains.clients[&8453].usd_price("WETH").await
+}
+
+#[tokio::test]
+async fn stale_round_falls_back_to_static_hint() {
+    assert_eq!(weth_price_with_round_age(60).await, Some(3_000.0));
+    assert_eq!(weth_price_with_round_age(7_200).await, Some(2_000.0));
+}
@@ -0,0 +1,97 @@
+use DeFiArbitraje::calldata::{LegKind, LegQuote};
+use DeFiArbitraje::router::{QuoteOutcome, leg_outcome, quote_on_dex};
+use crate::common::{self, token};
+use ethers::types::{Address, U256};
+
+#[tokio::test]
+async fn missing_dex_address_is_missing_config() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let mut dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap().clone();
+    dex.factory = None;
+    let res = quote_on_dex(&client, &net, &dex, &["WETH", "USDC"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    match res {
+        QuoteOutcome::MissingConfig(e) => assert!(e.to_string().contains("v3 factory"), "{e}"),
+        other => panic!("expected missing_config, got {}", other.reason()),
+    }
+
+    dex.dex_type = "curve".into();
+    let res = quote_on_dex(&client, &net, &dex, &["WETH", "USDC"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    assert_eq!(res.reason(), "missing_config");
+}
+
+#[tokio::test]
+async fn algebra_without_quoter_is_missing_config() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let mut dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap().clone();
+    dex.dex_type = "v3_algebra".into();
+    dex.quoter = None;
+    let res = quote_on_dex(&client, &net, &dex, &["WETH", "USDC"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    match res {
+        QuoteOutcome::MissingConfig(e) => assert!(e.to_string().contains("algebra quoter"), "{e}"),
+        other => panic!("expected missing_config, got {}", other.reason()),
+    }
+}
+
+#[tokio::test]
+async fn optional_token_without_algebra_pool_is_no_pool() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let mut dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap().clone();
+    dex.dex_type = "v3_algebra".into();
+    dex.quoter = dex.swap_router02.clone();
+    let factory: Address = dex.factory.as_ref().unwrap().parse().unwrap();
+    let (weth, aero) = (token(&net, "WETH"), token(&net, "AERO"));
+    client.prewarm_pools([((factory, weth, aero, 0), Address::zero())]);
+
+    let res = quote_on_dex(&client, &net, &dex, &["WETH", "AERO"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    assert_eq!(res.reason(), "no_pool");
+}
+
+#[tokio::test]
+async fn optional_token_without_pool_is_no_pool() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let dex = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap();
+    let factory: Address = dex.factory.as_ref().unwrap().parse().unwrap();
+    let (weth, aero) = (token(&net, "WETH"), token(&net, "AERO"));
+    // все fee tier'ы закэшированы как «пула нет» — RPC не трогается
+    let tiers = dex.fee_tiers_bps.clone().unwrap();
+    client.prewarm_pools(tiers.into_iter().map(|fee| ((factory, weth, aero, fee), Address::zero())));
+
+    let res = quote_on_dex(&client, &net, dex, &["WETH", "AERO"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    assert_eq!(res.reason(), "no_pool");
+}
+
+#[test]
+fn zero_leg_output_is_not_filled() {
+    let leg = || LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(1),
+            path: vec![Address::from_low_u64_be(2), Address::from_low_u64_be(3)],
+        },
+        quoted_out: U256::zero(),
+    };
+    assert_eq!(leg_outcome(U256::zero(), leg(), 110_000).reason(), "zero_output");
+    let filled = leg_outcome(U256::from(5), leg(), 110_000).filled().unwrap();
+    assert_eq!((filled.0, filled.2), (U256::from(5), 110_000));
+}
+
+#[test]
+fn skip_keeps_reason() {
+    let timeout: QuoteOutcome<u64> = QuoteOutcome::Timeout;
+    assert_eq!(timeout.skip::<String>().reason(), "timeout");
+    let below: QuoteOutcome<u64> = QuoteOutcome::BelowMinOut;
+    assert_eq!(below.skip::<()>().reason(), "below_min_out");
+}
@@ -0,0 +1,82 @@
+use DeFiArbitraje::config::RouteDex;
+use DeFiArbitraje::dex::{amount_out_v2, amount_out_v2_path};
+use DeFiArbitraje::route::warmup_legs;
+use DeFiArbitraje::router::quote_on_dex;
+use crate::common;
+use ethers::types::{Address, U256};
+
+fn via_route(via: &[&str]) -> RouteDex {
+    RouteDex {
+        pair: ["USDC".into(), "WBTC".into()],
+        dexes: vec!["QuickSwapV2".into(), "QuickSwapV2".into()],
+        via: via.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn three_token_path_chains_reserves() {
+    let u = U256::from;
+    // USDC→WETH (10k/20k), затем WETH→WBTC (50k/5k), комиссия 0.3%
+    let hops = [(u(10_000), u(20_000)), (u(50_000), u(5_000))];
+    let first = amount_out_v2(u(1_000), hops[0].0, hops[0].1, 30);
+    assert_eq!(first, u(1_813));
+    assert_eq!(amount_out_v2_path(u(1_000), &hops, 30), u(174));
+    assert_eq!(amount_out_v2_path(u(1_000), &hops[..1], 30), first);
+    // пустой резерв на любом хопе обнуляет выход
+    assert!(amount_out_v2_path(u(1_000), &[hops[0], (u(0), u(5_000))], 30).is_zero());
+}
+
+#[tokio::test]
+async fn multihop_path_is_v2_only() {
+    let client = common::client(137).await;
+    let net = client.cfg.clone();
+    let path = ["USDC", "WETH", "WBTC"];
+    let v3 = net.dexes.iter().find(|d| d.name == "UniswapV3").unwrap();
+    let res = quote_on_dex(&client, &net, v3, &path, U256::exp10(6), 0).await.unwrap();
+    assert_eq!(res.reason(), "missing_config");
+    // путь из одного токена — не нога
+    let v2 = net.dexes.iter().find(|d| d.name == "QuickSwapV2").unwrap();
+    assert!(quote_on_dex(&client, &net, v2, &path[..1], U256::one(), 0).await.is_err());
+}
+
+#[tokio::test]
+async fn missing_hop_pair_is_no_pool() {
+    let client = common::client(137).await;
+    let net = client.cfg.clone();
+    let dex = net.dexes.iter().find(|d| d.name == "QuickSwapV2").unwrap();
+    let addr = |sym: &str| -> Address { net.tokens[sym].address.parse().unwrap() };
+    let factory: Address = dex.factory.as_ref().unwrap().parse().unwrap();
+    // WETH опциональный: пары USDC-WETH нет — хоп пропускается без RPC
+    client.prewarm_pools([((factory, addr("USDC"), addr("WETH"), 0), Address::zero())]);
+
+    let res = quote_on_dex(&client, &net, dex, &["USDC", "WETH", "WBTC"], U256::exp10(6), 0)
+        .await
+        .unwrap();
+    assert_eq!(res.reason(), "no_pool");
+}
+
+#[test]
+fn route_via_warms_every_hop() {
+    let mut cfg = common::single_network(137);
+    let net = &mut cfg.networks[0];
+    net.routes_cross_dex = Some(vec![via_route(&["WETH"])]);
+    assert_eq!(net.routes_cross_dex.as_ref().unwrap()[0].path(), ["USDC", "WETH", "WBTC"]);
+    let legs: Vec<(String, String, String)> = [
+        ("USDC", "WETH"),
+        ("WETH", "WBTC"),
+        ("WBTC", "WETH"),
+        ("WETH", "USDC"),
+    ]
+    .iter()
+    .map(|(a, b)| ("QuickSwapV2".to_string(), a.to_string(), b.to_string()))
+    .collect();
+    assert_eq!(warmup_legs(net), legs);
+}
+
+#[test]
+fn route_via_unknown_token_fails_validation() {
+    let mut cfg = common::single_network(137);
+    cfg.networks[0].routes_cross_dex = Some(vec![via_route(&["NOPE"])]);
+    let err = cfg.validate().unwrap_err();
+    assert!(err.to_string().contains("via unknown token 'NOPE'"), "{err}");
+}
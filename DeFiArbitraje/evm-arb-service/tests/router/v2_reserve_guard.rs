@@ -0,0 +1,34 @@
+use DeFiArbitraje::config::V2ReserveGuard;
+use DeFiArbitraje::router::v2_reserve_violation;
+use crate::common;
+
+#[test]
+fn rejects_dust_and_skewed_pools() {
+    let guard = V2ReserveGuard { max_ratio: 3.0, min_reserve_usd: 10_000.0 };
+    // сбалансированный пул
+    assert_eq!(v2_reserve_violation(&guard, [Some(250_000.0), Some(240_000.0)]), None);
+    // пыльный: меньшая сторона ниже порога
+    let why = v2_reserve_violation(&guard, [Some(900.0), Some(850.0)]).unwrap();
+    assert!(why.contains("min_reserve_usd"), "{why}");
+    // перекос: пул далеко от рыночной цены (honeypot / мёртвый пул)
+    let why = v2_reserve_violation(&guard, [Some(400_000.0), Some(20_000.0)]).unwrap();
+    assert!(why.contains("max_ratio"), "{why}");
+    // одна сторона без цены: перекос не оценить, порог — по известной стороне
+    assert_eq!(v2_reserve_violation(&guard, [Some(50_000.0), None]), None);
+    assert!(v2_reserve_violation(&guard, [None, Some(10.0)]).is_some());
+    assert_eq!(v2_reserve_violation(&guard, [None, None]), None);
+}
+
+#[test]
+fn guard_is_per_network_and_off_by_default() {
+    let off = V2ReserveGuard::default();
+    assert!(!off.enabled());
+    assert_eq!(v2_reserve_violation(&off, [Some(1.0), Some(1_000_000.0)]), None);
+
+    let cfg = common::config();
+    let base = cfg.networks.iter().find(|n| n.chain_id == 8453).unwrap();
+    assert!(base.v2_reserve_guard.enabled());
+    assert_eq!(base.v2_reserve_guard.max_ratio, 3.0);
+    let arb = cfg.networks.iter().find(|n| n.chain_id == 42161).unwrap();
+    assert!(!arb.v2_reserve_guard.enabled());
+}
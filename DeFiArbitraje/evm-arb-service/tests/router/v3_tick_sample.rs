@@ -0,0 +1,42 @@
+use DeFiArbitraje::dex::{TickSample, v3_input_capacity};
+use ethers::types::U256;
+
+// sqrtPriceX96 для тика 0 (цена 1.0)
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+fn close(a: f64, b: f64) -> bool {
+    ((a - b) / b).abs() < 1e-6
+}
+
+#[test]
+fn one_for_zero_capacity_sums_ranges() {
+    let l = 1e18;
+    let samples = [
+        TickSample { tick: 100, liquidity: 1_000_000_000_000_000_000 },
+        TickSample { tick: 200, liquidity: 500_000_000_000_000_000 },
+    ];
+    let s100 = 1.0001f64.powf(50.0);
+    let s200 = 1.0001f64.powf(100.0);
+    // Δy = L·(√P_b − √P_a) по каждому участку
+    let expected = l * (s100 - 1.0) + 0.5 * l * (s200 - s100);
+    assert!(close(v3_input_capacity(q96(), &samples, false), expected));
+}
+
+#[test]
+fn zero_for_one_capacity_moves_price_down() {
+    let samples = [TickSample { tick: -60, liquidity: 2_000_000_000_000_000_000 }];
+    let s = 1.0001f64.powf(-30.0);
+    let expected = 2e18 * (1.0 / s - 1.0);
+    assert!(close(v3_input_capacity(q96(), &samples, true), expected));
+    // тики «не в ту сторону» ликвидности не добавляют
+    assert_eq!(v3_input_capacity(q96(), &samples, false), 0.0);
+}
+
+#[test]
+fn empty_pool_has_no_capacity() {
+    assert_eq!(v3_input_capacity(q96(), &[], true), 0.0);
+    let dry = [TickSample { tick: 600, liquidity: 0 }];
+    assert_eq!(v3_input_capacity(q96(), &dry, false), 0.0);
+}
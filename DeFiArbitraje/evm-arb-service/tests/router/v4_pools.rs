@@ -0,0 +1,163 @@
+use DeFiArbitraje::calldata::{LegKind, LegQuote, V4PoolKey, encode_route_calldata, quote_only};
+use DeFiArbitraje::dex::{
+    v4_default_tick_spacing, v4_pool_state_slot, v4_quote_exact_input_single,
+};
+use DeFiArbitraje::route::SkipReason;
+use DeFiArbitraje::router::{QuoteOutcome, quote_on_dex};
+use crate::common;
+use ethers::abi::{ParamType, Token, decode};
+use ethers::providers::{Http, Provider};
+use ethers::types::{Address, BlockId, BlockNumber, I256, U256};
+use std::sync::Arc;
+
+#[test]
+fn pool_key_sorts_currencies_and_id_is_order_independent() {
+    let (a, b) = (Address::from_low_u64_be(0xb), Address::from_low_u64_be(0xa));
+    let k1 = V4PoolKey::new(a, b, 3000, 60, Address::zero());
+    let k2 = V4PoolKey::new(b, a, 3000, 60, Address::zero());
+    assert_eq!(k1.currency0, b);
+    assert_eq!(k1, k2);
+    assert_eq!(k1.pool_id(), k2.pool_id());
+    assert_ne!(k1.pool_id(), V4PoolKey::new(a, b, 500, 10, Address::zero()).pool_id());
+    assert_ne!(v4_pool_state_slot(k1.pool_id()), k1.pool_id());
+}
+
+#[test]
+fn default_tick_spacing_follows_fee_tiers() {
+    assert_eq!(v4_default_tick_spacing(100), Some(1));
+    assert_eq!(v4_default_tick_spacing(3000), Some(60));
+    assert_eq!(v4_default_tick_spacing(10_000), Some(200));
+    assert_eq!(v4_default_tick_spacing(2500), None);
+}
+
+#[test]
+fn v4_routes_are_quote_only() {
+    let key = V4PoolKey::new(
+        Address::from_low_u64_be(0xa),
+        Address::from_low_u64_be(0xb),
+        500,
+        10,
+        Address::zero(),
+    );
+    let v2 = LegQuote {
+        kind: LegKind::V2 {
+            router: Address::from_low_u64_be(0x2),
+            path: vec![Address::from_low_u64_be(0xb), Address::from_low_u64_be(0xa)],
+        },
+        quoted_out: U256::from(1020),
+    };
+    let router = Address::from_low_u64_be(0x5);
+    let v4 = LegQuote {
+        kind: LegKind::V4 { router, pool_key: key, zero_for_one: true },
+        quoted_out: U256::from(1020),
+    };
+    assert!(!quote_only(std::slice::from_ref(&v2)));
+    assert!(quote_only(&[v4, v2]));
+    assert_eq!(SkipReason::QuoteOnly.label(), "quote_only");
+    assert!(SkipReason::ALL.contains(&SkipReason::QuoteOnly));
+}
+
+#[test]
+fn v4_leg_carries_pool_key() {
+    let router = Address::from_low_u64_be(0x5);
+    let key = V4PoolKey::new(
+        Address::from_low_u64_be(0xa),
+        Address::from_low_u64_be(0xb),
+        500,
+        -10,
+        Address::zero(),
+    );
+    let legs = vec![LegQuote {
+        kind: LegKind::V4 { router, pool_key: key, zero_for_one: true },
+        quoted_out: U256::from(1020),
+    }];
+    let data = encode_route_calldata(
+        &legs,
+        U256::from(1000),
+        U256::from(1010),
+        None,
+        U256::zero(),
+        3,
+    )
+    .unwrap();
+    let out = decode(
+        &[
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(24),
+            ParamType::Int(24),
+            ParamType::Address,
+            ParamType::Bool,
+            ParamType::Uint(256),
+        ],
+        &data,
+    )
+    .unwrap();
+    assert_eq!(out[3], Token::Uint(U256::from(5)));
+    assert_eq!(out[5], Token::Address(key.currency0));
+    assert_eq!(out[7], Token::Uint(U256::from(500)));
+    assert_eq!(out[8], Token::Int(I256::from(-10).into_raw()));
+    assert_eq!(out[10], Token::Bool(true));
+    assert_eq!(out[11], Token::Uint(U256::from(1010)));
+}
+
+#[tokio::test]
+async fn v4_without_pool_manager_is_missing_config() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let mut dex = net.dexes.iter().find(|d| d.name == "UniswapV4").unwrap().clone();
+    dex.pool_manager = None;
+    let res = quote_on_dex(&client, &net, &dex, &["WETH", "USDC"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    match res {
+        QuoteOutcome::MissingConfig(e) => assert!(e.to_string().contains("poolManager"), "{e}"),
+        other => panic!("expected missing_config, got {}", other.reason()),
+    }
+}
+
+#[tokio::test]
+async fn uninitialized_v4_pool_of_optional_token_is_no_pool() {
+    let client = common::client(8453).await;
+    let net = client.cfg.clone();
+    let dex = net.dexes.iter().find(|d| d.name == "UniswapV4").unwrap();
+    let pm: Address = dex.pool_manager.as_ref().unwrap().parse().unwrap();
+    let weth: Address = net.tokens["WETH"].address.parse().unwrap();
+    let aero: Address = net.tokens["AERO"].address.parse().unwrap();
+    let tiers = dex.fee_tiers_bps.clone().unwrap();
+    client.prewarm_pools(tiers.into_iter().map(|fee| ((pm, weth, aero, fee), Address::zero())));
+
+    let res = quote_on_dex(&client, &net, dex, &["WETH", "AERO"], U256::exp10(18), 0)
+        .await
+        .unwrap();
+    assert_eq!(res.reason(), "no_pool");
+}
+
+#[tokio::test]
+async fn quote_above_uint128_is_an_error() {
+    // до RPC не доходит: эндпоинт не слушает
+    let provider = Arc::new(Provider::<Http>::try_from("http://127.0.0.1:1").unwrap());
+    let key = V4PoolKey::new(
+        Address::from_low_u64_be(0xa),
+        Address::from_low_u64_be(0xb),
+        3000,
+        60,
+        Address::zero(),
+    );
+    let err = v4_quote_exact_input_single(
+        provider,
+        Address::from_low_u64_be(0xc),
+        &key,
+        true,
+        U256::from(u128::MAX) + 1,
+        BlockId::Number(BlockNumber::Latest),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("exceeds uint128"), "{err:#}");
+}
@@ -6,46 +6,94 @@ import {SafeTransferLib} from "./libraries/SafeTransferLib.sol";
 import {IERC20} from "./interfaces/IERC20.sol";
 import {IUniswapV2Router02} from "./interfaces/IUniswapV2Router02.sol";
 import {IUniswapV3SwapRouter} from "./interfaces/IUniswapV3SwapRouter.sol";
+import {IUniswapV3SwapRouter02} from "./interfaces/IUniswapV3SwapRouter02.sol";
 import {IQuoterV2} from "./interfaces/IQuoterV2.sol";
+import {IAlgebraSwapRouter} from "./interfaces/IAlgebraSwapRouter.sol";
+import {IAlgebraQuoter} from "./interfaces/IAlgebraQuoter.sol";
 import {ISolidlyRouter} from "./interfaces/ISolidlyRouter.sol";
 import {ISolidlyPair} from "./interfaces/ISolidlyPair.sol";
 import {IPermit2} from "./interfaces/IPermit2.sol";
+import {IWETH} from "./interfaces/IWETH.sol";
 
 contract Executor is ReentrancyGuard {
     using SafeTransferLib for address;
 
     address public owner;
     address public immutable DEFAULT_PERMIT2;
+    // кошельки исполнителя (ротация) и Safe: маршрут `execute(bytes,uint256)` тратит средства контракта
+    mapping(address => bool) public operators;
+
+    uint256 private constant MAX_ROUTE_LEGS = 8;
+    // native-токен в ногах Wrap/Unwrap и в `_balance`
+    address private constant NATIVE = address(0);
 
     event Executed(address indexed caller, address indexed recipient, address inputToken, uint256 amountIn, int256 profit, address lastToken);
     event OwnerChanged(address indexed oldOwner, address indexed newOwner);
+    event OperatorSet(address indexed operator, bool allowed);
     event Swept(address indexed token, address indexed to, uint256 amount);
 
+    // результат `simulate(bytes)`: маршрут исполняется во внутреннем вызове и откатывается
+    error SimulatedProfit(uint256 profit);
+
     modifier onlyOwner() {
         require(msg.sender == owner, "ONLY_OWNER");
         _;
     }
 
+    modifier onlyOperator() {
+        require(msg.sender == owner || operators[msg.sender], "ONLY_OPERATOR");
+        _;
+    }
+
     constructor(address _defaultPermit2) {
         owner = msg.sender;
         DEFAULT_PERMIT2 = _defaultPermit2;
     }
 
+    // выход ноги Unwrap: WETH.withdraw шлёт native на контракт
+    receive() external payable {}
+
     function setOwner(address n) external onlyOwner { emit OwnerChanged(owner, n); owner = n; }
 
+    function setOperator(address op, bool allowed) external onlyOwner {
+        operators[op] = allowed;
+        emit OperatorSet(op, allowed);
+    }
+
     struct Hop {
-        uint8 protocol;        // 1=UniV2, 2=UniV3, 3=Solidly
-        address router;        // V2/V3/Solidly router
+        uint8 protocol;        // 1=UniV2, 2=UniV3, 3=Solidly, 4=Algebra
+        address router;        // V2/V3/Solidly/Algebra router
         address pool;          // для Solidly: pair (simulate), иначе 0
-        address quoter;        // для V3 simulate: QuoterV2
+        address quoter;        // для V3/Algebra simulate: QuoterV2 / Algebra Quoter
         address tokenIn;
         address tokenOut;
         uint256 amountIn;      // 0 => взять текущий баланс tokenIn
-        uint24 fee;            // UniV3 fee
+        uint24 fee;            // UniV3 fee (у Algebra динамическая, не используется)
         bool stable;           // Solidly stable?
         uint256 minOut;        // minOut per hop
         uint256 deadline;      // deadline per hop
-        uint160 sqrtPriceLimitX96; // V3 лимит цены (обычно 0)
+        uint160 sqrtPriceLimitX96; // V3/Algebra лимит цены (обычно 0)
+    }
+
+    // Нога маршрута сервиса (evm-arb-service, calldata.rs `encode_route_calldata`).
+    // Тег 5 — V4 (в контракте не исполняется), поэтому Wrap/Unwrap — 6/7, а не 5/6
+    struct Leg {
+        uint8 tag;             // 1=UniV2, 2=UniV3 (SwapRouter02), 3=Solidly, 4=Algebra, 6=Wrap, 7=Unwrap
+        address router;        // Wrap/Unwrap: WETH
+        address tokenIn;
+        address tokenOut;
+        address[] path;        // UniV2: полный путь (мультихоп)
+        uint24 fee;            // UniV3 fee tier
+        bool stable;           // Solidly stable?
+        uint256 legMin;        // минимум выхода ноги
+    }
+
+    struct Route {
+        uint256 amountIn;
+        uint256 minOut;
+        Leg[] legs;
+        uint256 deadline;      // unix-секунды, 0 — без дедлайна
+        uint256 end;           // длина кодировки маршрута; за ней — необязательный хвост
     }
 
     struct Permit2Data {
@@ -107,6 +155,19 @@ contract Executor is ReentrancyGuard {
                 ISolidlyRouter(h.router).swapExactTokensForTokensSimple(
                     inAmt, h.minOut, h.tokenIn, h.tokenOut, h.stable, address(this), h.deadline
                 );
+            } else if (h.protocol == 4) {
+                _approveIfNeeded(h.tokenIn, h.router, inAmt);
+                IAlgebraSwapRouter.ExactInputSingleParams memory params =
+                    IAlgebraSwapRouter.ExactInputSingleParams({
+                        tokenIn: h.tokenIn,
+                        tokenOut: h.tokenOut,
+                        recipient: address(this),
+                        deadline: h.deadline,
+                        amountIn: inAmt,
+                        amountOutMinimum: h.minOut,
+                        limitSqrtPrice: h.sqrtPriceLimitX96
+                    });
+                IAlgebraSwapRouter(h.router).exactInputSingle(params);
             } else {
                 revert("BAD_PROTOCOL");
             }
@@ -129,6 +190,54 @@ contract Executor is ReentrancyGuard {
         emit Executed(msg.sender, recipient, inputToken, amountIn, profit, lastToken);
     }
 
+    /// Маршрут сервиса: `abi.encode` плоского списка — amountIn, minOut, число ног, ноги
+    /// (тег, поля ноги, минимум выхода ноги), deadline. Круговой: вход и выход — один токен
+    /// на балансе контракта (native — через ноги Wrap/Unwrap). Каждая нога проверяет свой
+    /// минимум (`LEG_MIN`), итог — `minOut`, прибыль по балансу — `minProfit` (убыток
+    /// откатывается всегда). Хвост после маршрута — `abi.encode(Permit2Data)`: вход
+    /// `amountIn` забирается у вызывающего по подписи Permit2 и в прибыль не входит
+    function execute(bytes calldata route, uint256 minProfit)
+        external
+        nonReentrant
+        onlyOperator
+        returns (int256 profit)
+    {
+        Route memory rt = _parseRoute(route);
+        address token = rt.legs[0].tokenIn;
+        uint256 balBefore = _balance(token);
+        balBefore += _pullPermitTail(route, rt, token);
+        _runRoute(rt);
+        uint256 balAfter = _balance(token);
+        require(balAfter >= balBefore + minProfit, "MIN_PROFIT");
+        profit = int256(balAfter - balBefore);
+        emit Executed(msg.sender, address(this), token, rt.amountIn, profit, token);
+    }
+
+    /// Прибыль маршрута `execute(bytes,uint256)` на средствах контракта; состояние не меняется —
+    /// маршрут исполняется во внутреннем вызове, который откатывается с результатом
+    function simulate(bytes calldata route) external returns (uint256 profit) {
+        try this.simulateAndRevert(route) {
+            revert("NO_RESULT");
+        } catch (bytes memory data) {
+            if (data.length != 36 || bytes4(data) != SimulatedProfit.selector) {
+                assembly { revert(add(data, 32), mload(data)) }
+            }
+            assembly { profit := mload(add(data, 36)) }
+        }
+    }
+
+    /// Только для `simulate(bytes)`: всегда откатывается, с `SimulatedProfit` или причиной revert.
+    /// Хвост Permit2 не читается — симуляция идёт на средствах контракта
+    function simulateAndRevert(bytes calldata route) external {
+        require(msg.sender == address(this), "ONLY_SELF");
+        Route memory rt = _parseRoute(route);
+        address token = rt.legs[0].tokenIn;
+        uint256 balBefore = _balance(token);
+        _runRoute(rt);
+        uint256 balAfter = _balance(token);
+        revert SimulatedProfit(balAfter > balBefore ? balAfter - balBefore : 0);
+    }
+
     function simulate(
         Hop[] calldata hops,
         address inputToken,
@@ -151,6 +260,11 @@ contract Executor is ReentrancyGuard {
                 amt = out;
             } else if (h.protocol == 3) {
                 amt = ISolidlyPair(h.pool).getAmountOut(inAmt, h.tokenIn);
+            } else if (h.protocol == 4) {
+                (uint256 out,) = IAlgebraQuoter(h.quoter).quoteExactInputSingle(
+                    h.tokenIn, h.tokenOut, inAmt, h.sqrtPriceLimitX96
+                );
+                amt = out;
             } else {
                 revert("BAD_PROTOCOL");
             }
@@ -158,6 +272,155 @@ contract Executor is ReentrancyGuard {
         expectedOut = amt;
     }
 
+    function _word(bytes calldata r, uint256 at) private pure returns (uint256) {
+        require(r.length >= at + 32, "BAD_ROUTE");
+        return uint256(bytes32(r[at:at + 32]));
+    }
+
+    function _addr(bytes calldata r, uint256 at) private pure returns (address) {
+        return address(uint160(_word(r, at)));
+    }
+
+    /// Разбор кодировки маршрута: голова — слова по порядку, путь UniV2 — смещение в хвост
+    function _parseRoute(bytes calldata r) internal view returns (Route memory rt) {
+        rt.amountIn = _word(r, 0);
+        rt.minOut = _word(r, 32);
+        uint256 n = _word(r, 64);
+        require(n > 0 && n <= MAX_ROUTE_LEGS, "BAD_ROUTE");
+        rt.legs = new Leg[](n);
+        uint256 p = 96;
+        uint256 tailEnd = 0;
+        for (uint256 i = 0; i < n; i++) {
+            Leg memory l = rt.legs[i];
+            l.tag = uint8(_word(r, p));
+            l.router = _addr(r, p + 32);
+            p += 64;
+            if (l.tag == 1) {
+                uint256 off = _word(r, p);
+                uint256 len = _word(r, off);
+                require(len >= 2 && len <= MAX_ROUTE_LEGS + 1, "BAD_ROUTE");
+                l.path = new address[](len);
+                for (uint256 j = 0; j < len; j++) {
+                    l.path[j] = _addr(r, off + 32 + 32 * j);
+                }
+                l.tokenIn = l.path[0];
+                l.tokenOut = l.path[len - 1];
+                if (off + 32 + 32 * len > tailEnd) tailEnd = off + 32 + 32 * len;
+                p += 32;
+            } else if (l.tag == 2) {
+                l.tokenIn = _addr(r, p);
+                l.tokenOut = _addr(r, p + 32);
+                l.fee = uint24(_word(r, p + 64));
+                p += 96;
+            } else if (l.tag == 3) {
+                address pair = _addr(r, p);
+                l.stable = _word(r, p + 32) != 0;
+                l.tokenIn = _addr(r, p + 64);
+                address t0 = ISolidlyPair(pair).token0();
+                l.tokenOut = t0 == l.tokenIn ? ISolidlyPair(pair).token1() : t0;
+                p += 96;
+            } else if (l.tag == 4) {
+                l.tokenIn = _addr(r, p);
+                l.tokenOut = _addr(r, p + 32);
+                p += 64;
+            } else if (l.tag == 6) {
+                l.tokenIn = NATIVE;
+                l.tokenOut = l.router;
+            } else if (l.tag == 7) {
+                l.tokenIn = l.router;
+                l.tokenOut = NATIVE;
+            } else {
+                revert("BAD_PROTOCOL");
+            }
+            l.legMin = _word(r, p);
+            p += 32;
+            require(i == 0 || l.tokenIn == rt.legs[i - 1].tokenOut, "BAD_ROUTE");
+        }
+        require(rt.legs[n - 1].tokenOut == rt.legs[0].tokenIn, "NOT_CIRCULAR");
+        rt.deadline = _word(r, p);
+        p += 32;
+        rt.end = tailEnd > p ? tailEnd : p;
+    }
+
+    /// Вход маршрута по хвосту Permit2 (если он есть): подпись — на `token` и `spender` =
+    /// контракт; возвращает забранную сумму
+    function _pullPermitTail(bytes calldata route, Route memory rt, address token) internal returns (uint256) {
+        if (route.length <= rt.end) return 0;
+        require(token != NATIVE, "BAD_PERMIT");
+        Permit2Data memory p2 = abi.decode(route[rt.end:], (Permit2Data));
+        require(p2.permit.permitted.token == token, "BAD_PERMIT");
+        // забирается ровно `amountIn`: он же вычитается из прибыли
+        uint256 requested = p2.transferDetails.requestedAmount;
+        require(requested == 0 || requested == rt.amountIn, "BAD_PERMIT");
+        require(p2.permit.permitted.amount >= rt.amountIn, "BAD_PERMIT");
+        _pullWithPermit2OrTransfer(token, msg.sender, rt.amountIn, p2);
+        return rt.amountIn;
+    }
+
+    /// Ноги по порядку: вход первой — `amountIn`, каждой следующей — выход предыдущей.
+    /// Маршрут после своего `deadline` не исполняется (`EXPIRED`)
+    function _runRoute(Route memory rt) internal returns (uint256 amt) {
+        require(rt.deadline == 0 || block.timestamp <= rt.deadline, "EXPIRED");
+        amt = rt.amountIn;
+        for (uint256 i = 0; i < rt.legs.length; i++) {
+            amt = _swapLeg(rt.legs[i], amt);
+        }
+        require(amt >= rt.minOut, "MIN_OUT");
+    }
+
+    /// Свап ноги; выход — по балансу `tokenOut`, не меньше `legMin` ноги
+    function _swapLeg(Leg memory l, uint256 inAmt) internal returns (uint256 out) {
+        require(inAmt > 0, "ZERO_IN");
+        uint256 before = _balance(l.tokenOut);
+        if (l.tag == 1) {
+            _approveIfNeeded(l.tokenIn, l.router, inAmt);
+            IUniswapV2Router02(l.router).swapExactTokensForTokens(
+                inAmt, l.legMin, l.path, address(this), block.timestamp
+            );
+        } else if (l.tag == 2) {
+            _approveIfNeeded(l.tokenIn, l.router, inAmt);
+            IUniswapV3SwapRouter02(l.router).exactInputSingle(
+                IUniswapV3SwapRouter02.ExactInputSingleParams({
+                    tokenIn: l.tokenIn,
+                    tokenOut: l.tokenOut,
+                    fee: l.fee,
+                    recipient: address(this),
+                    amountIn: inAmt,
+                    amountOutMinimum: l.legMin,
+                    sqrtPriceLimitX96: 0
+                })
+            );
+        } else if (l.tag == 3) {
+            _approveIfNeeded(l.tokenIn, l.router, inAmt);
+            ISolidlyRouter(l.router).swapExactTokensForTokensSimple(
+                inAmt, l.legMin, l.tokenIn, l.tokenOut, l.stable, address(this), block.timestamp
+            );
+        } else if (l.tag == 4) {
+            _approveIfNeeded(l.tokenIn, l.router, inAmt);
+            IAlgebraSwapRouter(l.router).exactInputSingle(
+                IAlgebraSwapRouter.ExactInputSingleParams({
+                    tokenIn: l.tokenIn,
+                    tokenOut: l.tokenOut,
+                    recipient: address(this),
+                    deadline: block.timestamp,
+                    amountIn: inAmt,
+                    amountOutMinimum: l.legMin,
+                    limitSqrtPrice: 0
+                })
+            );
+        } else if (l.tag == 6) {
+            IWETH(l.router).deposit{value: inAmt}();
+        } else {
+            IWETH(l.router).withdraw(inAmt);
+        }
+        out = _balance(l.tokenOut) - before;
+        require(out >= l.legMin, "LEG_MIN");
+    }
+
+    function _balance(address token) internal view returns (uint256) {
+        return token == NATIVE ? address(this).balance : token.balanceOf(address(this));
+    }
+
     function _approveIfNeeded(address token, address spender, uint256 amount) internal {
         (bool s, bytes memory d) = token.staticcall(abi.encodeWithSelector(0xdd62ed3e, address(this), spender));
         uint256 allowance = 0;
@@ -169,7 +432,7 @@ contract Executor is ReentrancyGuard {
     }
 
     function _pullWithPermit2OrTransfer(
-        address token, address ownerAddr, uint256 amount, Permit2Data calldata p2
+        address token, address ownerAddr, uint256 amount, Permit2Data memory p2
     ) internal {
         address permit = p2.permit2 == address(0) ? DEFAULT_PERMIT2 : p2.permit2;
         if (permit != address(0) && p2.signature.length > 0) {
@@ -183,9 +446,15 @@ contract Executor is ReentrancyGuard {
         }
     }
 
+    /// `token = address(0)` — native (прибыль маршрутов с ногой Unwrap)
     function sweep(address token, address to) external onlyOwner {
-        uint256 bal = token.balanceOf(address(this));
-        token.safeTransfer(to, bal);
+        uint256 bal = _balance(token);
+        if (token == NATIVE) {
+            (bool ok,) = to.call{value: bal}("");
+            require(ok, "TRANSFER_FAILED");
+        } else {
+            token.safeTransfer(to, bal);
+        }
         emit Swept(token, to, bal);
     }
 }
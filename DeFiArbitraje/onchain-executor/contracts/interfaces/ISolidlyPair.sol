@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MIT
 pragma solidity ^0.8.20;
 interface ISolidlyPair {
+    function token0() external view returns (address);
+    function token1() external view returns (address);
     function getAmountOut(uint amountIn, address tokenIn) external view returns (uint amountOut);
 }
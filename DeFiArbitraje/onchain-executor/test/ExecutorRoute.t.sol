@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import "forge-std/Test.sol";
+import "../contracts/Executor.sol";
+import {IPermit2} from "../contracts/interfaces/IPermit2.sol";
+import {MockERC20, MockPermit2, MockV2Router, MockWETH} from "./Mocks.sol";
+
+/// `execute(bytes,uint256)` / `simulate(bytes)` на кодировке маршрута сервиса
+contract ExecutorRouteTest is Test {
+    Executor exec;
+    MockERC20 usdc;
+    MockERC20 weth;
+    MockV2Router routerA;
+    MockV2Router routerB;
+
+    function setUp() public {
+        exec = new Executor(address(0));
+        usdc = new MockERC20();
+        weth = new MockERC20();
+        routerA = new MockV2Router();
+        routerB = new MockV2Router();
+        // USDC → WETH ×2, WETH → USDC ×0.51: 1000 → 2000 → 1020
+        routerA.setRate(20_000);
+        routerB.setRate(5_100);
+        usdc.mint(address(exec), 1_000);
+    }
+
+    /// Две UniV2-ноги USDC → WETH → USDC, как их кодирует `encode_route_calldata`:
+    /// голова из 12 слов (пути — смещениями), затем пути
+    function _route(uint256 minOut, uint256 leg0Min, uint256 deadline) internal view returns (bytes memory) {
+        bytes memory leg0 = abi.encode(uint256(1), address(routerA), uint256(12 * 32), leg0Min);
+        bytes memory leg1 = abi.encode(uint256(1), address(routerB), uint256(15 * 32), minOut);
+        return bytes.concat(
+            abi.encode(uint256(1_000), minOut, uint256(2)),
+            leg0,
+            leg1,
+            abi.encode(deadline),
+            abi.encode(uint256(2), address(usdc), address(weth)),
+            abi.encode(uint256(2), address(weth), address(usdc))
+        );
+    }
+
+    function test_routeKeepsProfitOnContract() public {
+        int256 profit = exec.execute(_route(1_010, 1_980, 0), 10);
+        assertEq(profit, 20);
+        assertEq(usdc.balanceOf(address(exec)), 1_020);
+        assertEq(weth.balanceOf(address(exec)), 0);
+    }
+
+    function test_legMinEnforcedByExecutor() public {
+        // роутер минимум не проверяет: 2000 WETH < минимума ноги 2001
+        routerA.setCheckMin(false);
+        vm.expectRevert(bytes("LEG_MIN"));
+        exec.execute(_route(1_010, 2_001, 0), 0);
+    }
+
+    function test_minProfitReverts() public {
+        vm.expectRevert(bytes("MIN_PROFIT"));
+        exec.execute(_route(1_010, 1_980, 0), 21);
+    }
+
+    function test_expiredRouteReverts() public {
+        vm.warp(1_000_000);
+        assertEq(exec.execute(_route(1_010, 1_980, 1_000_000), 0), 20);
+
+        vm.warp(1_000_001);
+        vm.expectRevert(bytes("EXPIRED"));
+        exec.execute(_route(1_010, 1_980, 1_000_000), 0);
+        vm.expectRevert(bytes("EXPIRED"));
+        exec.simulate(_route(1_010, 1_980, 1_000_000));
+    }
+
+    /// Native-круг Wrap → WETH → USDC → WETH → Unwrap: голова из 18 слов, затем пути
+    function _nativeRoute(MockWETH w, uint256 minOut) internal view returns (bytes memory) {
+        return bytes.concat(
+            abi.encode(uint256(1_000), minOut, uint256(4)),
+            abi.encode(uint256(6), address(w), uint256(1_000)),
+            abi.encode(uint256(1), address(routerB), uint256(18 * 32), uint256(0)),
+            abi.encode(uint256(1), address(routerA), uint256(21 * 32), uint256(0)),
+            abi.encode(uint256(7), address(w), minOut),
+            abi.encode(uint256(0)),
+            abi.encode(uint256(2), address(w), address(usdc)),
+            abi.encode(uint256(2), address(usdc), address(w))
+        );
+    }
+
+    function test_nativeRouteWrapsAndUnwraps() public {
+        MockWETH w = new MockWETH();
+        // WETH → USDC ×0.51, USDC → WETH ×2: 1000 → 510 → 1020; обеспечение WETH, выпущенного роутером
+        vm.deal(address(w), 20);
+        vm.deal(address(exec), 1_000);
+
+        assertEq(exec.simulate(_nativeRoute(w, 1_010)), 20);
+        assertEq(address(exec).balance, 1_000);
+
+        assertEq(exec.execute(_nativeRoute(w, 1_010), 20), 20);
+        assertEq(address(exec).balance, 1_020);
+        assertEq(w.balanceOf(address(exec)), 0);
+
+        vm.expectRevert(bytes("LEG_MIN"));
+        exec.execute(_nativeRoute(w, 1_021), 0);
+
+        exec.sweep(address(0), address(0xbeef));
+        assertEq(address(0xbeef).balance, 1_020);
+    }
+
+    /// Хвост `with_permit` сервиса: `abi.encode(Permit2Data)` после маршрута
+    function _permitTail(address permit2, address token) internal pure returns (bytes memory) {
+        return _permitTail(permit2, token, 1_000, 1_000);
+    }
+
+    function _permitTail(address permit2, address token, uint256 permitted, uint256 requested)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        Executor.Permit2Data memory p2 = Executor.Permit2Data({
+            permit2: permit2,
+            permit: IPermit2.PermitTransferFrom({
+                permitted: IPermit2.TokenPermissions({token: token, amount: permitted}),
+                nonce: 7,
+                deadline: type(uint256).max
+            }),
+            transferDetails: IPermit2.SignatureTransferDetails({to: address(0), requestedAmount: requested}),
+            signature: new bytes(65)
+        });
+        return abi.encode(p2);
+    }
+
+    function test_permitTailPullsInputFromCaller() public {
+        MockPermit2 permit2 = new MockPermit2();
+        address wallet = address(0xbeef);
+        exec.setOperator(wallet, true);
+        usdc.mint(wallet, 1_000);
+        vm.prank(wallet);
+        usdc.approve(address(permit2), type(uint256).max);
+
+        bytes memory route = bytes.concat(_route(1_010, 1_980, 0), _permitTail(address(permit2), address(usdc)));
+        vm.prank(wallet);
+        // забранный вход — не прибыль: 20, а не 1020
+        assertEq(exec.execute(route, 20), 20);
+        assertEq(permit2.lastOwner(), wallet);
+        assertEq(permit2.lastAmount(), 1_000);
+        assertEq(usdc.balanceOf(wallet), 0);
+        assertEq(usdc.balanceOf(address(exec)), 2_020);
+
+        // симуляция хвост не читает
+        assertEq(exec.simulate(route), 20);
+    }
+
+    function test_permitTailForOtherTokenReverts() public {
+        MockPermit2 permit2 = new MockPermit2();
+        bytes memory route = bytes.concat(_route(1_010, 1_980, 0), _permitTail(address(permit2), address(weth)));
+        vm.expectRevert(bytes("BAD_PERMIT"));
+        exec.execute(route, 0);
+    }
+
+    function test_permitTailAmountMismatchReverts() public {
+        MockPermit2 permit2 = new MockPermit2();
+        // requestedAmount не равен amountIn маршрута (1000)
+        bytes memory route =
+            bytes.concat(_route(1_010, 1_980, 0), _permitTail(address(permit2), address(usdc), 1_000, 900));
+        vm.expectRevert(bytes("BAD_PERMIT"));
+        exec.execute(route, 0);
+
+        // подпись на меньшую сумму, чем вход
+        route = bytes.concat(_route(1_010, 1_980, 0), _permitTail(address(permit2), address(usdc), 900, 0));
+        vm.expectRevert(bytes("BAD_PERMIT"));
+        exec.execute(route, 0);
+    }
+
+    function test_onlyOperatorsExecute() public {
+        vm.prank(address(0xbeef));
+        vm.expectRevert(bytes("ONLY_OPERATOR"));
+        exec.execute(_route(1_010, 1_980, 0), 0);
+
+        exec.setOperator(address(0xbeef), true);
+        vm.prank(address(0xbeef));
+        assertEq(exec.execute(_route(1_010, 1_980, 0), 0), 20);
+    }
+
+    function test_simulateLeavesStateUntouched() public {
+        assertEq(exec.simulate(_route(1_010, 1_980, 0)), 20);
+        assertEq(usdc.balanceOf(address(exec)), 1_000);
+
+        routerA.setCheckMin(false);
+        vm.expectRevert(bytes("LEG_MIN"));
+        exec.simulate(_route(1_010, 2_001, 0));
+    }
+}
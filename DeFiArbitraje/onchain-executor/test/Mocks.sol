@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {IPermit2} from "../contracts/interfaces/IPermit2.sol";
+
+/// ERC20 для тестов: свободный mint, без проверок allowance у владельца
+contract MockERC20 {
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    function mint(address to, uint256 amount) external {
+        balanceOf[to] += amount;
+    }
+
+    function approve(address spender, uint256 amount) external returns (bool) {
+        allowance[msg.sender][spender] = amount;
+        return true;
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) external returns (bool) {
+        if (allowance[from][msg.sender] != type(uint256).max) {
+            allowance[from][msg.sender] -= amount;
+        }
+        balanceOf[from] -= amount;
+        balanceOf[to] += amount;
+        return true;
+    }
+}
+
+/// Permit2 для тестов: подпись не проверяет (только непустая), переводит `requestedAmount`
+/// токена от `owner` через свой allowance и запоминает вызов
+contract MockPermit2 {
+    address public lastOwner;
+    uint256 public lastAmount;
+
+    function permitTransferFrom(
+        IPermit2.PermitTransferFrom calldata permit,
+        IPermit2.SignatureTransferDetails calldata transferDetails,
+        address owner,
+        bytes calldata signature
+    ) external {
+        require(signature.length == 65, "BAD_SIGNATURE");
+        require(transferDetails.requestedAmount <= permit.permitted.amount, "TOO_MUCH");
+        lastOwner = owner;
+        lastAmount = transferDetails.requestedAmount;
+        MockERC20(permit.permitted.token).transferFrom(owner, transferDetails.to, transferDetails.requestedAmount);
+    }
+}
+
+/// WETH для тестов: deposit/withdraw 1:1; `mint` роутеров не обеспечен native — контракт
+/// пополняется в тесте (`vm.deal`)
+contract MockWETH is MockERC20 {
+    function deposit() external payable {
+        balanceOf[msg.sender] += msg.value;
+    }
+
+    function withdraw(uint256 amount) external {
+        balanceOf[msg.sender] -= amount;
+        payable(msg.sender).transfer(amount);
+    }
+}
+
+/// UniV2-роутер по фиксированному курсу `rateBps` на хоп; `checkMin = false` — роутер
+/// не проверяет amountOutMin (минимум ноги должен проверить сам экзекутор)
+contract MockV2Router {
+    uint256 public rateBps;
+    bool public checkMin = true;
+
+    function setRate(uint256 bps) external {
+        rateBps = bps;
+    }
+
+    function setCheckMin(bool on) external {
+        checkMin = on;
+    }
+
+    function swapExactTokensForTokens(
+        uint256 amountIn, uint256 amountOutMin, address[] calldata path, address to, uint256
+    ) external returns (uint256[] memory amounts) {
+        MockERC20(path[0]).transferFrom(msg.sender, address(this), amountIn);
+        amounts = new uint256[](path.length);
+        amounts[0] = amountIn;
+        for (uint256 i = 1; i < path.length; i++) {
+            amounts[i] = amounts[i - 1] * rateBps / 10_000;
+        }
+        uint256 out = amounts[path.length - 1];
+        require(!checkMin || out >= amountOutMin, "INSUFFICIENT_OUTPUT_AMOUNT");
+        MockERC20(path[path.length - 1]).mint(to, out);
+    }
+}
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,18 +11,41 @@ pub struct Config {
 }
 
 impl Config {
+    /// .json / .yaml / .yml / .toml — по расширению файла
     pub fn load(path: &str) -> Result<Self> {
-        let s = std::fs::read_to_string(path)?;
-        let c: Self = serde_json::from_str(&s)?;
+        let s = std::fs::read_to_string(path).with_context(|| format!("read config {path}"))?;
+        let c: Self = parse_by_extension(path, &s)?;
         Ok(c)
     }
 }
 
+fn parse_by_extension<T: DeserializeOwned>(path: &str, data: &str) -> Result<T> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "json" => serde_json::from_str(data).with_context(|| format!("parse JSON config {path}")),
+        "yaml" | "yml" => {
+            serde_yaml::from_str(data).with_context(|| format!("parse YAML config {path}"))
+        }
+        "toml" => toml::from_str(data).with_context(|| format!("parse TOML config {path}")),
+        _ => serde_json::from_str(data).map_err(|e| {
+            anyhow!(
+                "config {path}: unknown extension '{ext}' (expected .json/.yaml/.yml/.toml), \
+                 JSON fallback failed: {e}"
+            )
+        }),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Network {
     pub id: String,
     pub name: String,
-    pub chainId: u64,
+    #[serde(rename = "chainId", alias = "chain_id")]
+    pub chain_id: u64,
     pub rpc: Vec<String>,
     pub tokens: HashMap<String, Token>,
     pub dexes: Vec<DexConfig>,
@@ -43,6 +67,8 @@ pub struct DexConfig {
     pub dex_type: String, // "v2" | "v3" | "solidly_v2"
     pub factory: Option<String>,
     pub router: Option<String>,
-    pub feeTiers_bps: Option<Vec<u32>>,
-    pub stablePools: Option<bool>,
+    #[serde(rename = "feeTiers_bps", alias = "fee_tiers_bps")]
+    pub fee_tiers_bps: Option<Vec<u32>>,
+    #[serde(rename = "stablePools", alias = "stable_pools")]
+    pub stable_pools: Option<bool>,
 }
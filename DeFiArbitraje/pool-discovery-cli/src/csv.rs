@@ -0,0 +1,120 @@
+use crate::discover::{OutDex, Output};
+
+const HEADER: &str = "chain_id,dex,type,pair,fee,stable,address,token0,token1,reserves0,reserves1,liquidity,decimals0,decimals1";
+
+/// Плоская выгрузка: одна строка на пул/пару. Пустые ячейки — поле неприменимо
+/// к типу DEX (у v3 нет резервов, у v2 нет fee/liquidity).
+pub fn to_csv(out: &Output) -> String {
+    let mut rows = vec![HEADER.to_string()];
+    for n in &out.networks {
+        for d in &n.dexes {
+            match d {
+                OutDex::V2 { name, pairs, .. } => {
+                    for p in pairs {
+                        rows.push(row(&[
+                            &n.chain_id.to_string(), name, "v2", &p.pair.join("/"), "", "",
+                            &p.address, &p.token0, &p.token1, &p.reserves0, &p.reserves1, "",
+                            &p.decimals0.to_string(), &p.decimals1.to_string(),
+                        ]));
+                    }
+                }
+                OutDex::Solidly { name, pairs, .. } => {
+                    for p in pairs {
+                        rows.push(row(&[
+                            &n.chain_id.to_string(), name, "solidly_v2", &p.pair.join("/"), "", &p.stable.to_string(),
+                            &p.address, &p.token0, &p.token1, &p.reserves0, &p.reserves1, "",
+                            &p.decimals0.to_string(), &p.decimals1.to_string(),
+                        ]));
+                    }
+                }
+                OutDex::V3 { name, pools, .. } => {
+                    for p in pools {
+                        rows.push(row(&[
+                            &n.chain_id.to_string(), name, "v3", &p.pair.join("/"), &p.fee.to_string(), "",
+                            &p.address, &p.token0, &p.token1, "", "", &p.liquidity,
+                            &p.decimals0.to_string(), &p.decimals1.to_string(),
+                        ]));
+                    }
+                }
+            }
+        }
+    }
+    rows.push(String::new());
+    rows.join("\n")
+}
+
+fn row(cells: &[&str]) -> String {
+    cells.iter().map(|c| escape(c)).collect::<Vec<_>>().join(",")
+}
+
+// RFC 4180: кавычки только если в значении есть разделитель/кавычка/перевод строки
+fn escape(v: &str) -> String {
+    if v.contains([',', '"', '\n']) {
+        format!("\"{}\"", v.replace('"', "\"\""))
+    } else {
+        v.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discover::{to_checksum_address, OutNetwork, OutV2Pair, OutV3Pool};
+    use ethers::types::Address;
+
+    fn output(addr: &str) -> Output {
+        let v2 = OutV2Pair {
+            pair: ["WETH".into(), "USDC".into()],
+            address: addr.into(),
+            token0: addr.into(),
+            token1: addr.into(),
+            reserves0: "1000".into(),
+            reserves1: "2000".into(),
+            decimals0: 18,
+            decimals1: 6,
+            suggested_amount_token0: "1".into(),
+            suggested_amount_token1: "1".into(),
+        };
+        let v3 = OutV3Pool {
+            pair: ["WETH".into(), "USDC".into()],
+            fee: 500,
+            address: addr.into(),
+            token0: addr.into(),
+            token1: addr.into(),
+            sqrt_price_x96: "1".into(),
+            tick: 0,
+            liquidity: "42".into(),
+            decimals0: 18,
+            decimals1: 6,
+            note: String::new(),
+        };
+        Output {
+            generated_at: "2024-01-01T00:00:00Z".into(),
+            networks: vec![OutNetwork {
+                chain_id: 8453,
+                name: "base".into(),
+                dexes: vec![
+                    OutDex::V2 { name: "Uni, \"v2\"".into(), factory: String::new(), pairs: vec![v2] },
+                    OutDex::V3 { name: "UniV3".into(), factory: String::new(), pools: vec![v3] },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn rows_follow_header_and_quote_separators() {
+        let addr = to_checksum_address(&Address::from_low_u64_be(0xabcdef));
+        let csv = to_csv(&output(&addr));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], HEADER);
+        assert_eq!(
+            lines[1],
+            format!("8453,\"Uni, \"\"v2\"\"\",v2,WETH/USDC,,,{addr},{addr},{addr},1000,2000,,18,6")
+        );
+        // v3: без резервов, decimals — как у v2
+        assert_eq!(lines[2], format!("8453,UniV3,v3,WETH/USDC,500,,{addr},{addr},{addr},,,42,18,6"));
+        // --checksum: регистр EIP-55 сохраняется, адрес не кавычится
+        assert_ne!(addr, addr.to_lowercase());
+        assert!(csv.ends_with('\n'));
+    }
+}
@@ -1,14 +1,13 @@
-use crate::config::{Config, Network, DexConfig};
+use crate::config::{Config, Network};
 use anyhow::{Result, anyhow};
 use ethers::abi::Abi;
-use ethers::contract::Contract;
-use ethers::providers::{Provider, Http};
+use ethers::contract::{Contract, ContractCall, Multicall};
+use ethers::providers::{Http, Middleware, Provider};
 use ethers::types::{Address, U256};
-use futures::stream::{StreamExt, FuturesUnordered};
-use itertools::Itertools;
+use futures::stream::StreamExt;
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{info, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,22 +73,36 @@ pub struct OutV3Pool {
     pub sqrt_price_x96: String,
     pub tick: i32,
     pub liquidity: String,
+    pub decimals0: u8,
+    pub decimals1: u8,
     pub note: String,
 }
 
-pub async fn run_discovery(cfg: Config, _concurrency: usize) -> Result<Output> {
+pub async fn run_discovery(cfg: Config, concurrency: usize, multicall_address: Address) -> Result<Output> {
     let mut out_networks = Vec::new();
     for n in cfg.networks {
         let Some(rpc) = n.rpc.first() else { continue };
         let provider = Arc::new(Provider::<Http>::try_from(rpc.clone())?);
-        info!(chainId=%n.chainId, "Скан сети");
+        info!(chainId=%n.chain_id, "Скан сети");
+        let multicall = match multicall_for(provider.clone(), multicall_address).await {
+            Ok(Some(mc)) => Some(mc),
+            Ok(None) => {
+                warn!(chainId=%n.chain_id, "Multicall3 не найден по {:#x} — поштучные RPC-вызовы", multicall_address);
+                None
+            }
+            Err(e) => {
+                warn!(chainId=%n.chain_id, "Проверка Multicall3 не удалась ({e:#}) — поштучные RPC-вызовы");
+                None
+            }
+        };
+        let mc = multicall.as_ref();
 
         let mut out_dexes = Vec::new();
         for d in &n.dexes {
             match d.dex_type.as_str() {
                 "v2" => {
                     if let Some(factory) = &d.factory {
-                        let pairs = discover_v2(&n, provider.clone(), factory).await?;
+                        let pairs = discover_v2(&n, provider.clone(), factory, mc, concurrency).await?;
                         out_dexes.push(OutDex::V2 { name: d.name.clone(), factory: factory.clone(), pairs });
                     } else {
                         warn!("Пропуск v2 {} — нет factory", d.name);
@@ -97,7 +110,7 @@ pub async fn run_discovery(cfg: Config, _concurrency: usize) -> Result<Output> {
                 }
                 "solidly_v2" => {
                     if let Some(factory) = &d.factory {
-                        let pairs = discover_solidly(&n, provider.clone(), factory).await?;
+                        let pairs = discover_solidly(&n, provider.clone(), factory, mc, concurrency).await?;
                         out_dexes.push(OutDex::Solidly { name: d.name.clone(), factory: factory.clone(), pairs });
                     } else {
                         warn!("Пропуск solidly {} — нет factory", d.name);
@@ -105,8 +118,8 @@ pub async fn run_discovery(cfg: Config, _concurrency: usize) -> Result<Output> {
                 }
                 "v3" => {
                     if let Some(factory) = &d.factory {
-                        let fees = d.feeTiers_bps.clone().unwrap_or(vec![100,500,1000,3000,10000]);
-                        let pools = discover_v3(&n, provider.clone(), factory, &fees).await?;
+                        let fees = d.fee_tiers_bps.clone().unwrap_or(vec![100,500,1000,3000,10000]);
+                        let pools = discover_v3(&n, provider.clone(), factory, &fees, mc, concurrency).await?;
                         out_dexes.push(OutDex::V3 { name: d.name.clone(), factory: factory.clone(), pools });
                     } else {
                         warn!("Пропуск v3 {} — нет factory", d.name);
@@ -119,7 +132,7 @@ pub async fn run_discovery(cfg: Config, _concurrency: usize) -> Result<Output> {
         }
 
         out_networks.push(OutNetwork {
-            chain_id: n.chainId, name: n.name.clone(), dexes: out_dexes
+            chain_id: n.chain_id, name: n.name.clone(), dexes: out_dexes
         });
     }
 
@@ -129,64 +142,173 @@ pub async fn run_discovery(cfg: Config, _concurrency: usize) -> Result<Output> {
     })
 }
 
-async fn discover_v2(n: &Network, provider: Arc<Provider<Http>>, factory: &String) -> Result<Vec<OutV2Pair>> {
+/// Выполняет задачи с ограничением параллелизма и возвращает результаты
+/// в исходном порядке задач (вывод детерминирован независимо от порядка ответов RPC).
+async fn run_ordered<J, T, F, Fut>(jobs: Vec<J>, concurrency: usize, f: F) -> Result<Vec<T>>
+where
+    F: Fn(J) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<T>>>,
+{
+    let f = &f;
+    let mut results: Vec<(usize, T)> = futures::stream::iter(jobs.into_iter().enumerate())
+        .map(|(i, job)| async move { f(job).await.map(|r| r.map(|v| (i, v))) })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .filter_map(|r| r.transpose())
+        .collect::<Result<Vec<_>>>()?;
+    results.sort_by_key(|(i, _)| *i);
+    Ok(results.into_iter().map(|(_, v)| v).collect())
+}
+
+fn pair_addresses(n: &Network) -> Result<Vec<(String, String, Address, Address)>> {
+    n.pairs
+        .iter()
+        .cloned()
+        .map(|[a_sym, b_sym]| {
+            let t_a = n.tokens.get(&a_sym).ok_or_else(|| anyhow!("token {} not found", a_sym))?.address.clone();
+            let t_b = n.tokens.get(&b_sym).ok_or_else(|| anyhow!("token {} not found", b_sym))?.address.clone();
+            Ok((a_sym, b_sym, parse_addr(&t_a), parse_addr(&t_b)))
+        })
+        .collect()
+}
+
+type Mc = Multicall<Provider<Http>>;
+
+/// Multicall3 по адресу `addr`. `None` — контракт в сети не задеплоен,
+/// тогда discovery работает поштучными вызовами.
+async fn multicall_for(provider: Arc<Provider<Http>>, addr: Address) -> Result<Option<Mc>> {
+    let code = provider.get_code(addr, None).await?;
+    if code.as_ref().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Multicall::new(provider, Some(addr)).await?))
+}
+
+/// token0/token1/getReserves пары (v2 и solidly) — одним aggregate, если есть multicall
+async fn pair_meta(mc: Option<&Mc>, c_pair: &Contract<Provider<Http>>) -> Result<(Address, Address, (U256, U256, u32))> {
+    match mc {
+        Some(mc) => {
+            let mut mc = mc.clone();
+            mc.add_call(c_pair.method::<_, Address>("token0", ())?, false)
+                .add_call(c_pair.method::<_, Address>("token1", ())?, false)
+                .add_call(c_pair.method::<_, (U256, U256, u32)>("getReserves", ())?, false);
+            Ok(mc.call().await?)
+        }
+        None => {
+            let token0: Address = c_pair.method("token0", ())?.call().await?;
+            let token1: Address = c_pair.method("token1", ())?.call().await?;
+            let reserves: (U256, U256, u32) = c_pair.method("getReserves", ())?.call().await?;
+            Ok((token0, token1, reserves))
+        }
+    }
+}
+
+type Slot0 = (U256, i32, u16, u16, u16, u8, bool);
+
+/// slot0/liquidity/token0/token1 v3-пула — одним aggregate, если есть multicall
+async fn pool_meta(mc: Option<&Mc>, c_pool: &Contract<Provider<Http>>) -> Result<(Slot0, U256, Address, Address)> {
+    match mc {
+        Some(mc) => {
+            let mut mc = mc.clone();
+            mc.add_call(c_pool.method::<_, Slot0>("slot0", ())?, false)
+                .add_call(c_pool.method::<_, U256>("liquidity", ())?, false)
+                .add_call(c_pool.method::<_, Address>("token0", ())?, false)
+                .add_call(c_pool.method::<_, Address>("token1", ())?, false);
+            Ok(mc.call().await?)
+        }
+        None => {
+            let slot0: Slot0 = c_pool.method("slot0", ())?.call().await?;
+            let liq: U256 = c_pool.method("liquidity", ())?.call().await?;
+            let t0: Address = c_pool.method("token0", ())?.call().await?;
+            let t1: Address = c_pool.method("token1", ())?.call().await?;
+            Ok((slot0, liq, t0, t1))
+        }
+    }
+}
+
+/// Адреса пулов для набора вызовов фабрики одной пары (fee tiers / stable-флаги):
+/// с multicall — один aggregate, без него — параллельные одиночные вызовы.
+async fn factory_lookups<D>(mc: Option<&Mc>, calls: Vec<(D, ContractCall<Provider<Http>, Address>)>) -> Result<Vec<(D, Address)>> {
+    let (keys, calls): (Vec<D>, Vec<_>) = calls.into_iter().unzip();
+    let addrs: Vec<Address> = match mc {
+        Some(mc) => {
+            let mut mc = mc.clone();
+            for c in calls {
+                mc.add_call(c, false);
+            }
+            mc.call_array().await?
+        }
+        None => futures::future::try_join_all(calls.iter().map(|c| c.call())).await?,
+    };
+    Ok(keys.into_iter().zip(addrs).filter(|(_, a)| *a != Address::zero()).collect())
+}
+
+async fn discover_v2(n: &Network, provider: Arc<Provider<Http>>, factory: &str, mc: Option<&Mc>, concurrency: usize) -> Result<Vec<OutV2Pair>> {
     let abi_factory: Abi = serde_json::from_str(include_str!("../abis/UniswapV2Factory.json"))?;
+    let abi_pair: Abi = serde_json::from_str(include_str!("../abis/UniswapV2Pair.json"))?;
     let c_factory = Contract::new(parse_addr(factory), abi_factory, provider.clone());
 
-    let mut out = Vec::new();
-    for [a_sym, b_sym] in n.pairs.iter().cloned() {
-        let t_a = n.tokens.get(&a_sym).ok_or_else(|| anyhow!("token {} not found", a_sym))?.address.clone();
-        let t_b = n.tokens.get(&b_sym).ok_or_else(|| anyhow!("token {} not found", b_sym))?.address.clone();
-        let pair_addr: Address = c_factory.method("getPair", (parse_addr(&t_a), parse_addr(&t_b)))?.call().await?;
-        if pair_addr == Address::zero() { continue; }
-        let abi_pair: Abi = serde_json::from_str(include_str!("../abis/UniswapV2Pair.json"))?;
-        let c_pair = Contract::new(pair_addr, abi_pair, provider.clone());
-        let token0: Address = c_pair.method("token0", ())?.call().await?;
-        let token1: Address = c_pair.method("token1", ())?.call().await?;
-        let (r0, r1, _): (U256, U256, u32) = c_pair.method("getReserves", ())?.call().await?;
-
-        let (dec0, dec1) = token_decimals_by_order(&n.tokens, token0, token1)?;
-        let (sug0, sug1) = suggested_from_reserves(r0, r1, 20);
-
-        out.push(OutV2Pair {
-            pair: [a_sym, b_sym],
-            address: to_hex(pair_addr),
-            token0: to_hex(token0),
-            token1: to_hex(token1),
-            reserves0: r0.to_string(),
-            reserves1: r1.to_string(),
-            decimals0: dec0,
-            decimals1: dec1,
-            suggested_amount_token0: sug0.to_string(),
-            suggested_amount_token1: sug1.to_string(),
-        });
-    }
-    Ok(out)
+    run_ordered(pair_addresses(n)?, concurrency, |(a_sym, b_sym, t_a, t_b)| {
+        let (c_factory, abi_pair, provider) = (c_factory.clone(), abi_pair.clone(), provider.clone());
+        async move {
+            let pair_addr: Address = c_factory.method("getPair", (t_a, t_b))?.call().await?;
+            if pair_addr == Address::zero() { return Ok(None); }
+            let c_pair = Contract::new(pair_addr, abi_pair, provider);
+            let (token0, token1, (r0, r1, _)) = pair_meta(mc, &c_pair).await?;
+
+            let (dec0, dec1) = token_decimals_by_order(&n.tokens, token0, token1)?;
+            let (sug0, sug1) = suggested_from_reserves(r0, r1, 20);
+
+            Ok(Some(OutV2Pair {
+                pair: [a_sym, b_sym],
+                address: to_hex(pair_addr),
+                token0: to_hex(token0),
+                token1: to_hex(token1),
+                reserves0: r0.to_string(),
+                reserves1: r1.to_string(),
+                decimals0: dec0,
+                decimals1: dec1,
+                suggested_amount_token0: sug0.to_string(),
+                suggested_amount_token1: sug1.to_string(),
+            }))
+        }
+    })
+    .await
 }
 
-async fn discover_solidly(n: &Network, provider: Arc<Provider<Http>>, factory: &String) -> Result<Vec<OutSolidlyPair>> {
+async fn discover_solidly(n: &Network, provider: Arc<Provider<Http>>, factory: &str, mc: Option<&Mc>, concurrency: usize) -> Result<Vec<OutSolidlyPair>> {
     let abi_factory: Abi = serde_json::from_str(include_str!("../abis/SolidlyFactory.json"))?;
+    // используем v2 ABI для token0/token1/getReserves
+    let abi_pair_v2: Abi = serde_json::from_str(include_str!("../abis/UniswapV2Pair.json"))?;
     let c_factory = Contract::new(parse_addr(factory), abi_factory, provider.clone());
 
-    let mut out = Vec::new();
-    for [a_sym, b_sym] in n.pairs.iter().cloned() {
-        let t_a = n.tokens.get(&a_sym).ok_or_else(|| anyhow!("token {} not found", a_sym))?.address.clone();
-        let t_b = n.tokens.get(&b_sym).ok_or_else(|| anyhow!("token {} not found", b_sym))?.address.clone();
-
-        for &stable in &[false, true] {
-            let pair_addr: Address = c_factory.method("getPair", (parse_addr(&t_a), parse_addr(&t_b), stable))?.call().await?;
-            if pair_addr == Address::zero() { continue; }
-            // используем v2 ABI для token0/token1/getReserves
-            let abi_pair_v2: Abi = serde_json::from_str(include_str!("../abis/UniswapV2Pair.json"))?;
-            let c_pair_v2 = Contract::new(pair_addr, abi_pair_v2, provider.clone());
-            let token0: Address = c_pair_v2.method("token0", ())?.call().await?;
-            let token1: Address = c_pair_v2.method("token1", ())?.call().await?;
-            let (r0, r1, _): (U256, U256, u32) = c_pair_v2.method("getReserves", ())?.call().await?;
+    // 1) адреса volatile/stable пар — по одному запросу на пару токенов
+    let found: Vec<Vec<_>> = run_ordered(pair_addresses(n)?, concurrency, |(a_sym, b_sym, t_a, t_b)| {
+        let c_factory = c_factory.clone();
+        async move {
+            let calls = [false, true]
+                .into_iter()
+                .map(|stable| Ok((stable, c_factory.method("getPair", (t_a, t_b, stable))?)))
+                .collect::<Result<Vec<_>>>()?;
+            let pairs = factory_lookups(mc, calls).await?;
+            Ok(Some(pairs.into_iter().map(|(stable, addr)| (a_sym.clone(), b_sym.clone(), stable, addr)).collect()))
+        }
+    })
+    .await?;
+
+    // 2) метаданные найденных пар
+    run_ordered(found.into_iter().flatten().collect(), concurrency, |(a_sym, b_sym, stable, pair_addr)| {
+        let (abi_pair_v2, provider) = (abi_pair_v2.clone(), provider.clone());
+        async move {
+            let c_pair_v2 = Contract::new(pair_addr, abi_pair_v2, provider);
+            let (token0, token1, (r0, r1, _)) = pair_meta(mc, &c_pair_v2).await?;
 
             let (dec0, dec1) = token_decimals_by_order(&n.tokens, token0, token1)?;
             let (sug0, sug1) = suggested_from_reserves(r0, r1, 15);
-            out.push(OutSolidlyPair {
-                pair: [a_sym.clone(), b_sym.clone()],
+            Ok(Some(OutSolidlyPair {
+                pair: [a_sym, b_sym],
                 stable,
                 address: to_hex(pair_addr),
                 token0: to_hex(token0),
@@ -197,57 +319,84 @@ async fn discover_solidly(n: &Network, provider: Arc<Provider<Http>>, factory: &
                 decimals1: dec1,
                 suggested_amount_token0: sug0.to_string(),
                 suggested_amount_token1: sug1.to_string(),
-            });
+            }))
         }
-    }
-    Ok(out)
+    })
+    .await
 }
 
-async fn discover_v3(n: &Network, provider: Arc<Provider<Http>>, factory: &String, fees: &Vec<u32>) -> Result<Vec<OutV3Pool>> {
+async fn discover_v3(n: &Network, provider: Arc<Provider<Http>>, factory: &str, fees: &[u32], mc: Option<&Mc>, concurrency: usize) -> Result<Vec<OutV3Pool>> {
     let abi_factory: Abi = serde_json::from_str(include_str!("../abis/UniswapV3Factory.json"))?;
+    let abi_pool: Abi = serde_json::from_str(include_str!("../abis/UniswapV3Pool.json"))?;
     let c_factory = Contract::new(parse_addr(factory), abi_factory, provider.clone());
 
-    let mut out = Vec::new();
-    for [a_sym, b_sym] in n.pairs.iter().cloned() {
-        let t_a = n.tokens.get(&a_sym).ok_or_else(|| anyhow!("token {} not found", a_sym))?.address.clone();
-        let t_b = n.tokens.get(&b_sym).ok_or_else(|| anyhow!("token {} not found", b_sym))?.address.clone();
-        for fee in fees {
-            let pool: Address = c_factory.method("getPool", (parse_addr(&t_a), parse_addr(&t_b), *fee))?.call().await?;
-            if pool == Address::zero() { continue; }
-            let abi_pool: Abi = serde_json::from_str(include_str!("../abis/UniswapV3Pool.json"))?;
-            let c_pool = Contract::new(pool, abi_pool, provider.clone());
-            let (spx96, tick, _oi, _oc, _ocn, _fp, _unlocked): (U256, i32, u16, u16, u16, u8, bool) = c_pool.method("slot0", ())?.call().await?;
-            let liq: U256 = c_pool.method("liquidity", ())?.call().await?;
-            let t0: Address = c_pool.method("token0", ())?.call().await?;
-            let t1: Address = c_pool.method("token1", ())?.call().await?;
-            out.push(OutV3Pool {
-                pair: [a_sym.clone(), b_sym.clone()],
-                fee: *fee,
+    // 1) пулы по всем fee tiers пары — одним aggregate
+    let found: Vec<Vec<_>> = run_ordered(pair_addresses(n)?, concurrency, |(a_sym, b_sym, t_a, t_b)| {
+        let c_factory = c_factory.clone();
+        async move {
+            let calls = fees
+                .iter()
+                .map(|&fee| Ok((fee, c_factory.method("getPool", (t_a, t_b, fee))?)))
+                .collect::<Result<Vec<_>>>()?;
+            let pools = factory_lookups(mc, calls).await?;
+            Ok(Some(pools.into_iter().map(|(fee, pool)| (a_sym.clone(), b_sym.clone(), fee, pool)).collect()))
+        }
+    })
+    .await?;
+
+    // 2) slot0/liquidity/token0/token1 найденных пулов
+    run_ordered(found.into_iter().flatten().collect(), concurrency, |(a_sym, b_sym, fee, pool)| {
+        let (abi_pool, provider) = (abi_pool.clone(), provider.clone());
+        async move {
+            let c_pool = Contract::new(pool, abi_pool, provider);
+            let ((spx96, tick, ..), liq, t0, t1) = pool_meta(mc, &c_pool).await?;
+            let (dec0, dec1) = token_decimals_by_order(&n.tokens, t0, t1)?;
+            Ok(Some(OutV3Pool {
+                pair: [a_sym, b_sym],
+                fee,
                 address: to_hex(pool),
                 token0: to_hex(t0),
                 token1: to_hex(t1),
                 sqrt_price_x96: spx96.to_string(),
                 tick,
                 liquidity: liq.to_string(),
-                note: "V3: нет getReserves; используйте liquidity+slot0",
-            });
+                decimals0: dec0,
+                decimals1: dec1,
+                note: "V3: нет getReserves; используйте liquidity+slot0".to_string(),
+            }))
         }
-    }
-    Ok(out)
+    })
+    .await
 }
 
 fn parse_addr(s: &str) -> Address {
     s.parse::<Address>().expect("bad address")
 }
 
+// Формат адресов в выводе: EIP-55 (--checksum) или lowercase
+static CHECKSUM_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_checksum_output(enabled: bool) {
+    CHECKSUM_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// EIP-55 checksum-представление адреса
+pub fn to_checksum_address(a: &Address) -> String {
+    ethers::utils::to_checksum(a, None)
+}
+
 fn to_hex(a: Address) -> String {
-    format!("{:#x}", a)
+    if CHECKSUM_OUTPUT.load(Ordering::Relaxed) {
+        to_checksum_address(&a)
+    } else {
+        format!("{:#x}", a)
+    }
 }
 
 fn token_decimals_by_order(tokens: &std::collections::HashMap<String, crate::config::Token>, t0: Address, t1: Address) -> anyhow::Result<(u8,u8)> {
     let mut dec0 = None;
     let mut dec1 = None;
-    for (_sym, t) in tokens {
+    for t in tokens.values() {
         let addr: Address = parse_addr(&t.address);
         if addr == t0 { dec0 = Some(t.decimals); }
         if addr == t1 { dec1 = Some(t.decimals); }
@@ -1,9 +1,10 @@
 mod config;
+mod csv;
 mod discover;
 
-use clap::Parser;
-use anyhow::Result;
-use tracing::{info, error};
+use clap::{Parser, ValueEnum};
+use anyhow::{anyhow, Result};
+use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 #[derive(Parser, Debug)]
@@ -14,13 +15,32 @@ struct Args {
     #[arg(long, default_value = "/mnt/data/defi_config.json")]
     config: String,
 
-    /// Путь к выходному JSON
+    /// Путь к выходному файлу
     #[arg(long, default_value = "/mnt/data/pools.generated.json")]
     out: String,
 
+    /// Формат вывода: json (вложенный network→dex→pairs) или csv (строка на пул)
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
     /// Максимум одновременных RPC задач
     #[arg(long, default_value_t = 32)]
     concurrency: usize,
+
+    /// Адреса в выводе в EIP-55 (checksum) вместо lowercase
+    #[arg(long, default_value_t = false)]
+    checksum: bool,
+
+    /// Адрес Multicall3 (по умолчанию канонический 0xcA11bde05977b3631167028862bE2a173976CA11).
+    /// Если контракта по адресу нет — поштучные RPC-вызовы
+    #[arg(long)]
+    multicall_address: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
 }
 
 #[tokio::main]
@@ -32,10 +52,20 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     info!("Загрузка конфига из {}", args.config);
     let cfg = config::Config::load(&args.config)?;
+    discover::set_checksum_output(args.checksum);
+
+    let multicall = match &args.multicall_address {
+        Some(a) => a.parse().map_err(|e| anyhow!("--multicall-address '{a}': {e}"))?,
+        None => ethers::contract::MULTICALL_ADDRESS,
+    };
 
-    let out = discover::run_discovery(cfg, args.concurrency).await?;
+    let out = discover::run_discovery(cfg, args.concurrency, multicall).await?;
 
-    std::fs::write(&args.out, serde_json::to_string_pretty(&out)?)?;
+    let body = match args.format {
+        Format::Json => serde_json::to_string_pretty(&out)?,
+        Format::Csv => csv::to_csv(&out),
+    };
+    std::fs::write(&args.out, body)?;
     info!("Готово: {}", &args.out);
     Ok(())
 }